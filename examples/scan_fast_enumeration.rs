@@ -0,0 +1,49 @@
+//! Compares the time it takes to scan a directory with many small files,
+//! with and without [`DirectoryScanOptions::fast_enumeration`].
+//!
+//! Run with `cargo run --release --example scan_fast_enumeration [number of files]`.
+
+use std::time::Instant;
+
+use fs_more::directory::{DirectoryScan, DirectoryScanOptions};
+
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let num_files: usize = match std::env::args().nth(1) {
+        Some(num_files) => num_files.parse()?,
+        None => 100_000,
+    };
+
+    // A synthetic directory with many tiny files, which makes the scan syscall-bound.
+    let temporary_directory = assert_fs::TempDir::new()?;
+
+    for file_index in 0..num_files {
+        std::fs::File::create(
+            temporary_directory
+                .path()
+                .join(format!("file-{}", file_index)),
+        )?;
+    }
+
+    for fast_enumeration in [false, true] {
+        let scan_started_at = Instant::now();
+
+        let scan = DirectoryScan::scan_with_options(
+            temporary_directory.path(),
+            DirectoryScanOptions {
+                fast_enumeration,
+                ..Default::default()
+            },
+        )?;
+
+        println!(
+            "scanned {} files (fast_enumeration = {}) in {:?}",
+            scan.files().len(),
+            fast_enumeration,
+            scan_started_at.elapsed()
+        );
+    }
+
+    temporary_directory.close()?;
+    Ok(())
+}
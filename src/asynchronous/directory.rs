@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use super::run_blocking;
+use crate::{
+    directory::{
+        DirectoryCopyOptions,
+        DirectoryCopyProgress,
+        DirectoryCopyWithProgressOptions,
+        FinishedDirectoryCopy,
+    },
+    error::DirectoryError,
+};
+
+
+/// Copies a directory from the source to the target directory path,
+/// without blocking the async runtime.
+///
+/// See [`directory::copy_directory`][crate::directory::copy_directory] for more details.
+pub async fn copy_directory<S, T>(
+    source_directory_path: S,
+    target_directory_path: T,
+    options: DirectoryCopyOptions,
+) -> Result<FinishedDirectoryCopy, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    let source_directory_path = source_directory_path.as_ref().to_path_buf();
+    let target_directory_path = target_directory_path.as_ref().to_path_buf();
+
+    run_blocking(move || {
+        crate::directory::copy_directory(
+            source_directory_path,
+            target_directory_path,
+            options,
+        )
+    })
+    .await
+}
+
+
+/// Copies a directory from the source to the target directory path, with progress reporting,
+/// without blocking the async runtime.
+///
+/// The progress handler is called on the blocking thread the copy runs on
+/// (see the [module-level documentation][super] for how to consume progress from async code).
+///
+/// See [`directory::copy_directory_with_progress`][crate::directory::copy_directory_with_progress]
+/// for more details.
+pub async fn copy_directory_with_progress<S, T, F>(
+    source_directory_path: S,
+    target_directory_path: T,
+    options: DirectoryCopyWithProgressOptions,
+    progress_handler: F,
+) -> Result<FinishedDirectoryCopy, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+    F: FnMut(&DirectoryCopyProgress) + Send + 'static,
+{
+    let source_directory_path = source_directory_path.as_ref().to_path_buf();
+    let target_directory_path = target_directory_path.as_ref().to_path_buf();
+
+    run_blocking(move || {
+        crate::directory::copy_directory_with_progress(
+            source_directory_path,
+            target_directory_path,
+            options,
+            progress_handler,
+        )
+    })
+    .await
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use super::run_blocking;
+use crate::{
+    error::FileError,
+    file::{
+        FileCopyOptions,
+        FileCopyWithProgressOptions,
+        FileMoveOptions,
+        FileMoveWithProgressOptions,
+        FileProgress,
+    },
+};
+
+
+/// Copies a single file from the source to the target path,
+/// without blocking the async runtime.
+///
+/// See [`file::copy_file`][crate::file::copy_file] for more details.
+pub async fn copy_file<P, T>(
+    source_file_path: P,
+    target_file_path: T,
+    options: FileCopyOptions,
+) -> Result<u64, FileError>
+where
+    P: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    let source_file_path = source_file_path.as_ref().to_path_buf();
+    let target_file_path = target_file_path.as_ref().to_path_buf();
+
+    run_blocking(move || crate::file::copy_file(source_file_path, target_file_path, options)).await
+}
+
+
+/// Copies a single file from the source to the target path, with progress reporting,
+/// without blocking the async runtime.
+///
+/// The progress handler is called on the blocking thread the copy runs on
+/// (see the [module-level documentation][super] for how to consume progress from async code).
+///
+/// See [`file::copy_file_with_progress`][crate::file::copy_file_with_progress] for more details.
+pub async fn copy_file_with_progress<P, T, F>(
+    source_file_path: P,
+    target_file_path: T,
+    options: FileCopyWithProgressOptions,
+    progress_handler: F,
+) -> Result<u64, FileError>
+where
+    P: AsRef<Path>,
+    T: AsRef<Path>,
+    F: FnMut(&FileProgress) + Send + 'static,
+{
+    let source_file_path = source_file_path.as_ref().to_path_buf();
+    let target_file_path = target_file_path.as_ref().to_path_buf();
+
+    run_blocking(move || {
+        crate::file::copy_file_with_progress(
+            source_file_path,
+            target_file_path,
+            options,
+            progress_handler,
+        )
+    })
+    .await
+}
+
+
+/// Moves a single file from the source to the target path,
+/// without blocking the async runtime.
+///
+/// See [`file::move_file`][crate::file::move_file] for more details.
+pub async fn move_file<P, T>(
+    source_file_path: P,
+    target_file_path: T,
+    options: FileMoveOptions,
+) -> Result<u64, FileError>
+where
+    P: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    let source_file_path = source_file_path.as_ref().to_path_buf();
+    let target_file_path = target_file_path.as_ref().to_path_buf();
+
+    run_blocking(move || crate::file::move_file(source_file_path, target_file_path, options)).await
+}
+
+
+/// Moves a single file from the source to the target path, with progress reporting,
+/// without blocking the async runtime.
+///
+/// The progress handler is called on the blocking thread the move runs on
+/// (see the [module-level documentation][super] for how to consume progress from async code).
+///
+/// See [`file::move_file_with_progress`][crate::file::move_file_with_progress] for more details.
+pub async fn move_file_with_progress<P, T, F>(
+    source_file_path: P,
+    target_file_path: T,
+    options: FileMoveWithProgressOptions,
+    progress_handler: F,
+) -> Result<u64, FileError>
+where
+    P: AsRef<Path>,
+    T: AsRef<Path>,
+    F: FnMut(&FileProgress) + Send + 'static,
+{
+    let source_file_path = source_file_path.as_ref().to_path_buf();
+    let target_file_path = target_file_path.as_ref().to_path_buf();
+
+    run_blocking(move || {
+        crate::file::move_file_with_progress(
+            source_file_path,
+            target_file_path,
+            options,
+            progress_handler,
+        )
+    })
+    .await
+}
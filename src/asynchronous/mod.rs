@@ -0,0 +1,48 @@
+//! Asynchronous variants of the file and directory copy and move operations.
+//!
+//! Each function here runs its synchronous counterpart on [`tokio`]'s blocking thread pool
+//! (see [`tokio::task::spawn_blocking`]), which means awaiting it doesn't stall the async runtime.
+//! The functions must therefore be called from within a Tokio runtime.
+//!
+//! ## Progress
+//! Progress handlers are called on the blocking thread the operation runs on,
+//! so they must be `Send + 'static`, and they should return quickly.
+//! To consume progress from async code, forward it into a channel, e.g.:
+//!
+//! ```ignore
+//! let (progress_sender, mut progress_receiver) = tokio::sync::mpsc::unbounded_channel();
+//!
+//! let copy = fs_more::asynchronous::copy_file_with_progress(
+//!     "source.bin",
+//!     "target.bin",
+//!     Default::default(),
+//!     move |progress| {
+//!         let _ = progress_sender.send(progress.clone());
+//!     },
+//! );
+//! ```
+//!
+//! This module is only available with the `async` feature enabled.
+
+mod directory;
+mod file;
+
+pub use directory::*;
+pub use file::*;
+
+
+/// Runs `operation` on Tokio's blocking thread pool and waits for it to finish.
+///
+/// If `operation` panics, the panic is resumed on the awaiting task.
+async fn run_blocking<F, R>(operation: F) -> R
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    match tokio::task::spawn_blocking(operation).await {
+        Ok(result) => result,
+        Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+        // Blocking tasks are only cancelled if the runtime is shutting down before they start.
+        Err(_) => panic!("the blocking task was cancelled because the runtime is shutting down"),
+    }
+}
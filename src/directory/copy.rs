@@ -1,20 +1,50 @@
 #[cfg(not(feature = "fs-err"))]
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashSet,
+    fs::Metadata,
+    ops::ControlFlow,
+    path::{Component, Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
 
 #[cfg(feature = "fs-err")]
 use fs_err as fs;
 
-use super::scan::is_directory_empty_unchecked;
+use super::{
+    junction::{create_junction, is_junction, read_junction_destination},
+    remove::remove_walked_entries_in_reverse,
+    scan::{is_directory_empty_unchecked, read_directory_items, TraversalOrder},
+    walk,
+    WalkEntry,
+    WalkOptions,
+};
 use crate::{
-    error::{DirectoryError, FileError},
+    error::{
+        is_locked_file_error,
+        is_read_only_filesystem_error,
+        DirectoryError,
+        FileError,
+        IoOperation,
+    },
     file::{
+        compute_file_checksum,
         copy_file,
+        copy_file_with_checksum,
         copy_file_with_progress,
+        copy_ownership,
+        BufferStrategy,
+        ChecksumAlgorithm,
+        CopyMethod,
         FileCopyOptions,
         FileCopyWithProgressOptions,
         FileProgress,
+        SparseMode,
+        DEFAULT_COPY_BUFFER_SIZE,
     },
+    path::{find_non_directory_ancestor, relative_path_from, resolve_through_existing_ancestor},
 };
 
 /// Ensures the given source directory path is valid.
@@ -23,7 +53,7 @@ use crate::{
 /// - is a directory.
 ///
 /// The returned path is a canonicalized version of the provided path.
-pub(super) fn validate_source_directory_path(
+pub(crate) fn validate_source_directory_path(
     source_directory_path: &Path,
 ) -> Result<PathBuf, DirectoryError> {
     // Ensure the source directory path exists. We use `try_exists`
@@ -65,10 +95,14 @@ pub(crate) struct ValidatedTargetPath {
 /// The returned tuple consists of:
 /// - a `PathBuf`, which is a cleaned `target_directory_path` (with the `path_clean` library), and,
 /// - a `bool`, which indicates whether the directory needs to be created.
-pub(super) fn validate_target_directory_path(
+pub(crate) fn validate_target_directory_path(
     target_directory_path: &Path,
     target_directory_rules: &TargetDirectoryRule,
 ) -> Result<ValidatedTargetPath, DirectoryError> {
+    if let Some(path) = find_non_directory_ancestor(target_directory_path) {
+        return Err(DirectoryError::TargetParentNotADirectory { path });
+    }
+
     let target_directory_exists = target_directory_path
         .try_exists()
         .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
@@ -94,10 +128,8 @@ pub(super) fn validate_target_directory_path(
                 path: target_directory_path.to_path_buf(),
             });
         }
-        TargetDirectoryRule::AllowEmpty if target_directory_exists => {
-            if !is_empty.unwrap_or(true) {
-                return Err(DirectoryError::TargetDirectoryIsNotEmpty);
-            }
+        TargetDirectoryRule::AllowEmpty if target_directory_exists && !is_empty.unwrap_or(true) => {
+            return Err(DirectoryError::TargetDirectoryIsNotEmpty);
         }
         _ => {}
     };
@@ -111,18 +143,48 @@ pub(super) fn validate_target_directory_path(
     })
 }
 
-pub(super) fn validate_source_target_directory_pair(
+/// Ensures the target directory path isn't equal to or inside the (canonicalized) source directory path.
+///
+/// The target path is resolved through symbolic links first, so a target that is
+/// (or is inside) a symbolic link pointing into the source directory is rejected as well.
+pub(crate) fn validate_source_target_directory_pair(
     source_directory_path: &Path,
     target_directory_path: &Path,
 ) -> Result<(), DirectoryError> {
+    let resolved_target_directory_path =
+        resolve_through_existing_ancestor(target_directory_path)
+            .map_err(|error| DirectoryError::UnableToAccessTarget { error })?;
+
     // Ensure `target_directory_path` isn't equal or a subdirectory of `source_directory_path`˙.
-    if target_directory_path.starts_with(source_directory_path) {
+    if resolved_target_directory_path.starts_with(source_directory_path) {
         return Err(DirectoryError::InvalidTargetDirectoryPath);
     }
 
     Ok(())
 }
 
+/// Returns the directory path the source directory's contents should be copied into.
+///
+/// This is `target_directory_path` itself, unless `copy_into_subdirectory` is `true`,
+/// in which case the name of the source directory is appended to it
+/// (e.g. copying `/foo/bar` into `/baz` means copying into `/baz/bar`).
+fn resolve_target_directory_path(
+    source_directory_path: &Path,
+    target_directory_path: &Path,
+    copy_into_subdirectory: bool,
+) -> Result<PathBuf, DirectoryError> {
+    if !copy_into_subdirectory {
+        return Ok(target_directory_path.to_path_buf());
+    }
+
+    // The source directory path is canonicalized, so this can only fail for root directories.
+    let source_directory_name = source_directory_path
+        .file_name()
+        .ok_or(DirectoryError::InvalidTargetDirectoryPath)?;
+
+    Ok(target_directory_path.join(source_directory_name))
+}
+
 /// Specifies whether you allow the target directory to exist
 /// before copying or moving files or directories into it.
 ///
@@ -158,13 +220,24 @@ pub(super) fn validate_source_target_directory_pair(
 ///     overwrite_existing_files: true,
 /// };
 /// ```
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+///
+/// If you want to merge the source into an existing target directory, keeping the target's files
+/// where both contain the same file (instead of returning an error), use [`TargetDirectoryRule::Merge`]:
+/// ```rust
+/// # use fs_more::directory::TargetDirectoryRule;
+/// let rules = TargetDirectoryRule::Merge {
+///     overwrite_existing_files: false,
+/// };
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum TargetDirectoryRule {
     /// Indicates the associated function should return an error if the target directory already exists.
     DisallowExisting,
 
     /// Indicates the associated function should return an error if the target directory
     /// exists *and is not empty*.
+    #[default]
     AllowEmpty,
 
     /// Indicates that an existing non-empty target directory should not cause an error.
@@ -179,12 +252,20 @@ pub enum TargetDirectoryRule {
         /// if a target file we would otherwise freshly create and copy into already exists.
         overwrite_existing_files: bool,
     },
-}
 
-impl Default for TargetDirectoryRule {
-    fn default() -> Self {
-        Self::AllowEmpty
-    }
+    /// Indicates the source should be merged into the target directory, which may exist and be non-empty.
+    ///
+    /// Existing subdirectories are merged into as well. Unlike with
+    /// [`AllowNonEmpty`][TargetDirectoryRule::AllowNonEmpty], existing target files
+    /// never cause an error: they are either overwritten or left as they are.
+    ///
+    /// When moving a directory, source files that were left out because of an existing
+    /// target file are not removed from the source directory.
+    Merge {
+        /// If enabled, existing target files are overwritten with the corresponding source files.
+        /// Otherwise, they are left as they are and the corresponding source files are skipped.
+        overwrite_existing_files: bool,
+    },
 }
 
 impl TargetDirectoryRule {
@@ -194,6 +275,41 @@ impl TargetDirectoryRule {
         !matches!(self, Self::DisallowExisting)
     }
 
+    /// Indicates whether this rule allows the target directory
+    /// to exist *and be non-empty* before performing an operation.
+    pub fn allows_non_empty_target_directory(&self) -> bool {
+        matches!(
+            self,
+            Self::AllowNonEmpty { .. } | Self::Merge { .. }
+        )
+    }
+
+    /// Indicates whether this rule allows a target directory in the given state,
+    /// i.e. whether the associated function would accept the target directory
+    /// (before checking for collisions between individual files and subdirectories).
+    ///
+    /// `is_empty` is only relevant if `exists` is `true`.
+    ///
+    /// ```rust
+    /// # use fs_more::directory::TargetDirectoryRule;
+    /// let rule = TargetDirectoryRule::AllowEmpty;
+    ///
+    /// assert!(rule.allows_target_directory_state(false, true));
+    /// assert!(rule.allows_target_directory_state(true, true));
+    /// assert!(!rule.allows_target_directory_state(true, false));
+    /// ```
+    pub fn allows_target_directory_state(&self, exists: bool, is_empty: bool) -> bool {
+        if !exists {
+            return true;
+        }
+
+        if is_empty {
+            self.allows_existing_target_directory()
+        } else {
+            self.allows_non_empty_target_directory()
+        }
+    }
+
     /// Indicates whether this rule allows existing files
     /// in the target directory to be overwritten with contents of the source.
     pub fn should_overwrite_existing_files(&self) -> bool {
@@ -204,9 +320,24 @@ impl TargetDirectoryRule {
                 overwrite_existing_files,
                 ..
             } => *overwrite_existing_files,
+            TargetDirectoryRule::Merge {
+                overwrite_existing_files,
+            } => *overwrite_existing_files,
         }
     }
 
+    /// Indicates whether this rule requires existing files in the target directory
+    /// to be left as they are, skipping the corresponding source files instead of returning an error
+    /// (see [`TargetDirectoryRule::Merge`]).
+    pub fn should_skip_existing_files(&self) -> bool {
+        matches!(
+            self,
+            TargetDirectoryRule::Merge {
+                overwrite_existing_files: false
+            }
+        )
+    }
+
     /// Indicates whether this rule allows existing (sub)directories
     /// in the target directory to be "overwritten" with contents of the source (sub)directory.
     pub fn should_overwrite_existing_directories(&self) -> bool {
@@ -217,250 +348,1842 @@ impl TargetDirectoryRule {
                 overwrite_existing_subdirectories,
                 ..
             } => *overwrite_existing_subdirectories,
+            TargetDirectoryRule::Merge { .. } => true,
         }
     }
 }
 
 
-/// Options that influence the [`copy_directory`] function.
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct DirectoryCopyOptions {
-    /// Specifies whether you allow the target directory to exist before copying
-    /// and whether it must be empty or not.
-    ///
-    /// If you allow a non-empty target directory, you may also specify whether you allow
-    /// target files or subdirectories to already exist (and be overwritten).
-    ///
-    /// See [`TargetDirectoryRule`] for more details and examples.
-    pub target_directory_rule: TargetDirectoryRule,
-
-    /// Maximum depth of the source directory to copy.
+/// Specifies how special files (FIFOs, sockets, block and character devices)
+/// in the source directory should be handled when copying.
+///
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`SpecialFileBehaviour::Skip`].
+///
+/// ## Platform support
+/// Special files are a Unix concept. On other platforms, [`SpecialFileBehaviour::Recreate`]
+/// behaves the same as [`SpecialFileBehaviour::Skip`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SpecialFileBehaviour {
+    /// Indicates the associated function should return
+    /// `Err(`[`DirectoryError::SourceContainsSpecialFile`][crate::error::DirectoryError::SourceContainsSpecialFile]`)`
+    /// if a special file is found in the source directory.
+    Error,
+
+    /// Indicates special files should be ignored (i.e. not copied).
+    #[default]
+    Skip,
+
+    /// Indicates special files should be recreated on the target
+    /// (using `mkfifo` for FIFOs and `mknod` for sockets and device nodes)
+    /// instead of having their "contents" copied.
     ///
-    /// - `None` indicates no limit.
-    /// - `Some(0)` means a directory copy operation that copies only the files and
-    ///   creates directories found directly in the root directory, ignoring any subdirectories.
-    /// - `Some(1)` includes the root directory's contents and one level of its subdirectories.
-    pub maximum_copy_depth: Option<usize>,
+    /// Note that recreating device nodes usually requires elevated privileges.
+    Recreate,
 }
 
-#[allow(clippy::derivable_impls)]
-impl Default for DirectoryCopyOptions {
-    fn default() -> Self {
-        Self {
-            target_directory_rule: TargetDirectoryRule::default(),
-            maximum_copy_depth: None,
-        }
-    }
+
+/// Specifies how broken symbolic links (i.e. symbolic links whose destination does not exist)
+/// in the source directory should be handled when copying.
+///
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`BrokenSymlinkBehaviour::Error`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum BrokenSymlinkBehaviour {
+    /// Indicates the associated function should return
+    /// `Err(`[`DirectoryError::SourceContainsBrokenSymlink`][crate::error::DirectoryError::SourceContainsBrokenSymlink]`)`
+    /// if a broken symbolic link is found in the source directory.
+    #[default]
+    Error,
+
+    /// Indicates broken symbolic links should be ignored (i.e. not copied).
+    Skip,
+
+    /// Indicates broken symbolic links should be recreated on the target verbatim,
+    /// i.e. as symbolic links pointing to the same (missing) destination.
+    CopyAsSymlink,
 }
 
 
-/// Given a source root path, a target root path and the source path to rejoin,
-/// this function takes the `source_path_to_rejoin`, removes the prefix provided by `source_root_path`
-/// and repplies that relative path back onto the `target_root_path`.
+/// Specifies how directory junctions in the source directory should be handled when copying.
 ///
-/// Returns a [`DirectoryError::SubdirectoryEscapesRoot`] if the `source_path_to_rejoin`
-/// is not a subpath of `source_root_path`.
+/// Junctions (NTFS mount points) are a Windows-only kind of link to a directory, distinct from
+/// directory symbolic links. Installers commonly create them, sometimes pointing back up the tree,
+/// in which case following them copies the same contents over and over.
 ///
-/// ## Example
-/// ```ignore
-/// # use std::path::Path;
-/// # use fs_more::directory::copy::rejoin_source_subpath_onto_target;
+/// This has no effect on platforms other than Windows.
 ///
-/// let root_a = Path::new("/hello/there");
-/// let foo = Path::new("/hello/there/some/content");
-/// let root_b = Path::new("/different/root");
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`JunctionBehaviour::Follow`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum JunctionBehaviour {
+    /// Indicates junctions should be handled like symbolic links to directories,
+    /// i.e. followed and copied as regular directories along with their contents.
+    #[default]
+    Follow,
+
+    /// Indicates junctions should be recreated on the target as junctions
+    /// pointing to the same (absolute) destination, without copying their contents.
+    Recreate,
+
+    /// Indicates junctions should be ignored (i.e. neither followed nor recreated).
+    Skip,
+}
+
+
+/// Specifies how the destinations of symbolic links are written when they are recreated on the target
+/// (see [`DirectoryCopyOptions::follow_root_symlink_only`] and [`BrokenSymlinkBehaviour::CopyAsSymlink`]).
 ///
-/// assert_eq!(
-///     rejoin_source_subpath_onto_target(
-///         root_a,
-///         foo,
-///         root_b
-///     ).unwrap(),
-///     Path::new("/different/root/some/content")
-/// );
-/// ```
-pub(crate) fn rejoin_source_subpath_onto_target(
-    source_root_path: &Path,
-    source_path_to_rejoin: &Path,
-    target_root_path: &Path,
-) -> Result<PathBuf, DirectoryError> {
-    // Strip the source subdirectory path from the full source path
-    // and place it on top of the target directory path.
-    let source_relative_subdirectory_path = if source_root_path.eq(source_path_to_rejoin) {
-        Path::new("")
-    } else {
-        source_path_to_rejoin
-            .strip_prefix(source_root_path)
-            .map_err(|_| DirectoryError::OtherReason {
-                reason: String::from("provided source path escapes its source root"),
-            })?
-    };
+/// Absolute destinations are always kept as they are.
+///
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`SymlinkRewrite::Verbatim`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SymlinkRewrite {
+    /// Indicates relative destinations should be recreated exactly as they are.
+    ///
+    /// Links that point to something inside the copied directory keep working,
+    /// as the directory structure is preserved, but links that lead outside of it
+    /// (e.g. `../../shared`) will now be resolved relative to the target directory.
+    #[default]
+    Verbatim,
+
+    /// Indicates relative destinations that lead outside of the source directory
+    /// should be rewritten so that they still point to the same location from the link's new place.
+    ///
+    /// The rewritten destination is relative as well, unless there is no relative path
+    /// between the two locations (e.g. on different drives on Windows), in which case it is absolute.
+    /// Relative destinations that stay inside the source directory are kept as they are.
+    RewriteRelative,
+}
 
-    Ok(target_root_path.join(source_relative_subdirectory_path))
+
+/// Specifies what to do when a file can't be copied because another process has it open or locked.
+///
+/// This happens on Windows, where a file opened without read sharing (or a locked byte range)
+/// can't be read by anyone else, e.g. a database or log file that is in use.
+/// File locks are only advisory on other platforms, so this has no effect there.
+///
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`LockedFileBehaviour::Error`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum LockedFileBehaviour {
+    /// Indicates a locked file should abort the copy with an error.
+    #[default]
+    Error,
+
+    /// Indicates locked files should be ignored (i.e. not copied).
+    /// The rest of the directory is still copied.
+    Skip,
+
+    /// Indicates copying a locked file should be attempted again up to `retries` times,
+    /// waiting `delay` before each new attempt. If the file is still locked after that,
+    /// the copy is aborted with an error.
+    Retry { retries: u32, delay: Duration },
 }
 
 
-/// Describes actions taken by the [`copy_directory`] function.
+/// How the copied files are compared against their source files
+/// after a directory copy, see [`DirectoryCopyOptions::verify_after_copy`].
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct FinishedDirectoryCopy {
-    /// Total amount of bytes copied.
-    pub total_bytes_copied: u64,
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum VerifyMode {
+    /// Only the sizes of the source and target files are compared.
+    /// This is fast, but won't catch corrupted contents.
+    SizeOnly,
+
+    /// The checksums of the source and target files are compared,
+    /// which means every file is read again in its entirety (on both sides).
+    ContentHash(ChecksumAlgorithm),
+}
 
-    /// Number of files copied when copying the directory.
-    pub num_files_copied: usize,
 
-    /// Number of directories created when copying the directory.
-    pub num_directories_created: usize,
+/// What will happen to the target path of a [`PlannedOperation`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PlannedTargetAction {
+    /// The target path does not exist yet and will be created.
+    Create,
+
+    /// The target path already exists and will be overwritten
+    /// (or, in the case of a directory, copied into).
+    Overwrite,
+
+    /// The target path already exists and will be left as it is,
+    /// see [`TargetDirectoryRule::Merge`].
+    Skip,
 }
 
 
-/// Represents a file copy or directory creation operation.
-///
-/// For more details, see the [`build_directory_copy_queue`] function.
-#[derive(Clone, Debug)]
-enum QueuedOperation {
+/// A single planned operation of a directory copy, see [`CopyPlan`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PlannedOperation {
+    /// A file will be copied.
     CopyFile {
+        /// Path of the source file.
         source_file_path: PathBuf,
-        source_size_bytes: u64,
+
+        /// Path the file will be copied to.
         target_file_path: PathBuf,
+
+        /// Size of the source file in bytes.
+        size_in_bytes: u64,
+
+        /// What will happen to the target path.
+        target_action: PlannedTargetAction,
     },
+
+    /// A directory will be created.
     CreateDirectory {
-        source_size_bytes: u64,
+        /// Path of the directory that will be created.
         target_directory_path: PathBuf,
+
+        /// What will happen to the target path.
+        target_action: PlannedTargetAction,
+    },
+
+    /// A special file will be recreated, see [`SpecialFileBehaviour::Recreate`].
+    RecreateSpecialFile {
+        /// Path of the source special file.
+        source_file_path: PathBuf,
+
+        /// Path the special file will be recreated at.
+        target_file_path: PathBuf,
+
+        /// What will happen to the target path.
+        target_action: PlannedTargetAction,
+    },
+
+    /// A broken symbolic link will be recreated, see [`BrokenSymlinkBehaviour::CopyAsSymlink`].
+    RecreateBrokenSymlink {
+        /// The (missing) destination of the symbolic link.
+        link_destination_path: PathBuf,
+
+        /// Path the symbolic link will be recreated at.
+        target_file_path: PathBuf,
+
+        /// What will happen to the target path.
+        target_action: PlannedTargetAction,
+    },
+
+    /// A symbolic link will be recreated as it is,
+    /// see [`DirectoryCopyOptions::follow_root_symlink_only`].
+    RecreateSymlink {
+        /// The destination of the symbolic link, exactly as stored in the source link.
+        link_destination_path: PathBuf,
+
+        /// Path the symbolic link will be recreated at.
+        target_file_path: PathBuf,
+
+        /// What will happen to the target path.
+        target_action: PlannedTargetAction,
+    },
+
+    /// A directory junction will be recreated, see [`JunctionBehaviour::Recreate`].
+    RecreateJunction {
+        /// The (absolute) destination of the junction.
+        junction_destination_path: PathBuf,
+
+        /// Path the junction will be recreated at.
+        target_file_path: PathBuf,
+
+        /// What will happen to the target path.
+        target_action: PlannedTargetAction,
     },
 }
 
 
-/// Given a source and target directory as well as, optionally, a maximum copy depth,
-/// this function builds a list of [`QueuedOperation`]s that are needed to fully,
-/// or up to the `maximum_depth` limit, copy the source directory to the target directory.
-///
-/// The order of directory creation and file copying operations is such that
-/// for any file in the list, its directory has previously been created
-/// (its creation appears before it in the queue).
-///
-/// Note, however, that **the queued operations do not include creation of the `target_directory_root_path`
-/// directory itself**, even if that is necessary in your case.
-fn build_directory_copy_queue<S, T>(
-    source_directory_root_path: S,
-    target_directory_root_path: T,
-    maximum_depth: Option<usize>,
-) -> Result<Vec<QueuedOperation>, DirectoryError>
-where
-    S: Into<PathBuf>,
-    T: Into<PathBuf>,
-{
-    let source_directory_root_path = source_directory_root_path.into();
-    let target_directory_root_path = target_directory_root_path.into();
+/// The complete list of operations a directory copy is about to perform,
+/// see [`copy_directory_with_plan`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CopyPlan {
+    /// Path of the source directory.
+    pub source_directory_path: PathBuf,
 
-    let mut operation_queue: Vec<QueuedOperation> = Vec::new();
+    /// Path of the target directory.
+    pub target_directory_path: PathBuf,
 
+    /// Planned operations, in the order they will be performed.
+    ///
+    /// The creation of the target directory itself is not included.
+    pub operations: Vec<PlannedOperation>,
 
-    // Scan the source directory and queue all copy and
-    // directory create operations that need to happen.
-    struct PendingDirectoryScan {
-        source_directory_path: PathBuf,
-        depth: usize,
-    }
+    /// Total number of bytes that will be copied
+    /// (i.e. excluding files whose target will be skipped).
+    pub total_bytes_to_copy: u64,
 
-    let mut directory_scan_queue = Vec::new();
-    directory_scan_queue.push(PendingDirectoryScan {
-        source_directory_path: source_directory_root_path.clone(),
-        depth: 0,
-    });
+    /// Entries of the target directory that would be deleted after copying, because they don't correspond
+    /// to any source entry (see [`DirectoryCopyOptions::delete_extraneous_from_target`]),
+    /// in the order they would be deleted in.
+    ///
+    /// This is empty unless [`DirectoryCopyOptions::delete_extraneous_from_target`] is enabled.
+    pub extraneous_target_paths: Vec<PathBuf>,
+}
 
-    // Perform directory scans using a queue.
-    while let Some(next_directory) = directory_scan_queue.pop() {
-        // Scan the directory for its files and directories.
-        // Files are queued for copying, directories are queued for creation.
-        let directory_iterator = fs::read_dir(&next_directory.source_directory_path)
-            .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
 
-        for directory_item in directory_iterator {
-            let directory_item =
-                directory_item.map_err(|error| DirectoryError::UnableToAccessSource { error })?;
+/// How a source file compares to the target file it would be merged into, see [`PlannedMergeFile`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum MergeFileReason {
+    /// The target file does not exist yet.
+    New,
+
+    /// The target file exists, but differs from the source file,
+    /// which was modified more recently than the target file.
+    SourceIsNewer,
+
+    /// The target file exists, but differs from the source file,
+    /// and was modified more recently than the source file.
+    TargetIsNewer,
+
+    /// The target file exists and differs from the source file, but their modification times
+    /// are the same (or aren't available on this platform).
+    Different,
+
+    /// The target file exists and is identical to the source file: they have the same size and
+    /// either the same modification time or the same contents.
+    Identical,
+}
 
-            let directory_item_source_path = directory_item.path();
-            let directory_item_target_path = rejoin_source_subpath_onto_target(
-                &source_directory_root_path,
-                &directory_item_source_path,
-                &target_directory_root_path,
-            )?;
 
-            let item_type = directory_item
-                .file_type()
-                .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
+/// A single file of a [`MergePlan`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PlannedMergeFile {
+    /// Path of the source file.
+    pub source_file_path: PathBuf,
 
-            if item_type.is_file() {
-                let file_metadata = directory_item
-                    .metadata()
-                    .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
+    /// Path the file would be copied to.
+    pub target_file_path: PathBuf,
 
-                let file_size_in_bytes = file_metadata.len();
+    /// Size of the source file in bytes.
+    pub size_in_bytes: u64,
 
-                operation_queue.push(QueuedOperation::CopyFile {
-                    source_file_path: directory_item_source_path,
-                    source_size_bytes: file_size_in_bytes,
-                    target_file_path: directory_item_target_path,
-                });
-            } else if item_type.is_dir() {
-                let directory_metadata = directory_item
-                    .metadata()
-                    .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
+    /// What would happen to the target file.
+    pub target_action: PlannedTargetAction,
 
-                // Note that this is the size of the directory itself, not of its contents.
-                let directory_size_in_bytes = directory_metadata.len();
+    /// How the source file compares to the target file.
+    pub reason: MergeFileReason,
+}
 
-                operation_queue.push(QueuedOperation::CreateDirectory {
-                    source_size_bytes: directory_size_in_bytes,
-                    target_directory_path: directory_item_target_path,
-                });
 
-                // If we haven't reached the maximum depth yet, we queue the directory for scanning.
-                if let Some(maximum_depth) = maximum_depth {
-                    if next_directory.depth < maximum_depth {
-                        directory_scan_queue.push(PendingDirectoryScan {
-                            source_directory_path: directory_item_source_path,
-                            depth: next_directory.depth + 1,
-                        });
-                    }
-                } else {
-                    directory_scan_queue.push(PendingDirectoryScan {
-                        source_directory_path: directory_item_source_path,
-                        depth: next_directory.depth + 1,
-                    });
-                }
-            } else if item_type.is_symlink() {
-                // If the path is a symbolic link, we need to follow it and queue a copy from the destination file.
-                // Can point to either a directory or a file.
+/// The changes a directory copy (usually one with the [`TargetDirectoryRule::Merge`] rule)
+/// would make to the target directory, see [`plan_merge`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MergePlan {
+    /// Path of the source directory.
+    pub source_directory_path: PathBuf,
 
-                // Now we should retrieve the metadata of the target of the symbolic link
-                // (unlike DirEntry::metadata, this metadata call *does* follow symolic links).
-                let underlying_path = fs::canonicalize(&directory_item_source_path)
-                    .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
+    /// Path of the target directory.
+    pub target_directory_path: PathBuf,
 
-                let underlying_item_metadata = fs::metadata(&underlying_path)
-                    .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
+    /// Files of the source directory, in the order they would be copied.
+    pub files: Vec<PlannedMergeFile>,
 
-                if underlying_item_metadata.is_file() {
-                    let underlying_file_size_in_bytes = underlying_item_metadata.len();
+    /// Directories that don't exist in the target directory yet and would be created.
+    /// The target directory itself is not included.
+    pub directories_to_create: Vec<PathBuf>,
 
-                    operation_queue.push(QueuedOperation::CopyFile {
-                        source_file_path: underlying_path,
-                        source_size_bytes: underlying_file_size_in_bytes,
-                        target_file_path: directory_item_target_path,
-                    });
-                } else if underlying_item_metadata.is_dir() {
-                    // Note that this is the size of the directory itself, not of its contents.
-                    let underlying_directory_size_in_bytes = underlying_item_metadata.len();
+    /// Entries of the target directory that would be deleted after copying, because they don't correspond
+    /// to any source entry (see [`DirectoryCopyOptions::delete_extraneous_from_target`]),
+    /// in the order they would be deleted in.
+    ///
+    /// This is empty unless [`DirectoryCopyOptions::delete_extraneous_from_target`] is enabled.
+    pub extraneous_target_paths: Vec<PathBuf>,
+}
 
-                    operation_queue.push(QueuedOperation::CreateDirectory {
-                        source_size_bytes: underlying_directory_size_in_bytes,
-                        target_directory_path: directory_item_target_path,
-                    });
+impl MergePlan {
+    /// Returns the files whose target doesn't exist yet and would be created.
+    pub fn files_to_create(&self) -> impl Iterator<Item = &PlannedMergeFile> {
+        self.files_with_target_action(PlannedTargetAction::Create)
+    }
 
-                    // If we haven't reached the maximum depth yet, we queue the directory for scanning.
-                    if let Some(maximum_depth) = maximum_depth {
+    /// Returns the files whose existing target would be overwritten.
+    pub fn files_to_overwrite(&self) -> impl Iterator<Item = &PlannedMergeFile> {
+        self.files_with_target_action(PlannedTargetAction::Overwrite)
+    }
+
+    /// Returns the files whose existing target would be left as it is.
+    pub fn files_to_skip(&self) -> impl Iterator<Item = &PlannedMergeFile> {
+        self.files_with_target_action(PlannedTargetAction::Skip)
+    }
+
+    fn files_with_target_action(
+        &self,
+        target_action: PlannedTargetAction,
+    ) -> impl Iterator<Item = &PlannedMergeFile> {
+        self.files
+            .iter()
+            .filter(move |file| file.target_action == target_action)
+    }
+}
+
+
+/// Options that influence the [`copy_directory`] function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DirectoryCopyOptions {
+    /// Specifies whether you allow the target directory to exist before copying
+    /// and whether it must be empty or not.
+    ///
+    /// If you allow a non-empty target directory, you may also specify whether you allow
+    /// target files or subdirectories to already exist (and be overwritten).
+    ///
+    /// See [`TargetDirectoryRule`] for more details and examples.
+    pub target_directory_rule: TargetDirectoryRule,
+
+    /// Maximum depth of the source directory to copy.
+    ///
+    /// The depth of an entry is the number of path components it is below the source directory,
+    /// i.e. entries directly in the source directory are at depth `1` (as with `find -maxdepth`).
+    ///
+    /// - `None` indicates no limit.
+    /// - `Some(0)` means that only the target directory itself is created; nothing inside the source
+    ///   directory is copied.
+    /// - `Some(1)` means a directory copy operation that copies only the files and
+    ///   creates directories found directly in the root directory, ignoring any subdirectories.
+    /// - `Some(2)` includes the root directory's contents and one level of its subdirectories.
+    ///
+    /// Directories at the maximum depth are created empty, i.e. their contents are not copied.
+    /// This counts one level more than
+    /// [`DirectoryScanOptions::maximum_scan_depth`][crate::directory::DirectoryScanOptions::maximum_scan_depth]
+    /// (a copy with a maximum depth of `Some(n + 1)` covers the entries a scan with `Some(n)` finds),
+    /// and a depth larger than that of the source tree is the same as `None`.
+    ///
+    /// If both this and [`minimum_copy_depth`][Self::minimum_copy_depth] are set,
+    /// the minimum must be smaller than the maximum, otherwise the copy returns
+    /// `Err(`[`DirectoryError::InvalidCopyDepthRange`]`)` before doing anything.
+    pub maximum_copy_depth: Option<usize>,
+
+    /// Minimum depth of the source directory to copy.
+    ///
+    /// Files at this depth or shallower are not copied, with depth counted
+    /// as in [`maximum_copy_depth`][Self::maximum_copy_depth].
+    ///
+    /// - `None` indicates no limit.
+    /// - `Some(1)` means files found directly in the root directory are not copied.
+    /// - `Some(2)` additionally skips files found directly in the root directory's subdirectories, and so on.
+    ///
+    /// Directories at this depth or shallower are still created
+    /// in the target directory, as deeper files keep their relative paths.
+    pub minimum_copy_depth: Option<usize>,
+
+    /// Specifies how special files (FIFOs, sockets and device nodes) are handled.
+    ///
+    /// See [`SpecialFileBehaviour`] for more details.
+    pub special_file_behaviour: SpecialFileBehaviour,
+
+    /// Specifies how broken symbolic links are handled.
+    ///
+    /// See [`BrokenSymlinkBehaviour`] for more details.
+    pub broken_symlink_behaviour: BrokenSymlinkBehaviour,
+
+    /// Whether to scan the entire source directory before copying anything.
+    ///
+    /// If `true`, all target collisions are checked for up front, meaning that
+    /// if there is a collision, the function returns an error before copying anything.
+    ///
+    /// If `false`, entries are copied as they are discovered during a single traversal
+    /// and collisions are checked for lazily, right before each entry is copied.
+    /// This makes the copy start sooner on large directory trees, but **a late collision will
+    /// leave the files and directories copied before it in place**.
+    ///
+    /// Note that enabling [`ensure_free_space`][Self::ensure_free_space],
+    /// [`ensure_free_inodes`][Self::ensure_free_inodes],
+    /// [`skip_empty_directories`][Self::skip_empty_directories] or
+    /// [`collect_all_collisions`][Self::collect_all_collisions] requires a scan
+    /// of the source directory, so any of them implies `pre_scan`.
+    ///
+    /// Defaults to `true`.
+    pub pre_scan: bool,
+
+    /// Whether to treat *any* existing target path as a collision and report all of them at once.
+    ///
+    /// If `true`, every file and directory the copy would create is checked before anything is copied,
+    /// regardless of whether the [`target_directory_rule`][Self::target_directory_rule] allows
+    /// overwriting or skipping existing entries (the target directory itself may still exist,
+    /// as the rule allows). If any of them already exist, the copy is aborted with
+    /// `Err(`[`DirectoryError::TargetItemsAlreadyExist`]`)`, which contains all the existing paths,
+    /// instead of `Err(`[`DirectoryError::TargetItemAlreadyExists`]`)` for the first one.
+    ///
+    /// This implies [`pre_scan`][Self::pre_scan].
+    ///
+    /// Defaults to `false`.
+    pub collect_all_collisions: bool,
+
+    /// If enabled, the source directory is copied into the target directory as a subdirectory
+    /// with the same name as the source directory, instead of its contents being copied
+    /// directly into the target directory.
+    ///
+    /// For example, copying `/foo/bar` to `/baz` copies into `/baz/bar` if this is `true`
+    /// (like `cp -r /foo/bar /baz` does when `/baz` already exists),
+    /// and directly into `/baz` if this is `false`.
+    /// The [`target_directory_rule`][Self::target_directory_rule] then applies to the subdirectory.
+    ///
+    /// Defaults to `false`.
+    pub copy_into_subdirectory: bool,
+
+    /// Whether to check that the target directory's filesystem is writable and
+    /// has enough free space for the source directory's contents before copying anything.
+    /// If it doesn't, `Err(`[`DirectoryError::ReadOnlyFilesystem`]`)` or
+    /// `Err(`[`DirectoryError::InsufficientFreeSpace`]`)` is returned.
+    ///
+    /// This check is currently only performed on Unix.
+    ///
+    /// Defaults to `false`.
+    pub ensure_free_space: bool,
+
+    /// Whether to check that the target directory's filesystem has enough free inodes
+    /// for every file, directory and link that would be created, before copying anything.
+    /// If it doesn't, `Err(`[`DirectoryError::InsufficientInodes`]`)` is returned.
+    ///
+    /// This catches copies of many small files failing midway on filesystems
+    /// that run out of inodes long before they run out of space. Filesystems that don't have
+    /// a fixed number of inodes (i.e. report a total of zero) are not checked.
+    ///
+    /// This check is currently only performed on Unix.
+    ///
+    /// Defaults to `false`.
+    pub ensure_free_inodes: bool,
+
+    /// Whether to copy the permissions and modification time of the source directory
+    /// onto the target directory once everything has been copied.
+    ///
+    /// This is only done if the target directory was created by the copy or was empty before it,
+    /// unless [`target_directory_rule`][Self::target_directory_rule] allows overwriting existing directories,
+    /// in which case the metadata of a pre-existing non-empty target directory is overwritten as well.
+    ///
+    /// The modification time is currently only preserved on Unix.
+    ///
+    /// Defaults to `false`.
+    pub preserve_root_metadata: bool,
+
+    /// Permission mode (e.g. `0o644`) to set on every file and directory created by the copy,
+    /// instead of keeping the permissions of the corresponding source entry.
+    ///
+    /// This includes the target directory itself if it is created by the copy.
+    /// If [`preserve_root_metadata`][Self::preserve_root_metadata] is also enabled,
+    /// this takes precedence over the preserved permissions of the target directory
+    /// (its modification time is still preserved).
+    ///
+    /// Directories only receive their mode once everything has been copied, so a restrictive mode
+    /// (e.g. one without the write bit) doesn't prevent their contents from being copied.
+    /// Pre-existing target directories, recreated special files and symbolic links are left as they are.
+    ///
+    /// This is currently only applied on Unix and is ignored on other platforms.
+    ///
+    /// Defaults to `None`.
+    pub force_mode: Option<u32>,
+
+    /// Whether to change the owner and group of every copied file, created directory
+    /// and recreated special file to match its source (like `cp -p` does when run as root).
+    /// This includes the target directory itself if it is created by the copy.
+    ///
+    /// Changing the owner usually requires elevated privileges. If it isn't permitted,
+    /// `Err(`[`DirectoryError::UnableToPreserveOwnership`]`)` is returned,
+    /// with the inner error being of the [`PermissionDenied`][std::io::ErrorKind::PermissionDenied] kind.
+    ///
+    /// This has no effect on platforms other than Unix.
+    ///
+    /// Defaults to `false`.
+    pub preserve_ownership: bool,
+
+    /// Whether to skip creating directories that would end up empty in the target directory,
+    /// i.e. directories that (at any depth) contain no files that are copied.
+    ///
+    /// This applies *after* other options have excluded entries, so e.g. a directory whose files
+    /// are all excluded by [`minimum_copy_depth`][Self::minimum_copy_depth] is not created either.
+    /// The number of skipped directories is reported in
+    /// [`FinishedDirectoryCopy::num_empty_directories_skipped`].
+    ///
+    /// Defaults to `false`.
+    pub skip_empty_directories: bool,
+
+    /// Order in which the entries of each source directory are copied.
+    ///
+    /// See [`TraversalOrder`] for more details.
+    pub traversal_order: TraversalOrder,
+
+    /// Whether to read source directories in larger batches, reducing the number of system calls
+    /// needed for directories with many entries.
+    ///
+    /// See [`DirectoryScanOptions::fast_enumeration`][super::DirectoryScanOptions::fast_enumeration]
+    /// for more details.
+    pub fast_enumeration: bool,
+
+    /// Whether to follow only the source directory path itself if it is a symbolic link,
+    /// and to recreate symbolic links found *inside* the source directory as they are,
+    /// instead of copying what they point to.
+    ///
+    /// The source directory path is always followed (i.e. if it is a symbolic link to a directory,
+    /// the directory it points to is copied). By default, symbolic links inside the source directory
+    /// are followed as well: linked files are copied as regular files and linked directories
+    /// are copied along with their contents. If this is `true`, each such link is instead recreated
+    /// on the target with the same (possibly relative) destination, and linked directories are not descended into.
+    /// The number of recreated links is reported in [`FinishedDirectoryCopy::num_symlinks_recreated`].
+    ///
+    /// Broken symbolic links are still handled according to
+    /// [`broken_symlink_behaviour`][Self::broken_symlink_behaviour].
+    ///
+    /// Defaults to `false`.
+    pub follow_root_symlink_only: bool,
+
+    /// Maximum number of symbolic links to directories to follow during the copy.
+    ///
+    /// Once this many linked directories have been descended into, any further symbolic links
+    /// to directories are treated as leaves: they are recreated on the target as they are
+    /// (as with [`follow_root_symlink_only`][Self::follow_root_symlink_only]) instead of being followed.
+    /// Symbolic links to files are not counted, and are always followed.
+    /// This bounds the work done for deeply chained (or cyclic) symbolic link structures.
+    ///
+    /// Defaults to `None`, i.e. no limit.
+    pub max_symlinks_to_follow: Option<usize>,
+
+    /// Specifies how directory junctions are handled (Windows only).
+    ///
+    /// See [`JunctionBehaviour`] for more details.
+    pub junction_behaviour: JunctionBehaviour,
+
+    /// Specifies how files that are open or locked by another process are handled (Windows only).
+    ///
+    /// See [`LockedFileBehaviour`] for more details.
+    pub locked_file_behaviour: LockedFileBehaviour,
+
+    /// Specifies how the destinations of recreated symbolic links are written.
+    ///
+    /// See [`SymlinkRewrite`] for more details.
+    pub symlink_target_rewrite: SymlinkRewrite,
+
+    /// Whether to recreate only the directory structure, without copying any files.
+    ///
+    /// Every subdirectory is created as usual (and given the owner and group of its source if
+    /// [`preserve_ownership`][Self::preserve_ownership] is enabled), but no files, special files
+    /// or symbolic links are copied or recreated. This is useful for pre-creating the layout
+    /// of a directory before distributing files into it by other means.
+    ///
+    /// If [`skip_empty_directories`][Self::skip_empty_directories] is enabled as well,
+    /// it still considers the files that *would* have been copied, i.e. directories that are empty
+    /// in the source directory are skipped.
+    ///
+    /// Defaults to `false`.
+    pub directories_only: bool,
+
+    /// Whether to delete files and directories from the target directory that don't exist in the source directory,
+    /// making the target directory a mirror of the source directory (like `rsync --delete`).
+    ///
+    /// After everything has been copied, the target directory is walked (without following symbolic links)
+    /// and each entry that doesn't correspond to a source entry (at the same relative path) and wasn't
+    /// copied, created or skipped by this copy is removed. Target entries corresponding to source entries that
+    /// were excluded from the copy (e.g. by [`maximum_copy_depth`][Self::maximum_copy_depth],
+    /// [`directories_only`][Self::directories_only] or a file filter) are *not* removed,
+    /// nor are the contents of target directories corresponding to source symbolic links to directories.
+    /// The number of removed entries is reported in [`FinishedDirectoryCopy::num_extraneous_entries_deleted`].
+    ///
+    /// The entries that would be removed are listed in [`CopyPlan::extraneous_target_paths`]
+    /// (see [`copy_directory_with_plan`]) and [`MergePlan::extraneous_target_paths`] (see [`plan_merge`]).
+    ///
+    /// *This is destructive:* anything in the target directory that did not come from the source is lost.
+    /// Nothing is deleted if the copy fails.
+    ///
+    /// Defaults to `false`.
+    pub delete_extraneous_from_target: bool,
+
+    /// If set, a manifest of the copied files is returned in [`FinishedDirectoryCopy::manifest`]:
+    /// the path (relative to the target directory), size and checksum of each file,
+    /// computed with the given algorithm.
+    ///
+    /// Checksums are computed from the data as it is being copied, so no file is read twice.
+    /// In exchange, file contents are always copied through a userspace buffer instead
+    /// of using [`std::fs::copy`], which may be slower on platforms that offload copies.
+    /// Files hard-linked from a previous copy (see [`copy_directory_incremental`])
+    /// are also included; those are read once to compute their checksum.
+    ///
+    /// Defaults to `None`.
+    pub produce_manifest: Option<ChecksumAlgorithm>,
+
+    /// If set, every copied file is compared against its source file once the copy has finished,
+    /// see [`VerifyMode`]. This is useful e.g. when copying to unreliable or removable media.
+    ///
+    /// Files that weren't copied (e.g. because they were filtered out or skipped as already existing)
+    /// are not verified. Files hard-linked from a previous copy (see [`copy_directory_incremental`])
+    /// are verified as well. If a file doesn't match its source,
+    /// `Err(`[`DirectoryError::VerificationFailed`]`)` is returned; the copied files are left in place.
+    /// If a file can't be read for the comparison, `Err(`[`DirectoryError::IoDuringOperation`]`)` is returned
+    /// with the path of that file and [`IoOperation::ReadSource`] or [`IoOperation::ReadTarget`].
+    ///
+    /// Defaults to `None`.
+    pub verify_after_copy: Option<VerifyMode>,
+
+    /// If set, files larger than this many bytes are not copied.
+    ///
+    /// This also applies to files reached through symbolic links, but not to recreated symbolic links
+    /// (see [`follow_root_symlink_only`][Self::follow_root_symlink_only]). The source paths of the skipped files
+    /// are reported in [`FinishedDirectoryCopy::skipped_oversized_file_paths`].
+    ///
+    /// Defaults to `None`.
+    pub max_file_size: Option<u64>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for DirectoryCopyOptions {
+    fn default() -> Self {
+        Self {
+            target_directory_rule: TargetDirectoryRule::default(),
+            minimum_copy_depth: None,
+            maximum_copy_depth: None,
+            special_file_behaviour: SpecialFileBehaviour::default(),
+            broken_symlink_behaviour: BrokenSymlinkBehaviour::default(),
+            pre_scan: true,
+            collect_all_collisions: false,
+            copy_into_subdirectory: false,
+            ensure_free_space: false,
+            ensure_free_inodes: false,
+            preserve_root_metadata: false,
+            force_mode: None,
+            preserve_ownership: false,
+            skip_empty_directories: false,
+            traversal_order: TraversalOrder::default(),
+            fast_enumeration: false,
+            follow_root_symlink_only: false,
+            max_symlinks_to_follow: None,
+            junction_behaviour: JunctionBehaviour::default(),
+            locked_file_behaviour: LockedFileBehaviour::default(),
+            symlink_target_rewrite: SymlinkRewrite::default(),
+            directories_only: false,
+            delete_extraneous_from_target: false,
+            produce_manifest: None,
+            verify_after_copy: None,
+            max_file_size: None,
+        }
+    }
+}
+
+
+impl DirectoryCopyOptions {
+    /// Returns a [`DirectoryCopyOptionsBuilder`], starting from the default options.
+    ///
+    /// ## Example
+    /// ```
+    /// # use fs_more::directory::{DirectoryCopyOptions, TargetDirectoryRule};
+    /// let options = DirectoryCopyOptions::builder()
+    ///     .with_target_rule(TargetDirectoryRule::AllowEmpty)
+    ///     .with_max_depth(2)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(options.maximum_copy_depth, Some(2));
+    /// ```
+    pub fn builder() -> DirectoryCopyOptionsBuilder {
+        DirectoryCopyOptionsBuilder {
+            options: Self::default(),
+        }
+    }
+}
+
+
+/// Builder for [`DirectoryCopyOptions`], created by [`DirectoryCopyOptions::builder`].
+///
+/// Each method sets the option field of the same name (see [`DirectoryCopyOptions`]
+/// for their meaning and defaults). Unlike constructing the options struct directly,
+/// [`build`][Self::build] also validates the combination of options.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DirectoryCopyOptionsBuilder {
+    options: DirectoryCopyOptions,
+}
+
+impl DirectoryCopyOptionsBuilder {
+    /// Sets [`DirectoryCopyOptions::target_directory_rule`].
+    pub fn with_target_rule(mut self, target_directory_rule: TargetDirectoryRule) -> Self {
+        self.options.target_directory_rule = target_directory_rule;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::minimum_copy_depth`].
+    pub fn with_min_depth(mut self, minimum_copy_depth: usize) -> Self {
+        self.options.minimum_copy_depth = Some(minimum_copy_depth);
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::maximum_copy_depth`].
+    pub fn with_max_depth(mut self, maximum_copy_depth: usize) -> Self {
+        self.options.maximum_copy_depth = Some(maximum_copy_depth);
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::special_file_behaviour`].
+    pub fn with_special_file_behaviour(
+        mut self,
+        special_file_behaviour: SpecialFileBehaviour,
+    ) -> Self {
+        self.options.special_file_behaviour = special_file_behaviour;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::broken_symlink_behaviour`].
+    pub fn with_broken_symlink_behaviour(
+        mut self,
+        broken_symlink_behaviour: BrokenSymlinkBehaviour,
+    ) -> Self {
+        self.options.broken_symlink_behaviour = broken_symlink_behaviour;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::pre_scan`].
+    pub fn with_pre_scan(mut self, pre_scan: bool) -> Self {
+        self.options.pre_scan = pre_scan;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::collect_all_collisions`].
+    pub fn with_collect_all_collisions(mut self, collect_all_collisions: bool) -> Self {
+        self.options.collect_all_collisions = collect_all_collisions;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::copy_into_subdirectory`].
+    pub fn with_copy_into_subdirectory(mut self, copy_into_subdirectory: bool) -> Self {
+        self.options.copy_into_subdirectory = copy_into_subdirectory;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::ensure_free_space`].
+    pub fn with_ensure_free_space(mut self, ensure_free_space: bool) -> Self {
+        self.options.ensure_free_space = ensure_free_space;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::ensure_free_inodes`].
+    pub fn with_ensure_free_inodes(mut self, ensure_free_inodes: bool) -> Self {
+        self.options.ensure_free_inodes = ensure_free_inodes;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::preserve_root_metadata`].
+    pub fn with_preserve_root_metadata(mut self, preserve_root_metadata: bool) -> Self {
+        self.options.preserve_root_metadata = preserve_root_metadata;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::force_mode`].
+    pub fn with_force_mode(mut self, force_mode: u32) -> Self {
+        self.options.force_mode = Some(force_mode);
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::preserve_ownership`].
+    pub fn with_preserve_ownership(mut self, preserve_ownership: bool) -> Self {
+        self.options.preserve_ownership = preserve_ownership;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::skip_empty_directories`].
+    pub fn with_skip_empty_directories(mut self, skip_empty_directories: bool) -> Self {
+        self.options.skip_empty_directories = skip_empty_directories;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::traversal_order`].
+    pub fn with_traversal_order(mut self, traversal_order: TraversalOrder) -> Self {
+        self.options.traversal_order = traversal_order;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::fast_enumeration`].
+    pub fn with_fast_enumeration(mut self, fast_enumeration: bool) -> Self {
+        self.options.fast_enumeration = fast_enumeration;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::follow_root_symlink_only`].
+    pub fn with_follow_root_symlink_only(mut self, follow_root_symlink_only: bool) -> Self {
+        self.options.follow_root_symlink_only = follow_root_symlink_only;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::max_symlinks_to_follow`].
+    pub fn with_max_symlinks_to_follow(mut self, max_symlinks_to_follow: usize) -> Self {
+        self.options.max_symlinks_to_follow = Some(max_symlinks_to_follow);
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::junction_behaviour`].
+    pub fn with_junction_behaviour(mut self, junction_behaviour: JunctionBehaviour) -> Self {
+        self.options.junction_behaviour = junction_behaviour;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::locked_file_behaviour`].
+    pub fn with_locked_file_behaviour(
+        mut self,
+        locked_file_behaviour: LockedFileBehaviour,
+    ) -> Self {
+        self.options.locked_file_behaviour = locked_file_behaviour;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::symlink_target_rewrite`].
+    pub fn with_symlink_target_rewrite(mut self, symlink_target_rewrite: SymlinkRewrite) -> Self {
+        self.options.symlink_target_rewrite = symlink_target_rewrite;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::directories_only`].
+    pub fn with_directories_only(mut self, directories_only: bool) -> Self {
+        self.options.directories_only = directories_only;
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::produce_manifest`].
+    pub fn with_produce_manifest(mut self, checksum_algorithm: ChecksumAlgorithm) -> Self {
+        self.options.produce_manifest = Some(checksum_algorithm);
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::verify_after_copy`].
+    pub fn with_verify_after_copy(mut self, verify_mode: VerifyMode) -> Self {
+        self.options.verify_after_copy = Some(verify_mode);
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::max_file_size`].
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.options.max_file_size = Some(max_file_size);
+        self
+    }
+
+    /// Sets [`DirectoryCopyOptions::delete_extraneous_from_target`].
+    pub fn with_delete_extraneous_from_target(
+        mut self,
+        delete_extraneous_from_target: bool,
+    ) -> Self {
+        self.options.delete_extraneous_from_target = delete_extraneous_from_target;
+        self
+    }
+
+    /// Validates the configured options and returns them.
+    ///
+    /// Returns `Err(`[`DirectoryError::InvalidCopyDepthRange`]`)` if both
+    /// a minimum and a maximum copy depth are set and the minimum is not smaller than the maximum,
+    /// as such a copy could never copy any files.
+    pub fn build(self) -> Result<DirectoryCopyOptions, DirectoryError> {
+        validate_copy_depth_range(
+            self.options.minimum_copy_depth,
+            self.options.maximum_copy_depth,
+        )?;
+
+        Ok(self.options)
+    }
+}
+
+
+/// Ensures the minimum copy depth is smaller than the maximum copy depth (if both are set),
+/// returning `Err(`[`DirectoryError::InvalidCopyDepthRange`]`)` otherwise.
+///
+/// A minimum depth of `0` skips nothing, so it is accepted with any maximum depth.
+fn validate_copy_depth_range(
+    minimum_copy_depth: Option<usize>,
+    maximum_copy_depth: Option<usize>,
+) -> Result<(), DirectoryError> {
+    if let (Some(minimum), Some(maximum)) = (minimum_copy_depth, maximum_copy_depth) {
+        if minimum > 0 && minimum >= maximum {
+            return Err(DirectoryError::InvalidCopyDepthRange { minimum, maximum });
+        }
+    }
+
+    Ok(())
+}
+
+
+/// Given a source root path, a target root path and the source path to rejoin,
+/// this function takes the `source_path_to_rejoin`, removes the prefix provided by `source_root_path`
+/// and repplies that relative path back onto the `target_root_path`.
+///
+/// Returns a [`DirectoryError::SourcePathEscapesRoot`] if the `source_path_to_rejoin`
+/// is not a subpath of `source_root_path`.
+///
+/// ## Example
+/// ```ignore
+/// # use std::path::Path;
+/// # use fs_more::directory::copy::rejoin_source_subpath_onto_target;
+///
+/// let root_a = Path::new("/hello/there");
+/// let foo = Path::new("/hello/there/some/content");
+/// let root_b = Path::new("/different/root");
+///
+/// assert_eq!(
+///     rejoin_source_subpath_onto_target(
+///         root_a,
+///         foo,
+///         root_b
+///     ).unwrap(),
+///     Path::new("/different/root/some/content")
+/// );
+/// ```
+pub(crate) fn rejoin_source_subpath_onto_target(
+    source_root_path: &Path,
+    source_path_to_rejoin: &Path,
+    target_root_path: &Path,
+) -> Result<PathBuf, DirectoryError> {
+    // Strip the source subdirectory path from the full source path
+    // and place it on top of the target directory path.
+    let source_relative_subdirectory_path = if source_root_path.eq(source_path_to_rejoin) {
+        Path::new("")
+    } else {
+        source_path_to_rejoin
+            .strip_prefix(source_root_path)
+            .map_err(|_| DirectoryError::SourcePathEscapesRoot {
+                path: source_path_to_rejoin.to_path_buf(),
+            })?
+    };
+
+    Ok(target_root_path.join(source_relative_subdirectory_path))
+}
+
+
+/// Computes where `source_path` would end up if `source_root` were copied to `target_root`.
+///
+/// The `source_root` prefix is removed from `source_path` and the remaining relative path
+/// is joined onto `target_root`. Paths are compared as given, so no filesystem access is performed;
+/// pass both `source_root` and `source_path` in the same form (e.g. both canonicalized).
+///
+/// Returns [`DirectoryError::SourcePathEscapesRoot`] if `source_path` is not inside `source_root`.
+///
+/// ## Example
+/// ```
+/// # use std::path::Path;
+/// # use fs_more::directory::map_source_to_target;
+/// let target_path = map_source_to_target(
+///     "/hello/there",
+///     "/different/root",
+///     "/hello/there/some/content.txt",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(target_path, Path::new("/different/root/some/content.txt"));
+/// ```
+pub fn map_source_to_target<R, T, P>(
+    source_root: R,
+    target_root: T,
+    source_path: P,
+) -> Result<PathBuf, DirectoryError>
+where
+    R: AsRef<Path>,
+    T: AsRef<Path>,
+    P: AsRef<Path>,
+{
+    rejoin_source_subpath_onto_target(
+        source_root.as_ref(),
+        source_path.as_ref(),
+        target_root.as_ref(),
+    )
+}
+
+
+/// A single file in the manifest of a directory copy, see [`DirectoryCopyOptions::produce_manifest`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CopyManifestEntry {
+    /// Path of the file, relative to the target directory.
+    pub relative_path: PathBuf,
+
+    /// Size of the file in bytes.
+    pub size_bytes: u64,
+
+    /// Checksum of the file's contents as a lowercase hexadecimal string.
+    pub checksum: String,
+}
+
+
+/// Describes actions taken by the [`copy_directory`] function.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FinishedDirectoryCopy {
+    /// Total amount of bytes copied.
+    pub total_bytes_copied: u64,
+
+    /// Number of files copied when copying the directory.
+    pub num_files_copied: usize,
+
+    /// Number of directories created when copying the directory.
+    pub num_directories_created: usize,
+
+    /// Number of special files (FIFOs, sockets and device nodes) that were recreated,
+    /// see [`SpecialFileBehaviour::Recreate`].
+    pub num_special_files_recreated: usize,
+
+    /// Number of special files (FIFOs, sockets and device nodes) that were skipped,
+    /// see [`SpecialFileBehaviour::Skip`].
+    pub num_special_files_skipped: usize,
+
+    /// Number of broken symbolic links that were recreated,
+    /// see [`BrokenSymlinkBehaviour::CopyAsSymlink`].
+    pub num_broken_symlinks_recreated: usize,
+
+    /// Number of broken symbolic links that were skipped,
+    /// see [`BrokenSymlinkBehaviour::Skip`].
+    pub num_broken_symlinks_skipped: usize,
+
+    /// Number of (non-broken) symbolic links that were recreated instead of followed,
+    /// see [`DirectoryCopyOptions::follow_root_symlink_only`].
+    pub num_symlinks_recreated: usize,
+
+    /// Number of directory junctions that were recreated,
+    /// see [`JunctionBehaviour::Recreate`].
+    pub num_junctions_recreated: usize,
+
+    /// Number of directory junctions that were skipped,
+    /// see [`JunctionBehaviour::Skip`].
+    pub num_junctions_skipped: usize,
+
+    /// Number of directories that were not created because they would have been empty,
+    /// see [`DirectoryCopyOptions::skip_empty_directories`].
+    pub num_empty_directories_skipped: usize,
+
+    /// Number of files that were not copied because the file filter rejected them,
+    /// see [`copy_directory_with_file_filter`].
+    pub num_files_filtered_out: usize,
+
+    /// Number of files that were hard-linked from a previous copy instead of being copied,
+    /// see [`copy_directory_incremental`]. These are not included in
+    /// `num_files_copied` or `total_bytes_copied`.
+    pub num_files_linked: usize,
+
+    /// Number of files (including special files and symbolic links) that were not copied
+    /// because the target already contained them, see [`TargetDirectoryRule::Merge`].
+    pub num_existing_files_skipped: usize,
+
+    /// Number of files that were not copied because another process had them open or locked,
+    /// see [`LockedFileBehaviour::Skip`].
+    pub num_locked_files_skipped: usize,
+
+    /// Number of files and directories that were removed from the target directory
+    /// because they were not part of the copy, see [`DirectoryCopyOptions::delete_extraneous_from_target`].
+    pub num_extraneous_entries_deleted: usize,
+
+    /// Manifest of the copied files along with their sizes and checksums, in the order
+    /// they were copied. This is `None` unless [`DirectoryCopyOptions::produce_manifest`] is set.
+    pub manifest: Option<Vec<CopyManifestEntry>>,
+
+    /// Source paths of the files that were not copied because they were larger than
+    /// [`DirectoryCopyOptions::max_file_size`].
+    pub skipped_oversized_file_paths: Vec<PathBuf>,
+}
+
+impl FinishedDirectoryCopy {
+    /// Returns a human-readable summary of the copy,
+    /// e.g. `Copied 123 files (4.2 GiB) and created 17 directories`.
+    ///
+    /// The size is [`total_bytes_copied`][Self::total_bytes_copied], formatted using binary units
+    /// (`B`, `KiB`, `MiB`, `GiB` or `TiB`). For any other format, use the fields directly.
+    pub fn summary(&self) -> String {
+        format!(
+            "Copied {} {} ({}) and created {} {}",
+            self.num_files_copied,
+            if self.num_files_copied == 1 {
+                "file"
+            } else {
+                "files"
+            },
+            format_size_in_bytes(self.total_bytes_copied),
+            self.num_directories_created,
+            if self.num_directories_created == 1 {
+                "directory"
+            } else {
+                "directories"
+            },
+        )
+    }
+}
+
+/// Formats the given size using the largest fitting binary unit, e.g. `512 B` or `4.2 GiB`
+/// (see [`FinishedDirectoryCopy::summary`]).
+fn format_size_in_bytes(size_in_bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if size_in_bytes < 1024 {
+        return format!("{} B", size_in_bytes);
+    }
+
+    let mut size = size_in_bytes as f64 / 1024.0;
+    let mut unit_index = 0;
+
+    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit_index])
+}
+
+
+/// Represents a file copy or directory creation operation.
+///
+/// For more details, see the [`build_directory_copy_queue`] function.
+#[derive(Clone, Debug)]
+enum QueuedOperation {
+    CopyFile {
+        source_file_path: PathBuf,
+        source_size_bytes: u64,
+        target_file_path: PathBuf,
+    },
+    CreateDirectory {
+        /// `None` for intermediate directories that don't correspond to a source directory
+        /// (see `transform_operation_queue`).
+        source_directory_path: Option<PathBuf>,
+        source_size_bytes: u64,
+        target_directory_path: PathBuf,
+    },
+    RecreateSpecialFile {
+        source_file_path: PathBuf,
+        target_file_path: PathBuf,
+    },
+    RecreateBrokenSymlink {
+        link_destination_path: PathBuf,
+        target_file_path: PathBuf,
+    },
+    RecreateSymlink {
+        link_destination_path: PathBuf,
+        target_file_path: PathBuf,
+        points_to_directory: bool,
+    },
+    RecreateJunction {
+        junction_destination_path: PathBuf,
+        target_file_path: PathBuf,
+    },
+}
+
+
+impl QueuedOperation {
+    /// Returns the target path of the file or directory this operation creates.
+    fn target_path(&self) -> &Path {
+        match self {
+            QueuedOperation::CopyFile {
+                target_file_path, ..
+            }
+            | QueuedOperation::RecreateSpecialFile {
+                target_file_path, ..
+            }
+            | QueuedOperation::RecreateBrokenSymlink {
+                target_file_path, ..
+            }
+            | QueuedOperation::RecreateSymlink {
+                target_file_path, ..
+            }
+            | QueuedOperation::RecreateJunction {
+                target_file_path, ..
+            } => target_file_path,
+            QueuedOperation::CreateDirectory {
+                target_directory_path,
+                ..
+            } => target_directory_path,
+        }
+    }
+}
+
+
+/// A list of [`QueuedOperation`]s along with some details about the source directory
+/// that were gathered while building the queue.
+///
+/// For more details, see the [`build_directory_copy_queue`] function.
+struct DirectoryCopyQueue {
+    operations: Vec<QueuedOperation>,
+
+    /// Entries that were found, but will not be copied.
+    skipped_entries: SkippedSourceEntries,
+}
+
+/// Numbers of source entries that were found, but will not be copied
+/// (see [`SpecialFileBehaviour`], [`BrokenSymlinkBehaviour`], [`JunctionBehaviour`],
+/// [`copy_directory_with_file_filter`] and [`DirectoryCopyOptions::max_file_size`]).
+#[derive(Clone, Default)]
+struct SkippedSourceEntries {
+    num_special_files: usize,
+    num_broken_symlinks: usize,
+    num_junctions: usize,
+    num_filtered_files: usize,
+    oversized_file_paths: Vec<PathBuf>,
+}
+
+/// Returns `true` if a file of the given size exceeds `max_file_size`
+/// (see [`DirectoryCopyOptions::max_file_size`]).
+fn is_oversized_file(file_size_in_bytes: u64, max_file_size: Option<u64>) -> bool {
+    max_file_size
+        .map(|max_file_size| file_size_in_bytes > max_file_size)
+        .unwrap_or(false)
+}
+
+/// Decides what to do with the given special file according to the given [`SpecialFileBehaviour`].
+///
+/// Returns `Ok(Some(operation))` if the special file should be recreated
+/// and `Ok(None)` if it should be skipped.
+fn special_file_operation(
+    source_file_path: PathBuf,
+    target_file_path: PathBuf,
+    special_file_behaviour: SpecialFileBehaviour,
+) -> Result<Option<QueuedOperation>, DirectoryError> {
+    match special_file_behaviour {
+        SpecialFileBehaviour::Error => Err(DirectoryError::SourceContainsSpecialFile {
+            path: source_file_path,
+        }),
+        SpecialFileBehaviour::Skip => Ok(None),
+        SpecialFileBehaviour::Recreate => {
+            if cfg!(unix) {
+                Ok(Some(QueuedOperation::RecreateSpecialFile {
+                    source_file_path,
+                    target_file_path,
+                }))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+}
+
+
+/// Returns the destination a recreated symbolic link should have, according to the given [`SymlinkRewrite`].
+///
+/// With [`SymlinkRewrite::RewriteRelative`], a relative destination that leads outside of
+/// `source_directory_root_path` is replaced with a relative path from the target link's location
+/// to the same (absolute) destination. Absolute destinations and relative ones that stay
+/// inside the copied tree are returned as they are.
+fn rewrite_link_destination(
+    link_destination_path: PathBuf,
+    source_directory_root_path: &Path,
+    source_link_path: &Path,
+    target_link_path: &Path,
+    symlink_target_rewrite: SymlinkRewrite,
+) -> PathBuf {
+    if symlink_target_rewrite == SymlinkRewrite::Verbatim || link_destination_path.is_absolute() {
+        return link_destination_path;
+    }
+
+    let (source_link_parent_path, target_link_parent_path) = match (
+        source_link_path.parent(),
+        target_link_path.parent(),
+    ) {
+        (Some(source_link_parent_path), Some(target_link_parent_path)) => {
+            (source_link_parent_path, target_link_parent_path)
+        }
+        _ => return link_destination_path,
+    };
+
+    let resolved_destination_path =
+        path_clean::clean(source_link_parent_path.join(&link_destination_path));
+
+    if resolved_destination_path.starts_with(source_directory_root_path) {
+        return link_destination_path;
+    }
+
+    relative_path_from(
+        &resolved_destination_path,
+        &path_clean::clean(target_link_parent_path),
+    )
+    .unwrap_or(resolved_destination_path)
+}
+
+
+/// Decides what to do with the given broken symbolic link according to the given [`BrokenSymlinkBehaviour`].
+///
+/// Returns `Ok(Some(operation))` if the symbolic link should be recreated
+/// and `Ok(None)` if it should be skipped.
+fn broken_symlink_operation(
+    source_directory_root_path: &Path,
+    source_link_path: PathBuf,
+    target_file_path: PathBuf,
+    broken_symlink_behaviour: BrokenSymlinkBehaviour,
+    symlink_target_rewrite: SymlinkRewrite,
+) -> Result<Option<QueuedOperation>, DirectoryError> {
+    match broken_symlink_behaviour {
+        BrokenSymlinkBehaviour::Error => Err(DirectoryError::SourceContainsBrokenSymlink {
+            path: source_link_path,
+        }),
+        BrokenSymlinkBehaviour::Skip => Ok(None),
+        BrokenSymlinkBehaviour::CopyAsSymlink => {
+            let link_destination_path = fs::read_link(&source_link_path).map_err(|error| {
+                DirectoryError::IoDuringOperation {
+                    path: source_link_path.clone(),
+                    operation: IoOperation::ReadSource,
+                    source: error,
+                }
+            })?;
+
+            Ok(Some(QueuedOperation::RecreateBrokenSymlink {
+                link_destination_path: rewrite_link_destination(
+                    link_destination_path,
+                    source_directory_root_path,
+                    &source_link_path,
+                    &target_file_path,
+                    symlink_target_rewrite,
+                ),
+                target_file_path,
+            }))
+        }
+    }
+}
+
+
+/// Decides what to do with the given directory junction according to the given [`JunctionBehaviour`].
+///
+/// Returns `Ok(Some(operation))` if the junction should be recreated and `Ok(None)` if it should be skipped.
+/// Followed junctions are copied like symbolic links to directories, so this is not called for them.
+fn junction_operation(
+    source_junction_path: PathBuf,
+    target_file_path: PathBuf,
+    junction_behaviour: JunctionBehaviour,
+) -> Result<Option<QueuedOperation>, DirectoryError> {
+    match junction_behaviour {
+        JunctionBehaviour::Follow | JunctionBehaviour::Skip => Ok(None),
+        JunctionBehaviour::Recreate => {
+            let junction_destination_path = read_junction_destination(&source_junction_path)
+                .map_err(|error| DirectoryError::IoDuringOperation {
+                    path: source_junction_path.clone(),
+                    operation: IoOperation::ReadSource,
+                    source: error,
+                })?;
+
+            Ok(Some(QueuedOperation::RecreateJunction {
+                junction_destination_path,
+                target_file_path,
+            }))
+        }
+    }
+}
+
+
+/// Recreates the special file (FIFO, socket or device node) at `source_file_path` on `target_file_path`,
+/// preserving its type, permission bits and, for device nodes, the device ID.
+#[cfg(unix)]
+fn recreate_special_file(source_file_path: &Path, target_file_path: &Path) -> std::io::Result<()> {
+    use std::{
+        ffi::CString,
+        os::unix::{ffi::OsStrExt, fs::FileTypeExt, fs::MetadataExt},
+    };
+
+    let source_metadata = fs::symlink_metadata(source_file_path)?;
+
+    let target_file_path_c =
+        CString::new(target_file_path.as_os_str().as_bytes()).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "target path contains an interior nul byte",
+            )
+        })?;
+
+    // SAFETY: `target_file_path_c` is a valid nul-terminated string that outlives both calls.
+    let result = if source_metadata.file_type().is_fifo() {
+        unsafe {
+            libc::mkfifo(
+                target_file_path_c.as_ptr(),
+                (source_metadata.mode() & 0o7777) as libc::mode_t,
+            )
+        }
+    } else {
+        unsafe {
+            libc::mknod(
+                target_file_path_c.as_ptr(),
+                source_metadata.mode() as libc::mode_t,
+                source_metadata.rdev() as libc::dev_t,
+            )
+        }
+    };
+
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn recreate_special_file(
+    _source_file_path: &Path,
+    _target_file_path: &Path,
+) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "recreating special files is supported only on Unix",
+    ))
+}
+
+
+/// Creates a symbolic link at `target_link_path` that points to `link_destination_path`.
+///
+/// On Windows, the link is created as a directory symbolic link if `points_to_directory` is `true`
+/// and as a file symbolic link otherwise (e.g. when the destination is missing, as its type can't be known).
+fn create_symbolic_link(
+    link_destination_path: &Path,
+    target_link_path: &Path,
+    points_to_directory: bool,
+) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        let _ = points_to_directory;
+        std::os::unix::fs::symlink(link_destination_path, target_link_path)
+    }
+
+    #[cfg(windows)]
+    {
+        if points_to_directory {
+            std::os::windows::fs::symlink_dir(link_destination_path, target_link_path)
+        } else {
+            std::os::windows::fs::symlink_file(link_destination_path, target_link_path)
+        }
+    }
+}
+
+
+/// Given a source and target directory as well as, optionally, a maximum copy depth,
+/// this function traverses the source directory and calls `on_operation` with each [`QueuedOperation`]
+/// that is needed to fully, or up to the `maximum_depth` limit, copy the source directory to the target directory.
+///
+/// The order of directory creation and file copying operations is such that
+/// for any file, its directory has previously been created
+/// (its creation is visited before it). This means `on_operation` may also
+/// execute each operation immediately instead of collecting them.
+///
+/// Entries directly in the source directory are at depth `1`. If `maximum_depth` is set, only entries
+/// up to that depth are visited (none at all, if it is `0`). If `minimum_depth` is set, files and special files
+/// at that depth or shallower are skipped. Directories are still created, as deeper entries keep their relative paths.
+///
+/// Special files (FIFOs, sockets and device nodes) are handled according to `special_file_behaviour`
+/// and broken symbolic links according to `broken_symlink_behaviour`. If `preserve_symlinks` is `true`,
+/// other symbolic links are recreated instead of followed (see [`DirectoryCopyOptions::follow_root_symlink_only`]).
+/// Directory junctions are handled according to `junction_behaviour`, unless they are to be followed,
+/// in which case they are treated like any other symbolic link. The destinations of recreated symbolic links
+/// are rewritten according to `symlink_target_rewrite`.
+/// If `file_filter` is set, files for which it returns `false` are skipped,
+/// as are files larger than `max_file_size` (if set). Once `max_symlinks_to_follow` symbolic links
+/// to directories have been followed, further ones are recreated instead.
+/// The entries of each directory are visited in the given `traversal_order`,
+/// and are read in larger batches if `fast_enumeration` is `true`.
+/// The numbers of skipped special files, broken symbolic links, junctions and filtered out files are returned.
+///
+/// Note, however, that **the visited operations do not include creation of the `target_directory_root_path`
+/// directory itself**, even if that is necessary in your case.
+#[allow(clippy::too_many_arguments)]
+fn visit_directory_copy_operations<F>(
+    source_directory_root_path: &Path,
+    target_directory_root_path: &Path,
+    minimum_depth: Option<usize>,
+    maximum_depth: Option<usize>,
+    special_file_behaviour: SpecialFileBehaviour,
+    broken_symlink_behaviour: BrokenSymlinkBehaviour,
+    preserve_symlinks: bool,
+    junction_behaviour: JunctionBehaviour,
+    symlink_target_rewrite: SymlinkRewrite,
+    traversal_order: TraversalOrder,
+    fast_enumeration: bool,
+    mut file_filter: Option<&mut FileFilter>,
+    max_file_size: Option<u64>,
+    max_symlinks_to_follow: Option<usize>,
+    mut on_operation: F,
+) -> Result<SkippedSourceEntries, DirectoryError>
+where
+    F: FnMut(QueuedOperation) -> Result<(), DirectoryError>,
+{
+    let mut skipped_entries = SkippedSourceEntries::default();
+
+    // Number of symbolic links to directories followed so far (see `max_symlinks_to_follow`).
+    let mut num_followed_directory_symlinks: usize = 0;
+
+
+    // Scan the source directory and visit all copy and
+    // directory create operations that need to happen.
+    struct PendingDirectoryScan {
+        source_directory_path: PathBuf,
+        depth: usize,
+    }
+
+    // With a maximum depth of 0, not even the entries directly in the source directory are copied.
+    if maximum_depth == Some(0) {
+        return Ok(skipped_entries);
+    }
+
+    // The depth of a pending directory is the depth of the entries directly in it.
+    let mut directory_scan_queue = Vec::new();
+    directory_scan_queue.push(PendingDirectoryScan {
+        source_directory_path: source_directory_root_path.to_path_buf(),
+        depth: 1,
+    });
+
+    // Perform directory scans using a queue.
+    while let Some(next_directory) = directory_scan_queue.pop() {
+        // Files (and special files) at or above the minimum depth are not copied.
+        let should_copy_files = minimum_depth
+            .map(|minimum_depth| next_directory.depth > minimum_depth)
+            .unwrap_or(true);
+
+        // Scan the directory for its files and directories.
+        // Files are queued for copying, directories are queued for creation.
+        let directory_items = read_directory_items(
+            &next_directory.source_directory_path,
+            traversal_order,
+            fast_enumeration,
+        )
+        .map_err(|error| DirectoryError::IoDuringOperation {
+            path: next_directory.source_directory_path.clone(),
+            operation: IoOperation::ReadSource,
+            source: error,
+        })?;
+
+        // Subdirectories found in this directory are pushed onto the (LIFO) queue in traversal order,
+        // so they are reversed afterwards in order to be visited in that same order.
+        let directory_scan_queue_length = directory_scan_queue.len();
+
+        for directory_item in directory_items {
+            let directory_item =
+                directory_item.map_err(|error| DirectoryError::IoDuringOperation {
+                    path: next_directory.source_directory_path.clone(),
+                    operation: IoOperation::ReadSource,
+                    source: error,
+                })?;
+
+            let directory_item_source_path = directory_item.path();
+            let directory_item_target_path = rejoin_source_subpath_onto_target(
+                source_directory_root_path,
+                &directory_item_source_path,
+                target_directory_root_path,
+            )?;
+
+            let item_type =
+                directory_item
+                    .file_type()
+                    .map_err(|error| DirectoryError::IoDuringOperation {
+                        path: directory_item_source_path.clone(),
+                        operation: IoOperation::ReadSource,
+                        source: error,
+                    })?;
+
+            if item_type.is_file() {
+                if !should_copy_files {
+                    continue;
+                }
+
+                let read_source_error = |error| DirectoryError::IoDuringOperation {
+                    path: directory_item_source_path.clone(),
+                    operation: IoOperation::ReadSource,
+                    source: error,
+                };
+
+                // The full metadata is only needed if there is a filter to pass it to.
+                let file_size_in_bytes = match file_filter.as_mut() {
+                    Some(file_filter) => {
+                        let file_metadata = directory_item.metadata().map_err(read_source_error)?;
+
+                        if !file_filter(&directory_item_source_path, &file_metadata) {
+                            skipped_entries.num_filtered_files += 1;
+                            continue;
+                        }
+
+                        file_metadata.len()
+                    }
+                    None => directory_item.size_in_bytes().map_err(read_source_error)?,
+                };
+
+                if is_oversized_file(file_size_in_bytes, max_file_size) {
+                    skipped_entries
+                        .oversized_file_paths
+                        .push(directory_item_source_path);
+                    continue;
+                }
+
+                on_operation(QueuedOperation::CopyFile {
+                    source_file_path: directory_item_source_path,
+                    source_size_bytes: file_size_in_bytes,
+                    target_file_path: directory_item_target_path,
+                })?;
+            } else if item_type.is_dir() {
+                // Note that this is the size of the directory itself, not of its contents.
+                let directory_size_in_bytes = directory_item.size_in_bytes().map_err(|error| {
+                    DirectoryError::IoDuringOperation {
+                        path: directory_item_source_path.clone(),
+                        operation: IoOperation::ReadSource,
+                        source: error,
+                    }
+                })?;
+
+                on_operation(QueuedOperation::CreateDirectory {
+                    source_directory_path: Some(directory_item_source_path.clone()),
+                    source_size_bytes: directory_size_in_bytes,
+                    target_directory_path: directory_item_target_path,
+                })?;
+
+                // If we haven't reached the maximum depth yet, we queue the directory for scanning.
+                if let Some(maximum_depth) = maximum_depth {
+                    if next_directory.depth < maximum_depth {
+                        directory_scan_queue.push(PendingDirectoryScan {
+                            source_directory_path: directory_item_source_path,
+                            depth: next_directory.depth + 1,
+                        });
+                    }
+                } else {
+                    directory_scan_queue.push(PendingDirectoryScan {
+                        source_directory_path: directory_item_source_path,
+                        depth: next_directory.depth + 1,
+                    });
+                }
+            } else if item_type.is_symlink() {
+                // Directory junctions are reported as symbolic links as well,
+                // but are only treated as such if they are to be followed.
+                if junction_behaviour != JunctionBehaviour::Follow {
+                    let item_is_junction =
+                        is_junction(&directory_item_source_path).map_err(|error| {
+                            DirectoryError::IoDuringOperation {
+                                path: directory_item_source_path.clone(),
+                                operation: IoOperation::ReadSource,
+                                source: error,
+                            }
+                        })?;
+
+                    if item_is_junction {
+                        if should_copy_files {
+                            match junction_operation(
+                                directory_item_source_path,
+                                directory_item_target_path,
+                                junction_behaviour,
+                            )? {
+                                Some(operation) => on_operation(operation)?,
+                                None => skipped_entries.num_junctions += 1,
+                            }
+                        }
+
+                        continue;
+                    }
+                }
+
+                // If the path is a symbolic link, we need to follow it and queue a copy from the destination file.
+                // Can point to either a directory or a file.
+
+                // Now we should retrieve the metadata of the target of the symbolic link
+                // (unlike DirEntry::metadata, this metadata call *does* follow symolic links).
+                let underlying_path = match fs::canonicalize(&directory_item_source_path) {
+                    Ok(underlying_path) => underlying_path,
+                    Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                        // The symbolic link is broken, i.e. its destination does not exist.
+                        if should_copy_files {
+                            match broken_symlink_operation(
+                                source_directory_root_path,
+                                directory_item_source_path,
+                                directory_item_target_path,
+                                broken_symlink_behaviour,
+                                symlink_target_rewrite,
+                            )? {
+                                Some(operation) => on_operation(operation)?,
+                                None => skipped_entries.num_broken_symlinks += 1,
+                            }
+                        }
+
+                        continue;
+                    }
+                    Err(error) => {
+                        return Err(DirectoryError::IoDuringOperation {
+                            path: directory_item_source_path,
+                            operation: IoOperation::ReadSource,
+                            source: error,
+                        });
+                    }
+                };
+
+                let underlying_item_metadata = fs::metadata(&underlying_path).map_err(|error| {
+                    DirectoryError::IoDuringOperation {
+                        path: underlying_path.clone(),
+                        operation: IoOperation::ReadSource,
+                        source: error,
+                    }
+                })?;
+
+                // Once the limit is reached, symbolic links to directories are recreated instead of followed.
+                let is_beyond_symlink_limit = underlying_item_metadata.is_dir()
+                    && max_symlinks_to_follow
+                        .map(|max_symlinks| num_followed_directory_symlinks >= max_symlinks)
+                        .unwrap_or(false);
+
+                if preserve_symlinks || is_beyond_symlink_limit {
+                    if !should_copy_files {
+                        continue;
+                    }
+
+                    // The filter still applies to links to files, as it would if they were followed.
+                    if let Some(file_filter) = file_filter.as_mut() {
+                        if underlying_item_metadata.is_file()
+                            && !file_filter(
+                                &directory_item_source_path,
+                                &underlying_item_metadata,
+                            )
+                        {
+                            skipped_entries.num_filtered_files += 1;
+                            continue;
+                        }
+                    }
+
+                    let link_destination_path = fs::read_link(&directory_item_source_path)
+                        .map_err(|error| DirectoryError::IoDuringOperation {
+                            path: directory_item_source_path.clone(),
+                            operation: IoOperation::ReadSource,
+                            source: error,
+                        })?;
+
+                    on_operation(QueuedOperation::RecreateSymlink {
+                        link_destination_path: rewrite_link_destination(
+                            link_destination_path,
+                            source_directory_root_path,
+                            &directory_item_source_path,
+                            &directory_item_target_path,
+                            symlink_target_rewrite,
+                        ),
+                        target_file_path: directory_item_target_path,
+                        points_to_directory: underlying_item_metadata.is_dir(),
+                    })?;
+                } else if underlying_item_metadata.is_file() {
+                    if !should_copy_files {
+                        continue;
+                    }
+
+                    // The filter sees the path of the symbolic link, but the metadata of the file it points to.
+                    if let Some(file_filter) = file_filter.as_mut() {
+                        if !file_filter(
+                            &directory_item_source_path,
+                            &underlying_item_metadata,
+                        ) {
+                            skipped_entries.num_filtered_files += 1;
+                            continue;
+                        }
+                    }
+
+                    let underlying_file_size_in_bytes = underlying_item_metadata.len();
+
+                    if is_oversized_file(underlying_file_size_in_bytes, max_file_size) {
+                        skipped_entries
+                            .oversized_file_paths
+                            .push(directory_item_source_path);
+                        continue;
+                    }
+
+                    on_operation(QueuedOperation::CopyFile {
+                        source_file_path: underlying_path,
+                        source_size_bytes: underlying_file_size_in_bytes,
+                        target_file_path: directory_item_target_path,
+                    })?;
+                } else if underlying_item_metadata.is_dir() {
+                    num_followed_directory_symlinks += 1;
+
+                    // Note that this is the size of the directory itself, not of its contents.
+                    let underlying_directory_size_in_bytes = underlying_item_metadata.len();
+
+                    on_operation(QueuedOperation::CreateDirectory {
+                        source_directory_path: Some(underlying_path.clone()),
+                        source_size_bytes: underlying_directory_size_in_bytes,
+                        target_directory_path: directory_item_target_path,
+                    })?;
+
+                    // If we haven't reached the maximum depth yet, we queue the directory for scanning.
+                    if let Some(maximum_depth) = maximum_depth {
                         if next_directory.depth < maximum_depth {
                             directory_scan_queue.push(PendingDirectoryScan {
                                 source_directory_path: directory_item_source_path,
@@ -473,251 +2196,2661 @@ where
                             depth: next_directory.depth + 1,
                         });
                     }
+                } else if should_copy_files {
+                    match special_file_operation(
+                        underlying_path,
+                        directory_item_target_path,
+                        special_file_behaviour,
+                    )? {
+                        Some(operation) => on_operation(operation)?,
+                        None => skipped_entries.num_special_files += 1,
+                    }
+                }
+            } else if should_copy_files {
+                // Neither a file, a directory nor a symbolic link, i.e. a FIFO, socket or device node.
+                match special_file_operation(
+                    directory_item_source_path,
+                    directory_item_target_path,
+                    special_file_behaviour,
+                )? {
+                    Some(operation) => on_operation(operation)?,
+                    None => skipped_entries.num_special_files += 1,
+                }
+            }
+        }
+
+        directory_scan_queue[directory_scan_queue_length..].reverse();
+    }
+
+    Ok(skipped_entries)
+}
+
+/// Given a source and target directory as well as, optionally, a maximum copy depth,
+/// this function builds a list of [`QueuedOperation`]s that are needed to fully,
+/// or up to the `maximum_depth` limit, copy the source directory to the target directory.
+///
+/// For more details, see the [`visit_directory_copy_operations`] function.
+#[allow(clippy::too_many_arguments)]
+fn build_directory_copy_queue<S, T>(
+    source_directory_root_path: S,
+    target_directory_root_path: T,
+    minimum_depth: Option<usize>,
+    maximum_depth: Option<usize>,
+    special_file_behaviour: SpecialFileBehaviour,
+    broken_symlink_behaviour: BrokenSymlinkBehaviour,
+    preserve_symlinks: bool,
+    junction_behaviour: JunctionBehaviour,
+    symlink_target_rewrite: SymlinkRewrite,
+    traversal_order: TraversalOrder,
+    fast_enumeration: bool,
+    file_filter: Option<&mut FileFilter>,
+    max_file_size: Option<u64>,
+    max_symlinks_to_follow: Option<usize>,
+) -> Result<DirectoryCopyQueue, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    let mut operations = Vec::new();
+
+    let skipped_entries = visit_directory_copy_operations(
+        source_directory_root_path.as_ref(),
+        target_directory_root_path.as_ref(),
+        minimum_depth,
+        maximum_depth,
+        special_file_behaviour,
+        broken_symlink_behaviour,
+        preserve_symlinks,
+        junction_behaviour,
+        symlink_target_rewrite,
+        traversal_order,
+        fast_enumeration,
+        file_filter,
+        max_file_size,
+        max_symlinks_to_follow,
+        |operation| {
+            operations.push(operation);
+            Ok(())
+        },
+    )?;
+
+    Ok(DirectoryCopyQueue {
+        operations,
+        skipped_entries,
+    })
+}
+
+/// A function that maps a relative source path to a relative target path,
+/// see [`copy_directory_with_path_transform`].
+type PathTransform<'a> = dyn FnMut(&Path) -> Option<PathBuf> + 'a;
+
+/// A function that decides whether a source file should be copied,
+/// see [`copy_directory_with_file_filter`].
+type FileFilter<'a> = dyn FnMut(&Path, &Metadata) -> bool + 'a;
+
+/// A function that receives the [`CopyPlan`] before anything is copied,
+/// see [`copy_directory_with_plan`].
+type PlanHandler<'a> = dyn FnMut(&CopyPlan) + 'a;
+
+/// A function that receives a [`FileEvent`] whenever a file starts or finishes copying or is skipped,
+/// see [`copy_directory_with_progress_and_file_events`].
+type FileEventHandler<'a> = dyn FnMut(FileEvent) + 'a;
+
+/// Returns the target path of the given [`QueuedOperation`].
+fn target_path_of_operation(operation: &mut QueuedOperation) -> &mut PathBuf {
+    match operation {
+        QueuedOperation::CopyFile {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateSpecialFile {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateBrokenSymlink {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateSymlink {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateJunction {
+            target_file_path, ..
+        } => target_file_path,
+        QueuedOperation::CreateDirectory {
+            target_directory_path,
+            ..
+        } => target_directory_path,
+    }
+}
+
+/// Given a list of queued operations, this function maps the target path of each operation
+/// (relative to `target_directory_root_path`) through `transform_path`.
+///
+/// Operations for which `transform_path` returns `None` are removed. If that operation
+/// creates a directory, the operations for all of the directory's contents are removed as well.
+///
+/// Operations that create any missing parent directories of the transformed target paths
+/// are inserted as needed, so the returned queue keeps the order guarantees of [`visit_directory_copy_operations`].
+fn transform_operation_queue(
+    operations: Vec<QueuedOperation>,
+    target_directory_root_path: &Path,
+    transform_path: &mut PathTransform,
+) -> Result<Vec<QueuedOperation>, DirectoryError> {
+    let mut transformed_operations = Vec::with_capacity(operations.len());
+
+    // Relative paths of directories that were skipped along with their contents.
+    let mut skipped_directory_paths: Vec<PathBuf> = Vec::new();
+
+    // Target paths that are the result of a transform, used to detect two entries mapping to the same path.
+    let mut transformed_target_paths: HashSet<PathBuf> = HashSet::new();
+
+    // Target directories that will have been created by the time the next operation is executed.
+    let mut created_directory_paths: HashSet<PathBuf> = HashSet::new();
+    created_directory_paths.insert(target_directory_root_path.to_path_buf());
+
+    for mut operation in operations {
+        let target_path = target_path_of_operation(&mut operation);
+
+        let relative_path = target_path
+            .strip_prefix(target_directory_root_path)
+            .map_err(|_| DirectoryError::OtherReason {
+                reason: String::from("queued target path escapes its target root"),
+            })?
+            .to_path_buf();
+
+        if skipped_directory_paths
+            .iter()
+            .any(|skipped_directory_path| relative_path.starts_with(skipped_directory_path))
+        {
+            continue;
+        }
+
+        let transformed_relative_path = match transform_path(&relative_path) {
+            Some(transformed_relative_path) => transformed_relative_path,
+            None => {
+                if matches!(operation, QueuedOperation::CreateDirectory { .. }) {
+                    skipped_directory_paths.push(relative_path);
+                }
+
+                continue;
+            }
+        };
+
+        let is_valid_relative_path = transformed_relative_path.components().next().is_some()
+            && transformed_relative_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+
+        if !is_valid_relative_path {
+            return Err(DirectoryError::InvalidTransformedPath {
+                path: transformed_relative_path,
+            });
+        }
+
+        let transformed_target_path = target_directory_root_path.join(transformed_relative_path);
+
+        if !transformed_target_paths.insert(transformed_target_path.clone()) {
+            return Err(DirectoryError::DuplicateTransformedPath {
+                path: transformed_target_path,
+            });
+        }
+
+        // Create any missing parent directories first, outermost first.
+        let mut missing_parent_directory_paths: Vec<&Path> = transformed_target_path
+            .ancestors()
+            .skip(1)
+            .take_while(|ancestor_path| !created_directory_paths.contains(*ancestor_path))
+            .collect();
+        missing_parent_directory_paths.reverse();
+
+        for missing_parent_directory_path in missing_parent_directory_paths {
+            // A file is already being copied to where this directory would need to be.
+            if transformed_target_paths.contains(missing_parent_directory_path) {
+                return Err(DirectoryError::DuplicateTransformedPath {
+                    path: missing_parent_directory_path.to_path_buf(),
+                });
+            }
+
+            created_directory_paths.insert(missing_parent_directory_path.to_path_buf());
+            transformed_operations.push(QueuedOperation::CreateDirectory {
+                source_directory_path: None,
+                source_size_bytes: 0,
+                target_directory_path: missing_parent_directory_path.to_path_buf(),
+            });
+        }
+
+        if created_directory_paths.contains(&transformed_target_path) {
+            // An earlier transformed path required this directory as its parent,
+            // so it has already been queued for creation.
+            if matches!(operation, QueuedOperation::CreateDirectory { .. }) {
+                continue;
+            }
+
+            return Err(DirectoryError::DuplicateTransformedPath {
+                path: transformed_target_path,
+            });
+        }
+
+        if matches!(operation, QueuedOperation::CreateDirectory { .. }) {
+            created_directory_paths.insert(transformed_target_path.clone());
+        }
+
+        *target_path_of_operation(&mut operation) = transformed_target_path;
+        transformed_operations.push(operation);
+    }
+
+    Ok(transformed_operations)
+}
+
+/// Removes the operations that create directories which would end up empty,
+/// i.e. directories with no file copy or special file operations inside them (at any depth).
+///
+/// Returns the remaining operations and the number of removed directory operations.
+fn remove_empty_directory_operations(
+    operations: Vec<QueuedOperation>,
+) -> (Vec<QueuedOperation>, usize) {
+    let mut non_empty_directory_paths: HashSet<PathBuf> = HashSet::new();
+
+    for operation in &operations {
+        if let QueuedOperation::CopyFile {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateSpecialFile {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateBrokenSymlink {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateSymlink {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateJunction {
+            target_file_path, ..
+        } = operation
+        {
+            for ancestor_path in target_file_path.ancestors().skip(1) {
+                // Ancestors are inserted from the innermost outwards,
+                // so if this one is already present, so are all of its ancestors.
+                if !non_empty_directory_paths.insert(ancestor_path.to_path_buf()) {
+                    break;
+                }
+            }
+        }
+    }
+
+    let num_operations = operations.len();
+    let remaining_operations: Vec<QueuedOperation> = operations
+        .into_iter()
+        .filter(|operation| match operation {
+            QueuedOperation::CreateDirectory {
+                target_directory_path,
+                ..
+            } => non_empty_directory_paths.contains(target_directory_path),
+            _ => true,
+        })
+        .collect();
+
+    let num_removed_operations = num_operations - remaining_operations.len();
+    (remaining_operations, num_removed_operations)
+}
+
+/// Given a list of queued operations, this function validates that
+/// the files we'd be copying into or target directories we'd create don't exist yet
+/// (or however the [`TargetDirectoryRule`] is configured).
+///
+/// If `collect_all_collisions` is `true`, any existing target path is a collision (regardless of the rule),
+/// and all of them are reported in a single [`DirectoryError::TargetItemsAlreadyExist`].
+fn check_operation_queue_for_collisions(
+    queue: &[QueuedOperation],
+    target_directory_rules: &TargetDirectoryRule,
+    collect_all_collisions: bool,
+) -> Result<(), DirectoryError> {
+    if collect_all_collisions {
+        let existing_target_paths: Vec<PathBuf> = queue
+            .iter()
+            .map(|queue_item| queue_item.target_path())
+            .filter(|target_path| fs::symlink_metadata(target_path).is_ok())
+            .map(Path::to_path_buf)
+            .collect();
+
+        if !existing_target_paths.is_empty() {
+            return Err(DirectoryError::TargetItemsAlreadyExist {
+                paths: existing_target_paths,
+            });
+        }
+
+        return Ok(());
+    }
+
+    // Existing files that will be skipped don't collide with anything either.
+    let can_overwrite_files = target_directory_rules.should_overwrite_existing_files()
+        || target_directory_rules.should_skip_existing_files();
+    let can_overwrite_directories = target_directory_rules.should_overwrite_existing_directories();
+
+    if can_overwrite_files && can_overwrite_directories {
+        // There is nothing to check, we can't have any collisions
+        // if we allow everything to be overwritten.
+        return Ok(());
+    }
+
+    for queue_item in queue {
+        match queue_item {
+            QueuedOperation::CopyFile {
+                target_file_path, ..
+            }
+            | QueuedOperation::RecreateSpecialFile {
+                target_file_path, ..
+            }
+            | QueuedOperation::RecreateBrokenSymlink {
+                target_file_path, ..
+            }
+            | QueuedOperation::RecreateSymlink {
+                target_file_path, ..
+            }
+            | QueuedOperation::RecreateJunction {
+                target_file_path, ..
+            } if !can_overwrite_files && target_file_path.exists() => {
+                return Err(DirectoryError::TargetItemAlreadyExists {
+                    path: target_file_path.clone(),
+                });
+            }
+            QueuedOperation::CreateDirectory {
+                target_directory_path,
+                ..
+            } if !can_overwrite_directories && target_directory_path.exists() => {
+                return Err(DirectoryError::TargetItemAlreadyExists {
+                    path: target_directory_path.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+
+/// Describes what will happen to an existing (or missing) target file according to `should_skip_existing_files`.
+fn planned_target_file_action(
+    target_file_path: &Path,
+    should_skip_existing_files: bool,
+) -> PlannedTargetAction {
+    if fs::symlink_metadata(target_file_path).is_err() {
+        PlannedTargetAction::Create
+    } else if should_skip_existing_files {
+        PlannedTargetAction::Skip
+    } else {
+        PlannedTargetAction::Overwrite
+    }
+}
+
+/// A [`DirectoryCopyQueue`] with all filtering options of a copy applied to it,
+/// see [`build_filtered_directory_copy_queue`].
+struct FilteredDirectoryCopyQueue {
+    operations: Vec<QueuedOperation>,
+
+    /// Entries that were found, but will not be copied.
+    skipped_entries: SkippedSourceEntries,
+
+    /// Number of empty directories that were left out, see [`DirectoryCopyOptions::skip_empty_directories`].
+    num_empty_directories_skipped: usize,
+}
+
+/// Builds the queue of operations a copy with the given `options` will perform, not yet checked for collisions.
+///
+/// This applies the options that leave out or alter queued operations ([`copy_directory_only_paths`],
+/// [`skip_empty_directories`][DirectoryCopyOptions::skip_empty_directories],
+/// [`directories_only`][DirectoryCopyOptions::directories_only]), as well as `transform_path`.
+fn build_filtered_directory_copy_queue(
+    source_directory_path: &Path,
+    target_directory_path: &Path,
+    options: &DirectoryCopyOptions,
+    only_paths: Option<&OnlyPaths>,
+    transform_path: Option<&mut PathTransform>,
+    file_filter: Option<&mut FileFilter>,
+) -> Result<FilteredDirectoryCopyQueue, DirectoryError> {
+    let DirectoryCopyQueue {
+        mut operations,
+        skipped_entries,
+    } = build_directory_copy_queue(
+        source_directory_path,
+        target_directory_path,
+        options.minimum_copy_depth,
+        options.maximum_copy_depth,
+        options.special_file_behaviour,
+        options.broken_symlink_behaviour,
+        options.follow_root_symlink_only,
+        options.junction_behaviour,
+        options.symlink_target_rewrite,
+        options.traversal_order,
+        options.fast_enumeration,
+        file_filter,
+        options.max_file_size,
+        options.max_symlinks_to_follow,
+    )?;
+
+    if let Some(only_paths) = only_paths {
+        operations.retain(|operation| only_paths.includes(operation));
+    }
+
+    if let Some(transform_path) = transform_path {
+        operations = transform_operation_queue(operations, target_directory_path, transform_path)?;
+    }
+
+    let mut num_empty_directories_skipped = 0;
+
+    if options.skip_empty_directories {
+        let (remaining_operations, num_skipped) = remove_empty_directory_operations(operations);
+
+        operations = remaining_operations;
+        num_empty_directories_skipped = num_skipped;
+    }
+
+    if options.directories_only {
+        operations.retain(is_create_directory_operation);
+    }
+
+    Ok(FilteredDirectoryCopyQueue {
+        operations,
+        skipped_entries,
+        num_empty_directories_skipped,
+    })
+}
+
+/// Builds a [`CopyPlan`] from the (already collision-checked) queue of operations.
+fn build_copy_plan(
+    queue: &[QueuedOperation],
+    source_directory_path: &Path,
+    target_directory_path: &Path,
+    should_skip_existing_files: bool,
+    extraneous_target_paths: Vec<PathBuf>,
+) -> CopyPlan {
+    let mut total_bytes_to_copy = 0;
+
+    let operations = queue
+        .iter()
+        .map(|operation| match operation {
+            QueuedOperation::CopyFile {
+                source_file_path,
+                source_size_bytes,
+                target_file_path,
+            } => {
+                let target_action =
+                    planned_target_file_action(target_file_path, should_skip_existing_files);
+
+                if target_action != PlannedTargetAction::Skip {
+                    total_bytes_to_copy += source_size_bytes;
+                }
+
+                PlannedOperation::CopyFile {
+                    source_file_path: source_file_path.clone(),
+                    target_file_path: target_file_path.clone(),
+                    size_in_bytes: *source_size_bytes,
+                    target_action,
+                }
+            }
+            QueuedOperation::CreateDirectory {
+                target_directory_path,
+                ..
+            } => PlannedOperation::CreateDirectory {
+                target_directory_path: target_directory_path.clone(),
+                target_action: if target_directory_path.exists() {
+                    PlannedTargetAction::Overwrite
+                } else {
+                    PlannedTargetAction::Create
+                },
+            },
+            QueuedOperation::RecreateSpecialFile {
+                source_file_path,
+                target_file_path,
+            } => PlannedOperation::RecreateSpecialFile {
+                source_file_path: source_file_path.clone(),
+                target_file_path: target_file_path.clone(),
+                target_action: planned_target_file_action(
+                    target_file_path,
+                    should_skip_existing_files,
+                ),
+            },
+            QueuedOperation::RecreateBrokenSymlink {
+                link_destination_path,
+                target_file_path,
+            } => PlannedOperation::RecreateBrokenSymlink {
+                link_destination_path: link_destination_path.clone(),
+                target_file_path: target_file_path.clone(),
+                target_action: planned_target_file_action(
+                    target_file_path,
+                    should_skip_existing_files,
+                ),
+            },
+            QueuedOperation::RecreateSymlink {
+                link_destination_path,
+                target_file_path,
+                ..
+            } => PlannedOperation::RecreateSymlink {
+                link_destination_path: link_destination_path.clone(),
+                target_file_path: target_file_path.clone(),
+                target_action: planned_target_file_action(
+                    target_file_path,
+                    should_skip_existing_files,
+                ),
+            },
+            QueuedOperation::RecreateJunction {
+                junction_destination_path,
+                target_file_path,
+            } => PlannedOperation::RecreateJunction {
+                junction_destination_path: junction_destination_path.clone(),
+                target_file_path: target_file_path.clone(),
+                target_action: planned_target_file_action(
+                    target_file_path,
+                    should_skip_existing_files,
+                ),
+            },
+        })
+        .collect();
+
+    CopyPlan {
+        source_directory_path: source_directory_path.to_path_buf(),
+        target_directory_path: target_directory_path.to_path_buf(),
+        operations,
+        total_bytes_to_copy,
+        extraneous_target_paths,
+    }
+}
+
+
+/// Queries `statvfs` for the filesystem `target_directory_path` is on (or would be created on).
+///
+/// If `target_directory_path` does not exist yet, its closest existing ancestor is queried instead.
+/// Returns the queried path along with the filesystem statistics.
+#[cfg(unix)]
+fn target_filesystem_stats(
+    target_directory_path: &Path,
+) -> Result<(&Path, libc::statvfs), DirectoryError> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let existing_path = target_directory_path
+        .ancestors()
+        .find(|path| path.exists())
+        .ok_or(DirectoryError::InvalidTargetDirectoryPath)?;
+
+    let existing_path_c = CString::new(existing_path.as_os_str().as_bytes()).map_err(|_| {
+        DirectoryError::UnableToAccessTarget {
+            error: std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "target path contains an interior nul byte",
+            ),
+        }
+    })?;
+
+    let mut filesystem_stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: `existing_path_c` is a valid nul-terminated string and `filesystem_stats`
+    // is only read after `statvfs` reports that it has been initialized.
+    let filesystem_stats = unsafe {
+        if libc::statvfs(
+            existing_path_c.as_ptr(),
+            filesystem_stats.as_mut_ptr(),
+        ) != 0
+        {
+            return Err(DirectoryError::UnableToAccessTarget {
+                error: std::io::Error::last_os_error(),
+            });
+        }
+
+        filesystem_stats.assume_init()
+    };
+
+    Ok((existing_path, filesystem_stats))
+}
+
+/// Ensures the filesystem `target_directory_path` is on (or would be created on) is writable
+/// and has at least `required_bytes` of free space available.
+///
+/// If `target_directory_path` does not exist yet, its closest existing ancestor is checked instead.
+#[cfg(unix)]
+fn ensure_target_has_free_space(
+    target_directory_path: &Path,
+    required_bytes: u64,
+) -> Result<(), DirectoryError> {
+    let (existing_path, filesystem_stats) = target_filesystem_stats(target_directory_path)?;
+
+    #[allow(clippy::unnecessary_cast)]
+    if (filesystem_stats.f_flag as u64) & (libc::ST_RDONLY as u64) != 0 {
+        return Err(DirectoryError::ReadOnlyFilesystem {
+            path: existing_path.to_path_buf(),
+        });
+    }
+
+    // `f_bavail` is the number of blocks available to unprivileged users.
+    #[allow(clippy::unnecessary_cast)]
+    let available_bytes =
+        (filesystem_stats.f_bavail as u64).saturating_mul(filesystem_stats.f_frsize as u64);
+
+    if available_bytes < required_bytes {
+        return Err(DirectoryError::InsufficientFreeSpace {
+            required: required_bytes,
+            available: available_bytes,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn ensure_target_has_free_space(
+    _target_directory_path: &Path,
+    _required_bytes: u64,
+) -> Result<(), DirectoryError> {
+    Ok(())
+}
+
+/// Ensures the filesystem `target_directory_path` is on (or would be created on)
+/// has at least `required_inodes` free inodes available.
+///
+/// Filesystems that report a total of zero inodes (e.g. btrfs) allocate them dynamically,
+/// so they are not checked.
+#[cfg(unix)]
+fn ensure_target_has_free_inodes(
+    target_directory_path: &Path,
+    required_inodes: u64,
+) -> Result<(), DirectoryError> {
+    let (_, filesystem_stats) = target_filesystem_stats(target_directory_path)?;
+
+    #[allow(clippy::unnecessary_cast)]
+    if filesystem_stats.f_files as u64 == 0 {
+        return Ok(());
+    }
+
+    // `f_favail` is the number of inodes available to unprivileged users.
+    #[allow(clippy::unnecessary_cast)]
+    let available_inodes = filesystem_stats.f_favail as u64;
+
+    if available_inodes < required_inodes {
+        return Err(DirectoryError::InsufficientInodes {
+            required: required_inodes,
+            available: available_inodes,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn ensure_target_has_free_inodes(
+    _target_directory_path: &Path,
+    _required_inodes: u64,
+) -> Result<(), DirectoryError> {
+    Ok(())
+}
+
+/// Returns the total size of all files that would be copied by the given queue of operations.
+fn total_file_size_of_operation_queue(queue: &[QueuedOperation]) -> u64 {
+    queue
+        .iter()
+        .map(|operation| match operation {
+            QueuedOperation::CopyFile {
+                source_size_bytes, ..
+            } => *source_size_bytes,
+            _ => 0,
+        })
+        .sum()
+}
+
+
+/// Maps a [`FileError`] that was returned while copying `source_file_path` to `target_file_path`
+/// as part of a directory copy to the corresponding [`DirectoryError`].
+fn directory_error_from_file_copy_error(
+    error: FileError,
+    source_file_path: &Path,
+    target_file_path: &Path,
+) -> DirectoryError {
+    match error {
+        FileError::NotFound => DirectoryError::SourceContentsInvalid,
+        FileError::NotAFile => DirectoryError::SourceContentsInvalid,
+        FileError::SourceModifiedDuringCopy => DirectoryError::SourceContentsInvalid,
+        FileError::UnableToAccessSourceFile { error } => DirectoryError::IoDuringOperation {
+            path: source_file_path.to_path_buf(),
+            operation: IoOperation::ReadSource,
+            source: error,
+        },
+        FileError::AlreadyExists => DirectoryError::TargetItemAlreadyExists {
+            path: target_file_path.to_path_buf(),
+        },
+        FileError::UnableToAccessTargetFile { error } => DirectoryError::IoDuringOperation {
+            path: target_file_path.to_path_buf(),
+            operation: IoOperation::WriteTarget,
+            source: error,
+        },
+        FileError::TargetParentNotADirectory { path } => {
+            DirectoryError::TargetParentNotADirectory { path }
+        }
+        FileError::ReadOnlyFilesystem { path } => DirectoryError::ReadOnlyFilesystem { path },
+        FileError::SourceAndTargetAreTheSameFile => DirectoryError::InvalidTargetDirectoryPath,
+        FileError::UnableToCreateTemporaryFile { error } => DirectoryError::IoDuringOperation {
+            path: target_file_path.to_path_buf(),
+            operation: IoOperation::WriteTarget,
+            source: error,
+        },
+        FileError::UnableToEnumerateAlternateDataStreams { error } => {
+            DirectoryError::IoDuringOperation {
+                path: source_file_path.to_path_buf(),
+                operation: IoOperation::ReadSource,
+                source: error,
+            }
+        }
+        FileError::UnableToPreserveOwnership { error } => {
+            DirectoryError::UnableToPreserveOwnership {
+                path: target_file_path.to_path_buf(),
+                error,
+            }
+        }
+        // None of these can occur when copying a file (e.g. copying never requires files to be on the same filesystem).
+        error @ (FileError::NotOnSameFilesystem
+        | FileError::VerificationFailed
+        | FileError::InvalidTargetFileName { .. }
+        | FileError::FileTooLarge { .. }
+        | FileError::InvalidUtf8 { .. }) => DirectoryError::OtherReason {
+            reason: error.to_string(),
+        },
+        FileError::OtherIoError { error } => DirectoryError::IoDuringOperation {
+            path: target_file_path.to_path_buf(),
+            operation: IoOperation::CopyFile,
+            source: error,
+        },
+    }
+}
+
+
+/// Returns [`DirectoryError::ReadOnlyFilesystem`] instead of the given `error`
+/// if it was caused by creating or writing to a target path on a read-only filesystem.
+fn classify_read_only_filesystem_error(error: DirectoryError) -> DirectoryError {
+    match error {
+        DirectoryError::IoDuringOperation {
+            path,
+            operation,
+            source,
+        } if operation != IoOperation::ReadSource && is_read_only_filesystem_error(&source) => {
+            DirectoryError::ReadOnlyFilesystem { path }
+        }
+        error => error,
+    }
+}
+
+
+/// Given [`QueuedOperation::RecreateSpecialFile`] data, this function
+/// recreates the given special file on the target.
+///
+/// If the target path already exists, it is removed first if `should_overwrite_files` is `true`,
+/// otherwise an error is returned.
+fn execute_recreate_special_file_operation(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    should_overwrite_files: bool,
+) -> Result<(), DirectoryError> {
+    if target_file_path.exists() {
+        if target_file_path.is_dir() || !should_overwrite_files {
+            return Err(DirectoryError::TargetItemAlreadyExists {
+                path: target_file_path.to_path_buf(),
+            });
+        }
+
+        fs::remove_file(target_file_path).map_err(|error| DirectoryError::IoDuringOperation {
+            path: target_file_path.to_path_buf(),
+            operation: IoOperation::WriteTarget,
+            source: error,
+        })?;
+    }
+
+    recreate_special_file(source_file_path, target_file_path).map_err(|error| {
+        DirectoryError::IoDuringOperation {
+            path: target_file_path.to_path_buf(),
+            operation: IoOperation::WriteTarget,
+            source: error,
+        }
+    })
+}
+
+
+/// Given [`QueuedOperation::RecreateBrokenSymlink`] or [`QueuedOperation::RecreateSymlink`] data,
+/// this function creates a symbolic link at `target_link_path` that points to `link_destination_path`.
+///
+/// If something already exists at `target_link_path`, it is replaced if `should_overwrite_files` is `true`
+/// (unless it is a directory), otherwise an error is returned.
+fn execute_recreate_symlink_operation(
+    link_destination_path: &Path,
+    target_link_path: &Path,
+    points_to_directory: bool,
+    should_overwrite_files: bool,
+) -> Result<(), DirectoryError> {
+    // Unlike `exists`, this also detects (broken) symbolic links that are already present.
+    if let Ok(target_metadata) = fs::symlink_metadata(target_link_path) {
+        if target_metadata.is_dir() || !should_overwrite_files {
+            return Err(DirectoryError::TargetItemAlreadyExists {
+                path: target_link_path.to_path_buf(),
+            });
+        }
+
+        fs::remove_file(target_link_path).map_err(|error| DirectoryError::IoDuringOperation {
+            path: target_link_path.to_path_buf(),
+            operation: IoOperation::WriteTarget,
+            source: error,
+        })?;
+    }
+
+    create_symbolic_link(
+        link_destination_path,
+        target_link_path,
+        points_to_directory,
+    )
+    .map_err(|error| DirectoryError::IoDuringOperation {
+        path: target_link_path.to_path_buf(),
+        operation: IoOperation::WriteTarget,
+        source: error,
+    })
+}
+
+
+/// Given [`QueuedOperation::RecreateJunction`] data, this function creates a directory junction
+/// at `target_junction_path` that points to `junction_destination_path`.
+///
+/// If something already exists at `target_junction_path`, it is replaced if `should_overwrite_files` is `true`
+/// (unless it is a directory), otherwise an error is returned.
+fn execute_recreate_junction_operation(
+    junction_destination_path: &Path,
+    target_junction_path: &Path,
+    should_overwrite_files: bool,
+) -> Result<(), DirectoryError> {
+    if let Ok(target_metadata) = fs::symlink_metadata(target_junction_path) {
+        if target_metadata.is_dir() || !should_overwrite_files {
+            return Err(DirectoryError::TargetItemAlreadyExists {
+                path: target_junction_path.to_path_buf(),
+            });
+        }
+
+        fs::remove_file(target_junction_path).map_err(|error| {
+            DirectoryError::IoDuringOperation {
+                path: target_junction_path.to_path_buf(),
+                operation: IoOperation::WriteTarget,
+                source: error,
+            }
+        })?;
+    }
+
+    create_junction(junction_destination_path, target_junction_path).map_err(|error| {
+        DirectoryError::IoDuringOperation {
+            path: target_junction_path.to_path_buf(),
+            operation: IoOperation::WriteTarget,
+            source: error,
+        }
+    })
+}
+
+
+/// Copies the permissions and (on Unix) the modification time of the source directory
+/// onto the target directory (see e.g. [`DirectoryCopyOptions::preserve_root_metadata`]).
+fn copy_directory_metadata(
+    source_directory_path: &Path,
+    target_directory_path: &Path,
+) -> Result<(), DirectoryError> {
+    let source_metadata =
+        fs::metadata(source_directory_path).map_err(|error| DirectoryError::IoDuringOperation {
+            path: source_directory_path.to_path_buf(),
+            operation: IoOperation::ReadSource,
+            source: error,
+        })?;
+
+    let write_target_error = |error: std::io::Error| DirectoryError::IoDuringOperation {
+        path: target_directory_path.to_path_buf(),
+        operation: IoOperation::WriteTarget,
+        source: error,
+    };
+
+    #[cfg(unix)]
+    {
+        use std::{ffi::CString, os::unix::ffi::OsStrExt, os::unix::fs::MetadataExt};
+
+        let target_directory_path_c = CString::new(target_directory_path.as_os_str().as_bytes())
+            .map_err(|error| write_target_error(error.into()))?;
+
+        // The access time is left untouched.
+        let times = [
+            libc::timespec {
+                tv_sec: 0,
+                tv_nsec: libc::UTIME_OMIT,
+            },
+            libc::timespec {
+                tv_sec: source_metadata.mtime() as libc::time_t,
+                tv_nsec: source_metadata.mtime_nsec() as _,
+            },
+        ];
+
+        // SAFETY: the path is a valid NUL-terminated string and `times` contains exactly two timespecs.
+        let result = unsafe {
+            libc::utimensat(
+                libc::AT_FDCWD,
+                target_directory_path_c.as_ptr(),
+                times.as_ptr(),
+                0,
+            )
+        };
+
+        if result != 0 {
+            return Err(write_target_error(std::io::Error::last_os_error()));
+        }
+    }
+
+    // Permissions are set last, as they might prevent us from modifying the directory any further.
+    fs::set_permissions(
+        target_directory_path,
+        source_metadata.permissions(),
+    )
+    .map_err(write_target_error)?;
+
+    Ok(())
+}
+
+
+/// Sets the permission `mode` on the file or directory at `target_path`
+/// (see e.g. [`DirectoryCopyOptions::force_mode`]).
+#[cfg(unix)]
+fn apply_forced_mode(target_path: &Path, mode: u32) -> Result<(), DirectoryError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(target_path, std::fs::Permissions::from_mode(mode)).map_err(|error| {
+        DirectoryError::IoDuringOperation {
+            path: target_path.to_path_buf(),
+            operation: IoOperation::WriteTarget,
+            source: error,
+        }
+    })
+}
+
+#[cfg(not(unix))]
+fn apply_forced_mode(_target_path: &Path, _mode: u32) -> Result<(), DirectoryError> {
+    Ok(())
+}
+
+
+/// Changes the owner and group of `target_path` to match `source_path`
+/// (see [`DirectoryCopyOptions::preserve_ownership`]).
+fn preserve_ownership(source_path: &Path, target_path: &Path) -> Result<(), DirectoryError> {
+    copy_ownership(source_path, target_path).map_err(|error| {
+        DirectoryError::UnableToPreserveOwnership {
+            path: target_path.to_path_buf(),
+            error,
+        }
+    })
+}
+
+
+/// Keeps track of the directories created during a copy with a forced permission mode
+/// (see e.g. [`DirectoryCopyOptions::force_mode`]).
+///
+/// Their mode is only applied once the copy is finished, as a restrictive mode
+/// could otherwise prevent their contents from being copied.
+struct ForcedMode {
+    mode: u32,
+    created_directory_paths: Vec<PathBuf>,
+}
+
+impl ForcedMode {
+    fn new(mode: u32) -> Self {
+        Self {
+            mode,
+            created_directory_paths: Vec::new(),
+        }
+    }
+
+    fn register_created_directory(&mut self, directory_path: PathBuf) {
+        self.created_directory_paths.push(directory_path);
+    }
+
+    /// Applies the mode to all registered directories, deepest ones first,
+    /// so that a mode without the execute bit doesn't prevent us from reaching their subdirectories.
+    fn apply_to_created_directories(self) -> Result<(), DirectoryError> {
+        for directory_path in self.created_directory_paths.iter().rev() {
+            apply_forced_mode(directory_path, self.mode)?;
+        }
+
+        Ok(())
+    }
+}
+
+
+/// Collects the entries of a copy's manifest as files are copied,
+/// see [`DirectoryCopyOptions::produce_manifest`].
+struct ManifestBuilder<'a> {
+    /// The root of the target directory, which manifest paths are relative to.
+    target_directory_root_path: &'a Path,
+
+    /// Algorithm to compute the checksums with.
+    checksum_algorithm: ChecksumAlgorithm,
+
+    entries: Vec<CopyManifestEntry>,
+}
+
+impl<'a> ManifestBuilder<'a> {
+    fn new(target_directory_root_path: &'a Path, checksum_algorithm: ChecksumAlgorithm) -> Self {
+        Self {
+            target_directory_root_path,
+            checksum_algorithm,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds the file at `target_file_path` to the manifest.
+    fn add_file(&mut self, target_file_path: &Path, size_bytes: u64, checksum: String) {
+        let relative_path = target_file_path
+            .strip_prefix(self.target_directory_root_path)
+            .unwrap_or(target_file_path)
+            .to_path_buf();
+
+        self.entries.push(CopyManifestEntry {
+            relative_path,
+            size_bytes,
+            checksum,
+        });
+    }
+}
+
+
+/// Compares each copied file (given as a pair of source and target file paths)
+/// against its source file, see [`DirectoryCopyOptions::verify_after_copy`].
+///
+/// Returns `Err(`[`DirectoryError::VerificationFailed`]`)` for the first target file that doesn't match.
+fn verify_copied_files(
+    copied_files: &[(PathBuf, PathBuf)],
+    verify_mode: VerifyMode,
+) -> Result<(), DirectoryError> {
+    for (source_file_path, target_file_path) in copied_files {
+        let read_source_error = |error: std::io::Error| DirectoryError::IoDuringOperation {
+            path: source_file_path.clone(),
+            operation: IoOperation::ReadSource,
+            source: error,
+        };
+        let read_target_error = |error: std::io::Error| DirectoryError::IoDuringOperation {
+            path: target_file_path.clone(),
+            operation: IoOperation::ReadTarget,
+            source: error,
+        };
+
+        let files_match = match verify_mode {
+            VerifyMode::SizeOnly => {
+                let source_size_bytes = fs::metadata(source_file_path)
+                    .map_err(read_source_error)?
+                    .len();
+                let target_size_bytes = fs::metadata(target_file_path)
+                    .map_err(read_target_error)?
+                    .len();
+
+                source_size_bytes == target_size_bytes
+            }
+            VerifyMode::ContentHash(checksum_algorithm) => {
+                let source_checksum = compute_file_checksum(source_file_path, checksum_algorithm)
+                    .map_err(read_source_error)?;
+                let target_checksum = compute_file_checksum(target_file_path, checksum_algorithm)
+                    .map_err(read_target_error)?;
+
+                source_checksum == target_checksum
+            }
+        };
+
+        if !files_match {
+            return Err(DirectoryError::VerificationFailed {
+                path: target_file_path.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+
+/// A previous copy of the source directory to hard-link unchanged files from,
+/// see [`copy_directory_incremental`].
+struct PreviousCopy<'a> {
+    /// The root of the target directory the source directory is being copied to.
+    target_directory_root_path: &'a Path,
+
+    /// The root of the previous copy of the source directory.
+    previous_copy_root_path: &'a Path,
+}
+
+impl<'a> PreviousCopy<'a> {
+    /// Returns the path of the file in the previous copy that corresponds to `target_file_path`,
+    /// but only if that file is unchanged compared to the `source_file_path`.
+    ///
+    /// If the files can't be compared (e.g. the previous file does not exist), `None` is returned.
+    fn find_unchanged_file(
+        &self,
+        source_file_path: &Path,
+        target_file_path: &Path,
+    ) -> Option<PathBuf> {
+        let relative_file_path = target_file_path
+            .strip_prefix(self.target_directory_root_path)
+            .ok()?;
+        let previous_file_path = self.previous_copy_root_path.join(relative_file_path);
+
+        let source_metadata = fs::metadata(source_file_path).ok()?;
+        let previous_metadata = fs::metadata(&previous_file_path).ok()?;
+
+        if is_file_unchanged(
+            source_file_path,
+            &source_metadata,
+            &previous_file_path,
+            &previous_metadata,
+        ) {
+            Some(previous_file_path)
+        } else {
+            None
+        }
+    }
+}
+
+
+/// Returns `true` if the file at `other_file_path` is an unchanged copy of the file at `source_file_path`:
+/// it has the same size and either the same modification time or the same contents.
+fn is_file_unchanged(
+    source_file_path: &Path,
+    source_metadata: &Metadata,
+    other_file_path: &Path,
+    other_metadata: &Metadata,
+) -> bool {
+    if !other_metadata.is_file() || other_metadata.len() != source_metadata.len() {
+        return false;
+    }
+
+    let has_same_modification_time = match (
+        source_metadata.modified(),
+        other_metadata.modified(),
+    ) {
+        (Ok(source_modified), Ok(other_modified)) => source_modified == other_modified,
+        _ => false,
+    };
+
+    has_same_modification_time
+        || files_have_same_contents(source_file_path, other_file_path).unwrap_or(false)
+}
+
+
+/// Compares the source file to the target file it would be merged into, see [`MergeFileReason`].
+fn merge_file_reason(
+    source_file_path: &Path,
+    target_file_path: &Path,
+) -> Result<MergeFileReason, DirectoryError> {
+    let target_metadata = match fs::metadata(target_file_path) {
+        Ok(target_metadata) => target_metadata,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(MergeFileReason::New);
+        }
+        Err(error) => {
+            return Err(DirectoryError::IoDuringOperation {
+                path: target_file_path.to_path_buf(),
+                operation: IoOperation::ReadSource,
+                source: error,
+            });
+        }
+    };
+
+    let source_metadata =
+        fs::metadata(source_file_path).map_err(|error| DirectoryError::IoDuringOperation {
+            path: source_file_path.to_path_buf(),
+            operation: IoOperation::ReadSource,
+            source: error,
+        })?;
+
+    if is_file_unchanged(
+        source_file_path,
+        &source_metadata,
+        target_file_path,
+        &target_metadata,
+    ) {
+        return Ok(MergeFileReason::Identical);
+    }
+
+    let reason = match (
+        source_metadata.modified(),
+        target_metadata.modified(),
+    ) {
+        (Ok(source_modified), Ok(target_modified)) if source_modified > target_modified => {
+            MergeFileReason::SourceIsNewer
+        }
+        (Ok(source_modified), Ok(target_modified)) if source_modified < target_modified => {
+            MergeFileReason::TargetIsNewer
+        }
+        _ => MergeFileReason::Different,
+    };
+
+    Ok(reason)
+}
+
+
+/// Builds a [`MergePlan`] from the (already collision-checked) queue of operations.
+fn build_merge_plan(
+    queue: &[QueuedOperation],
+    source_directory_path: &Path,
+    target_directory_path: &Path,
+    should_skip_existing_files: bool,
+    extraneous_target_paths: Vec<PathBuf>,
+) -> Result<MergePlan, DirectoryError> {
+    let mut files = Vec::new();
+    let mut directories_to_create = Vec::new();
+
+    for operation in queue {
+        match operation {
+            QueuedOperation::CopyFile {
+                source_file_path,
+                source_size_bytes,
+                target_file_path,
+            } => {
+                files.push(PlannedMergeFile {
+                    source_file_path: source_file_path.clone(),
+                    target_file_path: target_file_path.clone(),
+                    size_in_bytes: *source_size_bytes,
+                    target_action: planned_target_file_action(
+                        target_file_path,
+                        should_skip_existing_files,
+                    ),
+                    reason: merge_file_reason(source_file_path, target_file_path)?,
+                });
+            }
+            QueuedOperation::CreateDirectory {
+                target_directory_path,
+                ..
+            } if !target_directory_path.exists() => {
+                directories_to_create.push(target_directory_path.clone());
+            }
+            _ => {}
+        }
+    }
+
+    Ok(MergePlan {
+        source_directory_path: source_directory_path.to_path_buf(),
+        target_directory_path: target_directory_path.to_path_buf(),
+        files,
+        directories_to_create,
+        extraneous_target_paths,
+    })
+}
+
+
+/// Returns `Ok(true)` if the two files have exactly the same contents.
+fn files_have_same_contents(
+    first_file_path: &Path,
+    second_file_path: &Path,
+) -> std::io::Result<bool> {
+    use std::io::Read;
+
+    let mut first_file = fs::File::open(first_file_path)?;
+    let mut second_file = fs::File::open(second_file_path)?;
+
+    let mut first_buffer = vec![0u8; DEFAULT_COPY_BUFFER_SIZE];
+    let mut second_buffer = vec![0u8; DEFAULT_COPY_BUFFER_SIZE];
+
+    loop {
+        let num_bytes_read = first_file.read(&mut first_buffer)?;
+        if num_bytes_read == 0 {
+            // The first file has ended, so the second one must have ended as well.
+            return Ok(second_file.read(&mut second_buffer[..1])? == 0);
+        }
+
+        second_file.read_exact(&mut second_buffer[..num_bytes_read])?;
+
+        if first_buffer[..num_bytes_read] != second_buffer[..num_bytes_read] {
+            return Ok(false);
+        }
+    }
+}
+
+
+/// Returns `true` if the given operation would write to an existing target file (or symbolic link or special file)
+/// that must be left as it is, i.e. if `should_skip_existing_files` is `true`
+/// (see [`TargetDirectoryRule::should_skip_existing_files`]).
+fn is_operation_skipped_due_to_existing_target(
+    operation: &QueuedOperation,
+    should_skip_existing_files: bool,
+) -> bool {
+    if !should_skip_existing_files {
+        return false;
+    }
+
+    let target_file_path = match operation {
+        QueuedOperation::CopyFile {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateSpecialFile {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateBrokenSymlink {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateSymlink {
+            target_file_path, ..
+        }
+        | QueuedOperation::RecreateJunction {
+            target_file_path, ..
+        } => target_file_path,
+        QueuedOperation::CreateDirectory { .. } => return false,
+    };
+
+    // An existing directory in place of a file is still an error, which the operation itself reports.
+    fs::symlink_metadata(target_file_path)
+        .map(|target_metadata| !target_metadata.is_dir())
+        .unwrap_or(false)
+}
+
+
+/// Target paths that must be kept when deleting extraneous target entries,
+/// see [`DirectoryCopyOptions::delete_extraneous_from_target`].
+struct KeptTargetPaths {
+    /// Target paths corresponding to source entries (regardless of whether they were copied),
+    /// as well as the target paths of everything that was copied, created or skipped by the copy.
+    target_paths: HashSet<PathBuf>,
+
+    /// Target paths corresponding to source symbolic links to directories.
+    /// All of their contents are kept as well, as the links are not walked.
+    linked_directory_target_paths: HashSet<PathBuf>,
+}
+
+impl KeptTargetPaths {
+    /// Collects the target paths corresponding to all entries of the source directory,
+    /// before any filtering or depth limits are applied.
+    ///
+    /// If `follows_symbolic_links` is `true`, the copy descends into source symbolic links to directories,
+    /// so the contents of the corresponding target directories are kept as well.
+    fn from_source_mapping(
+        source_directory_path: &Path,
+        target_directory_path: &Path,
+        follows_symbolic_links: bool,
+    ) -> Result<Self, DirectoryError> {
+        let mut kept_target_paths = Self {
+            target_paths: HashSet::new(),
+            linked_directory_target_paths: HashSet::new(),
+        };
+
+        for entry in walk(source_directory_path, WalkOptions::default()) {
+            let entry = entry?;
+
+            let target_path = rejoin_source_subpath_onto_target(
+                source_directory_path,
+                &entry.path,
+                target_directory_path,
+            )?;
+
+            if follows_symbolic_links && entry.file_type.is_symlink() && entry.path.is_dir() {
+                kept_target_paths
+                    .linked_directory_target_paths
+                    .insert(target_path.clone());
+            }
+
+            kept_target_paths.target_paths.insert(target_path);
+        }
+
+        Ok(kept_target_paths)
+    }
+
+    /// Keeps the given target path (e.g. of a copied file), along with its ancestors.
+    fn insert(&mut self, target_path: &Path) {
+        for ancestor_path in target_path.ancestors() {
+            if !self.target_paths.insert(ancestor_path.to_path_buf()) {
+                break;
+            }
+        }
+    }
+
+    fn contains(&self, target_path: &Path) -> bool {
+        self.target_paths.contains(target_path)
+            || target_path
+                .ancestors()
+                .skip(1)
+                .any(|ancestor_path| self.linked_directory_target_paths.contains(ancestor_path))
+    }
+}
+
+
+/// Finds every entry in the target directory that isn't kept (see [`KeptTargetPaths`]),
+/// in the order [`walk`] visits them in.
+///
+/// If the target directory doesn't exist (yet), there are no extraneous entries.
+fn find_extraneous_target_entries(
+    target_directory_path: &Path,
+    kept_target_paths: &KeptTargetPaths,
+) -> Result<Vec<WalkEntry>, DirectoryError> {
+    let mut extraneous_entries = Vec::new();
+
+    if !target_directory_path.is_dir() {
+        return Ok(extraneous_entries);
+    }
+
+    for entry in walk(target_directory_path, WalkOptions::default()) {
+        let entry = entry?;
+
+        if !kept_target_paths.contains(&entry.path) {
+            extraneous_entries.push(entry);
+        }
+    }
+
+    Ok(extraneous_entries)
+}
+
+
+/// Lists the paths [`delete_extraneous_target_entries`] would remove, in the order it would remove them in.
+fn plan_extraneous_target_entries(
+    target_directory_path: &Path,
+    kept_target_paths: Option<&KeptTargetPaths>,
+) -> Result<Vec<PathBuf>, DirectoryError> {
+    let kept_target_paths = match kept_target_paths {
+        Some(kept_target_paths) => kept_target_paths,
+        None => return Ok(Vec::new()),
+    };
+
+    Ok(
+        find_extraneous_target_entries(target_directory_path, kept_target_paths)?
+            .into_iter()
+            .rev()
+            .map(|entry| entry.path)
+            .collect(),
+    )
+}
+
+
+/// Removes every entry in the target directory that isn't kept (see [`KeptTargetPaths`]),
+/// see [`DirectoryCopyOptions::delete_extraneous_from_target`].
+///
+/// Returns the number of removed files and directories.
+fn delete_extraneous_target_entries(
+    target_directory_path: &Path,
+    kept_target_paths: &KeptTargetPaths,
+) -> Result<usize, DirectoryError> {
+    let extraneous_entries =
+        find_extraneous_target_entries(target_directory_path, kept_target_paths)?;
+
+    let mut num_deleted_entries = 0;
+
+    // Entries are kept along with their ancestors, so the contents of an extraneous directory
+    // are extraneous as well and the directory is already empty by the time it is removed.
+    let _ = remove_walked_entries_in_reverse(
+        extraneous_entries,
+        |_| Ok(Some(())),
+        |_, ()| {
+            num_deleted_entries += 1;
+            ControlFlow::Continue(())
+        },
+    )?;
+
+    Ok(num_deleted_entries)
+}
+
+
+/// The set of relative paths a copy is restricted to, see [`copy_directory_only_paths`].
+struct OnlyPaths<'a> {
+    /// The root of the target directory, which the listed paths are matched against.
+    target_directory_root_path: &'a Path,
+
+    listed_paths: &'a HashSet<PathBuf>,
+
+    /// All ancestors of the listed paths (relative to the root), which are created, but not copied in full.
+    ancestor_paths: HashSet<&'a Path>,
+}
+
+impl<'a> OnlyPaths<'a> {
+    fn new(target_directory_root_path: &'a Path, listed_paths: &'a HashSet<PathBuf>) -> Self {
+        let ancestor_paths = listed_paths
+            .iter()
+            .flat_map(|listed_path| listed_path.ancestors().skip(1))
+            .filter(|ancestor_path| !ancestor_path.as_os_str().is_empty())
+            .collect();
+
+        Self {
+            target_directory_root_path,
+            listed_paths,
+            ancestor_paths,
+        }
+    }
+
+    /// Returns `true` if the given operation is part of the copy, i.e. it is a listed path,
+    /// inside a listed directory or an ancestor directory of a listed path.
+    ///
+    /// This must be called before any path transformation is applied,
+    /// as the target path is used to determine the operation's relative path.
+    fn includes(&self, operation: &QueuedOperation) -> bool {
+        let relative_path = match operation
+            .target_path()
+            .strip_prefix(self.target_directory_root_path)
+        {
+            Ok(relative_path) => relative_path,
+            Err(_) => return false,
+        };
+
+        if self.ancestor_paths.contains(relative_path) {
+            return is_create_directory_operation(operation);
+        }
+
+        relative_path
+            .ancestors()
+            .any(|ancestor_path| self.listed_paths.contains(ancestor_path))
+    }
+}
+
+
+/// Returns `Err(`[`DirectoryError::ListedPathsNotFound`]`)` if any of the `listed_paths`
+/// don't exist in the source directory, see [`copy_directory_only_paths`].
+fn ensure_listed_paths_exist(
+    source_directory_root_path: &Path,
+    listed_paths: Option<&HashSet<PathBuf>>,
+) -> Result<(), DirectoryError> {
+    let listed_paths = match listed_paths {
+        Some(listed_paths) => listed_paths,
+        None => return Ok(()),
+    };
+
+    let missing_paths = find_missing_listed_paths(source_directory_root_path, listed_paths)?;
+
+    if !missing_paths.is_empty() {
+        return Err(DirectoryError::ListedPathsNotFound {
+            paths: missing_paths,
+        });
+    }
+
+    Ok(())
+}
+
+
+/// Returns the paths of `listed_paths` that don't exist in the source directory,
+/// in sorted order (see [`copy_directory_only_paths`]).
+///
+/// Paths that are not plain relative paths (e.g. absolute ones or ones containing `..`)
+/// are returned as well, as they can't be matched against the copied entries.
+fn find_missing_listed_paths(
+    source_directory_root_path: &Path,
+    listed_paths: &HashSet<PathBuf>,
+) -> Result<Vec<PathBuf>, DirectoryError> {
+    let mut missing_paths = Vec::new();
+
+    for listed_path in listed_paths {
+        let is_relative = listed_path.is_relative()
+            && listed_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+
+        if !is_relative {
+            missing_paths.push(listed_path.clone());
+            continue;
+        }
+
+        let source_path = source_directory_root_path.join(listed_path);
+
+        match fs::symlink_metadata(&source_path) {
+            Ok(_) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                missing_paths.push(listed_path.clone());
+            }
+            Err(error) => {
+                return Err(DirectoryError::IoDuringOperation {
+                    path: source_path,
+                    operation: IoOperation::ReadSource,
+                    source: error,
+                });
+            }
+        }
+    }
+
+    missing_paths.sort();
+
+    Ok(missing_paths)
+}
+
+
+/// Returns `true` if the given operation creates a directory,
+/// see [`DirectoryCopyOptions::directories_only`].
+fn is_create_directory_operation(operation: &QueuedOperation) -> bool {
+    matches!(operation, QueuedOperation::CreateDirectory { .. })
+}
+
+
+/// Returns `true` if the given file copy error was caused by the source or target file
+/// being open or locked by another process (see [`LockedFileBehaviour`]).
+fn is_locked_file_copy_error(error: &FileError) -> bool {
+    match error {
+        FileError::UnableToAccessSourceFile { error }
+        | FileError::UnableToAccessTargetFile { error }
+        | FileError::OtherIoError { error } => is_locked_file_error(error),
+        _ => false,
+    }
+}
+
+
+/// Calls `copy_operation` (which copies a single file), handling errors caused by the file
+/// being locked according to the given [`LockedFileBehaviour`].
+///
+/// Returns `Ok(None)` if the file was skipped because it was locked.
+fn copy_file_respecting_locked_file_behaviour<T, C>(
+    locked_file_behaviour: LockedFileBehaviour,
+    mut copy_operation: C,
+) -> Result<Option<T>, FileError>
+where
+    C: FnMut() -> Result<T, FileError>,
+{
+    let mut retries_left = match locked_file_behaviour {
+        LockedFileBehaviour::Retry { retries, .. } => retries,
+        LockedFileBehaviour::Error | LockedFileBehaviour::Skip => 0,
+    };
+
+    loop {
+        let error = match copy_operation() {
+            Ok(value) => return Ok(Some(value)),
+            Err(error) => error,
+        };
+
+        if !is_locked_file_copy_error(&error) {
+            return Err(error);
+        }
+
+        match locked_file_behaviour {
+            LockedFileBehaviour::Error => return Err(error),
+            LockedFileBehaviour::Skip => return Ok(None),
+            LockedFileBehaviour::Retry { delay, .. } => {
+                if retries_left == 0 {
+                    return Err(error);
+                }
+
+                retries_left -= 1;
+                std::thread::sleep(delay);
+            }
+        }
+    }
+}
+
+
+/// Executes a single [`QueuedOperation`] (copying a file, creating a directory or recreating a special file)
+/// as part of [`copy_directory`], updating `finished_copy` accordingly.
+///
+/// Collisions with existing target files or directories are checked for right before the operation
+/// and are resolved according to `should_overwrite_files` and `should_overwrite_directories`.
+///
+/// If `previous_copy` is set, unchanged files are hard-linked from it instead of being copied.
+/// If `copied_files` is set, the source and target path of each copied (or linked) file are added to it.
+/// If `should_preserve_ownership` is `true`, created entries are given the owner and group of their source.
+/// Files that are open or locked by another process are handled according to `locked_file_behaviour`.
+#[allow(clippy::too_many_arguments)]
+fn execute_queued_operation(
+    operation: QueuedOperation,
+    should_overwrite_files: bool,
+    should_overwrite_directories: bool,
+    should_skip_existing_files: bool,
+    should_preserve_ownership: bool,
+    locked_file_behaviour: LockedFileBehaviour,
+    previous_copy: Option<&PreviousCopy>,
+    forced_mode: Option<&mut ForcedMode>,
+    manifest: Option<&mut ManifestBuilder>,
+    copied_files: Option<&mut Vec<(PathBuf, PathBuf)>>,
+    finished_copy: &mut FinishedDirectoryCopy,
+) -> Result<(), DirectoryError> {
+    if is_operation_skipped_due_to_existing_target(&operation, should_skip_existing_files) {
+        finished_copy.num_existing_files_skipped += 1;
+        return Ok(());
+    }
+
+    match operation {
+        QueuedOperation::CopyFile {
+            source_file_path: source_path,
+            source_size_bytes,
+            target_file_path: target_path,
+        } => {
+            let target_exists = target_path.exists();
+
+            if target_exists {
+                if !target_path.is_file() {
+                    return Err(DirectoryError::TargetItemAlreadyExists {
+                        path: target_path.clone(),
+                    });
+                }
+
+                if !should_overwrite_files {
+                    return Err(DirectoryError::TargetItemAlreadyExists {
+                        path: target_path.clone(),
+                    });
+                }
+            }
+
+            let unchanged_previous_file_path = previous_copy.and_then(|previous_copy| {
+                previous_copy.find_unchanged_file(&source_path, &target_path)
+            });
+
+            if let Some(previous_file_path) = unchanged_previous_file_path {
+                let write_target_error =
+                    |error: std::io::Error| DirectoryError::IoDuringOperation {
+                        path: target_path.clone(),
+                        operation: IoOperation::WriteTarget,
+                        source: error,
+                    };
+
+                if target_exists {
+                    fs::remove_file(&target_path).map_err(write_target_error)?;
+                }
+
+                fs::hard_link(&previous_file_path, &target_path).map_err(write_target_error)?;
+
+                if let Some(manifest) = manifest {
+                    let checksum = compute_file_checksum(&target_path, manifest.checksum_algorithm)
+                        .map_err(|error| DirectoryError::IoDuringOperation {
+                            path: target_path.clone(),
+                            operation: IoOperation::ReadSource,
+                            source: error,
+                        })?;
+
+                    manifest.add_file(&target_path, source_size_bytes, checksum);
+                }
+
+                if let Some(copied_files) = copied_files {
+                    copied_files.push((source_path, target_path));
+                }
+
+                finished_copy.num_files_linked += 1;
+                return Ok(());
+            }
+
+            let checksum_algorithm = manifest
+                .as_ref()
+                .map(|manifest| manifest.checksum_algorithm);
+
+            let copied_file =
+                copy_file_respecting_locked_file_behaviour(locked_file_behaviour, || {
+                    match checksum_algorithm {
+                        Some(checksum_algorithm) => copy_file_with_checksum(
+                            &source_path,
+                            &target_path,
+                            should_overwrite_files,
+                            checksum_algorithm,
+                        )
+                        .map(Some),
+                        None => copy_file(
+                            &source_path,
+                            &target_path,
+                            FileCopyOptions {
+                                overwrite_existing: should_overwrite_files,
+                                skip_existing: false,
+                                overwrite_only_if_source_is_newer: false,
+                                atomic: false,
+                                sparse: SparseMode::Never,
+                                preserve_alternate_data_streams: false,
+                                copy_method: CopyMethod::Standard,
+                                preserve_ownership: should_preserve_ownership,
+                                preserve_creation_time: false,
+                                target_permissions: None,
+                            },
+                        )
+                        .map(|_| None),
+                    }
+                })
+                .map_err(|error| {
+                    directory_error_from_file_copy_error(error, &source_path, &target_path)
+                })?;
+
+            let copied_with_checksum = match copied_file {
+                Some(copied_with_checksum) => copied_with_checksum,
+                None => {
+                    finished_copy.num_locked_files_skipped += 1;
+                    return Ok(());
                 }
+            };
+
+            if let Some((num_bytes_copied, checksum)) = copied_with_checksum {
+                // Unlike `copy_file`, copying with a checksum doesn't take care of ownership.
+                if should_preserve_ownership {
+                    preserve_ownership(&source_path, &target_path)?;
+                }
+
+                if let Some(manifest) = manifest {
+                    manifest.add_file(&target_path, num_bytes_copied, checksum);
+                }
+            }
+
+            if let Some(forced_mode) = forced_mode {
+                apply_forced_mode(&target_path, forced_mode.mode)?;
+            }
+
+            if let Some(copied_files) = copied_files {
+                copied_files.push((source_path, target_path));
+            }
+
+            finished_copy.num_files_copied += 1;
+            finished_copy.total_bytes_copied += source_size_bytes;
+        }
+        QueuedOperation::CreateDirectory {
+            source_directory_path,
+            source_size_bytes,
+            target_directory_path,
+        } => {
+            if target_directory_path.exists() {
+                if !target_directory_path.is_dir() {
+                    return Err(DirectoryError::TargetItemAlreadyExists {
+                        path: target_directory_path.clone(),
+                    });
+                }
+
+                if !should_overwrite_directories {
+                    return Err(DirectoryError::TargetItemAlreadyExists {
+                        path: target_directory_path.clone(),
+                    });
+                }
+
+                return Ok(());
+            }
+
+            fs::create_dir(&target_directory_path).map_err(|error| {
+                DirectoryError::IoDuringOperation {
+                    path: target_directory_path.clone(),
+                    operation: IoOperation::CreateDirectory,
+                    source: error,
+                }
+            })?;
+
+            if should_preserve_ownership {
+                if let Some(source_directory_path) = source_directory_path {
+                    preserve_ownership(&source_directory_path, &target_directory_path)?;
+                }
+            }
+
+            if let Some(forced_mode) = forced_mode {
+                forced_mode.register_created_directory(target_directory_path);
+            }
+
+            finished_copy.num_directories_created += 1;
+            finished_copy.total_bytes_copied += source_size_bytes;
+        }
+        QueuedOperation::RecreateSpecialFile {
+            source_file_path,
+            target_file_path,
+        } => {
+            execute_recreate_special_file_operation(
+                &source_file_path,
+                &target_file_path,
+                should_overwrite_files,
+            )?;
+
+            if should_preserve_ownership {
+                preserve_ownership(&source_file_path, &target_file_path)?;
             }
+
+            finished_copy.num_special_files_recreated += 1;
         }
-    }
+        QueuedOperation::RecreateBrokenSymlink {
+            link_destination_path,
+            target_file_path,
+        } => {
+            execute_recreate_symlink_operation(
+                &link_destination_path,
+                &target_file_path,
+                false,
+                should_overwrite_files,
+            )?;
+
+            finished_copy.num_broken_symlinks_recreated += 1;
+        }
+        QueuedOperation::RecreateSymlink {
+            link_destination_path,
+            target_file_path,
+            points_to_directory,
+        } => {
+            execute_recreate_symlink_operation(
+                &link_destination_path,
+                &target_file_path,
+                points_to_directory,
+                should_overwrite_files,
+            )?;
+
+            finished_copy.num_symlinks_recreated += 1;
+        }
+        QueuedOperation::RecreateJunction {
+            junction_destination_path,
+            target_file_path,
+        } => {
+            execute_recreate_junction_operation(
+                &junction_destination_path,
+                &target_file_path,
+                should_overwrite_files,
+            )?;
+
+            finished_copy.num_junctions_recreated += 1;
+        }
+    };
 
-    Ok(operation_queue)
+    Ok(())
 }
 
-/// Given a list of queued operations, this function validates that
-/// the files we'd be copying into or target directories we'd create don't exist yet
-/// (or however the [`TargetDirectoryRule`] is configured).
-fn check_operation_queue_for_collisions(
-    queue: &[QueuedOperation],
-    target_directory_rules: &TargetDirectoryRule,
-) -> Result<(), DirectoryError> {
-    let can_overwrite_files = target_directory_rules.should_overwrite_existing_files();
-    let can_overwrite_directories = target_directory_rules.should_overwrite_existing_directories();
 
-    if can_overwrite_files && can_overwrite_directories {
-        // There is nothing to check, we can't have any collisions
-        // if we allow everything to be overwritten.
-        return Ok(());
-    }
+/// Perform a copy from `source_directory_path` to `validated_target_path`.
+///
+/// For more details, see [`copy_directory`].
+///
+/// If `transform_path` is set, target paths are transformed as described in
+/// [`copy_directory_with_path_transform`] (which implies a pre-scan).
+/// If `file_filter` is set, files are filtered as described in [`copy_directory_with_file_filter`].
+/// If `on_plan` is set, it is called with the [`CopyPlan`] as described in [`copy_directory_with_plan`]
+/// (which implies a pre-scan).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn copy_directory_unchecked<S>(
+    source_directory_path: S,
+    validated_target_path: ValidatedTargetPath,
+    options: DirectoryCopyOptions,
+    transform_path: Option<&mut PathTransform>,
+    mut file_filter: Option<&mut FileFilter>,
+    on_plan: Option<&mut PlanHandler>,
+    previous_copy_path: Option<&Path>,
+    only_paths: Option<&HashSet<PathBuf>>,
+) -> Result<FinishedDirectoryCopy, DirectoryError>
+where
+    S: Into<PathBuf>,
+{
+    let source_directory_path: PathBuf = source_directory_path.into();
+    let ValidatedTargetPath {
+        target_directory_path,
+        target_directory_exists,
+        target_directory_is_empty,
+    } = validated_target_path;
 
-    for queue_item in queue {
-        match queue_item {
-            QueuedOperation::CopyFile {
-                target_file_path, ..
-            } if !can_overwrite_files => {
-                if target_file_path.exists() {
-                    return Err(DirectoryError::TargetItemAlreadyExists {
-                        path: target_file_path.clone(),
-                    });
-                }
+    let should_overwrite_files = options
+        .target_directory_rule
+        .should_overwrite_existing_files();
+    let should_overwrite_directories = options
+        .target_directory_rule
+        .should_overwrite_existing_directories();
+    let should_skip_existing_files = options.target_directory_rule.should_skip_existing_files();
+
+    let mut finished_copy = FinishedDirectoryCopy {
+        total_bytes_copied: 0,
+        num_files_copied: 0,
+        num_directories_created: 0,
+        num_special_files_recreated: 0,
+        num_special_files_skipped: 0,
+        num_broken_symlinks_recreated: 0,
+        num_broken_symlinks_skipped: 0,
+        num_symlinks_recreated: 0,
+        num_junctions_recreated: 0,
+        num_junctions_skipped: 0,
+        num_empty_directories_skipped: 0,
+        num_files_filtered_out: 0,
+        num_files_linked: 0,
+        num_existing_files_skipped: 0,
+        num_locked_files_skipped: 0,
+        num_extraneous_entries_deleted: 0,
+        manifest: None,
+        skipped_oversized_file_paths: Vec::new(),
+    };
+
+    ensure_listed_paths_exist(&source_directory_path, only_paths)?;
+
+    let only_paths =
+        only_paths.map(|only_paths| OnlyPaths::new(&target_directory_path, only_paths));
+
+    let previous_copy = previous_copy_path.map(|previous_copy_root_path| PreviousCopy {
+        target_directory_root_path: &target_directory_path,
+        previous_copy_root_path,
+    });
+
+    // Target paths that are part of the source mapping or the copy, used to find extraneous target entries.
+    let mut kept_target_paths = if options.delete_extraneous_from_target {
+        Some(KeptTargetPaths::from_source_mapping(
+            &source_directory_path,
+            &target_directory_path,
+            !options.follow_root_symlink_only,
+        )?)
+    } else {
+        None
+    };
+
+    // Initialize a queue of file copy or directory create operations (unless we're copying
+    // while traversing, in which case operations are executed as soon as they are discovered).
+    let operation_queue = if options.pre_scan
+        || options.ensure_free_space
+        || options.ensure_free_inodes
+        || options.skip_empty_directories
+        || options.collect_all_collisions
+        || on_plan.is_some()
+        || transform_path.is_some()
+    {
+        let FilteredDirectoryCopyQueue {
+            operations,
+            skipped_entries,
+            num_empty_directories_skipped,
+        } = build_filtered_directory_copy_queue(
+            &source_directory_path,
+            &target_directory_path,
+            &options,
+            only_paths.as_ref(),
+            transform_path,
+            file_filter.as_deref_mut(),
+        )?;
+
+        finished_copy.num_empty_directories_skipped = num_empty_directories_skipped;
+
+        // We should do a reasonable target directory file/directory collision check and return a TargetItemAlreadyExists early,
+        // before we copy any file at all. This way the target directory stays intact as often as possible,
+        // instead of returning an error after having copied some files already (which would be hard to reverse).
+        // It's still possible that due to a race condition we don't catch a collision here yet,
+        // but that should be very rare and is essentially unsolvable (unless there was
+        // a robust rollback mechanism, which is out of scope for this project).
+        check_operation_queue_for_collisions(
+            &operations,
+            &options.target_directory_rule,
+            options.collect_all_collisions,
+        )?;
+
+        if options.ensure_free_space {
+            ensure_target_has_free_space(
+                &target_directory_path,
+                total_file_size_of_operation_queue(&operations),
+            )?;
+        }
+
+        if options.ensure_free_inodes {
+            ensure_target_has_free_inodes(&target_directory_path, operations.len() as u64)?;
+        }
+
+        if let Some(kept_target_paths) = kept_target_paths.as_mut() {
+            for operation in &operations {
+                kept_target_paths.insert(operation.target_path());
             }
-            QueuedOperation::CreateDirectory {
-                target_directory_path,
-                ..
-            } if !can_overwrite_directories => {
-                if target_directory_path.exists() {
-                    return Err(DirectoryError::TargetItemAlreadyExists {
-                        path: target_directory_path.clone(),
-                    });
-                }
+        }
+
+        if let Some(on_plan) = on_plan {
+            on_plan(&build_copy_plan(
+                &operations,
+                &source_directory_path,
+                &target_directory_path,
+                should_skip_existing_files,
+                plan_extraneous_target_entries(&target_directory_path, kept_target_paths.as_ref())?,
+            ));
+        }
+
+        finished_copy.num_special_files_skipped = skipped_entries.num_special_files;
+        finished_copy.num_broken_symlinks_skipped = skipped_entries.num_broken_symlinks;
+        finished_copy.num_junctions_skipped = skipped_entries.num_junctions;
+        finished_copy.num_files_filtered_out = skipped_entries.num_filtered_files;
+        finished_copy.skipped_oversized_file_paths = skipped_entries.oversized_file_paths;
+        Some(operations)
+    } else {
+        None
+    };
+
+    let mut forced_mode = options.force_mode.map(ForcedMode::new);
+
+    let mut manifest = options
+        .produce_manifest
+        .map(|checksum_algorithm| ManifestBuilder::new(&target_directory_path, checksum_algorithm));
+
+    // Source and target paths of the copied files, used to verify them once the copy has finished.
+    let mut copied_files = options.verify_after_copy.map(|_| Vec::new());
+
+    // Create root target directory if needed.
+    if !target_directory_exists {
+        fs::create_dir_all(&target_directory_path).map_err(|error| {
+            DirectoryError::IoDuringOperation {
+                path: target_directory_path.clone(),
+                operation: IoOperation::CreateDirectory,
+                source: error,
             }
-            _ => {}
+        })?;
+
+        finished_copy.num_directories_created += 1;
+
+        if options.preserve_ownership {
+            preserve_ownership(&source_directory_path, &target_directory_path)?;
+        }
+    }
+
+    match operation_queue {
+        Some(operation_queue) => {
+            // So we've built the entire queue of operations and made sure there are no collisions we should worry about.
+            // What's left is performing the copy and directory create operations *precisely in the defined order*.
+            // If we ignore the order, we could get into situations where
+            // a directory doesn't exist yet, but we would want to copy a file into it.
+            // Instead, the `visit_directory_copy_operations` takes care of the correct operation order.
+            for operation in operation_queue {
+                execute_queued_operation(
+                    operation,
+                    should_overwrite_files,
+                    should_overwrite_directories,
+                    should_skip_existing_files,
+                    options.preserve_ownership,
+                    options.locked_file_behaviour,
+                    previous_copy.as_ref(),
+                    forced_mode.as_mut(),
+                    manifest.as_mut(),
+                    copied_files.as_mut(),
+                    &mut finished_copy,
+                )?;
+            }
+        }
+        None => {
+            // Operations are visited in an order that is safe to execute in immediately
+            // (see `visit_directory_copy_operations`). Collisions are checked for lazily, per operation,
+            // which means a late collision will leave the files copied before it in place.
+            let skipped_entries = visit_directory_copy_operations(
+                &source_directory_path,
+                &target_directory_path,
+                options.minimum_copy_depth,
+                options.maximum_copy_depth,
+                options.special_file_behaviour,
+                options.broken_symlink_behaviour,
+                options.follow_root_symlink_only,
+                options.junction_behaviour,
+                options.symlink_target_rewrite,
+                options.traversal_order,
+                options.fast_enumeration,
+                file_filter,
+                options.max_file_size,
+                options.max_symlinks_to_follow,
+                |operation| {
+                    if options.directories_only && !is_create_directory_operation(&operation) {
+                        return Ok(());
+                    }
+
+                    if let Some(only_paths) = only_paths.as_ref() {
+                        if !only_paths.includes(&operation) {
+                            return Ok(());
+                        }
+                    }
+
+                    if let Some(kept_target_paths) = kept_target_paths.as_mut() {
+                        kept_target_paths.insert(operation.target_path());
+                    }
+
+                    execute_queued_operation(
+                        operation,
+                        should_overwrite_files,
+                        should_overwrite_directories,
+                        should_skip_existing_files,
+                        options.preserve_ownership,
+                        options.locked_file_behaviour,
+                        previous_copy.as_ref(),
+                        forced_mode.as_mut(),
+                        manifest.as_mut(),
+                        copied_files.as_mut(),
+                        &mut finished_copy,
+                    )
+                },
+            )?;
+
+            finished_copy.num_special_files_skipped = skipped_entries.num_special_files;
+            finished_copy.num_broken_symlinks_skipped = skipped_entries.num_broken_symlinks;
+            finished_copy.num_junctions_skipped = skipped_entries.num_junctions;
+            finished_copy.num_files_filtered_out = skipped_entries.num_filtered_files;
+            finished_copy.skipped_oversized_file_paths = skipped_entries.oversized_file_paths;
+        }
+    }
+
+    finished_copy.manifest = manifest.map(|manifest| manifest.entries);
+
+    if let (Some(verify_mode), Some(copied_files)) = (options.verify_after_copy, copied_files) {
+        verify_copied_files(&copied_files, verify_mode)?;
+    }
+
+    if let Some(kept_target_paths) = kept_target_paths {
+        finished_copy.num_extraneous_entries_deleted =
+            delete_extraneous_target_entries(&target_directory_path, &kept_target_paths)?;
+    }
+
+    if let Some(forced_mode) = forced_mode {
+        forced_mode.apply_to_created_directories()?;
+    }
+
+    // This must happen last, as copying the contents modifies the target directory.
+    let should_preserve_root_metadata = options.preserve_root_metadata
+        && (!target_directory_exists
+            || target_directory_is_empty.unwrap_or(true)
+            || should_overwrite_directories);
+
+    if should_preserve_root_metadata {
+        copy_directory_metadata(&source_directory_path, &target_directory_path)?;
+    }
+
+    if let Some(mode) = options.force_mode {
+        if !target_directory_exists || should_preserve_root_metadata {
+            apply_forced_mode(&target_directory_path, mode)?;
         }
     }
 
-    Ok(())
+    Ok(finished_copy)
+}
+
+
+/// Copy a directory from `source_directory_path` to `target_directory_path`.
+///
+/// - `source_directory_path` must point to an existing directory path. This is checked before anything else:
+///   if nothing exists there, `Err(`[`DirectoryError::SourceDirectoryNotFound`]`)` is returned,
+///   and if it is not a directory (e.g. a file), `Err(`[`DirectoryError::SourceDirectoryIsNotADirectory`]`)` is returned.
+/// - `target_directory_path` represents a path to the directory that will contain `source_directory_path`'s contents.
+///
+/// ### Target directory
+/// Depending on the [`options.target_directory_rule`][DirectoryCopyOptions::target_directory_rule] option,
+/// the `target_directory_path` must:
+/// - [`DisallowExisting`][TargetDirectoryRule::DisallowExisting]: not exist,
+/// - [`AllowEmpty`][TargetDirectoryRule::AllowEmpty]: either not exist or be empty,
+/// - [`AllowNonEmpty`][TargetDirectoryRule::AllowNonEmpty]: either not exist, be empty, or be non-empty. Additionally,
+///   the specified overwriting rules are respected (see fields), or,
+/// - [`Merge`][TargetDirectoryRule::Merge]: either not exist, be empty, or be non-empty. Existing subdirectories
+///   are merged into and existing files are overwritten or skipped (see fields).
+///
+/// If the specified target directory rule does not hold,
+/// `Err(`[`DirectoryError::InvalidTargetDirectoryPath`]`)` or
+/// `Err(`[`DirectoryError::TargetDirectoryIsNotEmpty`]`)` is returned (depending on the rule).
+///
+/// If [`options.copy_into_subdirectory`][DirectoryCopyOptions::copy_into_subdirectory] is enabled,
+/// the above applies to the `target_directory_path/<source directory name>` directory instead.
+///
+/// ### Copy depth
+/// Depending on the [`DirectoryCopyOptions::maximum_copy_depth`] option, calling this function means copying:
+/// - `Some(0)` -- nothing, only the target directory itself is created,
+/// - `Some(1)` -- a single directory and its direct descendants (files and direct directories, but *not their contents*, i.e. just empty directories),
+/// - `Some(2+)` -- files and subdirectories (and their files and directories, etc.) up to a certain depth limit (e.g. `Some(2)` copies direct descendants as well as one layer deeper),
+/// - `None` -- the entire subtree. **This is probably what you want most of the time**.
+///
+/// A depth larger than that of the source tree copies the entire subtree, the same as `None`.
+///
+/// ## Symbolic links
+/// - If the `source_directory_path` directory contains a symbolic link to a file,
+///   the contents of the file it points to will be copied
+///   into the corresponding subpath inside `target_file_path`
+///   (same behaviour as `cp` without `-P` on Unix, i.e. link is followed, but not preserved).
+/// - If the `source_directory_path` directory contains a symbolic link to a directory,
+///   the directory and its contents will be copied as normal - the links will be followed, but not preserved.
+/// - If the `source_directory_path` directory contains a broken symbolic link (one whose destination does not exist),
+///   it is handled according to the `broken_symlink_behaviour` option, see [`BrokenSymlinkBehaviour`].
+/// - On Windows, directory junctions are followed like symbolic links to directories by default,
+///   but can also be recreated or skipped with the `junction_behaviour` option, see [`JunctionBehaviour`].
+/// - If `target_directory_path` is a symbolic link to a directory, it is followed and the contents are
+///   copied into the directory it points to. If that directory is the source directory or is inside it,
+///   `Err(`[`DirectoryError::InvalidTargetDirectoryPath`]`)` is returned, same as for a non-symlinked path.
+///
+/// ## Special files
+/// FIFOs, sockets and device nodes are handled according to the
+/// [`options.special_file_behaviour`][DirectoryCopyOptions::special_file_behaviour] option,
+/// see [`SpecialFileBehaviour`].
+///
+/// ## Locked files
+/// On Windows, files that are open or locked by another process can't be read. These are handled
+/// according to the [`options.locked_file_behaviour`][DirectoryCopyOptions::locked_file_behaviour] option,
+/// see [`LockedFileBehaviour`]. By default, such a file aborts the copy with an error.
+///
+/// ## Collisions
+/// By default ([`options.pre_scan`][DirectoryCopyOptions::pre_scan] is `true`), the entire source directory
+/// is scanned before anything is copied, so a collision with an existing target file or directory
+/// results in an error *before any file is copied*.
+///
+/// If `pre_scan` is `false`, entries are copied as they are discovered and collisions are checked for
+/// per entry. In that case, **a late collision leaves earlier files already copied**.
+///
+/// ### Return value
+/// Upon success, the function returns information about the files and directories that were copied or created
+/// as well as the total amount of bytes copied, see [`FinishedDirectoryCopy`].
+pub fn copy_directory<S, T>(
+    source_directory_path: S,
+    target_directory_path: T,
+    options: DirectoryCopyOptions,
+) -> Result<FinishedDirectoryCopy, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    let (source_directory_path, validated_target_path) = validate_directory_copy_paths(
+        source_directory_path.as_ref(),
+        target_directory_path.as_ref(),
+        &options,
+    )?;
+
+    copy_directory_unchecked(
+        source_directory_path,
+        validated_target_path,
+        options,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .map_err(classify_read_only_filesystem_error)
+}
+
+
+/// Copy a directory from `source_directory_path` to `target_directory_path`,
+/// hard-linking unchanged files from `previous_copy_path` instead of copying them.
+///
+/// This function behaves like [`copy_directory`], except that `previous_copy_path` is treated as
+/// a previous copy of the source directory (e.g. the previous backup). Each source file is compared
+/// to the file at the same relative path in that directory. If that file has the same size and
+/// either the same modification time or the same contents, it is hard-linked to the target path
+/// instead of being copied. This saves both time and space when making repeated copies
+/// of a directory that changes little between them.
+///
+/// The previous copy must be on the same filesystem as the target directory,
+/// otherwise creating the hard links fails. The number of linked files is reported in
+/// [`FinishedDirectoryCopy::num_files_linked`].
+pub fn copy_directory_incremental<S, T, P>(
+    source_directory_path: S,
+    target_directory_path: T,
+    previous_copy_path: P,
+    options: DirectoryCopyOptions,
+) -> Result<FinishedDirectoryCopy, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+    P: AsRef<Path>,
+{
+    let (source_directory_path, validated_target_path) = validate_directory_copy_paths(
+        source_directory_path.as_ref(),
+        target_directory_path.as_ref(),
+        &options,
+    )?;
+
+    copy_directory_unchecked(
+        source_directory_path,
+        validated_target_path,
+        options,
+        None,
+        None,
+        None,
+        Some(previous_copy_path.as_ref()),
+        None,
+    )
+    .map_err(classify_read_only_filesystem_error)
+}
+
+
+/// Copy only the listed paths of the directory at `source_directory_path` to `target_directory_path`.
+///
+/// This function behaves like [`copy_directory`], except that only the paths in `only_paths`
+/// (relative to the source directory) are copied, along with their ancestor directories.
+/// Everything else is skipped.
+///
+/// Paths are matched exactly, component by component, so no globbing or case folding is performed.
+/// Listing a directory copies it along with all of its contents, while its ancestors
+/// are only created, not copied in full. Options still apply to the listed paths, e.g. a listed
+/// file beyond the [`maximum_copy_depth`][DirectoryCopyOptions::maximum_copy_depth] is not copied.
+///
+/// Before anything is copied, each listed path is checked to exist in the source directory.
+/// If any of them don't (or aren't plain relative paths, e.g. contain `.` or `..`), the copy fails with
+/// [`DirectoryError::ListedPathsNotFound`] containing all such paths.
+pub fn copy_directory_only_paths<S, T, I, P>(
+    source_directory_path: S,
+    target_directory_path: T,
+    only_paths: I,
+    options: DirectoryCopyOptions,
+) -> Result<FinishedDirectoryCopy, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+    I: IntoIterator<Item = P>,
+    P: Into<PathBuf>,
+{
+    let only_paths: HashSet<PathBuf> = only_paths.into_iter().map(Into::into).collect();
+
+    let (source_directory_path, validated_target_path) = validate_directory_copy_paths(
+        source_directory_path.as_ref(),
+        target_directory_path.as_ref(),
+        &options,
+    )?;
+
+    copy_directory_unchecked(
+        source_directory_path,
+        validated_target_path,
+        options,
+        None,
+        None,
+        None,
+        None,
+        Some(&only_paths),
+    )
+    .map_err(classify_read_only_filesystem_error)
+}
+
+
+/// Copy a directory from `source_directory_path` to `target_directory_path`,
+/// mapping each entry's target path through `transform_path`.
+///
+/// This function behaves like [`copy_directory`], except that for each file, directory or special file
+/// in the source directory, `transform_path` is called with its path relative to the source directory
+/// (e.g. `subdirectory/file.txt`). It should return:
+/// - `Some(relative_target_path)` -- the entry is copied to `relative_target_path` inside the target directory
+///   (any missing parent directories are created), or,
+/// - `None` -- the entry is skipped. If the entry is a directory, its entire contents are skipped as well.
+///
+/// Note that returning a different path for a directory does *not* move its contents along with it --
+/// `transform_path` is called for each of them separately, with their original relative paths.
+///
+/// Because two entries can't be copied to the same place, `transform_path` is called for all entries
+/// before anything is copied (i.e. [`options.pre_scan`][DirectoryCopyOptions::pre_scan] is always enabled).
+///
+/// ### Errors
+/// In addition to the errors [`copy_directory`] can return, this returns
+/// - `Err(`[`DirectoryError::InvalidTransformedPath`]`)` if `transform_path` returns
+///   an absolute or empty path or a path containing `.` or `..`, and
+/// - `Err(`[`DirectoryError::DuplicateTransformedPath`]`)` if `transform_path` maps
+///   two entries to the same path (or a file to a path that must be a directory).
+///
+/// Collisions with existing files and directories in the target directory are checked for
+/// against the transformed paths, as described in the `Collisions` section of [`copy_directory`].
+pub fn copy_directory_with_path_transform<S, T, F>(
+    source_directory_path: S,
+    target_directory_path: T,
+    options: DirectoryCopyOptions,
+    mut transform_path: F,
+) -> Result<FinishedDirectoryCopy, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+    F: FnMut(&Path) -> Option<PathBuf>,
+{
+    let (source_directory_path, validated_target_path) = validate_directory_copy_paths(
+        source_directory_path.as_ref(),
+        target_directory_path.as_ref(),
+        &options,
+    )?;
+
+    copy_directory_unchecked(
+        source_directory_path,
+        validated_target_path,
+        options,
+        Some(&mut transform_path),
+        None,
+        None,
+        None,
+        None,
+    )
+    .map_err(classify_read_only_filesystem_error)
+}
+
+
+/// Copy a directory from `source_directory_path` to `target_directory_path`,
+/// copying only the files for which `file_filter` returns `true`.
+///
+/// This function behaves like [`copy_directory`], except that `file_filter` is called
+/// with the path and metadata of each file in the source directory. This makes it possible to copy
+/// e.g. only files larger than a certain size or files modified after a certain point in time.
+///
+/// If `file_filter` returns `false`, the file is skipped. Skipped files are counted in
+/// [`FinishedDirectoryCopy::num_files_filtered_out`].
+///
+/// Directories, special files and broken symbolic links are not passed to `file_filter`, meaning
+/// directories are still created, even if all their files have been filtered out
+/// (unless [`options.skip_empty_directories`][DirectoryCopyOptions::skip_empty_directories] is enabled).
+///
+/// ## Symbolic links
+/// For a symbolic link to a file, `file_filter` is called with the path of the link,
+/// but with the metadata of the file it points to.
+pub fn copy_directory_with_file_filter<S, T, F>(
+    source_directory_path: S,
+    target_directory_path: T,
+    options: DirectoryCopyOptions,
+    mut file_filter: F,
+) -> Result<FinishedDirectoryCopy, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+    F: FnMut(&Path, &Metadata) -> bool,
+{
+    let (source_directory_path, validated_target_path) = validate_directory_copy_paths(
+        source_directory_path.as_ref(),
+        target_directory_path.as_ref(),
+        &options,
+    )?;
+
+    copy_directory_unchecked(
+        source_directory_path,
+        validated_target_path,
+        options,
+        None,
+        Some(&mut file_filter),
+        None,
+        None,
+        None,
+    )
+    .map_err(classify_read_only_filesystem_error)
 }
 
 
-/// Perform a copy from `source_directory_path` to `validated_target_path`.
+/// Copy a directory from `source_directory_path` to `target_directory_path`,
+/// calling `on_plan` with the complete [`CopyPlan`] before anything is copied.
 ///
-/// For more details, see [`copy_directory`].
-pub(crate) fn copy_directory_unchecked<S>(
+/// This function behaves like [`copy_directory`], except that once the source directory has been scanned
+/// and checked for collisions, `on_plan` receives every file that will be copied, every directory
+/// that will be created, and whether each target will be created, overwritten or skipped
+/// (e.g. to show the plan to the user). The copy proceeds once `on_plan` returns.
+///
+/// The plan can only be built by scanning the entire source directory first,
+/// so [`options.pre_scan`][DirectoryCopyOptions::pre_scan] is always enabled.
+/// If the collision check fails, `on_plan` is not called.
+pub fn copy_directory_with_plan<S, T, F>(
     source_directory_path: S,
-    validated_target_path: ValidatedTargetPath,
+    target_directory_path: T,
     options: DirectoryCopyOptions,
+    on_plan: F,
 ) -> Result<FinishedDirectoryCopy, DirectoryError>
 where
-    S: Into<PathBuf>,
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+    F: FnOnce(&CopyPlan),
 {
-    let source_directory_path: PathBuf = source_directory_path.into();
-    let ValidatedTargetPath {
-        target_directory_path,
-        target_directory_exists,
-        ..
-    } = validated_target_path;
-
-    let should_overwrite_files = options
-        .target_directory_rule
-        .should_overwrite_existing_files();
-    let should_overwrite_directories = options
-        .target_directory_rule
-        .should_overwrite_existing_directories();
-
-    // Initialize a queue of file copy or directory create operations.
-    let operation_queue = build_directory_copy_queue(
-        &source_directory_path,
-        &target_directory_path,
-        options.maximum_copy_depth,
+    let (source_directory_path, validated_target_path) = validate_directory_copy_paths(
+        source_directory_path.as_ref(),
+        target_directory_path.as_ref(),
+        &options,
     )?;
 
-    // We should do a reasonable target directory file/directory collision check and return a TargetItemAlreadyExists early,
-    // before we copy any file at all. This way the target directory stays intact as often as possible,
-    // instead of returning an error after having copied some files already (which would be hard to reverse).
-    // It's still possible that due to a race condition we don't catch a collision here yet,
-    // but that should be very rare and is essentially unsolvable (unless there was
-    // a robust rollback mechanism, which is out of scope for this project).
-    check_operation_queue_for_collisions(&operation_queue, &options.target_directory_rule)?;
-
-    // So we've built the entire queue of operations and made sure there are no collisions we should worry about.
-    // What's left is performing the copy and directory create operations *precisely in the defined order*.
-    // If we ignore the order, we could get into situations where
-    // a directory doesn't exist yet, but we would want to copy a file into it.
-    // Instead, the `build_directory_copy_queue` takes care of the correct operation order.
-
-    let mut total_bytes_copied = 0;
-    let mut num_files_copied = 0;
-    let mut num_directories_created = 0;
-
-    // Create root target directory if needed.
-    if !target_directory_exists {
-        fs::create_dir_all(target_directory_path)
-            .map_err(|error| DirectoryError::UnableToAccessTarget { error })?;
-
-        num_directories_created += 1;
-    }
-
-    // Execute all queued operations (copying files and creating directories).
-    for operation in operation_queue {
-        match operation {
-            QueuedOperation::CopyFile {
-                source_file_path: source_path,
-                source_size_bytes,
-                target_file_path: target_path,
-            } => {
-                if target_path.exists() {
-                    if !target_path.is_file() {
-                        return Err(DirectoryError::TargetItemAlreadyExists {
-                            path: target_path.clone(),
-                        });
-                    }
-
-                    if !should_overwrite_files {
-                        return Err(DirectoryError::TargetItemAlreadyExists {
-                            path: target_path.clone(),
-                        });
-                    }
-                }
-
-                copy_file(
-                    source_path,
-                    &target_path,
-                    FileCopyOptions {
-                        overwrite_existing: should_overwrite_files,
-                        skip_existing: false,
-                    },
-                )
-                .map_err(|error| match error {
-                    FileError::NotFound => DirectoryError::SourceContentsInvalid,
-                    FileError::NotAFile => DirectoryError::SourceContentsInvalid,
-                    FileError::UnableToAccessSourceFile { error } => {
-                        DirectoryError::UnableToAccessSource { error }
-                    }
-                    FileError::AlreadyExists => DirectoryError::TargetItemAlreadyExists {
-                        path: target_path.clone(),
-                    },
-                    FileError::UnableToAccessTargetFile { error } => {
-                        DirectoryError::UnableToAccessTarget { error }
-                    }
-                    FileError::SourceAndTargetAreTheSameFile => {
-                        DirectoryError::InvalidTargetDirectoryPath
-                    }
-                    FileError::OtherIoError { error } => DirectoryError::OtherIoError { error },
-                })?;
-
-                num_files_copied += 1;
-                total_bytes_copied += source_size_bytes;
-            }
-            QueuedOperation::CreateDirectory {
-                source_size_bytes,
-                target_directory_path,
-            } => {
-                if target_directory_path.exists() {
-                    if !target_directory_path.is_dir() {
-                        return Err(DirectoryError::TargetItemAlreadyExists {
-                            path: target_directory_path.clone(),
-                        });
-                    }
-
-                    if !should_overwrite_directories {
-                        return Err(DirectoryError::TargetItemAlreadyExists {
-                            path: target_directory_path.clone(),
-                        });
-                    }
-
-                    continue;
-                }
-
-                fs::create_dir(target_directory_path)
-                    .map_err(|error| DirectoryError::UnableToAccessTarget { error })?;
-
-                num_directories_created += 1;
-                total_bytes_copied += source_size_bytes;
-            }
-        };
-    }
+    let mut on_plan = Some(on_plan);
+    let mut plan_handler = |plan: &CopyPlan| {
+        if let Some(on_plan) = on_plan.take() {
+            on_plan(plan);
+        }
+    };
 
-    Ok(FinishedDirectoryCopy {
-        total_bytes_copied,
-        num_files_copied,
-        num_directories_created,
-    })
+    copy_directory_unchecked(
+        source_directory_path,
+        validated_target_path,
+        options,
+        None,
+        None,
+        Some(&mut plan_handler),
+        None,
+        None,
+    )
+    .map_err(classify_read_only_filesystem_error)
 }
 
 
-/// Copy a directory from `source_directory_path` to `target_directory_path`.
-///
-/// - `source_directory_path` must point to an existing directory path.
-/// - `target_directory_path` represents a path to the directory that will contain `source_directory_path`'s contents.
-///
-/// ### Target directory
-/// Depending on the [`options.target_directory_rule`][DirectoryCopyOptions::target_directory_rule] option,
-/// the `target_directory_path` must:
-/// - [`DisallowExisting`][TargetDirectoryRule::DisallowExisting]: not exist,
-/// - [`AllowEmpty`][TargetDirectoryRule::AllowEmpty]: either not exist or be empty, or,
-/// - [`AllowNonEmpty`][TargetDirectoryRule::AllowNonEmpty]: either not exist, be empty, or be non-empty. Additionally,
-///   the specified overwriting rules are respected (see fields).
+/// Lists the changes copying `source_directory_path` into `target_directory_path` would make,
+/// without copying anything.
 ///
-/// If the specified target directory rule does not hold,
-/// `Err(`[`DirectoryError::InvalidTargetDirectoryPath`]`)` or
-/// `Err(`[`DirectoryError::TargetDirectoryIsNotEmpty`]`)` is returned (depending on the rule).
-///
-/// ### Copy depth
-/// Depending on the [`DirectoryCopyOptions::maximum_copy_depth`] option, calling this function means copying:
-/// - `Some(0)` -- a single directory and its direct descendants (files and direct directories, but *not their contents*, i.e. just empty directories),
-/// - `Some(1+)` -- files and subdirectories (and their files and directories, etc.) up to a certain depth limit (e.g. `Some(1)` copies direct descendants as well as one layer deeper),
-/// - `None` -- the entire subtree. **This is probably what you want most of the time**.
+/// This is meant for previewing a [`TargetDirectoryRule::Merge`] copy (e.g. in a sync UI):
+/// for each source file, the returned [`MergePlan`] contains whether its target would be created,
+/// overwritten or skipped, along with the reason (see [`MergeFileReason`]), e.g. whether an existing
+/// target file is older than or identical to the source file. Directories that would be created are listed as well.
 ///
-/// ## Symbolic links
-/// - If the `source_directory_path` directory contains a symbolic link to a file,
-/// the contents of the file it points to will be copied
-/// into the corresponding subpath inside `target_file_path`
-/// (same behaviour as `cp` without `-P` on Unix, i.e. link is followed, but not preserved).
-/// - If the `source_directory_path` directory contains a symbolic link to a directory,
-/// the directory and its contents will be copied as normal - the links will be followed, but not preserved.
+/// The plan follows `options` (including its [`target_directory_rule`][DirectoryCopyOptions::target_directory_rule])
+/// exactly as [`copy_directory`] would, and returns the same errors if the copy would fail before copying anything
+/// (e.g. due to a collision). Symbolic links, special files and junctions are not part of the plan;
+/// use [`copy_directory_with_plan`] for a plan of all operations.
 ///
-/// ### Return value
-/// Upon success, the function returns information about the files and directories that were copied or created
-/// as well as the total amount of bytes copied, see [`FinishedDirectoryCopy`].
-pub fn copy_directory<S, T>(
+/// Note that comparing existing target files may require reading them,
+/// see [`MergeFileReason::Identical`].
+pub fn plan_merge<S, T>(
     source_directory_path: S,
     target_directory_path: T,
     options: DirectoryCopyOptions,
-) -> Result<FinishedDirectoryCopy, DirectoryError>
+) -> Result<MergePlan, DirectoryError>
 where
     S: AsRef<Path>,
     T: AsRef<Path>,
 {
-    let source_directory_path = validate_source_directory_path(source_directory_path.as_ref())?;
-    let validated_target_path = validate_target_directory_path(
+    let (source_directory_path, validated_target_path) = validate_directory_copy_paths(
+        source_directory_path.as_ref(),
         target_directory_path.as_ref(),
+        &options,
+    )?;
+    let target_directory_path = validated_target_path.target_directory_path;
+
+    let FilteredDirectoryCopyQueue { operations, .. } = build_filtered_directory_copy_queue(
+        &source_directory_path,
+        &target_directory_path,
+        &options,
+        None,
+        None,
+        None,
+    )?;
+
+    check_operation_queue_for_collisions(
+        &operations,
+        &options.target_directory_rule,
+        options.collect_all_collisions,
+    )?;
+
+    let kept_target_paths = if options.delete_extraneous_from_target {
+        let mut kept_target_paths = KeptTargetPaths::from_source_mapping(
+            &source_directory_path,
+            &target_directory_path,
+            !options.follow_root_symlink_only,
+        )?;
+
+        for operation in &operations {
+            kept_target_paths.insert(operation.target_path());
+        }
+
+        Some(kept_target_paths)
+    } else {
+        None
+    };
+
+    build_merge_plan(
+        &operations,
+        &source_directory_path,
+        &target_directory_path,
+        options.target_directory_rule.should_skip_existing_files(),
+        plan_extraneous_target_entries(&target_directory_path, kept_target_paths.as_ref())?,
+    )
+}
+
+
+/// Validates the source and target directory paths of a [`copy_directory`] call
+/// (see [`validate_source_directory_path`] and [`validate_target_directory_path`]).
+///
+/// Returns the canonicalized source directory path and the validated target path.
+fn validate_directory_copy_paths(
+    source_directory_path: &Path,
+    target_directory_path: &Path,
+    options: &DirectoryCopyOptions,
+) -> Result<(PathBuf, ValidatedTargetPath), DirectoryError> {
+    validate_copy_depth_range(
+        options.minimum_copy_depth,
+        options.maximum_copy_depth,
+    )?;
+
+    let source_directory_path = validate_source_directory_path(source_directory_path)?;
+    let target_directory_path = resolve_target_directory_path(
+        &source_directory_path,
+        target_directory_path,
+        options.copy_into_subdirectory,
+    )?;
+    let validated_target_path = validate_target_directory_path(
+        &target_directory_path,
         &options.target_directory_rule,
     )?;
 
@@ -726,11 +4859,7 @@ where
         &validated_target_path.target_directory_path,
     )?;
 
-    copy_directory_unchecked(
-        source_directory_path,
-        validated_target_path,
-        options,
-    )
+    Ok((source_directory_path, validated_target_path))
 }
 
 
@@ -738,6 +4867,7 @@ where
 ///
 /// Used in progress reporting in [`copy_directory_with_progress`].
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum DirectoryCopyOperation {
     /// Describes a directory creation operation.
     CreatingDirectory { target_path: PathBuf },
@@ -747,6 +4877,68 @@ pub enum DirectoryCopyOperation {
         target_path: PathBuf,
         progress: FileProgress,
     },
+    /// Describes a special file (FIFO, socket or device node) being recreated,
+    /// see [`SpecialFileBehaviour::Recreate`].
+    RecreatingSpecialFile { target_path: PathBuf },
+    /// Describes a broken symbolic link being recreated,
+    /// see [`BrokenSymlinkBehaviour::CopyAsSymlink`].
+    RecreatingBrokenSymlink { target_path: PathBuf },
+    /// Describes a symbolic link being recreated instead of followed,
+    /// see [`DirectoryCopyOptions::follow_root_symlink_only`].
+    RecreatingSymlink { target_path: PathBuf },
+    /// Describes a directory junction being recreated,
+    /// see [`JunctionBehaviour::Recreate`].
+    RecreatingJunction { target_path: PathBuf },
+}
+
+
+/// Describes why a file was not copied, see [`FileEvent::Skipped`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum FileSkipReason {
+    /// The target file already exists and must be left as it is,
+    /// see [`TargetDirectoryRule::Merge`].
+    TargetAlreadyExists,
+
+    /// Another process had the source or target file open or locked,
+    /// see [`LockedFileBehaviour::Skip`].
+    Locked,
+}
+
+
+/// A discrete event in the life of a single file being copied,
+/// reported by [`copy_directory_with_progress_and_file_events`].
+///
+/// All paths are target paths, i.e. where the file is (or would have been) copied to.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum FileEvent {
+    /// The file is about to be copied.
+    Started {
+        /// Path the file is being copied to.
+        path: PathBuf,
+
+        /// Size of the source file, in bytes, as found when the source directory was scanned.
+        size: u64,
+    },
+
+    /// The file has been copied.
+    Finished {
+        /// Path the file has been copied to.
+        path: PathBuf,
+
+        /// Amount of bytes that were actually copied.
+        bytes: u64,
+    },
+
+    /// The file will not be copied.
+    Skipped {
+        /// Path the file would have been copied to.
+        path: PathBuf,
+
+        /// Why the file is not copied.
+        reason: FileSkipReason,
+    },
 }
 
 
@@ -754,6 +4946,7 @@ pub enum DirectoryCopyOperation {
 ///
 /// Used to report directory copying progress to a user-provided closure, see [`copy_directory_with_progress`].
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DirectoryCopyProgress {
     /// Amount of bytes that need to be copied for the directory copy to be complete.
     pub bytes_total: u64,
@@ -761,25 +4954,68 @@ pub struct DirectoryCopyProgress {
     /// Amount of bytes that have been copied so far.
     pub bytes_finished: u64,
 
+    /// Amount of bytes that would have been copied, but were skipped because
+    /// the corresponding target files already exist and must be left as they are
+    /// (see [`TargetDirectoryRule::Merge`] with `overwrite_existing_files` disabled)
+    /// or because the source files were locked (see [`LockedFileBehaviour::Skip`]).
+    ///
+    /// Files skipped due to existing targets are determined before anything is copied, so those are
+    /// already included in the first progress report; locked files are added as they are encountered
+    /// (and removed from `bytes_total` and `total_files` at the same time). These bytes are not included in
+    /// [`bytes_total`][Self::bytes_total] or [`bytes_finished`][Self::bytes_finished].
+    pub bytes_skipped: u64,
+
     /// Number of files that have been copied so far.
     pub files_copied: usize,
 
     /// Number of directories that have been created so far.
     pub directories_created: usize,
 
+    /// Total number of files that need to be copied for the directory copy to be complete.
+    pub total_files: usize,
+
+    /// Total number of directories that need to be created for the directory copy to be complete
+    /// (including the target directory itself, if it doesn't exist yet).
+    pub total_directories: usize,
+
     /// The current operation being performed.
     pub current_operation: DirectoryCopyOperation,
 
     /// The index of the current operation (starts at `0`, goes to `total_operations - 1`).
+    ///
+    /// Operations are performed in a depth-first order in which every directory is created
+    /// before any of the files or directories it contains. If the target directory
+    /// itself needs to be created, that is always the operation at index `0`.
     pub current_operation_index: isize,
 
     /// The total amount of operations that need to be performed to copy the requested directory.
     ///
-    /// A single operation is either copying a file or creating a directory, see [`DirectoryCopyOperation`].
+    /// A single operation is either copying a file, creating a directory or recreating a special file,
+    /// see [`DirectoryCopyOperation`]. This means that `total_operations` is the sum of
+    /// [`total_files`][Self::total_files], [`total_directories`][Self::total_directories]
+    /// and the number of special files that need to be recreated.
     pub total_operations: isize,
 }
 
 impl DirectoryCopyProgress {
+    /// Returns the completed fraction of the copy in bytes, between `0.0` and `1.0`.
+    ///
+    /// If there is nothing to copy (`bytes_total` is `0`), this returns `1.0`.
+    pub fn fraction_complete(&self) -> f64 {
+        if self.bytes_total == 0 {
+            return 1.0;
+        }
+
+        (self.bytes_finished as f64 / self.bytes_total as f64).min(1.0)
+    }
+
+    /// Returns `true` if the last operation has been reached and all bytes have been copied,
+    /// i.e. this is the final progress report of the copy.
+    pub fn is_finished(&self) -> bool {
+        self.current_operation_index + 1 >= self.total_operations
+            && self.bytes_finished >= self.bytes_total
+    }
+
     /// Update the current [`DirectoryCopyOperation`] with the given closure.
     /// After updating the operation, this function calls the given progress handler.
     fn update_operation_and_emit<M, F>(&mut self, mut modifer_closure: M, progress_handler: &mut F)
@@ -804,50 +5040,242 @@ impl DirectoryCopyProgress {
         self.current_operation_index += 1;
         self.current_operation = operation;
 
-        progress_handler(self)
-    }
-}
+        progress_handler(self)
+    }
+}
+
+
+
+/// Options that influence the [`copy_directory_with_progress`] function.
+///
+/// The progress handler is passed to [`copy_directory_with_progress`] separately,
+/// which keeps these options plain data that can be built once and reused.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DirectoryCopyWithProgressOptions {
+    /// Specifies whether you allow the target directory to exist before copying and whether it must be empty or not.
+    /// If you allow a non-empty target directory, you may also specify whether you allow
+    /// target files or subdirectories to already exist (and be overwritten).
+    ///
+    /// See [`TargetDirectoryRule`] for more details and examples.
+    pub target_directory_rule: TargetDirectoryRule,
+
+    /// Whether to treat *any* existing target path as a collision and report all of them at once.
+    ///
+    /// See [`DirectoryCopyOptions::collect_all_collisions`] for more details.
+    ///
+    /// Defaults to `false`.
+    pub collect_all_collisions: bool,
+
+    /// Maximum depth of the source directory to copy.
+    ///
+    /// The depth of an entry is the number of path components it is below the source directory,
+    /// i.e. entries directly in the source directory are at depth `1` (as with `find -maxdepth`).
+    ///
+    /// - `None` indicates no limit.
+    /// - `Some(0)` means that only the target directory itself is created; nothing inside the source
+    ///   directory is copied.
+    /// - `Some(1)` means a directory copy operation that copies only the files and
+    ///   creates directories found directly in the root directory, ignoring any subdirectories.
+    /// - `Some(2)` includes the root directory's contents and one level of its subdirectories.
+    ///
+    /// Directories at the maximum depth are created empty, i.e. their contents are not copied.
+    /// This counts one level more than
+    /// [`DirectoryScanOptions::maximum_scan_depth`][crate::directory::DirectoryScanOptions::maximum_scan_depth]
+    /// (a copy with a maximum depth of `Some(n + 1)` covers the entries a scan with `Some(n)` finds),
+    /// and a depth larger than that of the source tree is the same as `None`.
+    ///
+    /// If both this and [`minimum_copy_depth`][Self::minimum_copy_depth] are set,
+    /// the minimum must be smaller than the maximum, otherwise the copy returns
+    /// `Err(`[`DirectoryError::InvalidCopyDepthRange`]`)` before doing anything.
+    pub maximum_copy_depth: Option<usize>,
+
+    /// Minimum depth of the source directory to copy.
+    ///
+    /// Files at this depth or shallower are not copied, with depth counted
+    /// as in [`maximum_copy_depth`][Self::maximum_copy_depth].
+    ///
+    /// - `None` indicates no limit.
+    /// - `Some(1)` means files found directly in the root directory are not copied.
+    /// - `Some(2)` additionally skips files found directly in the root directory's subdirectories, and so on.
+    ///
+    /// Directories at this depth or shallower are still created
+    /// in the target directory, as deeper files keep their relative paths.
+    pub minimum_copy_depth: Option<usize>,
+
+    /// Internal buffer size (for both reading and writing) when copying files,
+    /// defaults to [`DEFAULT_COPY_BUFFER_SIZE`] (64 KiB).
+    pub buffer_size: usize,
+
+    /// *Minimum* amount of bytes written between two consecutive progress reports.
+    /// Defaults to 64 KiB.
+    ///
+    /// *Note that the interval can be larger.*
+    pub progress_update_byte_interval: u64,
+
+    /// Specifies how special files (FIFOs, sockets and device nodes) are handled.
+    ///
+    /// See [`SpecialFileBehaviour`] for more details.
+    pub special_file_behaviour: SpecialFileBehaviour,
+
+    /// Specifies how broken symbolic links are handled.
+    ///
+    /// See [`BrokenSymlinkBehaviour`] for more details.
+    pub broken_symlink_behaviour: BrokenSymlinkBehaviour,
+
+    /// If enabled, the source directory is copied into the target directory as a subdirectory
+    /// with the same name as the source directory, instead of its contents being copied
+    /// directly into the target directory.
+    ///
+    /// For example, copying `/foo/bar` to `/baz` copies into `/baz/bar` if this is `true`
+    /// (like `cp -r /foo/bar /baz` does when `/baz` already exists),
+    /// and directly into `/baz` if this is `false`.
+    /// The [`target_directory_rule`][Self::target_directory_rule] then applies to the subdirectory.
+    ///
+    /// Defaults to `false`.
+    pub copy_into_subdirectory: bool,
+
+    /// Whether to check that the target directory's filesystem is writable and
+    /// has enough free space for the source directory's contents before copying anything.
+    /// If it doesn't, `Err(`[`DirectoryError::ReadOnlyFilesystem`]`)` or
+    /// `Err(`[`DirectoryError::InsufficientFreeSpace`]`)` is returned.
+    ///
+    /// This check is currently only performed on Unix.
+    ///
+    /// Defaults to `false`.
+    pub ensure_free_space: bool,
+
+    /// Whether to check that the target directory's filesystem has enough free inodes
+    /// for every file, directory and link that would be created, before copying anything.
+    /// If it doesn't, `Err(`[`DirectoryError::InsufficientInodes`]`)` is returned.
+    ///
+    /// This catches copies of many small files failing midway on filesystems
+    /// that run out of inodes long before they run out of space. Filesystems that don't have
+    /// a fixed number of inodes (i.e. report a total of zero) are not checked.
+    ///
+    /// This check is currently only performed on Unix.
+    ///
+    /// Defaults to `false`.
+    pub ensure_free_inodes: bool,
+
+    /// Whether to copy the permissions and modification time of the source directory
+    /// onto the target directory once everything has been copied.
+    ///
+    /// This is only done if the target directory was created by the copy or was empty before it,
+    /// unless [`target_directory_rule`][Self::target_directory_rule] allows overwriting existing directories,
+    /// in which case the metadata of a pre-existing non-empty target directory is overwritten as well.
+    ///
+    /// The modification time is currently only preserved on Unix.
+    ///
+    /// Defaults to `false`.
+    pub preserve_root_metadata: bool,
+
+    /// Permission mode (e.g. `0o644`) to set on every file and directory created by the copy,
+    /// instead of keeping the permissions of the corresponding source entry.
+    ///
+    /// This includes the target directory itself if it is created by the copy.
+    /// If [`preserve_root_metadata`][Self::preserve_root_metadata] is also enabled,
+    /// this takes precedence over the preserved permissions of the target directory
+    /// (its modification time is still preserved).
+    ///
+    /// Directories only receive their mode once everything has been copied, so a restrictive mode
+    /// (e.g. one without the write bit) doesn't prevent their contents from being copied.
+    /// Pre-existing target directories, recreated special files and symbolic links are left as they are.
+    ///
+    /// This is currently only applied on Unix and is ignored on other platforms.
+    ///
+    /// Defaults to `None`.
+    pub force_mode: Option<u32>,
+
+    /// Whether to skip creating directories that would end up empty in the target directory,
+    /// i.e. directories that (at any depth) contain no files that are copied.
+    ///
+    /// This applies *after* other options have excluded entries, so e.g. a directory whose files
+    /// are all excluded by [`minimum_copy_depth`][Self::minimum_copy_depth] is not created either.
+    /// The number of skipped directories is reported in
+    /// [`FinishedDirectoryCopy::num_empty_directories_skipped`].
+    ///
+    /// Defaults to `false`.
+    pub skip_empty_directories: bool,
 
+    /// Order in which the entries of each source directory are copied.
+    ///
+    /// See [`TraversalOrder`] for more details.
+    pub traversal_order: TraversalOrder,
 
+    /// Whether to read source directories in larger batches, reducing the number of system calls
+    /// needed for directories with many entries.
+    ///
+    /// See [`DirectoryScanOptions::fast_enumeration`][super::DirectoryScanOptions::fast_enumeration]
+    /// for more details.
+    pub fast_enumeration: bool,
 
-/// Options that influence the [`copy_directory_with_progress`] function.
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct DirectoryCopyWithProgressOptions {
-    /// Specifies whether you allow the target directory to exist before copying and whether it must be empty or not.
-    /// If you allow a non-empty target directory, you may also specify whether you allow
-    /// target files or subdirectories to already exist (and be overwritten).
+    /// Whether to follow only the source directory path itself if it is a symbolic link,
+    /// and to recreate symbolic links found inside the source directory as they are.
     ///
-    /// See [`TargetDirectoryRule`] for more details and examples.
-    pub target_directory_rule: TargetDirectoryRule,
+    /// See [`DirectoryCopyOptions::follow_root_symlink_only`] for more details.
+    ///
+    /// Defaults to `false`.
+    pub follow_root_symlink_only: bool,
 
-    /// Maximum depth of the source directory to copy.
+    /// Maximum number of symbolic links to directories to follow during the copy.
     ///
-    /// - `None` indicates no limit.
-    /// - `Some(0)` means a directory copy operation that copies only the files and
-    ///   creates directories directly in the root directory and doesn't scan any subdirectories.
-    /// - `Some(1)` includes the root directory's contents and one level of its subdirectories.
-    pub maximum_copy_depth: Option<usize>,
+    /// See [`DirectoryCopyOptions::max_symlinks_to_follow`] for more details.
+    ///
+    /// Defaults to `None`, i.e. no limit.
+    pub max_symlinks_to_follow: Option<usize>,
 
-    /// Internal buffer size (for both reading and writing) when copying filea,
-    /// defaults to 64 KiB.
-    pub buffer_size: usize,
+    /// Specifies how directory junctions are handled (Windows only).
+    ///
+    /// See [`JunctionBehaviour`] for more details.
+    pub junction_behaviour: JunctionBehaviour,
 
-    /// *Minimum* amount of bytes written between two consecutive progress reports.
-    /// Defaults to 64 KiB.
+    /// Specifies how files that are open or locked by another process are handled (Windows only).
     ///
-    /// *Note that the interval can be larger.*
-    pub progress_update_byte_interval: u64,
+    /// See [`LockedFileBehaviour`] for more details.
+    pub locked_file_behaviour: LockedFileBehaviour,
+
+    /// Specifies how the destinations of recreated symbolic links are written.
+    ///
+    /// See [`SymlinkRewrite`] for more details.
+    pub symlink_target_rewrite: SymlinkRewrite,
+
+    /// Maximum amount of time the entire copy (including scanning the source directory) may take.
+    ///
+    /// The elapsed time is checked between operations, i.e. before each file is copied
+    /// and each directory is created, so a file that is already being copied is always finished.
+    /// Once the timeout is exceeded, `Err(`[`DirectoryError::TimedOut`]`)` is returned,
+    /// containing the progress up to that point. Everything copied until then is left in place.
+    ///
+    /// Defaults to `None`, i.e. no timeout.
+    pub timeout: Option<Duration>,
 }
 
 impl Default for DirectoryCopyWithProgressOptions {
     fn default() -> Self {
         Self {
             target_directory_rule: TargetDirectoryRule::default(),
+            collect_all_collisions: false,
+            minimum_copy_depth: None,
             maximum_copy_depth: None,
-            // 64 KiB
-            buffer_size: 1024 * 64,
-            // 64 KiB
-            progress_update_byte_interval: 1024 * 64,
+            special_file_behaviour: SpecialFileBehaviour::default(),
+            broken_symlink_behaviour: BrokenSymlinkBehaviour::default(),
+            copy_into_subdirectory: false,
+            ensure_free_space: false,
+            ensure_free_inodes: false,
+            preserve_root_metadata: false,
+            force_mode: None,
+            skip_empty_directories: false,
+            traversal_order: TraversalOrder::default(),
+            fast_enumeration: false,
+            follow_root_symlink_only: false,
+            max_symlinks_to_follow: None,
+            junction_behaviour: JunctionBehaviour::default(),
+            locked_file_behaviour: LockedFileBehaviour::default(),
+            symlink_target_rewrite: SymlinkRewrite::default(),
+            timeout: None,
+            buffer_size: DEFAULT_COPY_BUFFER_SIZE,
+            progress_update_byte_interval: DEFAULT_COPY_BUFFER_SIZE as u64,
         }
     }
 }
@@ -859,6 +5287,10 @@ impl Default for DirectoryCopyWithProgressOptions {
 ///
 /// The function respects given `options` (e.g. returning an error
 /// if the file already exists if configured to do so).
+///
+/// Returns `Ok(false)` if the file was skipped because another process had it open or locked
+/// (see [`LockedFileBehaviour::Skip`]), in which case its size is moved
+/// from `progress.bytes_total` to `progress.bytes_skipped`.
 fn execute_copy_file_operation_with_progress<F>(
     source_path: PathBuf,
     source_size_bytes: u64,
@@ -866,7 +5298,8 @@ fn execute_copy_file_operation_with_progress<F>(
     options: &DirectoryCopyWithProgressOptions,
     progress: &mut DirectoryCopyProgress,
     progress_handler: &mut F,
-) -> Result<(), DirectoryError>
+    mut file_event_handler: Option<&mut FileEventHandler>,
+) -> Result<bool, DirectoryError>
 where
     F: FnMut(&DirectoryCopyProgress),
 {
@@ -888,6 +5321,12 @@ where
         }
     }
 
+    if let Some(file_event_handler) = file_event_handler.as_mut() {
+        file_event_handler(FileEvent::Started {
+            path: target_path.clone(),
+            size: source_size_bytes,
+        });
+    }
 
     progress.set_next_operation_and_emit(
         DirectoryCopyOperation::CopyingFile {
@@ -903,58 +5342,74 @@ where
     let mut did_update_with_fresh_total = false;
     let bytes_copied_before = progress.bytes_finished;
 
-    let num_bytes_copied = copy_file_with_progress(
-        source_path,
-        &target_path,
-        FileCopyWithProgressOptions {
-            overwrite_existing: should_overwrite_files,
-            skip_existing: false,
-            buffer_size: options.buffer_size,
-            progress_update_byte_interval: options.progress_update_byte_interval,
-        },
-        |new_file_progress| progress.update_operation_and_emit(
-                |progress| {
-                    if let DirectoryCopyOperation::CopyingFile {
-                        progress: file_progress,
-                        ..
-                    } = &mut progress.current_operation
-                    {
-                        // It is somewhat possible that a file is written to between the scanning phase and copying.
-                        // In that case, it is *possible* that the file size changes, which means we should listen
-                        // to the size `copy_file_with_progress` is reporting. There is no point
-                        // to doing this each update, so we do it only once.
-                        if !did_update_with_fresh_total {
-                            file_progress.bytes_total = new_file_progress.bytes_total;
-                            did_update_with_fresh_total = true;
-                        }
-
-                        file_progress.bytes_finished = new_file_progress.bytes_finished;
-                        progress.bytes_finished =
-                            bytes_copied_before + file_progress.bytes_finished;
-                    } else {
-                        panic!(
-                            "bug: `progress.current_operation` miraculously doesn't match CopyingFile"
-                        );
-                    }
+    let copied_bytes =
+        copy_file_respecting_locked_file_behaviour(options.locked_file_behaviour, || {
+            copy_file_with_progress(
+                &source_path,
+                &target_path,
+                FileCopyWithProgressOptions {
+                    overwrite_existing: should_overwrite_files,
+                    skip_existing: false,
+                    preallocate_target: false,
+                    unbuffered: false,
+                    detect_source_modification: false,
+                    buffer_strategy: BufferStrategy::Fixed(options.buffer_size),
+                    progress_update_byte_interval: options.progress_update_byte_interval,
                 },
-                progress_handler,
+                |new_file_progress| progress.update_operation_and_emit(
+                    |progress| {
+                        if let DirectoryCopyOperation::CopyingFile {
+                            progress: file_progress,
+                            ..
+                        } = &mut progress.current_operation
+                        {
+                            // It is somewhat possible that a file is written to between the scanning phase and copying.
+                            // In that case, it is *possible* that the file size changes, which means we should listen
+                            // to the size `copy_file_with_progress` is reporting. There is no point
+                            // to doing this each update, so we do it only once.
+                            if !did_update_with_fresh_total {
+                                file_progress.bytes_total = new_file_progress.bytes_total;
+                                did_update_with_fresh_total = true;
+                            }
+
+                            file_progress.bytes_finished = new_file_progress.bytes_finished;
+                            progress.bytes_finished =
+                                bytes_copied_before + file_progress.bytes_finished;
+                        } else {
+                            panic!(
+                                "bug: `progress.current_operation` miraculously doesn't match CopyingFile"
+                            );
+                        }
+                    },
+                    progress_handler,
+                )
             )
-    )
-    .map_err(|error| match error {
-        FileError::NotFound => DirectoryError::SourceContentsInvalid,
-        FileError::NotAFile => DirectoryError::SourceContentsInvalid,
-        FileError::UnableToAccessSourceFile { error } => {
-            DirectoryError::UnableToAccessSource { error }
-        }
-        FileError::AlreadyExists => DirectoryError::TargetItemAlreadyExists {
-            path: target_path.clone(),
-        },
-        FileError::UnableToAccessTargetFile { error } => {
-            DirectoryError::UnableToAccessTarget { error }
+        })
+        .map_err(|error| directory_error_from_file_copy_error(error, &source_path, &target_path))?;
+
+    let num_bytes_copied = match copied_bytes {
+        Some(num_bytes_copied) => num_bytes_copied,
+        None => {
+            // The file was locked, so nothing (or only part of it, before the lock was hit) was copied.
+            progress.bytes_finished = bytes_copied_before;
+            progress.bytes_total -= source_size_bytes;
+            progress.bytes_skipped += source_size_bytes;
+            progress.total_files -= 1;
+
+            if let Some(file_event_handler) = file_event_handler {
+                file_event_handler(FileEvent::Skipped {
+                    path: target_path,
+                    reason: FileSkipReason::Locked,
+                });
+            }
+
+            return Ok(false);
         }
-        FileError::SourceAndTargetAreTheSameFile => DirectoryError::InvalidTargetDirectoryPath,
-        FileError::OtherIoError { error } => DirectoryError::OtherIoError { error },
-    })?;
+    };
+
+    if let Some(mode) = options.force_mode {
+        apply_forced_mode(&target_path, mode)?;
+    }
 
     progress.files_copied += 1;
 
@@ -964,21 +5419,29 @@ where
         "bug: reported incorrect amount of copied bytes"
     );
 
-    Ok(())
+    if let Some(file_event_handler) = file_event_handler {
+        file_event_handler(FileEvent::Finished {
+            path: target_path,
+            bytes: num_bytes_copied,
+        });
+    }
+
+    Ok(true)
 }
 
 /// Given [`QueuedOperation::CreateDirectory`] data, this function
 /// creates the given directory with progress information.
 ///
-/// If the directory already exists, no action
-/// is taken, unless the given options indicate that to be an error
-/// ([`overwrite_existing_subdirectories`][DirectoryCopyWithProgressOptions::overwrite_existing_subdirectories]).
+/// If the directory already exists, no action is taken, unless `should_overwrite_directories` is `false`,
+/// in which case an error is returned
+/// (see [`overwrite_existing_subdirectories`][TargetDirectoryRule::AllowNonEmpty::overwrite_existing_subdirectories]).
 ///
 /// If the given path exists, but is not a directory, an error is returned as well.
 fn execute_create_directory_operation_with_progress<F>(
     target_directory_path: PathBuf,
     source_size_bytes: u64,
     should_overwrite_directories: bool,
+    forced_mode: Option<&mut ForcedMode>,
     progress: &mut DirectoryCopyProgress,
     progress_handler: &mut F,
 ) -> Result<(), DirectoryError>
@@ -1008,8 +5471,15 @@ where
         progress_handler,
     );
 
-    fs::create_dir(target_directory_path)
-        .map_err(|error| DirectoryError::UnableToAccessTarget { error })?;
+    fs::create_dir(&target_directory_path).map_err(|error| DirectoryError::IoDuringOperation {
+        path: target_directory_path.clone(),
+        operation: IoOperation::CreateDirectory,
+        source: error,
+    })?;
+
+    if let Some(forced_mode) = forced_mode {
+        forced_mode.register_created_directory(target_directory_path);
+    }
 
     progress.directories_created += 1;
     progress.bytes_finished += source_size_bytes;
@@ -1020,21 +5490,28 @@ where
 
 /// Copy an entire directory from `source_directory_path` to `target_directory_path`.
 ///
-/// - `source_directory_path` must point to an existing directory path.
+/// - `source_directory_path` must point to an existing directory path. This is checked before anything else:
+///   if nothing exists there, `Err(`[`DirectoryError::SourceDirectoryNotFound`]`)` is returned,
+///   and if it is not a directory (e.g. a file), `Err(`[`DirectoryError::SourceDirectoryIsNotADirectory`]`)` is returned.
 /// - `target_directory_path` represents a path to the directory that will contain `source_directory_path`'s contents.
 ///
 /// ### Target directory
 /// Depending on the [`options.target_directory_rules`][DirectoryCopyOptions::target_directory_rule] option,
 /// the `target_directory_path` must:
 /// - [`DisallowExisting`][TargetDirectoryRule::DisallowExisting]: not exist,
-/// - [`AllowEmpty`][TargetDirectoryRule::AllowEmpty]: either not exist or be empty, or,
+/// - [`AllowEmpty`][TargetDirectoryRule::AllowEmpty]: either not exist or be empty,
 /// - [`AllowNonEmpty`][TargetDirectoryRule::AllowNonEmpty]: either not exist, be empty, or be non-empty. Additionally,
-///   the specified overwriting rules are respected (see variant's fields).
+///   the specified overwriting rules are respected (see variant's fields), or,
+/// - [`Merge`][TargetDirectoryRule::Merge]: either not exist, be empty, or be non-empty. Existing subdirectories
+///   are merged into and existing files are overwritten or skipped (see fields).
 ///
 /// If the specified target directory rule does not hold,
 /// `Err(`[`DirectoryError::InvalidTargetDirectoryPath`]`)` or
 /// `Err(`[`DirectoryError::TargetDirectoryIsNotEmpty`]`)` is returned (depending on the rule).
 ///
+/// If [`options.copy_into_subdirectory`][DirectoryCopyWithProgressOptions::copy_into_subdirectory] is enabled,
+/// the above applies to the `target_directory_path/<source directory name>` directory instead.
+///
 /// ## Progress reporting
 /// You must also provide a progress handler closure that will receive
 /// a [`&DirectoryCopyProgress`][DirectoryCopyProgress] containing progress state.
@@ -1050,19 +5527,46 @@ where
 /// For more information about update frequency of specifically file copy updates, refer to the `Progress reporting` section
 /// of the [`copy_file_with_progress`][crate::file::copy_file_with_progress] function.
 ///
+/// The progress handler is only ever invoked from the thread that called this function, one call at a time,
+/// and each [`DirectoryCopyProgress`] it receives describes the state of the entire copy.
+/// This is guaranteed to hold for any future parallel copying as well (progress from multiple workers
+/// would be aggregated and reported from a single thread), so the handler never needs to be
+/// [`Send`] or [`Sync`] and doesn't require any locking.
+///
 /// ## Copy depth
-/// Depending on the [`options.maximum_copy_depth`] option, calling this function means copying:
-/// - `Some(0)` -- a single directory and its direct descendants (files and direct directories, but *not their contents*, i.e. just empty directories),
-/// - `Some(1+)` -- files and subdirectories (and their files and directories, etc.) up to a certain depth limit (e.g. `Some(1)` copies direct descendants as well as one layer deeper),
+/// Depending on the [`options.maximum_copy_depth`][DirectoryCopyWithProgressOptions::maximum_copy_depth] option,
+/// calling this function means copying:
+/// - `Some(0)` -- nothing, only the target directory itself is created,
+/// - `Some(1)` -- a single directory and its direct descendants (files and direct directories, but *not their contents*, i.e. just empty directories),
+/// - `Some(2+)` -- files and subdirectories (and their files and directories, etc.) up to a certain depth limit (e.g. `Some(2)` copies direct descendants as well as one layer deeper),
 /// - `None` -- the entire subtree. **This is probably what you want most of the time**.
 ///
+/// A depth larger than that of the source tree copies the entire subtree, the same as `None`.
+///
 /// ## Symbolic links
 /// - If the `source_directory_path` directory contains a symbolic link to a file,
-/// the contents of the file it points to will be copied
-/// into the corresponding subpath inside `target_file_path`
-/// (same behaviour as `cp` without `-P` on Unix, i.e. link is followed, but not preserved).
+///   the contents of the file it points to will be copied
+///   into the corresponding subpath inside `target_file_path`
+///   (same behaviour as `cp` without `-P` on Unix, i.e. link is followed, but not preserved).
 /// - If the `source_directory_path` directory contains a symbolic link to a directory,
-/// the directory and its contents will be copied as normal - the links will be followed, but not preserved.
+///   the directory and its contents will be copied as normal - the links will be followed, but not preserved.
+/// - If the `source_directory_path` directory contains a broken symbolic link (one whose destination does not exist),
+///   it is handled according to the `broken_symlink_behaviour` option, see [`BrokenSymlinkBehaviour`].
+/// - On Windows, directory junctions are followed like symbolic links to directories by default,
+///   but can also be recreated or skipped with the `junction_behaviour` option, see [`JunctionBehaviour`].
+/// - If `target_directory_path` is a symbolic link to a directory, it is followed and the contents are
+///   copied into the directory it points to. If that directory is the source directory or is inside it,
+///   `Err(`[`DirectoryError::InvalidTargetDirectoryPath`]`)` is returned, same as for a non-symlinked path.
+///
+/// ## Special files
+/// FIFOs, sockets and device nodes are handled according to the
+/// [`options.special_file_behaviour`][DirectoryCopyWithProgressOptions::special_file_behaviour] option,
+/// see [`SpecialFileBehaviour`].
+///
+/// ## Locked files
+/// On Windows, files that are open or locked by another process can't be read. These are handled
+/// according to the [`options.locked_file_behaviour`][DirectoryCopyWithProgressOptions::locked_file_behaviour] option,
+/// see [`LockedFileBehaviour`]. By default, such a file aborts the copy with an error.
 ///
 /// ## Return value
 /// Upon success, the function returns information about the files and directories that were copied or created
@@ -1071,40 +5575,272 @@ pub fn copy_directory_with_progress<S, T, F>(
     source_directory_path: S,
     target_directory_path: T,
     options: DirectoryCopyWithProgressOptions,
-    mut progress_handler: F,
+    progress_handler: F,
+) -> Result<FinishedDirectoryCopy, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+    F: FnMut(&DirectoryCopyProgress),
+{
+    let (source_directory_path, validated_target_path) =
+        validate_directory_copy_with_progress_paths(
+            source_directory_path.as_ref(),
+            target_directory_path.as_ref(),
+            &options,
+        )?;
+
+    copy_directory_with_progress_unchecked(
+        source_directory_path,
+        validated_target_path,
+        options,
+        progress_handler,
+        None,
+    )
+    .map_err(classify_read_only_filesystem_error)
+}
+
+
+/// Copy a directory from `source_directory_path` to `target_directory_path` on a new thread,
+/// delivering progress through a channel instead of a progress handler.
+///
+/// This function spawns a thread that runs [`copy_directory_with_progress`] and sends each
+/// [`DirectoryCopyProgress`] report to the returned [`Receiver`][mpsc::Receiver].
+/// This decouples consuming progress from the copy itself, e.g. for use in an event loop.
+/// The receiver is disconnected once the copy finishes (successfully or not),
+/// after which the result can be obtained by joining the returned [`JoinHandle`][thread::JoinHandle].
+///
+/// The channel is unbounded, so the copy never waits for progress to be received.
+/// If the receiver is dropped, the copy continues, but progress is no longer sent.
+///
+/// See [`copy_directory_with_progress`] for details on the copy itself and on progress reporting.
+pub fn copy_directory_with_progress_stream<S, T>(
+    source_directory_path: S,
+    target_directory_path: T,
+    options: DirectoryCopyWithProgressOptions,
+) -> (
+    thread::JoinHandle<Result<FinishedDirectoryCopy, DirectoryError>>,
+    mpsc::Receiver<DirectoryCopyProgress>,
+)
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    let source_directory_path = source_directory_path.as_ref().to_path_buf();
+    let target_directory_path = target_directory_path.as_ref().to_path_buf();
+
+    let (progress_sender, progress_receiver) = mpsc::channel();
+
+    let copy_thread_handle = thread::spawn(move || {
+        copy_directory_with_progress(
+            source_directory_path,
+            target_directory_path,
+            options,
+            |progress| {
+                // A disconnected receiver just means nobody is interested in the progress anymore.
+                let _ = progress_sender.send(progress.clone());
+            },
+        )
+    });
+
+    (copy_thread_handle, progress_receiver)
+}
+
+
+/// Copy a directory from `source_directory_path` to `target_directory_path` with progress reporting,
+/// additionally calling `file_event_handler` with a [`FileEvent`] for each file
+/// that starts or finishes copying or is skipped.
+///
+/// This function behaves like [`copy_directory_with_progress`], and `progress_handler` receives
+/// the same cumulative progress. The file events are a more convenient way of following
+/// individual files than comparing successive [`DirectoryCopyProgress`] reports:
+/// - [`FileEvent::Started`] is reported right before a file starts copying,
+///   before the first progress report for that file,
+/// - [`FileEvent::Finished`] is reported once a file has been copied, and,
+/// - [`FileEvent::Skipped`] is reported for each file that won't be copied
+///   (e.g. because the target already contains it, see [`TargetDirectoryRule::Merge`]).
+///   These events are reported before anything is copied, except for locked files
+///   (see [`LockedFileBehaviour::Skip`]), which are reported after their [`FileEvent::Started`] event.
+///
+/// Both handlers are only ever invoked from the thread that called this function, one call at a time.
+pub fn copy_directory_with_progress_and_file_events<S, T, F, E>(
+    source_directory_path: S,
+    target_directory_path: T,
+    options: DirectoryCopyWithProgressOptions,
+    progress_handler: F,
+    mut file_event_handler: E,
 ) -> Result<FinishedDirectoryCopy, DirectoryError>
 where
     S: AsRef<Path>,
     T: AsRef<Path>,
     F: FnMut(&DirectoryCopyProgress),
+    E: FnMut(FileEvent),
+{
+    let (source_directory_path, validated_target_path) =
+        validate_directory_copy_with_progress_paths(
+            source_directory_path.as_ref(),
+            target_directory_path.as_ref(),
+            &options,
+        )?;
+
+    copy_directory_with_progress_unchecked(
+        source_directory_path,
+        validated_target_path,
+        options,
+        progress_handler,
+        Some(&mut file_event_handler),
+    )
+    .map_err(classify_read_only_filesystem_error)
+}
+
+
+/// Validates the source and target directory paths of a [`copy_directory_with_progress`] call
+/// (see [`validate_directory_copy_paths`]).
+///
+/// Returns the canonicalized source directory path and the validated target path.
+fn validate_directory_copy_with_progress_paths(
+    source_directory_path: &Path,
+    target_directory_path: &Path,
+    options: &DirectoryCopyWithProgressOptions,
+) -> Result<(PathBuf, ValidatedTargetPath), DirectoryError> {
+    validate_copy_depth_range(
+        options.minimum_copy_depth,
+        options.maximum_copy_depth,
+    )?;
+
+    let source_directory_path = validate_source_directory_path(source_directory_path)?;
+    let target_directory_path = resolve_target_directory_path(
+        &source_directory_path,
+        target_directory_path,
+        options.copy_into_subdirectory,
+    )?;
+    let validated_target_path = validate_target_directory_path(
+        &target_directory_path,
+        &options.target_directory_rule,
+    )?;
+
+    validate_source_target_directory_pair(
+        &source_directory_path,
+        &validated_target_path.target_directory_path,
+    )?;
+
+    Ok((source_directory_path, validated_target_path))
+}
+
+
+/// Perform a copy from `source_directory_path` to `validated_target_path`, reporting progress.
+///
+/// For more details, see [`copy_directory_with_progress`].
+/// If `file_event_handler` is set, it is called with file events as described in
+/// [`copy_directory_with_progress_and_file_events`].
+fn copy_directory_with_progress_unchecked<F>(
+    source_directory_path: PathBuf,
+    validated_target_path: ValidatedTargetPath,
+    options: DirectoryCopyWithProgressOptions,
+    mut progress_handler: F,
+    mut file_event_handler: Option<&mut FileEventHandler>,
+) -> Result<FinishedDirectoryCopy, DirectoryError>
+where
+    F: FnMut(&DirectoryCopyProgress),
 {
+    // A timeout too large to be represented is the same as no timeout at all.
+    let deadline = options
+        .timeout
+        .and_then(|timeout| Instant::now().checked_add(timeout));
+
     let allows_existing_target_directory = options
         .target_directory_rule
         .allows_existing_target_directory();
     let should_overwrite_directories = options
         .target_directory_rule
         .should_overwrite_existing_directories();
+    let should_overwrite_files = options
+        .target_directory_rule
+        .should_overwrite_existing_files();
 
-    let source_directory_path = validate_source_directory_path(source_directory_path.as_ref())?;
     let ValidatedTargetPath {
         target_directory_path,
         target_directory_exists,
-        ..
-    } = validate_target_directory_path(
-        target_directory_path.as_ref(),
-        &options.target_directory_rule,
-    )?;
-
-    validate_source_target_directory_pair(&source_directory_path, &target_directory_path)?;
+        target_directory_is_empty,
+    } = validated_target_path;
 
     // Initialize a queue of file copy or directory create operations.
-    let operation_queue = build_directory_copy_queue(
+    let DirectoryCopyQueue {
+        operations: mut operation_queue,
+        skipped_entries,
+    } = build_directory_copy_queue(
         &source_directory_path,
         &target_directory_path,
+        options.minimum_copy_depth,
         options.maximum_copy_depth,
+        options.special_file_behaviour,
+        options.broken_symlink_behaviour,
+        options.follow_root_symlink_only,
+        options.junction_behaviour,
+        options.symlink_target_rewrite,
+        options.traversal_order,
+        options.fast_enumeration,
+        None,
+        None,
+        options.max_symlinks_to_follow,
+    )?;
+
+    let mut num_empty_directories_skipped = 0;
+
+    if options.skip_empty_directories {
+        (operation_queue, num_empty_directories_skipped) =
+            remove_empty_directory_operations(operation_queue);
+    }
+
+    check_operation_queue_for_collisions(
+        &operation_queue,
+        &options.target_directory_rule,
+        options.collect_all_collisions,
     )?;
 
-    check_operation_queue_for_collisions(&operation_queue, &options.target_directory_rule)?;
+    let should_skip_existing_files = options.target_directory_rule.should_skip_existing_files();
+    let num_operations_before_skipping = operation_queue.len();
+    let mut bytes_skipped = 0;
+
+    operation_queue.retain(|operation| {
+        let is_skipped =
+            is_operation_skipped_due_to_existing_target(operation, should_skip_existing_files);
+
+        if is_skipped {
+            if let QueuedOperation::CopyFile {
+                source_size_bytes,
+                target_file_path,
+                ..
+            } = operation
+            {
+                bytes_skipped += *source_size_bytes;
+
+                if let Some(file_event_handler) = file_event_handler.as_mut() {
+                    file_event_handler(FileEvent::Skipped {
+                        path: target_file_path.clone(),
+                        reason: FileSkipReason::TargetAlreadyExists,
+                    });
+                }
+            }
+        }
+
+        !is_skipped
+    });
+
+    let num_existing_files_skipped = num_operations_before_skipping - operation_queue.len();
+
+    if options.ensure_free_space {
+        ensure_target_has_free_space(
+            &target_directory_path,
+            total_file_size_of_operation_queue(&operation_queue),
+        )?;
+    }
+
+    if options.ensure_free_inodes {
+        ensure_target_has_free_inodes(
+            &target_directory_path,
+            operation_queue.len() as u64,
+        )?;
+    }
 
     let bytes_total = operation_queue
         .iter()
@@ -1115,9 +5851,24 @@ where
             QueuedOperation::CreateDirectory {
                 source_size_bytes, ..
             } => *source_size_bytes,
+            QueuedOperation::RecreateSpecialFile { .. } => 0,
+            QueuedOperation::RecreateBrokenSymlink { .. } => 0,
+            QueuedOperation::RecreateSymlink { .. } => 0,
+            QueuedOperation::RecreateJunction { .. } => 0,
         })
         .sum::<u64>();
 
+    let total_files = operation_queue
+        .iter()
+        .filter(|item| matches!(item, QueuedOperation::CopyFile { .. }))
+        .count();
+    let total_directories = operation_queue
+        .iter()
+        .filter(|item| matches!(item, QueuedOperation::CreateDirectory { .. }))
+        .count();
+
+    let mut forced_mode = options.force_mode.map(ForcedMode::new);
+
     // Create root target directory if needed.
     let mut progress = if target_directory_exists {
         if !allows_existing_target_directory && !should_overwrite_directories {
@@ -1129,8 +5880,11 @@ where
         DirectoryCopyProgress {
             bytes_total,
             bytes_finished: 0,
+            bytes_skipped,
             files_copied: 0,
             directories_created: 0,
+            total_files,
+            total_directories,
             // This is a bogus operation - we don't emit this progress,
             // but we need something here before the next operation starts.
             current_operation: DirectoryCopyOperation::CreatingDirectory {
@@ -1144,8 +5898,11 @@ where
         let mut progress = DirectoryCopyProgress {
             bytes_total,
             bytes_finished: 0,
+            bytes_skipped,
             files_copied: 0,
             directories_created: 0,
+            total_files,
+            total_directories: total_directories + 1,
             current_operation: DirectoryCopyOperation::CreatingDirectory {
                 target_path: target_directory_path.to_path_buf(),
             },
@@ -1155,39 +5912,165 @@ where
 
         progress_handler(&progress);
 
-        fs::create_dir_all(target_directory_path)
-            .map_err(|error| DirectoryError::UnableToAccessTarget { error })?;
+        fs::create_dir_all(&target_directory_path).map_err(|error| {
+            DirectoryError::IoDuringOperation {
+                path: target_directory_path.clone(),
+                operation: IoOperation::CreateDirectory,
+                source: error,
+            }
+        })?;
 
         progress.directories_created += 1;
 
         progress
     };
 
+    let mut num_special_files_recreated = 0;
+    let mut num_broken_symlinks_recreated = 0;
+    let mut num_symlinks_recreated = 0;
+    let mut num_junctions_recreated = 0;
+    let mut num_locked_files_skipped = 0;
 
     for operation in operation_queue {
+        if let Some(deadline) = deadline {
+            if Instant::now() >= deadline {
+                return Err(DirectoryError::TimedOut {
+                    progress: Box::new(progress),
+                });
+            }
+        }
+
         match operation {
             QueuedOperation::CopyFile {
                 source_file_path: source_path,
                 source_size_bytes,
                 target_file_path: target_path,
-            } => execute_copy_file_operation_with_progress(
-                source_path,
-                source_size_bytes,
-                target_path,
-                &options,
-                &mut progress,
-                &mut progress_handler,
-            )?,
+            } => {
+                let was_copied = execute_copy_file_operation_with_progress(
+                    source_path,
+                    source_size_bytes,
+                    target_path,
+                    &options,
+                    &mut progress,
+                    &mut progress_handler,
+                    file_event_handler.as_deref_mut(),
+                )?;
+
+                if !was_copied {
+                    num_locked_files_skipped += 1;
+                }
+            }
             QueuedOperation::CreateDirectory {
                 source_size_bytes,
                 target_directory_path,
+                ..
             } => execute_create_directory_operation_with_progress(
                 target_directory_path,
                 source_size_bytes,
                 should_overwrite_directories,
+                forced_mode.as_mut(),
                 &mut progress,
                 &mut progress_handler,
             )?,
+            QueuedOperation::RecreateSpecialFile {
+                source_file_path,
+                target_file_path,
+            } => {
+                progress.set_next_operation_and_emit(
+                    DirectoryCopyOperation::RecreatingSpecialFile {
+                        target_path: target_file_path.clone(),
+                    },
+                    &mut progress_handler,
+                );
+
+                execute_recreate_special_file_operation(
+                    &source_file_path,
+                    &target_file_path,
+                    should_overwrite_files,
+                )?;
+
+                num_special_files_recreated += 1;
+            }
+            QueuedOperation::RecreateBrokenSymlink {
+                link_destination_path,
+                target_file_path,
+            } => {
+                progress.set_next_operation_and_emit(
+                    DirectoryCopyOperation::RecreatingBrokenSymlink {
+                        target_path: target_file_path.clone(),
+                    },
+                    &mut progress_handler,
+                );
+
+                execute_recreate_symlink_operation(
+                    &link_destination_path,
+                    &target_file_path,
+                    false,
+                    should_overwrite_files,
+                )?;
+
+                num_broken_symlinks_recreated += 1;
+            }
+            QueuedOperation::RecreateSymlink {
+                link_destination_path,
+                target_file_path,
+                points_to_directory,
+            } => {
+                progress.set_next_operation_and_emit(
+                    DirectoryCopyOperation::RecreatingSymlink {
+                        target_path: target_file_path.clone(),
+                    },
+                    &mut progress_handler,
+                );
+
+                execute_recreate_symlink_operation(
+                    &link_destination_path,
+                    &target_file_path,
+                    points_to_directory,
+                    should_overwrite_files,
+                )?;
+
+                num_symlinks_recreated += 1;
+            }
+            QueuedOperation::RecreateJunction {
+                junction_destination_path,
+                target_file_path,
+            } => {
+                progress.set_next_operation_and_emit(
+                    DirectoryCopyOperation::RecreatingJunction {
+                        target_path: target_file_path.clone(),
+                    },
+                    &mut progress_handler,
+                );
+
+                execute_recreate_junction_operation(
+                    &junction_destination_path,
+                    &target_file_path,
+                    should_overwrite_files,
+                )?;
+
+                num_junctions_recreated += 1;
+            }
+        }
+    }
+
+    if let Some(forced_mode) = forced_mode {
+        forced_mode.apply_to_created_directories()?;
+    }
+
+    // This must happen last, as copying the contents modifies the target directory.
+    let should_preserve_root_metadata = options.preserve_root_metadata
+        && (!target_directory_exists
+            || target_directory_is_empty.unwrap_or(true)
+            || should_overwrite_directories);
+
+    if should_preserve_root_metadata {
+        copy_directory_metadata(&source_directory_path, &target_directory_path)?;
+    }
+
+    if let Some(mode) = options.force_mode {
+        if !target_directory_exists || should_preserve_root_metadata {
+            apply_forced_mode(&target_directory_path, mode)?;
         }
     }
 
@@ -1198,6 +6081,21 @@ where
         total_bytes_copied: progress.bytes_finished,
         num_files_copied: progress.files_copied,
         num_directories_created: progress.directories_created,
+        num_special_files_recreated,
+        num_special_files_skipped: skipped_entries.num_special_files,
+        num_broken_symlinks_recreated,
+        num_broken_symlinks_skipped: skipped_entries.num_broken_symlinks,
+        num_symlinks_recreated,
+        num_junctions_recreated,
+        num_junctions_skipped: skipped_entries.num_junctions,
+        num_empty_directories_skipped,
+        num_files_filtered_out: 0,
+        num_files_linked: 0,
+        num_existing_files_skipped,
+        num_locked_files_skipped,
+        num_extraneous_entries_deleted: 0,
+        manifest: None,
+        skipped_oversized_file_paths: Vec::new(),
     })
 }
 
@@ -1236,16 +6134,86 @@ mod tests {
         let rejoin_err = rejoin_result.unwrap_err();
 
         match rejoin_err {
-            DirectoryError::OtherReason { reason } => {
-                if reason != "provided source path escapes its source root" {
-                    panic!(
-                        "rejoin_source_subpath_onto_target returned DirectoryError::OtherReason \
-                        with the following reason: {}",
-                        reason
-                    );
-                }
+            DirectoryError::SourcePathEscapesRoot { path } => {
+                assert_eq!(
+                    path, foo,
+                    "rejoin_source_subpath_onto_target returned \
+                    DirectoryError::SourcePathEscapesRoot with an unexpected path"
+                );
             }
             _ => panic!("Unexpected error: {}", rejoin_err),
         }
     }
+
+    #[test]
+    fn file_copy_errors_include_operation_context() {
+        let source_path = Path::new("/hello/there/file.txt");
+        let target_path = Path::new("/different/root/file.txt");
+
+        let directory_error = directory_error_from_file_copy_error(
+            FileError::OtherIoError {
+                error: std::io::Error::new(std::io::ErrorKind::Other, "disk on fire"),
+            },
+            source_path,
+            target_path,
+        );
+
+        match directory_error {
+            DirectoryError::IoDuringOperation {
+                path, operation, ..
+            } => {
+                assert_eq!(path, target_path);
+                assert_eq!(operation, IoOperation::CopyFile);
+            }
+            _ => panic!("Unexpected error: {}", directory_error),
+        }
+    }
+
+    #[test]
+    fn format_sizes_with_binary_units() {
+        assert_eq!(format_size_in_bytes(0), "0 B");
+        assert_eq!(format_size_in_bytes(1023), "1023 B");
+        assert_eq!(format_size_in_bytes(1024), "1.0 KiB");
+        assert_eq!(
+            format_size_in_bytes(1024 * 1024 * 3 / 2),
+            "1.5 MiB"
+        );
+        assert_eq!(format_size_in_bytes(4_509_715_660), "4.2 GiB");
+        assert_eq!(
+            format_size_in_bytes(1024 * 1024 * 1024 * 1024 * 2048),
+            "2048.0 TiB"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_filesystem_errors_are_classified() {
+        let target_path = Path::new("/read-only/root/file.txt");
+
+        let classified_error =
+            classify_read_only_filesystem_error(directory_error_from_file_copy_error(
+                FileError::UnableToAccessTargetFile {
+                    error: std::io::Error::from_raw_os_error(libc::EROFS),
+                },
+                Path::new("/hello/there/file.txt"),
+                target_path,
+            ));
+
+        match classified_error {
+            DirectoryError::ReadOnlyFilesystem { path } => assert_eq!(path, target_path),
+            _ => panic!("Unexpected error: {}", classified_error),
+        }
+
+        // Failing to read the source is never caused by the target being read-only.
+        let source_error = classify_read_only_filesystem_error(DirectoryError::IoDuringOperation {
+            path: target_path.to_path_buf(),
+            operation: IoOperation::ReadSource,
+            source: std::io::Error::from_raw_os_error(libc::EROFS),
+        });
+
+        assert!(matches!(
+            source_error,
+            DirectoryError::IoDuringOperation { .. }
+        ));
+    }
 }
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use super::{walk, WalkOptions};
+use crate::error::DirectoryError;
+
+
+/// Options that influence the [`count_entries`] function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CountOptions {
+    /// Maximum depth of the count.
+    ///
+    /// - `None` indicates no depth limit.
+    /// - `Some(0)` means only the files and directories directly in the root directory are counted.
+    /// - `Some(1)` includes the root directory's contents and one level of its subdirectories.
+    pub maximum_depth: Option<usize>,
+
+    /// Whether to descend into symbolic links to directories
+    /// (see the `Symbolic links` section of [`count_entries`]).
+    pub follow_symbolic_links: bool,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for CountOptions {
+    fn default() -> Self {
+        Self {
+            maximum_depth: None,
+            follow_symbolic_links: false,
+        }
+    }
+}
+
+
+/// Number of entries in a directory tree, as returned by [`count_entries`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EntryCounts {
+    /// Number of files.
+    pub files: usize,
+
+    /// Number of directories (not including the root directory).
+    pub directories: usize,
+
+    /// Number of symbolic links.
+    pub symlinks: usize,
+}
+
+
+/// Counts the files, directories and symbolic links in the directory tree at `directory_path`.
+///
+/// This is a lighter-weight alternative to a [`DirectoryScan`][super::DirectoryScan]
+/// for when only the number of entries is needed: no paths or sizes are retained.
+/// Other entries (e.g. FIFOs, sockets or device nodes) are not counted.
+///
+/// ## Symbolic links
+/// Symbolic links are always counted as symbolic links, never as the file or directory they point to.
+/// If the [`follow_symbolic_links`][CountOptions::follow_symbolic_links] option is `true`,
+/// the contents of symbolic links to directories are counted as well.
+///
+/// **Careful!** Following symbolic links that form a cycle makes the count only end
+/// at the maximum depth (or never, if there is no depth limit).
+///
+/// ## Internals
+/// This function is a shortcut for consuming a [`walk`] with the equivalent [`WalkOptions`].
+pub fn count_entries<P>(
+    directory_path: P,
+    options: CountOptions,
+) -> Result<EntryCounts, DirectoryError>
+where
+    P: AsRef<Path>,
+{
+    let mut counts = EntryCounts::default();
+
+    let entries = walk(
+        directory_path,
+        WalkOptions {
+            maximum_depth: options.maximum_depth,
+            follow_symbolic_links: options.follow_symbolic_links,
+            ..Default::default()
+        },
+    );
+
+    for entry in entries {
+        let entry = entry?;
+
+        if entry.file_type.is_symlink() {
+            counts.symlinks += 1;
+        } else if entry.file_type.is_dir() {
+            counts.directories += 1;
+        } else if entry.file_type.is_file() {
+            counts.files += 1;
+        }
+    }
+
+    Ok(counts)
+}
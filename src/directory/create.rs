@@ -0,0 +1,193 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use crate::{
+    error::{is_read_only_filesystem_error, DirectoryError, IoOperation},
+    path::{find_non_directory_ancestor, probe, PathKind},
+};
+
+
+/// Options that influence the [`create_directory`] function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CreateDirectoryOptions {
+    /// Whether to return `Err(`[`DirectoryError::TargetItemAlreadyExists`]`)`
+    /// if the directory already exists.
+    pub fail_if_exists: bool,
+
+    /// Permission mode bits (e.g. `0o750`) to apply to each created directory.
+    ///
+    /// If `None`, the created directories get the default permissions
+    /// (i.e. as influenced by the process's `umask`).
+    /// Directories that already existed are never modified.
+    ///
+    /// This has no effect on platforms other than Unix.
+    pub unix_permissions: Option<u32>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for CreateDirectoryOptions {
+    fn default() -> Self {
+        Self {
+            fail_if_exists: false,
+            unix_permissions: None,
+        }
+    }
+}
+
+
+/// Describes the result of a successful [`create_directory`] call.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CreatedDirectories {
+    /// Directories that were created, from the outermost to the innermost one
+    /// (i.e. each directory comes after its parent).
+    ///
+    /// This is empty if the directory already existed. To undo the creation,
+    /// remove these directories in reverse order.
+    pub created_directories: Vec<PathBuf>,
+}
+
+
+/// Maps an error encountered while creating `directory_path` to a [`DirectoryError`].
+fn directory_error_from_creation_error(
+    error: std::io::Error,
+    directory_path: &Path,
+) -> DirectoryError {
+    if is_read_only_filesystem_error(&error) {
+        return DirectoryError::ReadOnlyFilesystem {
+            path: directory_path.to_path_buf(),
+        };
+    }
+
+    DirectoryError::IoDuringOperation {
+        path: directory_path.to_path_buf(),
+        operation: IoOperation::CreateDirectory,
+        source: error,
+    }
+}
+
+
+/// Applies the given permission mode bits to the directory at `directory_path`.
+#[cfg(unix)]
+fn set_directory_permissions(directory_path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(
+        directory_path,
+        std::fs::Permissions::from_mode(mode),
+    )
+}
+
+
+/// Creates the directory at `directory_path`, along with any of its missing ancestors
+/// (like [`std::fs::create_dir_all`]), and returns the directories that were actually created.
+///
+/// ### Return value
+/// If successful, [`CreatedDirectories`] is returned, listing the newly created directories
+/// from the outermost one to `directory_path` itself. If the directory already existed,
+/// the list is empty (unless `options.fail_if_exists` is `true`, in which case
+/// `Err(`[`DirectoryError::TargetItemAlreadyExists`]`)` is returned instead).
+///
+/// If `directory_path` exists, but is not a directory, `Err(`[`DirectoryError::InvalidTargetDirectoryPath`]`)`
+/// is returned, and if one of its ancestors is not a directory,
+/// `Err(`[`DirectoryError::TargetParentNotADirectory`]`)`.
+///
+/// ## Options
+/// See [`CreateDirectoryOptions`] for the available options, e.g. the permissions
+/// of the created directories on Unix.
+///
+/// ## Partial creation
+/// If creating one of the directories fails, the directories created before it are not removed.
+pub fn create_directory<P>(
+    directory_path: P,
+    options: CreateDirectoryOptions,
+) -> Result<CreatedDirectories, DirectoryError>
+where
+    P: AsRef<Path>,
+{
+    let directory_path = directory_path.as_ref();
+
+    if let Some(path) = find_non_directory_ancestor(directory_path) {
+        return Err(DirectoryError::TargetParentNotADirectory { path });
+    }
+
+    // Collect the missing directories, from `directory_path` up to the nearest existing ancestor.
+    let mut missing_directory_paths = Vec::new();
+
+    for ancestor in directory_path.ancestors() {
+        // An empty ancestor means the path is relative to the current directory, which exists.
+        if ancestor.as_os_str().is_empty() {
+            break;
+        }
+
+        let ancestor_kind =
+            probe(ancestor).map_err(|error| DirectoryError::UnableToAccessTarget { error })?;
+
+        match ancestor_kind {
+            PathKind::Missing => missing_directory_paths.push(ancestor.to_path_buf()),
+            PathKind::Directory | PathKind::SymlinkToDirectory => break,
+            _ if ancestor == directory_path => {
+                return Err(DirectoryError::InvalidTargetDirectoryPath);
+            }
+            _ => {
+                return Err(DirectoryError::TargetParentNotADirectory {
+                    path: ancestor.to_path_buf(),
+                });
+            }
+        }
+    }
+
+    if missing_directory_paths.is_empty() && options.fail_if_exists {
+        return Err(DirectoryError::TargetItemAlreadyExists {
+            path: directory_path.to_path_buf(),
+        });
+    }
+
+
+    let mut created_directories = Vec::with_capacity(missing_directory_paths.len());
+
+    for missing_directory_path in missing_directory_paths.into_iter().rev() {
+        match fs::create_dir(&missing_directory_path) {
+            Ok(()) => {}
+            // The directory was created by someone else in the meantime, so it isn't ours to report.
+            Err(error)
+                if error.kind() == std::io::ErrorKind::AlreadyExists
+                    && missing_directory_path.is_dir() =>
+            {
+                continue;
+            }
+            Err(error) => {
+                return Err(directory_error_from_creation_error(
+                    error,
+                    &missing_directory_path,
+                ));
+            }
+        }
+
+        created_directories.push(missing_directory_path);
+    }
+
+    // Permissions are applied once everything has been created (innermost directories first),
+    // as restrictive permissions could otherwise prevent us from creating the subdirectories.
+    #[cfg(unix)]
+    {
+        if let Some(mode) = options.unix_permissions {
+            for created_directory_path in created_directories.iter().rev() {
+                set_directory_permissions(created_directory_path, mode).map_err(|error| {
+                    DirectoryError::IoDuringOperation {
+                        path: created_directory_path.clone(),
+                        operation: IoOperation::WriteTarget,
+                        source: error,
+                    }
+                })?;
+            }
+        }
+    }
+
+    Ok(CreatedDirectories {
+        created_directories,
+    })
+}
@@ -0,0 +1,195 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::{
+    ffi::{CString, OsStr, OsString},
+    fs::Metadata,
+    os::unix::{
+        ffi::OsStrExt,
+        io::{AsRawFd, FromRawFd, OwnedFd},
+    },
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use super::scan::DirectoryItemType;
+
+
+/// Size of the buffer the directory entries are read into (see [`read_directory_batched`]).
+///
+/// This is considerably larger than the buffer used by `readdir`, which means
+/// large directories are read using far fewer `getdents64` calls.
+const BATCH_BUFFER_SIZE: usize = 256 * 1024;
+
+/// Byte offset of `d_reclen` in a `linux_dirent64` record
+/// (it is preceded by the 64-bit `d_ino` and `d_off` fields).
+const RECORD_LENGTH_OFFSET: usize = 16;
+
+/// Byte offset of `d_type` in a `linux_dirent64` record.
+const TYPE_OFFSET: usize = 18;
+
+/// Byte offset of the NUL-terminated `d_name` in a `linux_dirent64` record.
+const NAME_OFFSET: usize = 19;
+
+
+/// A single directory entry, as read by [`read_directory_batched`].
+pub(crate) struct BatchedDirectoryEntry {
+    /// The directory this entry was read from, kept open so the entry
+    /// can be queried relative to it (see [`Self::size_in_bytes`]).
+    directory_fd: Arc<OwnedFd>,
+
+    path: PathBuf,
+
+    /// Type of the entry, or `None` if the filesystem did not report it (`DT_UNKNOWN`).
+    file_type: Option<DirectoryItemType>,
+}
+
+impl BatchedDirectoryEntry {
+    pub(crate) fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    pub(crate) fn file_name(&self) -> OsString {
+        self.path
+            .file_name()
+            .map(OsStr::to_os_string)
+            .unwrap_or_default()
+    }
+
+    /// Returns the type of the entry, without following symbolic links.
+    ///
+    /// If the filesystem did not report the type while reading the directory,
+    /// it is read from the entry's metadata instead.
+    pub(crate) fn file_type(&self) -> std::io::Result<DirectoryItemType> {
+        match self.file_type {
+            Some(file_type) => Ok(file_type),
+            None => Ok(self.metadata()?.file_type().into()),
+        }
+    }
+
+    /// Returns the metadata of the entry, without following symbolic links.
+    pub(crate) fn metadata(&self) -> std::io::Result<Metadata> {
+        fs::symlink_metadata(&self.path)
+    }
+
+    /// Returns the size of the entry in bytes, without following symbolic links.
+    ///
+    /// Unlike [`Self::metadata`], this queries the entry relative to its (still open) directory,
+    /// which means the full path doesn't need to be resolved again for every entry.
+    pub(crate) fn size_in_bytes(&self) -> std::io::Result<u64> {
+        let file_name_c = CString::new(self.file_name().as_bytes())?;
+
+        let mut entry_stats = std::mem::MaybeUninit::<libc::stat>::uninit();
+
+        // SAFETY: the directory file descriptor is open for as long as `self` is alive,
+        // `file_name_c` is a valid NUL-terminated string and `entry_stats`
+        // is only read after `fstatat` reports that it has been initialized.
+        let entry_stats = unsafe {
+            if libc::fstatat(
+                self.directory_fd.as_raw_fd(),
+                file_name_c.as_ptr(),
+                entry_stats.as_mut_ptr(),
+                libc::AT_SYMLINK_NOFOLLOW,
+            ) != 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+
+            entry_stats.assume_init()
+        };
+
+        Ok(entry_stats.st_size as u64)
+    }
+}
+
+
+/// Reads all entries of the given directory using `getdents64` directly,
+/// with a larger buffer than the one `readdir` uses.
+///
+/// Entries are returned in the same order [`std::fs::read_dir`] returns them in,
+/// and the `.` and `..` entries are left out. If reading the directory fails partway,
+/// the entries read so far are followed by the error.
+pub(crate) fn read_directory_batched(
+    directory_path: &Path,
+) -> std::io::Result<Vec<std::io::Result<BatchedDirectoryEntry>>> {
+    let directory_path_c = CString::new(directory_path.as_os_str().as_bytes())?;
+
+    // SAFETY: the path is a valid NUL-terminated string.
+    let raw_directory_fd = unsafe {
+        libc::open(
+            directory_path_c.as_ptr(),
+            libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC,
+        )
+    };
+
+    if raw_directory_fd < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    // SAFETY: the file descriptor was just opened and is not owned by anything else.
+    let directory_fd = Arc::new(unsafe { OwnedFd::from_raw_fd(raw_directory_fd) });
+
+    let mut buffer = vec![0u8; BATCH_BUFFER_SIZE];
+    let mut entries = Vec::new();
+
+    loop {
+        // SAFETY: the buffer is valid for writes of `buffer.len()` bytes.
+        let bytes_read = unsafe {
+            libc::syscall(
+                libc::SYS_getdents64,
+                directory_fd.as_raw_fd(),
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+            )
+        };
+
+        if bytes_read < 0 {
+            entries.push(Err(std::io::Error::last_os_error()));
+            break;
+        }
+
+        if bytes_read == 0 {
+            break;
+        }
+
+        let mut records = &buffer[..bytes_read as usize];
+
+        while records.len() > NAME_OFFSET {
+            let record_length = u16::from_ne_bytes([
+                records[RECORD_LENGTH_OFFSET],
+                records[RECORD_LENGTH_OFFSET + 1],
+            ]) as usize;
+
+            let name_bytes = &records[NAME_OFFSET..record_length];
+            let name_length = name_bytes
+                .iter()
+                .position(|byte| *byte == 0)
+                .unwrap_or(name_bytes.len());
+            let name = &name_bytes[..name_length];
+
+            let file_type = match records[TYPE_OFFSET] {
+                libc::DT_REG => Some(DirectoryItemType::File),
+                libc::DT_DIR => Some(DirectoryItemType::Directory),
+                libc::DT_LNK => Some(DirectoryItemType::Symlink),
+                libc::DT_UNKNOWN => None,
+                _ => Some(DirectoryItemType::Other),
+            };
+
+            records = &records[record_length..];
+
+            if name == b"." || name == b".." {
+                continue;
+            }
+
+            entries.push(Ok(BatchedDirectoryEntry {
+                directory_fd: directory_fd.clone(),
+                path: directory_path.join(OsStr::from_bytes(name)),
+                file_type,
+            }));
+        }
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,258 @@
+use std::path::{Path, PathBuf};
+
+
+/// Returns `true` if `path` is a directory junction (and not e.g. a directory symbolic link).
+#[cfg(windows)]
+pub(super) fn is_junction(path: &Path) -> std::io::Result<bool> {
+    use std::os::windows::fs::MetadataExt;
+
+    let metadata = std::fs::symlink_metadata(path)?;
+
+    if metadata.file_attributes() & ffi::FILE_ATTRIBUTE_REPARSE_POINT == 0 {
+        return Ok(false);
+    }
+
+    let reparse_data = ffi::read_reparse_data(path)?;
+
+    Ok(ffi::reparse_tag(&reparse_data) == Some(ffi::IO_REPARSE_TAG_MOUNT_POINT))
+}
+
+/// Returns `true` if `path` is a directory junction. Junctions only exist on Windows.
+#[cfg(not(windows))]
+pub(super) fn is_junction(_path: &Path) -> std::io::Result<bool> {
+    Ok(false)
+}
+
+
+/// Reads the destination of the directory junction at `junction_path`
+/// (without the `\??\` prefix of the NT path stored in the junction).
+#[cfg(windows)]
+pub(super) fn read_junction_destination(junction_path: &Path) -> std::io::Result<PathBuf> {
+    use std::{ffi::OsString, os::windows::ffi::OsStringExt};
+
+    let reparse_data = ffi::read_reparse_data(junction_path)?;
+
+    let invalid_data = || {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "invalid directory junction reparse data",
+        )
+    };
+
+    if ffi::reparse_tag(&reparse_data) != Some(ffi::IO_REPARSE_TAG_MOUNT_POINT) {
+        return Err(invalid_data());
+    }
+
+    let read_u16 = |offset: usize| -> Option<u16> {
+        reparse_data
+            .get(offset..offset + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+    };
+
+    let substitute_name_offset = read_u16(8).ok_or_else(invalid_data)? as usize;
+    let substitute_name_length = read_u16(10).ok_or_else(invalid_data)? as usize;
+
+    let substitute_name_start = ffi::MOUNT_POINT_PATH_BUFFER_OFFSET + substitute_name_offset;
+    let substitute_name_bytes = reparse_data
+        .get(substitute_name_start..substitute_name_start + substitute_name_length)
+        .ok_or_else(invalid_data)?;
+
+    let mut substitute_name: Vec<u16> = substitute_name_bytes
+        .chunks_exact(2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect();
+
+    let nt_prefix: Vec<u16> = ffi::NT_PATH_PREFIX.encode_utf16().collect();
+    if substitute_name.starts_with(&nt_prefix) {
+        substitute_name.drain(..nt_prefix.len());
+    }
+
+    Ok(PathBuf::from(OsString::from_wide(
+        &substitute_name,
+    )))
+}
+
+/// Reads the destination of the directory junction at `junction_path`. Junctions only exist on Windows.
+#[cfg(not(windows))]
+pub(super) fn read_junction_destination(_junction_path: &Path) -> std::io::Result<PathBuf> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "directory junctions are supported only on Windows",
+    ))
+}
+
+
+/// Creates a directory junction at `junction_path` that points to the absolute `destination_path`.
+///
+/// The junction is created as an empty directory that is then turned into a mount point
+/// using the reparse point API. If that fails, the empty directory is removed again.
+#[cfg(windows)]
+pub(super) fn create_junction(
+    destination_path: &Path,
+    junction_path: &Path,
+) -> std::io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let print_name: Vec<u16> = destination_path.as_os_str().encode_wide().collect();
+    let substitute_name: Vec<u16> = ffi::NT_PATH_PREFIX
+        .encode_utf16()
+        .chain(print_name.iter().copied())
+        .collect();
+
+    // Both names are followed by a NUL terminator in the path buffer.
+    let path_buffer_length = (substitute_name.len() + 1 + print_name.len() + 1) * 2;
+    let reparse_data_length = ffi::MOUNT_POINT_PATH_BUFFER_OFFSET - 8 + path_buffer_length;
+
+    let reparse_data_length_u16 = u16::try_from(reparse_data_length).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "junction destination path is too long",
+        )
+    })?;
+
+    let mut reparse_data = Vec::with_capacity(8 + reparse_data_length);
+    reparse_data.extend_from_slice(&ffi::IO_REPARSE_TAG_MOUNT_POINT.to_le_bytes());
+    reparse_data.extend_from_slice(&reparse_data_length_u16.to_le_bytes());
+    // Reserved.
+    reparse_data.extend_from_slice(&0u16.to_le_bytes());
+    // Substitute name offset and length (in bytes, relative to the path buffer).
+    reparse_data.extend_from_slice(&0u16.to_le_bytes());
+    reparse_data.extend_from_slice(&((substitute_name.len() * 2) as u16).to_le_bytes());
+    // Print name offset and length.
+    reparse_data.extend_from_slice(&(((substitute_name.len() + 1) * 2) as u16).to_le_bytes());
+    reparse_data.extend_from_slice(&((print_name.len() * 2) as u16).to_le_bytes());
+
+    for unit in substitute_name
+        .iter()
+        .chain(std::iter::once(&0))
+        .chain(print_name.iter())
+        .chain(std::iter::once(&0))
+    {
+        reparse_data.extend_from_slice(&unit.to_le_bytes());
+    }
+
+    std::fs::create_dir(junction_path)?;
+
+    if let Err(error) = ffi::write_reparse_data(junction_path, &reparse_data) {
+        let _ = std::fs::remove_dir(junction_path);
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// Creates a directory junction at `junction_path`. Junctions only exist on Windows.
+#[cfg(not(windows))]
+pub(super) fn create_junction(
+    _destination_path: &Path,
+    _junction_path: &Path,
+) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "directory junctions are supported only on Windows",
+    ))
+}
+
+
+#[cfg(windows)]
+mod ffi {
+    use std::{
+        os::windows::{fs::OpenOptionsExt, io::AsRawHandle},
+        path::Path,
+    };
+
+    use windows_sys::Win32::{
+        Foundation::HANDLE,
+        Storage::FileSystem::{
+            FILE_FLAG_BACKUP_SEMANTICS,
+            FILE_FLAG_OPEN_REPARSE_POINT,
+            MAXIMUM_REPARSE_DATA_BUFFER_SIZE,
+        },
+        System::{
+            Ioctl::{FSCTL_GET_REPARSE_POINT, FSCTL_SET_REPARSE_POINT},
+            IO::DeviceIoControl,
+        },
+    };
+    pub(super) use windows_sys::Win32::{
+        Storage::FileSystem::FILE_ATTRIBUTE_REPARSE_POINT,
+        System::SystemServices::IO_REPARSE_TAG_MOUNT_POINT,
+    };
+
+    /// Prefix of the NT paths stored as the substitute name of a junction.
+    pub(super) const NT_PATH_PREFIX: &str = r"\??\";
+
+    /// Offset of the path buffer in a mount point `REPARSE_DATA_BUFFER`
+    /// (after the tag, data length, reserved field and four name offsets and lengths).
+    pub(super) const MOUNT_POINT_PATH_BUFFER_OFFSET: usize = 16;
+
+    /// Returns the reparse tag at the start of the given reparse data.
+    pub(super) fn reparse_tag(reparse_data: &[u8]) -> Option<u32> {
+        reparse_data
+            .get(..4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads the raw reparse point data (a `REPARSE_DATA_BUFFER`) of the reparse point at `path`.
+    pub(super) fn read_reparse_data(path: &Path) -> std::io::Result<Vec<u8>> {
+        let file = std::fs::OpenOptions::new()
+            .access_mode(0)
+            .custom_flags(FILE_FLAG_OPEN_REPARSE_POINT | FILE_FLAG_BACKUP_SEMANTICS)
+            .open(path)?;
+
+        let mut reparse_data = vec![0u8; MAXIMUM_REPARSE_DATA_BUFFER_SIZE as usize];
+        let mut bytes_returned = 0u32;
+
+        // SAFETY: the handle is valid for as long as `file` is alive
+        // and the output buffer is exactly as large as we report it to be.
+        let result = unsafe {
+            DeviceIoControl(
+                file.as_raw_handle() as HANDLE,
+                FSCTL_GET_REPARSE_POINT,
+                std::ptr::null(),
+                0,
+                reparse_data.as_mut_ptr() as *mut std::ffi::c_void,
+                reparse_data.len() as u32,
+                &mut bytes_returned,
+                std::ptr::null_mut(),
+            )
+        };
+
+        if result == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        reparse_data.truncate(bytes_returned as usize);
+        Ok(reparse_data)
+    }
+
+    /// Turns the (empty) directory at `path` into a reparse point with the given raw reparse data.
+    pub(super) fn write_reparse_data(path: &Path, reparse_data: &[u8]) -> std::io::Result<()> {
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .custom_flags(FILE_FLAG_OPEN_REPARSE_POINT | FILE_FLAG_BACKUP_SEMANTICS)
+            .open(path)?;
+
+        let mut bytes_returned = 0u32;
+
+        // SAFETY: the handle is valid for as long as `file` is alive
+        // and the input buffer is exactly as large as we report it to be.
+        let result = unsafe {
+            DeviceIoControl(
+                file.as_raw_handle() as HANDLE,
+                FSCTL_SET_REPARSE_POINT,
+                reparse_data.as_ptr() as *const std::ffi::c_void,
+                reparse_data.len() as u32,
+                std::ptr::null_mut(),
+                0,
+                &mut bytes_returned,
+                std::ptr::null_mut(),
+            )
+        };
+
+        if result == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+}
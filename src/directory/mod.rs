@@ -1,11 +1,22 @@
-//! Directory scanning, sizing, copying and moving operations. Includes progress monitoring variants.
+//! Directory creation, scanning, walking, counting, sizing, copying, moving and removal operations. Includes progress monitoring variants.
 
 mod copy;
+mod count;
+mod create;
+#[cfg(target_os = "linux")]
+mod getdents;
+mod junction;
 mod r#move;
+mod remove;
 mod scan;
 mod size;
+mod walk;
 
 pub use copy::*;
+pub use count::*;
+pub use create::*;
 pub use r#move::*;
+pub use remove::*;
 pub use scan::*;
 pub use size::*;
+pub use walk::*;
@@ -1,25 +1,53 @@
 #[cfg(not(feature = "fs-err"))]
 use std::fs;
-use std::path::Path;
+use std::{
+    collections::HashSet,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 #[cfg(feature = "fs-err")]
 use fs_err as fs;
 
-use super::{copy::TargetDirectoryRule, copy_directory_unchecked, DirectoryScan};
+use super::{
+    copy::TargetDirectoryRule,
+    copy_directory_unchecked,
+    copy_directory_with_progress,
+    remove::remove_walked_entries_in_reverse,
+    scan::is_directory_empty_unchecked,
+    walk,
+    DirectoryCopyProgress,
+    DirectoryCopyWithProgressOptions,
+    DirectoryScan,
+    DirectoryScanOptions,
+    WalkOptions,
+};
 use crate::{
     directory::{
         copy::{
+            rejoin_source_subpath_onto_target,
             validate_source_directory_path,
             validate_source_target_directory_pair,
             validate_target_directory_path,
+            ValidatedTargetPath,
         },
-        rejoin_source_subpath_onto_target,
+        BrokenSymlinkBehaviour,
         DirectoryCopyOptions,
+        JunctionBehaviour,
+        LockedFileBehaviour,
+        SpecialFileBehaviour,
+        SymlinkRewrite,
+        TraversalOrder,
     },
-    error::{DirectoryError, DirectoryScanError, DirectorySizeScanError},
+    error::{DirectoryError, DirectoryScanError, DirectorySizeScanError, IoOperation},
+    file::DEFAULT_COPY_BUFFER_SIZE,
 };
 
 /// Options that influence the [`move_directory`] function.
+///
+/// The staging directory is passed to [`move_directory_with_staging_directory`] separately.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct DirectoryMoveOptions {
     /// Specifies whether you allow the target directory to exist before moving
     /// and whether it must be empty or not.
@@ -29,6 +57,18 @@ pub struct DirectoryMoveOptions {
     ///
     /// See [`TargetDirectoryRule`] for more details and examples.
     pub target_directory_rule: TargetDirectoryRule,
+
+    /// If the directory can't simply be renamed and its contents are copied instead, this controls
+    /// how the source directory is removed afterwards.
+    ///
+    /// If `false`, the source directory is removed entirely, except for any files
+    /// a merge left in place (see [`TargetDirectoryRule::Merge`]).
+    ///
+    /// If `true`, only the files that existed in the source directory before the move
+    /// (and were not left in place by a merge) are removed. Directories, including the source directory itself,
+    /// are then removed only if they are empty, so anything that was not moved
+    /// (e.g. a file created in the source directory while the move was in progress) is left intact.
+    pub remove_source_if_empty: bool,
 }
 
 /// Describes actions taken by the [`copy_directory`][crate::directory::copy_directory] function.
@@ -57,7 +97,7 @@ struct DirectoryContentDetails {
 fn collect_source_directory_details(
     source_directory_path: &Path
 ) -> Result<DirectoryContentDetails, DirectoryError> {
-    let scan = DirectoryScan::scan_with_options(source_directory_path, None, false)
+    let scan = DirectoryScan::scan_with_options(source_directory_path, DirectoryScanOptions::default())
         .map_err(|error| match error {
             DirectoryScanError::NotFound => 
                 DirectoryError::SourceDirectoryNotFound,
@@ -95,11 +135,519 @@ fn collect_source_directory_details(
 
     Ok(DirectoryContentDetails {
         total_bytes: total_size_in_bytes,
-        total_files: scan.files.len(),
-        total_directories: scan.directories.len(),
+        total_files: scan.file_entries().len(),
+        total_directories: scan.directory_entries().len(),
     })
 }
 
+/// Source entries that are left in the source directory after a move,
+/// because a merge skipped them (see [`TargetDirectoryRule::Merge`]).
+#[derive(Default)]
+struct RetainedSourceEntries {
+    /// Paths of the retained files (including symbolic links and special files),
+    /// along with all the directories containing them, including the source directory itself.
+    paths: HashSet<PathBuf>,
+
+    /// Number of retained regular files.
+    num_files: usize,
+
+    /// Total size of the retained regular files in bytes.
+    total_file_bytes: u64,
+}
+
+/// Finds the source entries whose target path already exists and which will therefore be skipped
+/// when merging into the target directory with the given rule, leaving them in the source directory.
+///
+/// This is empty unless the rule requires existing target files to be skipped
+/// (see [`TargetDirectoryRule::should_skip_existing_files`]).
+fn collect_retained_source_entries(
+    source_directory_path: &Path,
+    target_directory_path: &Path,
+    target_directory_rule: &TargetDirectoryRule,
+) -> Result<RetainedSourceEntries, DirectoryError> {
+    let mut retained_entries = RetainedSourceEntries::default();
+
+    if !target_directory_rule.should_skip_existing_files() || !target_directory_path.is_dir() {
+        return Ok(retained_entries);
+    }
+
+    for entry in walk(source_directory_path, WalkOptions::default()) {
+        let entry = entry?;
+
+        if entry.file_type.is_dir() {
+            continue;
+        }
+
+        let target_path = rejoin_source_subpath_onto_target(
+            source_directory_path,
+            &entry.path,
+            target_directory_path,
+        )?;
+
+        // An existing directory in place of a file is an error, which the copy will report.
+        let target_file_exists = fs::symlink_metadata(&target_path)
+            .map(|target_metadata| !target_metadata.is_dir())
+            .unwrap_or(false);
+
+        if !target_file_exists {
+            continue;
+        }
+
+        if entry.file_type.is_file() {
+            retained_entries.num_files += 1;
+            retained_entries.total_file_bytes += fs::symlink_metadata(&entry.path)
+                .map(|metadata| metadata.len())
+                .unwrap_or(0);
+        }
+
+        for retained_path in entry.path.ancestors() {
+            // Ancestors of an already retained path have already been added as well.
+            if !retained_entries.paths.insert(retained_path.to_path_buf())
+                || retained_path == source_directory_path
+            {
+                break;
+            }
+        }
+    }
+
+    Ok(retained_entries)
+}
+
+/// Collects the paths of all non-directory entries in the source directory,
+/// see [`DirectoryMoveOptions::remove_source_if_empty`].
+fn collect_source_file_paths(
+    source_directory_path: &Path,
+) -> Result<HashSet<PathBuf>, DirectoryError> {
+    let mut source_file_paths = HashSet::new();
+
+    for entry in walk(source_directory_path, WalkOptions::default()) {
+        let entry = entry?;
+
+        if !entry.file_type.is_dir() {
+            source_file_paths.insert(entry.path);
+        }
+    }
+
+    Ok(source_file_paths)
+}
+
+/// Removes the source directory after its contents have been copied to the target directory,
+/// except for the retained entries (see [`collect_retained_source_entries`]).
+///
+/// If `moved_file_paths` is set, only those files are removed, and directories
+/// (including the source directory itself) are removed only if they are empty afterwards
+/// (see [`DirectoryMoveOptions::remove_source_if_empty`]).
+fn remove_source_directory(
+    source_directory_path: &Path,
+    retained_entries: &RetainedSourceEntries,
+    moved_file_paths: Option<&HashSet<PathBuf>>,
+) -> Result<(), DirectoryError> {
+    if retained_entries.paths.is_empty() && moved_file_paths.is_none() {
+        return fs::remove_dir_all(source_directory_path)
+            .map_err(|error| DirectoryError::OtherIoError { error });
+    }
+
+    let mut source_entries = Vec::new();
+
+    for entry in walk(source_directory_path, WalkOptions::default()) {
+        source_entries.push(entry?);
+    }
+
+    // Directories are empty by the time they are removed, unless they contain retained entries.
+    let _ = remove_walked_entries_in_reverse(
+        source_entries,
+        |entry| {
+            if retained_entries.paths.contains(&entry.path) {
+                return Ok(None);
+            }
+
+            if let Some(moved_file_paths) = moved_file_paths {
+                let should_keep_entry = if entry.file_type.is_dir() {
+                    !is_directory_empty_unchecked(&entry.path)
+                        .map_err(|error| DirectoryError::UnableToAccessSource { error })?
+                } else {
+                    !moved_file_paths.contains(&entry.path)
+                };
+
+                if should_keep_entry {
+                    return Ok(None);
+                }
+            }
+
+            Ok(Some(()))
+        },
+        |_, ()| ControlFlow::Continue(()),
+    )?;
+
+    // The source directory itself is not part of the walk. Unless only empty directories
+    // are to be removed, it contains retained entries at this point and is left in place.
+    if moved_file_paths.is_some()
+        && is_directory_empty_unchecked(source_directory_path)
+            .map_err(|error| DirectoryError::UnableToAccessSource { error })?
+    {
+        fs::remove_dir(source_directory_path).map_err(|error| {
+            DirectoryError::IoDuringOperation {
+                path: source_directory_path.to_path_buf(),
+                operation: IoOperation::RemoveDirectory,
+                source: error,
+            }
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Creates a new empty temporary directory inside the given staging directory and returns its path.
+///
+/// This also serves as a check that the staging directory exists and is writable.
+fn create_temporary_staging_directory(
+    staging_directory_path: &Path,
+) -> Result<PathBuf, DirectoryError> {
+    static STAGING_DIRECTORY_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let staging_error = |error: std::io::Error| DirectoryError::UnableToUseStagingDirectory {
+        path: staging_directory_path.to_path_buf(),
+        error,
+    };
+
+    if !staging_directory_path.is_dir() {
+        return Err(staging_error(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "staging directory does not exist or is not a directory",
+        )));
+    }
+
+    loop {
+        let temporary_directory_path = staging_directory_path.join(format!(
+            ".{}-{}.fs-more-staging",
+            std::process::id(),
+            STAGING_DIRECTORY_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        match fs::create_dir(&temporary_directory_path) {
+            Ok(()) => return Ok(temporary_directory_path),
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(staging_error(error)),
+        }
+    }
+}
+
+
+/// Removes the temporary staging directory (if any) once it is no longer needed.
+///
+/// Errors are ignored, as a left over (empty) staging directory doesn't affect the result of the move.
+fn remove_temporary_staging_directory(temporary_staging_directory_path: Option<PathBuf>) {
+    if let Some(temporary_staging_directory_path) = temporary_staging_directory_path {
+        let _ = fs::remove_dir(temporary_staging_directory_path);
+    }
+}
+
+
+/// Returns `true` if `path` and the nearest existing ancestor of `target_directory_path`
+/// (or `target_directory_path` itself, if it exists) reside on the same filesystem,
+/// meaning entries can be renamed from one to the other.
+///
+/// This can only be checked up front on Unix, on other platforms this always returns `true`
+/// and the renames themselves will fail.
+fn is_on_same_filesystem_as_target(
+    path: &Path,
+    target_directory_path: &Path,
+) -> std::io::Result<bool> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let device = fs::metadata(path)?.dev();
+
+        for ancestor in target_directory_path.ancestors() {
+            match fs::metadata(ancestor) {
+                Ok(ancestor_metadata) => return Ok(ancestor_metadata.dev() == device),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(true)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (path, target_directory_path);
+        Ok(true)
+    }
+}
+
+
+/// Creates the temporary staging directory for a move, see [`move_directory_with_staging_directory`].
+///
+/// If `staging_directory_path` is `None`, the temporary directory is created next to the target directory.
+/// If that isn't possible (e.g. because its parent directory isn't writable or is on a different filesystem),
+/// `Ok(None)` is returned and the move should copy directly into the target directory instead.
+fn create_staging_directory_for_target(
+    staging_directory_path: Option<&Path>,
+    target_directory_path: &Path,
+) -> Result<Option<PathBuf>, DirectoryError> {
+    let staging_directory_path = match staging_directory_path {
+        Some(staging_directory_path) => staging_directory_path,
+        None => {
+            let target_parent_directory_path = match target_directory_path.parent() {
+                Some(parent_path) if !parent_path.as_os_str().is_empty() => parent_path,
+                _ => return Ok(None),
+            };
+
+            if fs::create_dir_all(target_parent_directory_path).is_err() {
+                return Ok(None);
+            }
+
+            let temporary_staging_directory_path =
+                match create_temporary_staging_directory(target_parent_directory_path) {
+                    Ok(temporary_staging_directory_path) => temporary_staging_directory_path,
+                    Err(_) => return Ok(None),
+                };
+
+            // The target directory itself might be a mount point.
+            return match is_on_same_filesystem_as_target(
+                &temporary_staging_directory_path,
+                target_directory_path,
+            ) {
+                Ok(true) => Ok(Some(temporary_staging_directory_path)),
+                _ => {
+                    remove_temporary_staging_directory(Some(temporary_staging_directory_path));
+                    Ok(None)
+                }
+            };
+        }
+    };
+
+    let temporary_staging_directory_path =
+        create_temporary_staging_directory(staging_directory_path)?;
+
+    match is_on_same_filesystem_as_target(
+        &temporary_staging_directory_path,
+        target_directory_path,
+    ) {
+        Ok(true) => Ok(Some(temporary_staging_directory_path)),
+        Ok(false) => {
+            remove_temporary_staging_directory(Some(temporary_staging_directory_path));
+
+            Err(DirectoryError::UnableToUseStagingDirectory {
+                path: staging_directory_path.to_path_buf(),
+                error: std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "staging directory is not on the same filesystem as the target directory",
+                ),
+            })
+        }
+        Err(error) => {
+            remove_temporary_staging_directory(Some(temporary_staging_directory_path));
+
+            Err(DirectoryError::UnableToUseStagingDirectory {
+                path: staging_directory_path.to_path_buf(),
+                error,
+            })
+        }
+    }
+}
+
+
+/// Collects the renames that move the contents of `staged_directory_path` into the existing
+/// `target_directory_path`, merging into existing subdirectories according to `target_directory_rule`.
+///
+/// Existing target files are either overwritten (included in the renames), skipped (left in the staging directory)
+/// or cause `Err(`[`DirectoryError::TargetItemAlreadyExists`]`)`. As this is checked for all entries
+/// before anything is renamed, a collision leaves the target directory untouched.
+fn collect_staged_renames(
+    staged_directory_path: &Path,
+    target_directory_path: &Path,
+    target_directory_rule: &TargetDirectoryRule,
+    renames: &mut Vec<(PathBuf, PathBuf)>,
+) -> Result<(), DirectoryError> {
+    let read_staged_directory_error = |error: std::io::Error| DirectoryError::IoDuringOperation {
+        path: staged_directory_path.to_path_buf(),
+        operation: IoOperation::ReadSource,
+        source: error,
+    };
+
+    for staged_entry in fs::read_dir(staged_directory_path).map_err(read_staged_directory_error)? {
+        let staged_entry = staged_entry.map_err(read_staged_directory_error)?;
+        let staged_entry_is_directory = staged_entry
+            .file_type()
+            .map_err(read_staged_directory_error)?
+            .is_dir();
+
+        let staged_entry_path = staged_entry.path();
+        let target_entry_path = target_directory_path.join(staged_entry.file_name());
+
+        let target_entry_metadata = match fs::symlink_metadata(&target_entry_path) {
+            Ok(target_entry_metadata) => target_entry_metadata,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                renames.push((staged_entry_path, target_entry_path));
+                continue;
+            }
+            Err(error) => return Err(DirectoryError::UnableToAccessTarget { error }),
+        };
+
+        let already_exists_error = || DirectoryError::TargetItemAlreadyExists {
+            path: target_entry_path.clone(),
+        };
+
+        if staged_entry_is_directory != target_entry_metadata.is_dir() {
+            return Err(already_exists_error());
+        }
+
+        if staged_entry_is_directory {
+            if !target_directory_rule.should_overwrite_existing_directories() {
+                return Err(already_exists_error());
+            }
+
+            collect_staged_renames(
+                &staged_entry_path,
+                &target_entry_path,
+                target_directory_rule,
+                renames,
+            )?;
+        } else if target_directory_rule.should_skip_existing_files() {
+            continue;
+        } else if target_directory_rule.should_overwrite_existing_files() {
+            renames.push((staged_entry_path, target_entry_path));
+        } else {
+            return Err(already_exists_error());
+        }
+    }
+
+    Ok(())
+}
+
+
+/// Moves the fully copied contents of the temporary staging directory into the target directory
+/// by renaming them (see [`collect_staged_renames`]).
+fn move_staged_directory_into_target(
+    temporary_staging_directory_path: &Path,
+    validated_target_path: &ValidatedTargetPath,
+    target_directory_rule: &TargetDirectoryRule,
+) -> Result<(), DirectoryError> {
+    let target_directory_path = &validated_target_path.target_directory_path;
+
+    let renames = if validated_target_path.target_directory_exists {
+        let mut renames = Vec::new();
+        collect_staged_renames(
+            temporary_staging_directory_path,
+            target_directory_path,
+            target_directory_rule,
+            &mut renames,
+        )?;
+
+        renames
+    } else {
+        // The staging directory might not be next to the target, so the target's parent may not exist yet.
+        if let Some(target_parent_directory_path) = target_directory_path.parent() {
+            fs::create_dir_all(target_parent_directory_path).map_err(|error| {
+                DirectoryError::IoDuringOperation {
+                    path: target_parent_directory_path.to_path_buf(),
+                    operation: IoOperation::CreateDirectory,
+                    source: error,
+                }
+            })?;
+        }
+
+        vec![(
+            temporary_staging_directory_path.to_path_buf(),
+            target_directory_path.clone(),
+        )]
+    };
+
+    for (staged_path, target_path) in renames {
+        fs::rename(&staged_path, &target_path).map_err(|error| {
+            DirectoryError::IoDuringOperation {
+                path: target_path,
+                operation: IoOperation::WriteTarget,
+                source: error,
+            }
+        })?;
+    }
+
+    Ok(())
+}
+
+
+/// Attempts to move the source directory to the target directory by renaming it, which is much faster
+/// than copying, but fails if the source and target paths aren't on the same mount point or filesystem or,
+/// if on Windows, the target directory already exists (in that case, the *contents* are renamed instead).
+///
+/// Returns `Ok(true)` if the directory has been moved and `Ok(false)` if it must be copied instead.
+fn try_moving_directory_by_renaming(
+    source_directory_path: &Path,
+    validated_target_path: &ValidatedTargetPath,
+) -> Result<bool, DirectoryError> {
+    if !validated_target_path
+        .target_directory_is_empty
+        .unwrap_or(true)
+    {
+        return Ok(false);
+    }
+
+    #[cfg(unix)]
+    {
+        // If the target directory exists, but is empty, we can (on Unix only)
+        // directly rename the source directory to the target (this might still fail due to different mount points).
+        Ok(fs::rename(
+            source_directory_path,
+            &validated_target_path.target_directory_path,
+        )
+        .is_ok())
+    }
+
+    #[cfg(windows)]
+    {
+        // On Windows, `rename`'s target directory must not exist.
+        if !validated_target_path.target_directory_exists
+            && fs::rename(
+                source_directory_path,
+                &validated_target_path.target_directory_path,
+            )
+            .is_ok()
+        {
+            return Ok(true);
+        }
+
+        // Otherwise, we can rename the *contents* of the directory instead.
+        // Note that this is not a recursive scan, we're simply moving (by renaming)
+        // the files and directories directly inside the source directory into the target directory.
+        let source_directory_contents = fs::read_dir(source_directory_path)
+            .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
+
+        for source_entry in source_directory_contents {
+            let source_entry =
+                source_entry.map_err(|error| DirectoryError::UnableToAccessSource { error })?;
+
+            let source_path = source_entry.path();
+            let target_path = rejoin_source_subpath_onto_target(
+                source_directory_path,
+                &source_path,
+                &validated_target_path.target_directory_path,
+            )?;
+
+            fs::rename(source_path, target_path)
+                .map_err(|error| DirectoryError::OtherIoError { error })?;
+        }
+
+        // Finally, we need to remove the, now empty, source directory path.
+        fs::remove_dir(source_directory_path)
+            .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
+
+        Ok(true)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        compile_error!(
+            "fs-more supports only the following values of target_family: unix and windows \
+            (notably, wasm is unsupported)."
+        );
+    }
+}
+
+
 /// Move a directory from `source_directory_path` to `target_directory_path`.
 ///
 /// - `source_directory_path` must point to an existing directory path.
@@ -110,15 +658,29 @@ fn collect_source_directory_details(
 /// Depending on the [`options.target_directory_rule`][DirectoryMoveOptions::target_directory_rule] option,
 /// the `target_directory_path` must:
 /// - [`DisallowExisting`][TargetDirectoryRule::DisallowExisting]: not exist,
-/// - [`AllowEmpty`][TargetDirectoryRule::AllowEmpty]: either not exist or be empty, or,
+/// - [`AllowEmpty`][TargetDirectoryRule::AllowEmpty]: either not exist or be empty,
 /// - [`AllowNonEmpty`][TargetDirectoryRule::AllowNonEmpty]: either not exist, be empty, or be non-empty. Additionally,
-///   the specified overwriting rules are respected (see fields).
+///   the specified overwriting rules are respected (see fields), or,
+/// - [`Merge`][TargetDirectoryRule::Merge]: either not exist, be empty, or be non-empty. Existing subdirectories
+///   are merged into and existing files are overwritten or skipped (see fields). Source files that are skipped
+///   because of an existing target file are left in the source directory.
 ///
 ///
 /// ### Return value
 /// Upon success, the function returns the number of files and directories that were moved
 /// as well as the total amount of bytes moved, see [`FinishedDirectoryMove`].
 ///
+/// ### Staging directory
+/// If the directory can't simply be renamed, the source directory is first copied into a temporary
+/// staging directory next to the target directory and then renamed from there into the target.
+/// If that isn't possible, the contents are copied directly into the target directory.
+/// To use a different staging directory, see [`move_directory_with_staging_directory`].
+///
+/// ### Special files
+/// If the directory can't simply be renamed and must be copied instead, special files
+/// (FIFOs, sockets and device nodes) are recreated on the target,
+/// see [`SpecialFileBehaviour::Recreate`].
+///
 /// ### Warnings
 /// *Warning:* this function **does not follow symbolic links**.
 pub fn move_directory<S, T>(
@@ -130,9 +692,57 @@ where
     S: AsRef<Path>,
     T: AsRef<Path>,
 {
-    let source_directory_path = validate_source_directory_path(source_directory_path.as_ref())?;
-    let validated_target_path = validate_target_directory_path(
+    move_directory_through_staging_directory(
+        source_directory_path.as_ref(),
         target_directory_path.as_ref(),
+        None,
+        options,
+    )
+}
+
+
+/// Moves a directory from `source_directory_path` to `target_directory_path`,
+/// staging the copied contents in `staging_directory_path` if the directory can't simply be renamed.
+///
+/// This function behaves like [`move_directory`], except that if the directory can't simply be renamed
+/// (e.g. because the target is on a different filesystem), its contents are first copied into a temporary
+/// directory inside `staging_directory_path`, from which they are then renamed into the target.
+/// A partially failed copy therefore never leaves half-copied files in the target directory.
+///
+/// The staging directory must exist, be writable and be on the same filesystem as the target directory,
+/// which is checked before anything is moved. If it isn't usable,
+/// `Err(`[`DirectoryError::UnableToUseStagingDirectory`]`)` is returned.
+pub fn move_directory_with_staging_directory<S, T, G>(
+    source_directory_path: S,
+    target_directory_path: T,
+    staging_directory_path: G,
+    options: DirectoryMoveOptions,
+) -> Result<FinishedDirectoryMove, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+    G: AsRef<Path>,
+{
+    move_directory_through_staging_directory(
+        source_directory_path.as_ref(),
+        target_directory_path.as_ref(),
+        Some(staging_directory_path.as_ref()),
+        options,
+    )
+}
+
+
+/// Moves a directory (see [`move_directory`]), staging the copied contents in `staging_directory_path`
+/// or, if it is `None`, next to the target directory (see [`create_staging_directory_for_target`]).
+fn move_directory_through_staging_directory(
+    source_directory_path: &Path,
+    target_directory_path: &Path,
+    staging_directory_path: Option<&Path>,
+    options: DirectoryMoveOptions,
+) -> Result<FinishedDirectoryMove, DirectoryError> {
+    let source_directory_path = validate_source_directory_path(source_directory_path)?;
+    let validated_target_path = validate_target_directory_path(
+        target_directory_path,
         &options.target_directory_rule,
     )?;
 
@@ -142,84 +752,119 @@ where
     )?;
 
     let source_details = collect_source_directory_details(&source_directory_path)?;
+    let retained_entries = collect_retained_source_entries(
+        &source_directory_path,
+        &validated_target_path.target_directory_path,
+        &options.target_directory_rule,
+    )?;
 
-    // We can attempt to simply rename the directory. This is much faster,
-    // but will fail if the source and target paths aren't on the same mount point or filesystem or,
-    // if on Windows, the target directory already exists.
-    if validated_target_path
-        .target_directory_is_empty
-        .unwrap_or(true)
-    {
-        #[cfg(unix)]
-        {
-            // If the target directory exists, but is empty, we can (on Unix only)
-            // directly rename the source directory to the target (this might still fail due to different mount points).
-            if fs::rename(&source_directory_path, &target_directory_path).is_ok() {
-                return Ok(FinishedDirectoryMove {
-                    total_bytes_moved: source_details.total_bytes,
-                    num_files_moved: source_details.total_files,
-                    num_directories_moved: source_details.total_directories,
-                });
-            }
-        }
-
-        #[cfg(windows)]
-        {
-            // On Windows, `rename`'s target directory must not exist.
-            if !validated_target_path.target_directory_exists
-                && fs::rename(&source_directory_path, &target_directory_path).is_ok()
-            {
-                return Ok(FinishedDirectoryMove {
-                    total_bytes_moved: source_details.total_bytes,
-                    num_files_moved: source_details.total_files,
-                    num_directories_moved: source_details.total_directories,
-                });
-            }
+    let moved_file_paths = if options.remove_source_if_empty {
+        Some(collect_source_file_paths(&source_directory_path)?)
+    } else {
+        None
+    };
 
-            // Otherwise, we can rename the *contents* of the directory instead.
-            // Note that this is not a recursive scan, we're simply moving (by renaming)
-            // the files and directories directly inside the source directory into the target directory.
-            let source_directory_contents = fs::read_dir(&source_directory_path)
-                .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
-
-            for source_entry in source_directory_contents {
-                let source_entry =
-                    source_entry.map_err(|error| DirectoryError::UnableToAccessSource { error })?;
-
-                let source_path = source_entry.path();
-                let target_path = rejoin_source_subpath_onto_target(
-                    &source_directory_path,
-                    &source_path,
-                    &validated_target_path.target_directory_path,
-                )?;
-
-                fs::rename(source_path, target_path)
-                    .map_err(|error| DirectoryError::OtherIoError { error })?;
-            }
+    let finished_move = FinishedDirectoryMove {
+        total_bytes_moved: source_details.total_bytes - retained_entries.total_file_bytes,
+        num_files_moved: source_details.total_files - retained_entries.num_files,
+        num_directories_moved: source_details.total_directories,
+    };
 
-            // Finally, we need to remove the, now empty, source directory path.
-            fs::remove_dir(&source_directory_path)
-                .map_err(|error| DirectoryError::UnableToAccessSource { error })?;
+    // A user-provided staging directory is validated before anything is moved.
+    let mut temporary_staging_directory_path = match staging_directory_path {
+        Some(staging_directory_path) => create_staging_directory_for_target(
+            Some(staging_directory_path),
+            &validated_target_path.target_directory_path,
+        )?,
+        None => None,
+    };
 
-            return Ok(FinishedDirectoryMove {
-                total_bytes_moved: source_details.total_bytes,
-                num_files_moved: source_details.total_files,
-                num_directories_moved: source_details.total_directories,
-            });
-        }
+    // We can attempt to simply rename the directory. This is much faster,
+    // but will fail if the source and target paths aren't on the same mount point or filesystem.
+    if try_moving_directory_by_renaming(&source_directory_path, &validated_target_path)? {
+        remove_temporary_staging_directory(temporary_staging_directory_path);
 
-        #[cfg(not(any(unix, windows)))]
-        {
-            compile_error!(
-                "fs-more supports only the following values of target_family: unix and windows \
-                (notably, wasm is unsupported)."
-            );
-        }
+        return Ok(finished_move);
     }
 
     // At this point a simple rename was either impossible or failed.
     // We need to copy and delete instead.
 
+    if staging_directory_path.is_none() {
+        temporary_staging_directory_path = create_staging_directory_for_target(
+            None,
+            &validated_target_path.target_directory_path,
+        )?;
+    }
+
+    if let Some(temporary_staging_directory_path) = temporary_staging_directory_path {
+        // Copy the source into the staging directory first, then rename it from there into the target.
+        let staging_result = copy_directory_unchecked(
+            source_directory_path.clone(),
+            ValidatedTargetPath {
+                target_directory_path: temporary_staging_directory_path.clone(),
+                target_directory_exists: true,
+                target_directory_is_empty: Some(true),
+            },
+            DirectoryCopyOptions {
+                target_directory_rule: TargetDirectoryRule::AllowEmpty,
+                minimum_copy_depth: None,
+                maximum_copy_depth: None,
+                special_file_behaviour: SpecialFileBehaviour::Recreate,
+                broken_symlink_behaviour: BrokenSymlinkBehaviour::CopyAsSymlink,
+                pre_scan: true,
+                copy_into_subdirectory: false,
+                ensure_free_space: false,
+                ensure_free_inodes: false,
+                preserve_root_metadata: false,
+                force_mode: None,
+                preserve_ownership: false,
+                skip_empty_directories: false,
+                traversal_order: TraversalOrder::Unordered,
+                fast_enumeration: false,
+                follow_root_symlink_only: false,
+                max_symlinks_to_follow: None,
+                collect_all_collisions: false,
+                junction_behaviour: JunctionBehaviour::Follow,
+                locked_file_behaviour: LockedFileBehaviour::Error,
+                symlink_target_rewrite: SymlinkRewrite::Verbatim,
+                directories_only: false,
+                delete_extraneous_from_target: false,
+                produce_manifest: None,
+                max_file_size: None,
+                verify_after_copy: None,
+            },
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .and_then(|_| {
+            move_staged_directory_into_target(
+                &temporary_staging_directory_path,
+                &validated_target_path,
+                &options.target_directory_rule,
+            )
+        });
+
+        // The staging directory itself is left over if its contents were renamed one by one,
+        // and so are copies of files that were skipped by a merge (or everything, if the move failed).
+        if temporary_staging_directory_path.exists() {
+            let _ = fs::remove_dir_all(&temporary_staging_directory_path);
+        }
+
+        staging_result?;
+
+        remove_source_directory(
+            &source_directory_path,
+            &retained_entries,
+            moved_file_paths.as_ref(),
+        )?;
+
+        return Ok(finished_move);
+    }
+
     if !validated_target_path.target_directory_exists {
         fs::create_dir_all(&source_directory_path)
             .map_err(|error| DirectoryError::UnableToAccessTarget { error })?;
@@ -230,16 +875,312 @@ where
         validated_target_path,
         DirectoryCopyOptions {
             target_directory_rule: options.target_directory_rule,
+            minimum_copy_depth: None,
             maximum_copy_depth: None,
+            // The source will be removed afterwards, so we must not silently drop special files.
+            special_file_behaviour: SpecialFileBehaviour::Recreate,
+            broken_symlink_behaviour: BrokenSymlinkBehaviour::CopyAsSymlink,
+            pre_scan: true,
+            copy_into_subdirectory: false,
+            ensure_free_space: false,
+            ensure_free_inodes: false,
+            preserve_root_metadata: false,
+            force_mode: None,
+            preserve_ownership: false,
+            skip_empty_directories: false,
+            traversal_order: TraversalOrder::Unordered,
+            fast_enumeration: false,
+            follow_root_symlink_only: false,
+            max_symlinks_to_follow: None,
+            collect_all_collisions: false,
+            junction_behaviour: JunctionBehaviour::Follow,
+            locked_file_behaviour: LockedFileBehaviour::Error,
+            symlink_target_rewrite: SymlinkRewrite::Verbatim,
+            directories_only: false,
+            delete_extraneous_from_target: false,
+            produce_manifest: None,
+            max_file_size: None,
+            verify_after_copy: None,
+        },
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+
+    remove_source_directory(
+        &source_directory_path,
+        &retained_entries,
+        moved_file_paths.as_ref(),
+    )?;
+
+    Ok(finished_move)
+}
+
+
+/// Options that influence the [`move_directory_with_progress`] function.
+///
+/// The progress handler is passed to [`move_directory_with_progress`] separately.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DirectoryMoveWithProgressOptions {
+    /// Specifies whether you allow the target directory to exist before moving
+    /// and whether it must be empty or not.
+    ///
+    /// If you allow a non-empty target directory, you may also specify whether you allow
+    /// target files or subdirectories to already exist (and be overwritten).
+    ///
+    /// See [`TargetDirectoryRule`] for more details and examples.
+    pub target_directory_rule: TargetDirectoryRule,
+
+    /// Internal buffer size (for both reading and writing) when copying files,
+    /// defaults to [`DEFAULT_COPY_BUFFER_SIZE`] (64 KiB).
+    pub buffer_size: usize,
+
+    /// *Minimum* amount of bytes written between two consecutive progress reports
+    /// while copying. Defaults to 64 KiB.
+    ///
+    /// *Note that the interval can be larger.*
+    pub progress_update_byte_interval: u64,
+}
+
+impl Default for DirectoryMoveWithProgressOptions {
+    fn default() -> Self {
+        Self {
+            target_directory_rule: TargetDirectoryRule::default(),
+            buffer_size: DEFAULT_COPY_BUFFER_SIZE,
+            progress_update_byte_interval: DEFAULT_COPY_BUFFER_SIZE as u64,
+        }
+    }
+}
+
+
+/// Describes the phase a [`move_directory_with_progress`] call is in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MovePhase {
+    /// The source directory was moved by renaming it, which happens in a single step.
+    /// This phase is only ever reported once, with everything finished.
+    Renaming,
+
+    /// The source directory's contents are being copied to the target directory.
+    Copying,
+
+    /// The source directory's contents have been copied and are now being removed.
+    Deleting,
+}
+
+
+/// Represents the progress of moving a directory.
+///
+/// Used to report directory moving progress to a user-provided closure, see [`move_directory_with_progress`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DirectoryMoveProgress {
+    /// The phase the move is currently in.
+    pub phase: MovePhase,
+
+    /// Amount of bytes that need to be moved for the directory move to be complete.
+    pub bytes_total: u64,
+
+    /// Amount of bytes that have been copied to the target directory so far.
+    pub bytes_copied: u64,
+
+    /// Number of files that have been copied to the target directory so far.
+    pub files_copied: usize,
+
+    /// Number of directories that have been created in the target directory so far.
+    pub directories_created: usize,
+
+    /// Number of source entries (files, directories and symbolic links, including the source directory itself)
+    /// that need to be removed in the [`Deleting`][MovePhase::Deleting] phase. This is `0` before that phase starts.
+    pub entries_to_delete: usize,
+
+    /// Number of source entries that have been removed so far.
+    pub entries_deleted: usize,
+
+    /// Amount of bytes (i.e. sizes of files) that have been removed from the source directory so far.
+    pub bytes_deleted: u64,
+}
+
+
+/// Removes the source directory of a move after its contents have been copied, bottom-up,
+/// reporting progress after each removed entry.
+///
+/// Retained entries (see [`collect_retained_source_entries`]) are left in place.
+fn remove_source_directory_with_progress<F>(
+    source_directory_path: &Path,
+    retained_entries: &RetainedSourceEntries,
+    progress: &mut DirectoryMoveProgress,
+    progress_handler: &mut F,
+) -> Result<(), DirectoryError>
+where
+    F: FnMut(&DirectoryMoveProgress),
+{
+    let mut source_entries = Vec::new();
+
+    for entry in walk(source_directory_path, WalkOptions::default()) {
+        let entry = entry?;
+
+        if !retained_entries.paths.contains(&entry.path) {
+            source_entries.push(entry);
+        }
+    }
+
+    let should_remove_source_directory = retained_entries.paths.is_empty();
+
+    progress.phase = MovePhase::Deleting;
+    progress.entries_to_delete = source_entries.len() + usize::from(should_remove_source_directory);
+    progress_handler(progress);
+
+    let _ = remove_walked_entries_in_reverse(
+        source_entries,
+        |entry| {
+            // The size of a file has to be read before it is removed.
+            let file_size_in_bytes = if entry.file_type.is_file() {
+                fs::symlink_metadata(&entry.path)
+                    .map(|metadata| metadata.len())
+                    .unwrap_or(0)
+            } else {
+                0
+            };
+
+            Ok(Some(file_size_in_bytes))
+        },
+        |_, file_size_in_bytes| {
+            progress.bytes_deleted += file_size_in_bytes;
+            progress.entries_deleted += 1;
+            progress_handler(progress);
+
+            ControlFlow::Continue(())
+        },
+    )?;
+
+    if !should_remove_source_directory {
+        return Ok(());
+    }
+
+    fs::remove_dir(source_directory_path).map_err(|error| DirectoryError::IoDuringOperation {
+        path: source_directory_path.to_path_buf(),
+        operation: IoOperation::RemoveDirectory,
+        source: error,
+    })?;
+
+    progress.entries_deleted += 1;
+    progress_handler(progress);
+
+    Ok(())
+}
+
+
+/// Move a directory from `source_directory_path` to `target_directory_path`
+/// with progress reporting.
+///
+/// This function behaves like [`move_directory`] (without a staging directory), but additionally calls
+/// `progress_handler` with a [`&DirectoryMoveProgress`][DirectoryMoveProgress] as the move progresses.
+///
+/// ### Progress phases
+/// If the directory can simply be renamed, a single progress report with
+/// [`MovePhase::Renaming`] is emitted once the directory has been moved.
+///
+/// Otherwise, the directory is moved in two phases:
+/// - [`MovePhase::Copying`]: the source directory is copied to the target directory
+///   (see [`copy_directory_with_progress`][crate::directory::copy_directory_with_progress]),
+///   reported at most every [`options.progress_update_byte_interval`][DirectoryMoveWithProgressOptions::progress_update_byte_interval] bytes, and
+/// - [`MovePhase::Deleting`]: the source directory is removed, reported after each removed entry
+///   (see [`DirectoryMoveProgress::entries_deleted`] and [`DirectoryMoveProgress::entries_to_delete`]).
+///
+/// ### Return value
+/// Upon success, the function returns the number of files and directories that were moved
+/// as well as the total amount of bytes moved, see [`FinishedDirectoryMove`].
+///
+/// ### Warnings
+/// *Warning:* this function **does not follow symbolic links**.
+pub fn move_directory_with_progress<S, T, F>(
+    source_directory_path: S,
+    target_directory_path: T,
+    options: DirectoryMoveWithProgressOptions,
+    mut progress_handler: F,
+) -> Result<FinishedDirectoryMove, DirectoryError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+    F: FnMut(&DirectoryMoveProgress),
+{
+    let source_directory_path = validate_source_directory_path(source_directory_path.as_ref())?;
+    let validated_target_path = validate_target_directory_path(
+        target_directory_path.as_ref(),
+        &options.target_directory_rule,
+    )?;
+
+    validate_source_target_directory_pair(
+        &source_directory_path,
+        &validated_target_path.target_directory_path,
+    )?;
+
+    let source_details = collect_source_directory_details(&source_directory_path)?;
+    let retained_entries = collect_retained_source_entries(
+        &source_directory_path,
+        &validated_target_path.target_directory_path,
+        &options.target_directory_rule,
+    )?;
+
+    let mut progress = DirectoryMoveProgress {
+        phase: MovePhase::Copying,
+        bytes_total: source_details.total_bytes,
+        bytes_copied: 0,
+        files_copied: 0,
+        directories_created: 0,
+        entries_to_delete: 0,
+        entries_deleted: 0,
+        bytes_deleted: 0,
+    };
+
+    if try_moving_directory_by_renaming(&source_directory_path, &validated_target_path)? {
+        progress.phase = MovePhase::Renaming;
+        progress.bytes_copied = source_details.total_bytes;
+        progress.files_copied = source_details.total_files;
+        progress.directories_created = source_details.total_directories;
+        progress_handler(&progress);
+
+        return Ok(FinishedDirectoryMove {
+            total_bytes_moved: source_details.total_bytes,
+            num_files_moved: source_details.total_files,
+            num_directories_moved: source_details.total_directories,
+        });
+    }
+
+    // At this point a simple rename was either impossible or failed.
+    // We need to copy and delete instead.
+    copy_directory_with_progress(
+        &source_directory_path,
+        &validated_target_path.target_directory_path,
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: options.target_directory_rule,
+            buffer_size: options.buffer_size,
+            progress_update_byte_interval: options.progress_update_byte_interval,
+            // The source will be removed afterwards, so we must not silently drop special files.
+            special_file_behaviour: SpecialFileBehaviour::Recreate,
+            broken_symlink_behaviour: BrokenSymlinkBehaviour::CopyAsSymlink,
+            ..Default::default()
+        },
+        |copy_progress: &DirectoryCopyProgress| {
+            progress.bytes_copied = copy_progress.bytes_finished;
+            progress.files_copied = copy_progress.files_copied;
+            progress.directories_created = copy_progress.directories_created;
+
+            progress_handler(&progress);
         },
     )?;
 
-    fs::remove_dir_all(source_directory_path)
-        .map_err(|error| DirectoryError::OtherIoError { error })?;
+    remove_source_directory_with_progress(
+        &source_directory_path,
+        &retained_entries,
+        &mut progress,
+        &mut progress_handler,
+    )?;
 
     Ok(FinishedDirectoryMove {
-        total_bytes_moved: source_details.total_bytes,
-        num_files_moved: source_details.total_files,
+        total_bytes_moved: source_details.total_bytes - retained_entries.total_file_bytes,
+        num_files_moved: source_details.total_files - retained_entries.num_files,
         num_directories_moved: source_details.total_directories,
     })
 }
@@ -0,0 +1,289 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::{
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use super::{scan::is_directory_empty_unchecked, walk, WalkEntry, WalkOptions};
+use crate::error::{DirectoryError, IoOperation};
+
+
+/// Removes the given entries, which must be in the order [`walk`] visits them in, bottom-up.
+///
+/// The walk visits each directory before its contents, so going over the entries in reverse
+/// means each directory's contents are handled before the directory itself.
+///
+/// For each entry, `prepare_removal` is called first: if it returns `None`, the entry is left in place,
+/// otherwise the entry is removed and the returned value is passed to `on_removed` along with it.
+/// Note that directories are removed with [`std::fs::remove_dir`], meaning `prepare_removal` must leave
+/// in place any directory that still contains entries at that point.
+///
+/// If `on_removed` returns [`ControlFlow::Break`], no further entries are removed
+/// and `Ok(ControlFlow::Break(()))` is returned.
+pub(super) fn remove_walked_entries_in_reverse<T, P, R>(
+    entries: Vec<WalkEntry>,
+    mut prepare_removal: P,
+    mut on_removed: R,
+) -> Result<ControlFlow<()>, DirectoryError>
+where
+    P: FnMut(&WalkEntry) -> Result<Option<T>, DirectoryError>,
+    R: FnMut(WalkEntry, T) -> ControlFlow<()>,
+{
+    for entry in entries.into_iter().rev() {
+        let removal_context = match prepare_removal(&entry)? {
+            Some(removal_context) => removal_context,
+            None => continue,
+        };
+
+        if entry.file_type.is_dir() {
+            fs::remove_dir(&entry.path).map_err(|error| DirectoryError::IoDuringOperation {
+                path: entry.path.clone(),
+                operation: IoOperation::RemoveDirectory,
+                source: error,
+            })?;
+        } else {
+            fs::remove_file(&entry.path).map_err(|error| DirectoryError::IoDuringOperation {
+                path: entry.path.clone(),
+                operation: IoOperation::RemoveFile,
+                source: error,
+            })?;
+        }
+
+        if on_removed(entry, removal_context).is_break() {
+            return Ok(ControlFlow::Break(()));
+        }
+    }
+
+    Ok(ControlFlow::Continue(()))
+}
+
+
+/// Removes all empty subdirectories of the given directory, bottom-up.
+///
+/// Because subdirectories are removed before their parents, a directory that contains
+/// only empty subdirectories (at any depth) is empty once they are removed, and is removed as well.
+/// The directory at `directory_path` itself is never removed, even if it ends up empty.
+///
+/// ### Return value
+/// If successful, the paths of the removed directories are returned,
+/// in the order they were removed in (i.e. subdirectories before their parents).
+///
+/// ## Symbolic links
+/// Symbolic links are never followed. A directory containing only a symbolic link
+/// (even one to an empty directory) is not empty and is not removed.
+///
+/// ## Internals
+/// This function uses [`walk`] to find all subdirectories and [`std::fs::remove_dir`] to remove them.
+pub fn remove_empty_directories<P>(directory_path: P) -> Result<Vec<PathBuf>, DirectoryError>
+where
+    P: AsRef<Path>,
+{
+    let directory_path = directory_path.as_ref();
+
+    let mut subdirectory_entries = Vec::new();
+
+    for entry in walk(directory_path, WalkOptions::default()) {
+        let entry = entry?;
+
+        if entry.file_type.is_dir() {
+            subdirectory_entries.push(entry);
+        }
+    }
+
+
+    let mut removed_directory_paths = Vec::new();
+
+    let _ = remove_walked_entries_in_reverse(
+        subdirectory_entries,
+        |subdirectory_entry| {
+            let is_empty =
+                is_directory_empty_unchecked(&subdirectory_entry.path).map_err(|error| {
+                    DirectoryError::IoDuringOperation {
+                        path: subdirectory_entry.path.clone(),
+                        operation: IoOperation::ReadSource,
+                        source: error,
+                    }
+                })?;
+
+            Ok(if is_empty { Some(()) } else { None })
+        },
+        |subdirectory_entry, ()| {
+            removed_directory_paths.push(subdirectory_entry.path);
+            ControlFlow::Continue(())
+        },
+    )?;
+
+    Ok(removed_directory_paths)
+}
+
+
+/// Ensures the directory at `directory_path` exists and is a directory.
+fn ensure_directory_exists(directory_path: &Path) -> Result<(), DirectoryError> {
+    match directory_path.try_exists() {
+        Ok(exists) => {
+            if !exists {
+                return Err(DirectoryError::SourceDirectoryNotFound);
+            }
+        }
+        Err(error) => {
+            return Err(DirectoryError::UnableToAccessSource { error });
+        }
+    }
+
+    if !directory_path.is_dir() {
+        return Err(DirectoryError::SourceDirectoryIsNotADirectory);
+    }
+
+    Ok(())
+}
+
+
+/// Permanently removes a directory, along with all of its contents.
+///
+/// ## Symbolic links
+/// Symbolic links inside the directory are removed themselves, they are never followed.
+///
+/// ## Internals
+/// This function uses [`std::fs::remove_dir_all`] internally.
+/// See [`delete_directory_with_progress`] for a variant with progress reporting.
+pub fn delete_directory<P>(directory_path: P) -> Result<(), DirectoryError>
+where
+    P: AsRef<Path>,
+{
+    let directory_path = directory_path.as_ref();
+
+    ensure_directory_exists(directory_path)?;
+
+    fs::remove_dir_all(directory_path).map_err(|error| DirectoryError::IoDuringOperation {
+        path: directory_path.to_path_buf(),
+        operation: IoOperation::RemoveDirectory,
+        source: error,
+    })
+}
+
+
+/// Represents the progress of removing a directory.
+///
+/// Used to report directory removal progress to a user-provided closure,
+/// see [`delete_directory_with_progress`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DirectoryDeleteProgress {
+    /// Number of files (including symbolic links and other non-directory entries)
+    /// that have been removed so far.
+    pub files_deleted: usize,
+
+    /// Number of directories that have been removed so far
+    /// (including the removed directory itself, once it has been removed).
+    pub directories_deleted: usize,
+
+    /// Path of the entry that was removed last.
+    pub current_path: PathBuf,
+}
+
+
+/// Permanently removes a directory, along with all of its contents, with progress reporting.
+///
+/// This function behaves like [`delete_directory`], but additionally calls `progress_handler`
+/// with a [`&DirectoryDeleteProgress`][DirectoryDeleteProgress] after each removed entry.
+/// The directory itself is removed (and reported) last.
+///
+/// ### Cancellation
+/// If `progress_handler` returns [`ControlFlow::Break`], no further entries are removed and
+/// `Err(`[`DirectoryError::Cancelled`]`)` is returned, containing the progress at the time of cancellation.
+/// Returning [`ControlFlow::Break`] after the directory itself has been removed has no effect.
+///
+/// If removing an entry fails or the removal is cancelled, the entries removed up to that point stay removed.
+///
+/// ## Internals
+/// This function uses [`walk`] to find all entries, which are then removed bottom-up.
+pub fn delete_directory_with_progress<P, F>(
+    directory_path: P,
+    mut progress_handler: F,
+) -> Result<(), DirectoryError>
+where
+    P: AsRef<Path>,
+    F: FnMut(&DirectoryDeleteProgress) -> ControlFlow<()>,
+{
+    let directory_path = directory_path.as_ref();
+
+    ensure_directory_exists(directory_path)?;
+
+    let mut entries = Vec::new();
+
+    for entry in walk(directory_path, WalkOptions::default()) {
+        entries.push(entry?);
+    }
+
+    let mut progress = DirectoryDeleteProgress {
+        files_deleted: 0,
+        directories_deleted: 0,
+        current_path: directory_path.to_path_buf(),
+    };
+
+    let removal_flow = remove_walked_entries_in_reverse(
+        entries,
+        |_| Ok(Some(())),
+        |entry, ()| {
+            if entry.file_type.is_dir() {
+                progress.directories_deleted += 1;
+            } else {
+                progress.files_deleted += 1;
+            }
+
+            progress.current_path = entry.path;
+            progress_handler(&progress)
+        },
+    )?;
+
+    if removal_flow.is_break() {
+        return Err(DirectoryError::Cancelled {
+            progress: Box::new(progress),
+        });
+    }
+
+    fs::remove_dir(directory_path).map_err(|error| DirectoryError::IoDuringOperation {
+        path: directory_path.to_path_buf(),
+        operation: IoOperation::RemoveDirectory,
+        source: error,
+    })?;
+
+    progress.directories_deleted += 1;
+    progress.current_path = directory_path.to_path_buf();
+    let _ = progress_handler(&progress);
+
+    Ok(())
+}
+
+
+/// Moves a directory, along with all of its contents, to the trash (recycle bin)
+/// instead of removing it permanently.
+///
+/// If the directory can't be moved to the trash (e.g. because no trash is available),
+/// `Err(`[`DirectoryError::TrashUnavailable`]`)` is returned and the directory is left in place.
+///
+/// ## Symbolic links
+/// If `directory_path` is a symbolic link to a directory, the link itself is moved to the trash,
+/// not the directory it points to.
+///
+/// ## Internals
+/// This function uses the [`trash`](https://docs.rs/trash) crate internally,
+/// see [`trash_file`][crate::file::trash_file] for more details.
+/// It is only available with the `trash` feature enabled.
+#[cfg(feature = "trash")]
+pub fn trash_directory<P>(directory_path: P) -> Result<(), DirectoryError>
+where
+    P: AsRef<Path>,
+{
+    let directory_path = directory_path.as_ref();
+
+    ensure_directory_exists(directory_path)?;
+
+    trash::delete(directory_path).map_err(|error| DirectoryError::TrashUnavailable {
+        reason: error.to_string(),
+    })
+}
@@ -1,6 +1,12 @@
 #[cfg(not(feature = "fs-err"))]
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    fs::Metadata,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 #[cfg(feature = "fs-err")]
 use fs_err as fs;
@@ -11,34 +17,523 @@ use crate::{
 };
 
 
+/// Specifies what a directory scan should do when it encounters
+/// a directory or entry it isn't permitted to read.
+///
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`PermissionDeniedBehaviour::Abort`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PermissionDeniedBehaviour {
+    /// Indicates the scan should stop and return an error.
+    #[default]
+    Abort,
+
+    /// Indicates the unreadable directory or entry should be left out of the scan results and
+    /// the scan should continue. Skipped paths are recorded in [`DirectoryScan::skipped_paths`].
+    Skip,
+}
+
+
+/// Specifies the order in which the entries of each directory are traversed
+/// when scanning or copying a directory.
+///
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`TraversalOrder::Unordered`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TraversalOrder {
+    /// Indicates entries should be traversed in the order the operating system returns them in.
+    /// This order can differ between platforms, filesystems and even runs.
+    #[default]
+    Unordered,
+
+    /// Indicates the entries of each directory should be traversed in ascending order by their name
+    /// (compared byte-wise, i.e. case-sensitively), and so should its subdirectories.
+    ///
+    /// This makes the traversal order (and with it e.g. the order of progress reports) stable
+    /// across runs and platforms, at the cost of reading each directory in full before traversing it.
+    ByNameAscending,
+}
+
+
+/// Type of a [`DirectoryItem`]. Symbolic links are not followed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum DirectoryItemType {
+    File,
+    Directory,
+    Symlink,
+
+    /// A FIFO, socket, device node or another kind of special file.
+    Other,
+}
+
+impl DirectoryItemType {
+    pub(crate) fn is_file(self) -> bool {
+        self == Self::File
+    }
+
+    pub(crate) fn is_dir(self) -> bool {
+        self == Self::Directory
+    }
+
+    pub(crate) fn is_symlink(self) -> bool {
+        self == Self::Symlink
+    }
+}
+
+impl From<std::fs::FileType> for DirectoryItemType {
+    fn from(file_type: std::fs::FileType) -> Self {
+        if file_type.is_symlink() {
+            Self::Symlink
+        } else if file_type.is_dir() {
+            Self::Directory
+        } else if file_type.is_file() {
+            Self::File
+        } else {
+            Self::Other
+        }
+    }
+}
+
+
+/// A single entry of a directory, as returned by [`read_directory_items`].
+pub(crate) enum DirectoryItem {
+    /// An entry read using [`std::fs::read_dir`].
+    Standard(fs::DirEntry),
+
+    /// An entry read using `getdents64` directly (see [`DirectoryScanOptions::fast_enumeration`]).
+    #[cfg(target_os = "linux")]
+    Batched(super::getdents::BatchedDirectoryEntry),
+}
+
+impl DirectoryItem {
+    pub(crate) fn path(&self) -> PathBuf {
+        match self {
+            Self::Standard(entry) => entry.path(),
+            #[cfg(target_os = "linux")]
+            Self::Batched(entry) => entry.path(),
+        }
+    }
+
+    pub(crate) fn file_name(&self) -> OsString {
+        match self {
+            Self::Standard(entry) => entry.file_name(),
+            #[cfg(target_os = "linux")]
+            Self::Batched(entry) => entry.file_name(),
+        }
+    }
+
+    /// Returns the type of the entry, without following symbolic links.
+    pub(crate) fn file_type(&self) -> std::io::Result<DirectoryItemType> {
+        match self {
+            Self::Standard(entry) => entry.file_type().map(DirectoryItemType::from),
+            #[cfg(target_os = "linux")]
+            Self::Batched(entry) => entry.file_type(),
+        }
+    }
+
+    /// Returns the metadata of the entry, without following symbolic links.
+    pub(crate) fn metadata(&self) -> std::io::Result<Metadata> {
+        match self {
+            Self::Standard(entry) => entry.metadata(),
+            #[cfg(target_os = "linux")]
+            Self::Batched(entry) => entry.metadata(),
+        }
+    }
+
+    /// Returns the size of the entry in bytes, without following symbolic links.
+    ///
+    /// This can be cheaper than reading the full [`metadata`][Self::metadata] of the entry.
+    pub(crate) fn size_in_bytes(&self) -> std::io::Result<u64> {
+        match self {
+            Self::Standard(entry) => entry.metadata().map(|metadata| metadata.len()),
+            #[cfg(target_os = "linux")]
+            Self::Batched(entry) => entry.size_in_bytes(),
+        }
+    }
+}
+
+
+/// Reads the entries of the given directory, sorting them according to `traversal_order`.
+///
+/// If `fast_enumeration` is `true`, the entries are read in larger batches where the platform
+/// supports it (see [`DirectoryScanOptions::fast_enumeration`]).
+/// Entries that could not be read are returned as `Err`s (and are placed first if sorting).
+pub(crate) fn read_directory_items(
+    directory_path: &Path,
+    traversal_order: TraversalOrder,
+    fast_enumeration: bool,
+) -> std::io::Result<Vec<std::io::Result<DirectoryItem>>> {
+    let mut directory_items: Vec<std::io::Result<DirectoryItem>> = if fast_enumeration {
+        read_directory_items_batched(directory_path)?
+    } else {
+        fs::read_dir(directory_path)?
+            .map(|item| item.map(DirectoryItem::Standard))
+            .collect()
+    };
+
+    if traversal_order == TraversalOrder::ByNameAscending {
+        directory_items.sort_by_key(|item| item.as_ref().ok().map(|item| item.file_name()));
+    }
+
+    Ok(directory_items)
+}
+
+/// Reads the entries of the given directory in larger batches (see [`read_directory_items`]).
+#[cfg(target_os = "linux")]
+fn read_directory_items_batched(
+    directory_path: &Path,
+) -> std::io::Result<Vec<std::io::Result<DirectoryItem>>> {
+    Ok(
+        super::getdents::read_directory_batched(directory_path)?
+            .into_iter()
+            .map(|item| item.map(DirectoryItem::Batched))
+            .collect(),
+    )
+}
+
+/// Batched reading is not supported on this platform, so this reads the entries as usual.
+#[cfg(not(target_os = "linux"))]
+fn read_directory_items_batched(
+    directory_path: &Path,
+) -> std::io::Result<Vec<std::io::Result<DirectoryItem>>> {
+    Ok(fs::read_dir(directory_path)?
+        .map(|item| item.map(DirectoryItem::Standard))
+        .collect())
+}
+
+
+/// Returns `true` if the given directory entry is hidden, see [`DirectoryScanOptions::exclude_hidden`].
+fn is_hidden_directory_item(item: &DirectoryItem) -> std::io::Result<bool> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        Ok(item.file_name().as_bytes().first() == Some(&b'.'))
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+
+        // FILE_ATTRIBUTE_HIDDEN
+        Ok(item.metadata()?.file_attributes() & 0x2 != 0)
+    }
+}
+
+
+/// Options that influence [`DirectoryScan::scan_with_options`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DirectoryScanOptions {
+    /// Maximum depth of the scan.
+    ///
+    /// - `None` indicates no depth limit.
+    /// - `Some(0)` means a scan that returns only the files and directories directly
+    ///   in the root directory and doesn't scan any subdirectories.
+    /// - `Some(1)` includes the root directory's contents and one level of its subdirectories.
+    pub maximum_scan_depth: Option<usize>,
+
+    /// Minimum depth of the scan.
+    ///
+    /// - `None` indicates no limit.
+    /// - `Some(1)` means files and directories directly in the root directory
+    ///   are not included in the scan results (they are still traversed).
+    /// - `Some(2)` additionally leaves out the contents of the root directory's subdirectories, and so on.
+    pub minimum_scan_depth: Option<usize>,
+
+    /// Whether to follow symbolic links (see the `Symbolic links` section of [`DirectoryScan`]).
+    pub follow_symbolic_links: bool,
+
+    /// Maximum number of symbolic links to directories to follow during the scan
+    /// (only relevant if [`follow_symbolic_links`][Self::follow_symbolic_links] is `true`).
+    ///
+    /// Once this many linked directories have been descended into, any further symbolic links
+    /// to directories are treated as leaves and left out of the scan, as if symbolic links
+    /// weren't followed at all. Symbolic links to files are not counted.
+    ///
+    /// `None` indicates no limit.
+    pub max_symlinks_to_follow: Option<usize>,
+
+    /// Specifies what happens when a directory or entry can't be read due to missing permissions.
+    ///
+    /// See [`PermissionDeniedBehaviour`] for more details.
+    pub on_permission_denied: PermissionDeniedBehaviour,
+
+    /// Order in which the entries of each directory are scanned (and appear in the results).
+    ///
+    /// See [`TraversalOrder`] for more details.
+    pub traversal_order: TraversalOrder,
+
+    /// If set, the scan stops early once the total size of the scanned files and directories
+    /// (as returned by [`DirectoryScan::total_size_in_bytes_cached`]) reaches this many bytes.
+    ///
+    /// If the scan stopped before covering everything, [`DirectoryScan::was_truncated`] is set.
+    pub stop_after_bytes: Option<u64>,
+
+    /// If set, the scan stops early once this many files have been scanned.
+    ///
+    /// If the scan stopped before covering everything, [`DirectoryScan::was_truncated`] is set.
+    pub stop_after_files: Option<usize>,
+
+    /// Whether to leave hidden files and directories out of the scan.
+    ///
+    /// On Unix, entries whose name starts with a dot are considered hidden.
+    /// On Windows, entries with the hidden attribute are considered hidden.
+    /// Hidden directories are not descended into, so their contents are left out as well
+    /// (even if they aren't hidden themselves). The scanned root directory is never excluded.
+    pub exclude_hidden: bool,
+
+    /// Whether to read directories in larger batches, reducing the number of system calls
+    /// needed for directories with many entries.
+    ///
+    /// On Linux, directories are then read using `getdents64` directly, with a larger buffer
+    /// than the one used by [`std::fs::read_dir`]. The results are identical to the ones
+    /// of a standard scan. This has no effect on other platforms.
+    pub fast_enumeration: bool,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for DirectoryScanOptions {
+    fn default() -> Self {
+        Self {
+            maximum_scan_depth: None,
+            minimum_scan_depth: None,
+            follow_symbolic_links: false,
+            max_symlinks_to_follow: None,
+            on_permission_denied: PermissionDeniedBehaviour::default(),
+            traversal_order: TraversalOrder::default(),
+            stop_after_bytes: None,
+            stop_after_files: None,
+            exclude_hidden: false,
+            fast_enumeration: false,
+        }
+    }
+}
+
+
+/// A file found by a [`DirectoryScan`], along with its depth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanFileEntry {
+    /// Path of the file (as returned by [`DirectoryScan::files`]).
+    pub path: PathBuf,
+
+    /// Depth of the file relative to the scan root: `0` for files directly in the root directory,
+    /// `1` for files in its subdirectories, and so on.
+    ///
+    /// If the file was found by following a symbolic link, this is the depth of the link.
+    pub depth: usize,
+
+    /// Size of the file in bytes, as it was at the time of the scan.
+    pub size_in_bytes: u64,
+
+    /// Index of the root directory the file was found in, see [`DirectoryScan::root_directory_paths`].
+    ///
+    /// This is always `0`, unless the scan was performed with [`DirectoryScan::scan_multiple`].
+    pub root_index: usize,
+}
+
+
+/// A directory found by a [`DirectoryScan`], along with its depth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanDirectoryEntry {
+    /// Path of the directory (as returned by [`DirectoryScan::directories`]).
+    pub path: PathBuf,
+
+    /// Depth of the directory relative to the scan root: `0` for directories directly in the root directory,
+    /// `1` for directories in its subdirectories, and so on.
+    ///
+    /// If the directory was found by following a symbolic link, this is the depth of the link.
+    pub depth: usize,
+
+    /// Size of the directory entry itself in bytes (as reported by its metadata),
+    /// as it was at the time of the scan. This does not include the size of its contents.
+    pub size_in_bytes: u64,
+
+    /// Total size in bytes of all the scanned files and directories beneath this directory, at any depth
+    /// (i.e. the sum of their `size_in_bytes`).
+    /// This does not include the size of the directory entry itself.
+    ///
+    /// Only entries that are part of the scan results are counted, meaning this doesn't cover
+    /// contents beyond the maximum scan depth or after the scan was truncated.
+    pub recursive_size_in_bytes: u64,
+
+    /// Index of the root directory the directory was found in, see [`DirectoryScan::root_directory_paths`].
+    ///
+    /// This is always `0`, unless the scan was performed with [`DirectoryScan::scan_multiple`].
+    pub root_index: usize,
+}
+
+
+/// A file or directory found by a [`DirectoryScan`], see [`DirectoryScan::find`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanEntryRef<'a> {
+    /// A scanned file.
+    File(&'a ScanFileEntry),
+
+    /// A scanned directory.
+    Directory(&'a ScanDirectoryEntry),
+}
+
+impl<'a> ScanEntryRef<'a> {
+    /// Returns the path of the entry.
+    pub fn path(&self) -> &'a Path {
+        match self {
+            ScanEntryRef::File(file_entry) => &file_entry.path,
+            ScanEntryRef::Directory(directory_entry) => &directory_entry.path,
+        }
+    }
+
+    /// Returns the depth of the entry relative to the scan root.
+    pub fn depth(&self) -> usize {
+        match self {
+            ScanEntryRef::File(file_entry) => file_entry.depth,
+            ScanEntryRef::Directory(directory_entry) => directory_entry.depth,
+        }
+    }
+}
+
+
+/// Position of a scanned entry in [`DirectoryScan::file_entries`] or [`DirectoryScan::directory_entries`].
+#[derive(Clone, Copy)]
+enum IndexedScanEntry {
+    File(usize),
+    Directory(usize),
+}
+
+/// An index of the scanned entries by path, built once the scan has finished (see [`DirectoryScan::find`]).
+///
+/// As the entries can be modified through the public fields of [`DirectoryScan`] afterwards,
+/// positions found in the index must be checked against the entries they point to.
+/// The index doesn't influence how scans are compared: all indices compare equal.
+#[derive(Clone, Default)]
+struct ScanEntryIndex {
+    entries_by_path: HashMap<PathBuf, IndexedScanEntry>,
+}
+
+impl ScanEntryIndex {
+    /// Indexes the given scanned files and directories by their path.
+    fn build(file_entries: &[ScanFileEntry], directory_entries: &[ScanDirectoryEntry]) -> Self {
+        let mut entries_by_path =
+            HashMap::with_capacity(file_entries.len() + directory_entries.len());
+
+        for (index, file_entry) in file_entries.iter().enumerate() {
+            entries_by_path
+                .entry(file_entry.path.clone())
+                .or_insert(IndexedScanEntry::File(index));
+        }
+
+        for (index, directory_entry) in directory_entries.iter().enumerate() {
+            entries_by_path
+                .entry(directory_entry.path.clone())
+                .or_insert(IndexedScanEntry::Directory(index));
+        }
+
+        Self { entries_by_path }
+    }
+}
+
+impl PartialEq for ScanEntryIndex {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ScanEntryIndex {}
+
+impl std::fmt::Debug for ScanEntryIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScanEntryIndex").finish_non_exhaustive()
+    }
+}
+
+
+/// Computes [`ScanDirectoryEntry::recursive_size_in_bytes`] for all scanned directories.
+///
+/// Each file's size is first added to its parent directory, after which the directories are visited
+/// from the deepest to the shallowest, adding each directory's total to its own parent directory.
+/// This way each entry is only visited once.
+///
+/// Entries are related by their scan paths (`file_scan_paths` and `directory_scan_paths`, in the same order
+/// as the entries), i.e. the paths through which they were reached, not the paths stored in the entries.
+/// These differ for entries reached through followed symbolic links, which are stored by their destination path.
+fn compute_recursive_directory_sizes(
+    file_entries: &[ScanFileEntry],
+    file_scan_paths: &[PathBuf],
+    directory_entries: &mut [ScanDirectoryEntry],
+    directory_scan_paths: &[PathBuf],
+) {
+    let mut directory_indices_by_path: HashMap<&Path, usize> =
+        HashMap::with_capacity(directory_entries.len());
+
+    for (index, directory_scan_path) in directory_scan_paths.iter().enumerate() {
+        directory_indices_by_path
+            .entry(directory_scan_path.as_path())
+            .or_insert(index);
+    }
+
+    let parent_directory_index = |path: &Path| {
+        path.parent()
+            .and_then(|parent_path| directory_indices_by_path.get(parent_path))
+            .copied()
+    };
+
+    for (file_entry, file_scan_path) in file_entries.iter().zip(file_scan_paths) {
+        if let Some(parent_index) = parent_directory_index(file_scan_path) {
+            directory_entries[parent_index].recursive_size_in_bytes += file_entry.size_in_bytes;
+        }
+    }
+
+    let mut directory_indices_by_depth: Vec<usize> = (0..directory_entries.len()).collect();
+    directory_indices_by_depth
+        .sort_by_key(|index| std::cmp::Reverse(directory_entries[*index].depth));
+
+    for directory_index in directory_indices_by_depth {
+        let directory_entry = &directory_entries[directory_index];
+
+        if let Some(parent_index) = parent_directory_index(&directory_scan_paths[directory_index]) {
+            let subtree_size_in_bytes =
+                directory_entry.size_in_bytes + directory_entry.recursive_size_in_bytes;
+
+            directory_entries[parent_index].recursive_size_in_bytes += subtree_size_in_bytes;
+        }
+    }
+}
+
+
 /// A directory scanner abstraction.
 ///
 /// ### Scan depth
 /// Maximum scanning depth can be configured by setting
-/// the `maximum_scan_depth` parameter in the [`DirectoryScan::scan_with_options`] initializer to:
+/// the [`maximum_scan_depth`][DirectoryScanOptions::maximum_scan_depth] option to:
 /// - `Some(0)` -- scans direct contents of the directory (a single layer of files and directories),
 /// - `Some(1+)` -- scans up to a certain subdirectory limit, or,
 /// - `None` -- scans the entire subtree, as deep as required.
 ///
+/// ### Permissions
+/// By default, a directory or entry that can't be read due to missing permissions aborts the scan.
+/// You can configure the scan to skip such paths instead, see [`PermissionDeniedBehaviour`].
+///
 /// ### Symbolic links
 /// **Careful!** This scanner follows symbolic links.
 ///
-/// This means that if you set the `follow_symbolic_links` option to `true` (see [`Self::scan_with_options`]),
+/// This means that if you set the [`follow_symbolic_links`][DirectoryScanOptions::follow_symbolic_links]
+/// option to `true`,
 /// the resulting `files` and `directories` included in the scan results
 /// *might not all be sub-paths of the root* `directory_path`.
 ///
 /// This is because we followed symbolic links included their full target path in the results,
-/// not their original path.
+/// not their original path. To bound the number of linked directories that are followed,
+/// see [`max_symlinks_to_follow`][DirectoryScanOptions::max_symlinks_to_follow].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DirectoryScan {
-    /// The directory that was scanned.
-    pub(crate) root_directory_path: PathBuf,
+    /// The directories that were scanned (a single one, unless scanned with [`scan_multiple`][Self::scan_multiple]).
+    pub(crate) root_directory_paths: Vec<PathBuf>,
 
     /// The maximum depth setting used in this scan.
     ///
     /// - `None` indicates no depth limit.
     /// - `Some(0)` means a scan that returns only the files and directories directly
-    /// in the root directory and doesn't scan any subdirectories.
+    ///   in the root directory and doesn't scan any subdirectories.
     /// - `Some(1)` includes the root directory's contents and one level of its subdirectories.
     pub(crate) maximum_scanned_depth: Option<usize>,
 
@@ -58,11 +553,31 @@ pub struct DirectoryScan {
     /// method as before to get the correct result.
     pub is_real_directory_deeper_than_scan: bool,
 
-    /// Files that were found in the scan.
-    pub files: Vec<PathBuf>,
+    /// Indicates whether the scan stopped early, before covering all of the files and subdirectories,
+    /// because the [`stop_after_bytes`][DirectoryScanOptions::stop_after_bytes] or
+    /// [`stop_after_files`][DirectoryScanOptions::stop_after_files] threshold was reached.
+    ///
+    /// If this is `true`, the scan results contain only the entries found before the scan stopped.
+    pub was_truncated: bool,
+
+    /// Files that were found in the scan, along with their depth.
+    ///
+    /// Their paths are also available through [`files`][Self::files].
+    pub file_entries: Vec<ScanFileEntry>,
+
+    /// Directories that were found in the scan, along with their depth. Doesn't include the root directory.
+    ///
+    /// Their paths are also available through [`directories`][Self::directories].
+    pub directory_entries: Vec<ScanDirectoryEntry>,
+
+    /// Directories and entries that were skipped because they couldn't be read due to missing permissions.
+    ///
+    /// This can be non-empty only if [`on_permission_denied`][DirectoryScanOptions::on_permission_denied]
+    /// is set to [`PermissionDeniedBehaviour::Skip`].
+    pub skipped_paths: Vec<PathBuf>,
 
-    /// Directories that were found in the scan. Doesn't include the root directory.
-    pub directories: Vec<PathBuf>,
+    /// Index of the scanned entries by path, used by [`find`][Self::find].
+    entry_index: ScanEntryIndex,
 }
 
 impl DirectoryScan {
@@ -70,15 +585,33 @@ impl DirectoryScan {
     ///
     /// `directory_path` must point to a directory that exists,
     /// otherwise an `Err(`[`DirectoryScanError::NotFound`][crate::error::DirectoryScanError::NotFound]`)` is returned.
+    ///
+    /// See [`DirectoryScanOptions`] for available options.
     pub fn scan_with_options<P>(
         directory_path: P,
-        maximum_scan_depth: Option<usize>,
-        follow_symbolic_links: bool,
+        options: DirectoryScanOptions,
     ) -> Result<Self, DirectoryScanError>
     where
         P: Into<PathBuf>,
     {
         let directory_path = directory_path.into();
+        let DirectoryScanOptions {
+            maximum_scan_depth,
+            minimum_scan_depth,
+            follow_symbolic_links,
+            max_symlinks_to_follow,
+            on_permission_denied,
+            traversal_order,
+            stop_after_bytes,
+            stop_after_files,
+            exclude_hidden,
+            fast_enumeration,
+        } = options;
+
+        let should_skip_on_error = |error: &std::io::Error| {
+            on_permission_denied == PermissionDeniedBehaviour::Skip
+                && error.kind() == std::io::ErrorKind::PermissionDenied
+        };
 
         // Ensure the directory exists. We use `try_exists`
         // instead of `exists` to catch permission and other IO errors
@@ -99,45 +632,150 @@ impl DirectoryScan {
         }
 
 
-        let mut file_list = Vec::new();
-        let mut directory_list = Vec::new();
+        let mut file_entry_list = Vec::new();
+        let mut directory_entry_list = Vec::new();
+        // Paths through which each entry was reached, see `compute_recursive_directory_sizes`.
+        let mut file_scan_path_list = Vec::new();
+        let mut directory_scan_path_list = Vec::new();
+        let mut skipped_path_list = Vec::new();
         let mut is_deeper_than_scan_allows = false;
+        let mut was_truncated = false;
+
+        // Number of symbolic links to directories followed so far (see `max_symlinks_to_follow`).
+        let mut num_followed_directory_symlinks: usize = 0;
+
+        // Total size of the files and directories included in the scan so far,
+        // used for the `stop_after_bytes` threshold.
+        let mut scanned_bytes: u64 = 0;
+
+        let is_threshold_reached = |scanned_bytes: u64, num_scanned_files: usize| {
+            stop_after_bytes
+                .map(|maximum_bytes| scanned_bytes >= maximum_bytes)
+                .unwrap_or(false)
+                || stop_after_files
+                    .map(|maximum_files| num_scanned_files >= maximum_files)
+                    .unwrap_or(false)
+        };
 
         // Create a FIFO (queue) of directories that need to be scanned.
         let mut directory_scan_queue = Vec::new();
 
         struct PendingDirectoryScan {
             path: PathBuf,
+            /// The path through which this directory was reached, which differs from `path`
+            /// if a symbolic link was followed along the way.
+            scan_path: PathBuf,
             depth: usize,
         }
 
         impl PendingDirectoryScan {
             #[inline]
-            pub fn new(path: PathBuf, depth: usize) -> Self {
-                Self { path, depth }
+            pub fn new(path: PathBuf, scan_path: PathBuf, depth: usize) -> Self {
+                Self {
+                    path,
+                    scan_path,
+                    depth,
+                }
             }
         }
 
         directory_scan_queue.push(PendingDirectoryScan::new(
+            directory_path.clone(),
             directory_path.clone(),
             0,
         ));
 
-        while let Some(next_directory) = directory_scan_queue.pop() {
-            let directory_iterator = fs::read_dir(&next_directory.path)
-                .map_err(|error| DirectoryScanError::UnableToReadDirectory { error })?;
+        'scan: while let Some(next_directory) = directory_scan_queue.pop() {
+            // Entries shallower than the minimum depth are traversed, but not included in the results.
+            let is_included_in_scan = minimum_scan_depth
+                .map(|minimum_depth| next_directory.depth >= minimum_depth)
+                .unwrap_or(true);
 
-            for item in directory_iterator {
-                let item =
-                    item.map_err(|error| DirectoryScanError::UnableToReadDirectoryItem { error })?;
+            let directory_items = match read_directory_items(
+                &next_directory.path,
+                traversal_order,
+                fast_enumeration,
+            ) {
+                Ok(directory_items) => directory_items,
+                Err(error) if should_skip_on_error(&error) => {
+                    skipped_path_list.push(next_directory.path);
+                    continue;
+                }
+                Err(error) => return Err(DirectoryScanError::UnableToReadDirectory { error }),
+            };
 
-                let item_file_type = item
-                    .file_type()
-                    .map_err(|error| DirectoryScanError::UnableToReadDirectoryItem { error })?;
+            // Subdirectories found in this directory are pushed onto the (LIFO) queue in traversal order,
+            // so they are reversed afterwards in order to be scanned in that same order.
+            let directory_scan_queue_length = directory_scan_queue.len();
+
+            for item in directory_items {
+                // Stop early if a threshold has been reached, but there's still something left to scan.
+                if is_threshold_reached(scanned_bytes, file_entry_list.len()) {
+                    was_truncated = true;
+                    break 'scan;
+                }
+
+                let item = match item {
+                    Ok(item) => item,
+                    Err(error) if should_skip_on_error(&error) => {
+                        skipped_path_list.push(next_directory.path.clone());
+                        continue;
+                    }
+                    Err(error) => {
+                        return Err(DirectoryScanError::UnableToReadDirectoryItem { error })
+                    }
+                };
+
+                if exclude_hidden {
+                    match is_hidden_directory_item(&item) {
+                        Ok(true) => continue,
+                        Ok(false) => {}
+                        Err(error) if should_skip_on_error(&error) => {
+                            skipped_path_list.push(item.path());
+                            continue;
+                        }
+                        Err(error) => {
+                            return Err(DirectoryScanError::UnableToReadDirectoryItem { error })
+                        }
+                    }
+                }
+
+                let item_file_type = match item.file_type() {
+                    Ok(item_file_type) => item_file_type,
+                    Err(error) if should_skip_on_error(&error) => {
+                        skipped_path_list.push(item.path());
+                        continue;
+                    }
+                    Err(error) => {
+                        return Err(DirectoryScanError::UnableToReadDirectoryItem { error })
+                    }
+                };
+
+                let item_scan_path = next_directory.scan_path.join(item.file_name());
 
                 if item_file_type.is_file() {
                     // Files are simply added to the resulting scan and no further action is needed.
-                    file_list.push(item.path());
+                    if is_included_in_scan {
+                        let item_size_in_bytes = match item.size_in_bytes() {
+                            Ok(item_size_in_bytes) => item_size_in_bytes,
+                            Err(error) if should_skip_on_error(&error) => {
+                                skipped_path_list.push(item.path());
+                                continue;
+                            }
+                            Err(error) => {
+                                return Err(DirectoryScanError::UnableToReadDirectoryItem { error })
+                            }
+                        };
+
+                        scanned_bytes += item_size_in_bytes;
+                        file_entry_list.push(ScanFileEntry {
+                            path: item.path(),
+                            depth: next_directory.depth,
+                            size_in_bytes: item_size_in_bytes,
+                            root_index: 0,
+                        });
+                        file_scan_path_list.push(item_scan_path);
+                    }
                 } else if item_file_type.is_dir() {
                     // Directories might in addition to being stored in the results need
                     // to be scanned themselves, but only if the depth limit permits it.
@@ -146,6 +784,7 @@ impl DirectoryScan {
                         if next_directory.depth < maximum_depth {
                             directory_scan_queue.push(PendingDirectoryScan::new(
                                 item.path(),
+                                item_scan_path.clone(),
                                 next_directory.depth + 1,
                             ));
                         } else {
@@ -154,31 +793,82 @@ impl DirectoryScan {
                     } else {
                         directory_scan_queue.push(PendingDirectoryScan::new(
                             item.path(),
+                            item_scan_path.clone(),
                             next_directory.depth + 1,
                         ));
                     }
 
 
-                    directory_list.push(item.path());
+                    if is_included_in_scan {
+                        let item_size_in_bytes = match item.size_in_bytes() {
+                            Ok(item_size_in_bytes) => item_size_in_bytes,
+                            Err(error) if should_skip_on_error(&error) => {
+                                skipped_path_list.push(item.path());
+                                continue;
+                            }
+                            Err(error) => {
+                                return Err(DirectoryScanError::UnableToReadDirectoryItem { error })
+                            }
+                        };
+
+                        scanned_bytes += item_size_in_bytes;
+                        directory_entry_list.push(ScanDirectoryEntry {
+                            path: item.path(),
+                            depth: next_directory.depth,
+                            size_in_bytes: item_size_in_bytes,
+                            recursive_size_in_bytes: 0,
+                            root_index: 0,
+                        });
+                        directory_scan_path_list.push(item_scan_path);
+                    }
                 } else if item_file_type.is_symlink() && follow_symbolic_links {
                     // If an item is a symbolic link, we ignore it, unless `follow_symbolic_links` is enabled.
                     // If enabled, we follow it to its destination and append that *destination* path
                     // to the file or directory list.
-                    let real_path = fs::read_link(item.path())
-                        .map_err(|error| DirectoryScanError::UnableToReadDirectoryItem { error })?;
+                    let real_path = match fs::read_link(item.path()) {
+                        Ok(real_path) => real_path,
+                        Err(error) if should_skip_on_error(&error) => {
+                            skipped_path_list.push(item.path());
+                            continue;
+                        }
+                        Err(error) => {
+                            return Err(DirectoryScanError::UnableToReadDirectoryItem { error })
+                        }
+                    };
 
-                    if !real_path.exists() {
-                        continue;
-                    }
+                    // Symbolic links whose destination doesn't exist (or can't be accessed) are ignored.
+                    let real_path_metadata = match fs::metadata(&real_path) {
+                        Ok(real_path_metadata) => real_path_metadata,
+                        Err(_) => continue,
+                    };
+
+                    if real_path_metadata.is_file() {
+                        if is_included_in_scan {
+                            scanned_bytes += real_path_metadata.len();
+                            file_entry_list.push(ScanFileEntry {
+                                path: real_path,
+                                depth: next_directory.depth,
+                                size_in_bytes: real_path_metadata.len(),
+                                root_index: 0,
+                            });
+                            file_scan_path_list.push(item_scan_path);
+                        }
+                    } else if real_path_metadata.is_dir() {
+                        // Once the limit is reached, further linked directories are ignored.
+                        if let Some(max_symlinks) = max_symlinks_to_follow {
+                            if num_followed_directory_symlinks >= max_symlinks {
+                                continue;
+                            }
+                        }
+
+                        num_followed_directory_symlinks += 1;
 
-                    if real_path.is_file() {
-                        file_list.push(real_path);
-                    } else if real_path.is_dir() {
                         // Depth settings are respected if the destination is a directory.
                         if let Some(maximum_depth) = maximum_scan_depth {
                             if next_directory.depth < maximum_depth {
                                 directory_scan_queue.push(PendingDirectoryScan::new(
                                     real_path.clone(),
+                                    item_scan_path.clone(),
                                     next_directory.depth + 1,
                                 ));
                             } else {
@@ -187,34 +877,213 @@ impl DirectoryScan {
                         } else {
                             directory_scan_queue.push(PendingDirectoryScan::new(
                                 real_path.clone(),
+                                item_scan_path.clone(),
                                 next_directory.depth + 1,
                             ));
                         }
 
-                        directory_list.push(real_path);
+                        if is_included_in_scan {
+                            scanned_bytes += real_path_metadata.len();
+                            directory_entry_list.push(ScanDirectoryEntry {
+                                path: real_path,
+                                depth: next_directory.depth,
+                                size_in_bytes: real_path_metadata.len(),
+                                recursive_size_in_bytes: 0,
+                                root_index: 0,
+                            });
+                            directory_scan_path_list.push(item_scan_path);
+                        }
                     }
                 }
             }
+
+            directory_scan_queue[directory_scan_queue_length..].reverse();
+
+            if !directory_scan_queue.is_empty()
+                && is_threshold_reached(scanned_bytes, file_entry_list.len())
+            {
+                was_truncated = true;
+                break;
+            }
         }
 
+        compute_recursive_directory_sizes(
+            &file_entry_list,
+            &file_scan_path_list,
+            &mut directory_entry_list,
+            &directory_scan_path_list,
+        );
+
         Ok(Self {
-            root_directory_path: directory_path,
+            root_directory_paths: vec![directory_path],
             maximum_scanned_depth: maximum_scan_depth,
             is_real_directory_deeper_than_scan: is_deeper_than_scan_allows,
-            files: file_list,
-            directories: directory_list,
+            was_truncated,
+            entry_index: ScanEntryIndex::build(&file_entry_list, &directory_entry_list),
+            file_entries: file_entry_list,
+            directory_entries: directory_entry_list,
+            skipped_paths: skipped_path_list,
         })
     }
 
 
-    /// Returns a slice of all scanned files (items are full file paths).
-    pub fn files(&self) -> &[PathBuf] {
-        &self.files
+    /// Performs a single scan of multiple directories, aggregating their results
+    /// as if they were one directory (e.g. [`total_size_in_bytes`][Self::total_size_in_bytes]
+    /// covers all of them).
+    ///
+    /// Each scanned entry is tagged with the root directory it was found in
+    /// (see [`ScanFileEntry::root_index`] and [`ScanDirectoryEntry::root_index`]), which is its
+    /// index in `directory_paths` and in [`root_directory_paths`][Self::root_directory_paths].
+    /// Entry depths are relative to that root.
+    ///
+    /// The root directories are canonicalized before being scanned, so all paths in the results are absolute.
+    /// This way, overlapping roots (e.g. a directory and one of its subdirectories) are handled properly:
+    /// each entry is included only once, tagged with the first root (in the order of `directory_paths`)
+    /// it was found in.
+    ///
+    /// The options apply to each root separately, i.e. depth limits are relative to each root
+    /// and [`stop_after_bytes`][DirectoryScanOptions::stop_after_bytes]
+    /// and [`stop_after_files`][DirectoryScanOptions::stop_after_files] limit the scan of each root.
+    /// [`is_real_directory_deeper_than_scan`][Self::is_real_directory_deeper_than_scan]
+    /// and [`was_truncated`][Self::was_truncated] are set if they apply to any of the roots.
+    ///
+    /// All directories must exist, otherwise an `Err(`[`DirectoryScanError::NotFound`][crate::error::DirectoryScanError::NotFound]`)`
+    /// is returned.
+    pub fn scan_multiple<P>(
+        directory_paths: &[P],
+        options: DirectoryScanOptions,
+    ) -> Result<Self, DirectoryScanError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut combined_scan = Self {
+            root_directory_paths: Vec::with_capacity(directory_paths.len()),
+            maximum_scanned_depth: options.maximum_scan_depth,
+            is_real_directory_deeper_than_scan: false,
+            was_truncated: false,
+            file_entries: Vec::new(),
+            directory_entries: Vec::new(),
+            skipped_paths: Vec::new(),
+            entry_index: ScanEntryIndex::default(),
+        };
+
+        // Paths of all entries included so far, used to skip entries of overlapping roots.
+        let mut included_paths: HashSet<PathBuf> = HashSet::new();
+
+        for (root_index, directory_path) in directory_paths.iter().enumerate() {
+            let canonical_directory_path =
+                fs::canonicalize(directory_path.as_ref()).map_err(|error| {
+                    if error.kind() == std::io::ErrorKind::NotFound {
+                        DirectoryScanError::NotFound
+                    } else {
+                        DirectoryScanError::UnableToReadDirectory { error }
+                    }
+                })?;
+            let canonical_directory_path =
+                dunce::simplified(&canonical_directory_path).to_path_buf();
+
+            let root_scan = Self::scan_with_options(canonical_directory_path.clone(), options)?;
+
+            combined_scan
+                .root_directory_paths
+                .push(canonical_directory_path);
+            combined_scan.is_real_directory_deeper_than_scan |=
+                root_scan.is_real_directory_deeper_than_scan;
+            combined_scan.was_truncated |= root_scan.was_truncated;
+
+            for mut file_entry in root_scan.file_entries {
+                if included_paths.insert(file_entry.path.clone()) {
+                    file_entry.root_index = root_index;
+
+                    combined_scan.file_entries.push(file_entry);
+                }
+            }
+
+            for mut directory_entry in root_scan.directory_entries {
+                if included_paths.insert(directory_entry.path.clone()) {
+                    directory_entry.root_index = root_index;
+
+                    combined_scan.directory_entries.push(directory_entry);
+                }
+            }
+
+            for skipped_path in root_scan.skipped_paths {
+                if !combined_scan.skipped_paths.contains(&skipped_path) {
+                    combined_scan.skipped_paths.push(skipped_path);
+                }
+            }
+        }
+
+        combined_scan.entry_index = ScanEntryIndex::build(
+            &combined_scan.file_entries,
+            &combined_scan.directory_entries,
+        );
+
+        Ok(combined_scan)
+    }
+
+
+    /// Returns the scanned root directories, in the order they were scanned.
+    ///
+    /// This contains a single directory, unless the scan was performed with [`scan_multiple`][Self::scan_multiple].
+    pub fn root_directory_paths(&self) -> &[PathBuf] {
+        &self.root_directory_paths
+    }
+
+    /// Returns an iterator over all scanned files (items are full file paths),
+    /// in the same order as [`file_entries`][Self::file_entries].
+    pub fn files(&self) -> impl ExactSizeIterator<Item = &Path> + '_ {
+        self.file_entries.iter().map(|entry| entry.path.as_path())
+    }
+
+    /// Returns an iterator over all scanned directories (items are full directory paths),
+    /// in the same order as [`directory_entries`][Self::directory_entries].
+    pub fn directories(&self) -> impl ExactSizeIterator<Item = &Path> + '_ {
+        self.directory_entries
+            .iter()
+            .map(|entry| entry.path.as_path())
+    }
+
+    /// Returns the entries of all scanned directories that contain no scanned files or subdirectories,
+    /// in the same order as [`directory_entries`][Self::directory_entries].
+    ///
+    /// This is based only on the data collected during the scan, meaning:
+    /// - directories at the maximum scan depth are included if their contents weren't scanned
+    ///   (see [`is_real_directory_deeper_than_scan`][Self::is_real_directory_deeper_than_scan]),
+    /// - directories containing only entries that aren't part of scan results
+    ///   (e.g. symbolic links that weren't followed) are included, and,
+    /// - directories containing only empty subdirectories are *not* included.
+    ///
+    /// To remove empty directories, including ones that contain only empty subdirectories,
+    /// see [`remove_empty_directories`][super::remove_empty_directories].
+    pub fn empty_directories(&self) -> Vec<&ScanDirectoryEntry> {
+        let non_empty_directory_paths: HashSet<&Path> = self
+            .files()
+            .chain(self.directories())
+            .filter_map(|path| path.parent())
+            .collect();
+
+        self.directory_entries
+            .iter()
+            .filter(|entry| !non_empty_directory_paths.contains(entry.path.as_path()))
+            .filter(|entry| !self.skipped_paths.contains(&entry.path))
+            .collect()
+    }
+
+    /// Returns a slice of all scanned files along with their depth.
+    pub fn file_entries(&self) -> &[ScanFileEntry] {
+        &self.file_entries
+    }
+
+    /// Returns a slice of all scanned directories along with their depth.
+    pub fn directory_entries(&self) -> &[ScanDirectoryEntry] {
+        &self.directory_entries
     }
 
-    /// Returns a slice of all scanned directories (items are full directory paths).
-    pub fn directories(&self) -> &[PathBuf] {
-        &self.directories
+    /// Returns a slice of all paths that were skipped due to missing permissions
+    /// (see [`PermissionDeniedBehaviour::Skip`]).
+    pub fn skipped_paths(&self) -> &[PathBuf] {
+        &self.skipped_paths
     }
 
     /// Returns a total size of the scanned files in bytes.
@@ -233,13 +1102,13 @@ impl DirectoryScan {
     pub fn total_size_in_bytes(&self) -> Result<u64, DirectorySizeScanError> {
         let mut total_bytes = 0;
 
-        for file_path in &self.files {
+        for file_path in self.files() {
             let file_size_bytes = file_size_in_bytes(file_path).map_err(|error| match error {
                 FileSizeError::NotFound => DirectorySizeScanError::EntryNoLongerExists {
-                    path: file_path.clone(),
+                    path: file_path.to_path_buf(),
                 },
                 FileSizeError::NotAFile => DirectorySizeScanError::EntryNoLongerExists {
-                    path: file_path.clone(),
+                    path: file_path.to_path_buf(),
                 },
                 FileSizeError::UnableToAccessFile { error } => {
                     DirectorySizeScanError::UnableToAccessFile { error }
@@ -252,7 +1121,7 @@ impl DirectoryScan {
             total_bytes += file_size_bytes;
         }
 
-        for directory_path in &self.directories {
+        for directory_path in self.directories() {
             let directory_size_bytes = fs::metadata(directory_path)
                 .map_err(|_| DirectorySizeScanError::EntryNoLongerExists {
                     path: directory_path.to_path_buf(),
@@ -264,6 +1133,125 @@ impl DirectoryScan {
 
         Ok(total_bytes)
     }
+
+    /// Returns a total size of the scanned files and directories in bytes,
+    /// using the sizes captured while scanning (see [`ScanFileEntry::size_in_bytes`]
+    /// and [`ScanDirectoryEntry::size_in_bytes`]).
+    ///
+    /// Unlike [`total_size_in_bytes`][Self::total_size_in_bytes], this does not touch the filesystem
+    /// and can't fail, but the returned size will not reflect any changes made after the scan.
+    ///
+    /// The same caveat about depth-limited scans applies as with
+    /// [`total_size_in_bytes`][Self::total_size_in_bytes].
+    pub fn total_size_in_bytes_cached(&self) -> u64 {
+        let total_file_bytes: u64 = self
+            .file_entries
+            .iter()
+            .map(|entry| entry.size_in_bytes)
+            .sum();
+
+        let total_directory_bytes: u64 = self
+            .directory_entries
+            .iter()
+            .map(|entry| entry.size_in_bytes)
+            .sum();
+
+        total_file_bytes + total_directory_bytes
+    }
+
+    /// Returns an estimate of how long copying the scanned contents would take
+    /// at the given throughput (e.g. measured from an earlier copy).
+    ///
+    /// The estimate is based on [`total_size_in_bytes_cached`][Self::total_size_in_bytes_cached],
+    /// which is also how [`FinishedDirectoryCopy::total_bytes_copied`][super::FinishedDirectoryCopy::total_bytes_copied]
+    /// is counted. It does not account for per-file overhead, so it will be optimistic
+    /// for trees containing many small files.
+    ///
+    /// If `bytes_per_second` is `0`, [`Duration::MAX`] is returned.
+    pub fn estimate_copy_duration(&self, bytes_per_second: u64) -> Duration {
+        if bytes_per_second == 0 {
+            return Duration::MAX;
+        }
+
+        let total_bytes = self.total_size_in_bytes_cached();
+
+        let whole_seconds = total_bytes / bytes_per_second;
+        let remaining_nanoseconds =
+            (total_bytes % bytes_per_second) as u128 * 1_000_000_000 / bytes_per_second as u128;
+
+        Duration::new(whole_seconds, remaining_nanoseconds as u32)
+    }
+
+    /// Returns `true` if the given path was found by the scan (as a file or a directory).
+    ///
+    /// See [`find`][Self::find] for details on how paths are matched.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.find(path).is_some()
+    }
+
+    /// Returns the scanned file or directory with the given path, if it was found by the scan.
+    ///
+    /// Paths are compared as they are, meaning `path` must have the same form as the scanned paths,
+    /// i.e. start with the path the scan was performed on. The root directory itself is never found.
+    ///
+    /// The scanned entries are indexed by path when the scan finishes, so looking up a scanned path is fast.
+    /// Looking up a path that isn't in the index (e.g. one that wasn't scanned, or one that was
+    /// changed or added in [`file_entries`][Self::file_entries] or [`directory_entries`][Self::directory_entries]
+    /// after the scan) searches through all entries instead, which takes time proportional to their number.
+    pub fn find(&self, path: &Path) -> Option<ScanEntryRef<'_>> {
+        let indexed_entry = match self.entry_index.entries_by_path.get(path) {
+            Some(IndexedScanEntry::File(index)) => {
+                self.file_entries.get(*index).map(ScanEntryRef::File)
+            }
+            Some(IndexedScanEntry::Directory(index)) => self
+                .directory_entries
+                .get(*index)
+                .map(ScanEntryRef::Directory),
+            None => None,
+        };
+
+        // An entry at a different path means the entries were modified after the scan.
+        if let Some(entry) = indexed_entry.filter(|entry| entry.path() == path) {
+            return Some(entry);
+        }
+
+        self.file_entries
+            .iter()
+            .map(ScanEntryRef::File)
+            .chain(self.directory_entries.iter().map(ScanEntryRef::Directory))
+            .find(|entry| entry.path() == path)
+    }
+
+    /// Returns all scanned files and directories, grouped by the path of their parent directory.
+    ///
+    /// Entries directly inside the scan root are keyed by the path the scan was performed on.
+    /// Within each group, directories come before files, and both keep the order
+    /// of [`directory_entries`][Self::directory_entries] and [`file_entries`][Self::file_entries].
+    /// Directories without any scanned entries (see [`empty_directories`][Self::empty_directories])
+    /// have no group.
+    ///
+    /// This is derived purely from the scan results, which makes it convenient for building
+    /// a tree of the scanned entries without reconstructing it from the flat lists.
+    pub fn entries_by_parent(&self) -> HashMap<PathBuf, Vec<ScanEntryRef<'_>>> {
+        let mut entries_by_parent: HashMap<PathBuf, Vec<ScanEntryRef<'_>>> = HashMap::new();
+
+        let entries = self
+            .directory_entries
+            .iter()
+            .map(ScanEntryRef::Directory)
+            .chain(self.file_entries.iter().map(ScanEntryRef::File));
+
+        for entry in entries {
+            if let Some(parent_path) = entry.path().parent() {
+                entries_by_parent
+                    .entry(parent_path.to_path_buf())
+                    .or_default()
+                    .push(entry);
+            }
+        }
+
+        entries_by_parent
+    }
 }
 
 /// Returns `Ok(true)` if the given directory is completely empty, `Ok(false)` otherwise.
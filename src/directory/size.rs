@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use crate::{
-    directory::DirectoryScan,
+    directory::{DirectoryScan, DirectoryScanOptions},
     error::{DirectoryScanError, DirectorySizeScanError},
 };
 
@@ -22,19 +22,24 @@ pub fn directory_size_in_bytes<P>(
 where
     P: Into<PathBuf>,
 {
-    let unlimited_depth_scan =
-        DirectoryScan::scan_with_options(directory_path, None, follow_symbolic_links).map_err(
-            |error| match error {
-                DirectoryScanError::NotFound => DirectorySizeScanError::RootDirectoryNotFound,
-                DirectoryScanError::NotADirectory => DirectorySizeScanError::RootIsNotADirectory,
-                DirectoryScanError::UnableToReadDirectory { error } => {
-                    DirectorySizeScanError::UnableToAccessDirectory { error }
-                }
-                DirectoryScanError::UnableToReadDirectoryItem { error } => {
-                    DirectorySizeScanError::UnableToAccessFile { error }
-                }
-            },
-        )?;
+    let unlimited_depth_scan = DirectoryScan::scan_with_options(
+        directory_path,
+        DirectoryScanOptions {
+            maximum_scan_depth: None,
+            follow_symbolic_links,
+            ..Default::default()
+        },
+    )
+    .map_err(|error| match error {
+        DirectoryScanError::NotFound => DirectorySizeScanError::RootDirectoryNotFound,
+        DirectoryScanError::NotADirectory => DirectorySizeScanError::RootIsNotADirectory,
+        DirectoryScanError::UnableToReadDirectory { error } => {
+            DirectorySizeScanError::UnableToAccessDirectory { error }
+        }
+        DirectoryScanError::UnableToReadDirectoryItem { error } => {
+            DirectorySizeScanError::UnableToAccessFile { error }
+        }
+    })?;
 
     unlimited_depth_scan.total_size_in_bytes()
 }
@@ -0,0 +1,276 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::{
+    cmp::Reverse,
+    fs::FileType,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use crate::error::{DirectoryError, IoOperation};
+
+
+/// Specifies the order in which a [`walk`] visits the entries of each directory.
+///
+/// Sorting is applied to the entries of each directory separately,
+/// as the walk is depth-first (see [`walk`] for more details).
+///
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`WalkSortOrder::None`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum WalkSortOrder {
+    /// Entries are visited in the order the operating system returns them in.
+    #[default]
+    None,
+
+    /// Entries are visited in ascending order of their file names.
+    ByName,
+
+    /// Entries are visited in descending order of their size in bytes.
+    ///
+    /// Note that the size of a directory is the size of the directory entry itself, not of its contents.
+    BySizeDesc,
+}
+
+
+/// Options that influence the [`walk`] function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WalkOptions {
+    /// Maximum depth of the walk.
+    ///
+    /// - `None` indicates no depth limit.
+    /// - `Some(0)` means a walk that visits only the files and directories directly
+    ///   in the root directory and doesn't descend into any subdirectories.
+    /// - `Some(1)` includes the root directory's contents and one level of its subdirectories.
+    pub maximum_depth: Option<usize>,
+
+    /// Whether to descend into symbolic links to directories
+    /// (see the `Symbolic links` section of [`walk`]).
+    pub follow_symbolic_links: bool,
+
+    /// The order in which the entries of each directory are visited.
+    ///
+    /// See [`WalkSortOrder`] for more details.
+    pub sort: WalkSortOrder,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            maximum_depth: None,
+            follow_symbolic_links: false,
+            sort: WalkSortOrder::default(),
+        }
+    }
+}
+
+
+/// A single file, directory or other entry visited by a [`walk`].
+#[derive(Clone, Debug)]
+pub struct WalkEntry {
+    /// Path of the entry. This is always the root directory path joined with
+    /// the entry's subpath, even if a symbolic link was followed to get to it.
+    pub path: PathBuf,
+
+    /// Depth of the entry: `0` for entries directly in the root directory,
+    /// `1` for entries in its subdirectories, and so on.
+    pub depth: usize,
+
+    /// Type of the entry. Symbolic links are *not* followed,
+    /// i.e. a symbolic link to a directory has a symbolic link file type.
+    pub file_type: FileType,
+}
+
+
+/// A directory whose entries are yet to be read by [`DirectoryWalk`].
+struct PendingDirectory {
+    directory_path: PathBuf,
+
+    /// Depth of the entries of this directory.
+    depth: usize,
+}
+
+
+/// An iterator over the entries of a directory tree, created by [`walk`].
+pub struct DirectoryWalk {
+    options: WalkOptions,
+
+    /// Directory whose entries should be read before continuing the walk.
+    ///
+    /// Directories are read lazily (when the walk first needs their entries),
+    /// so that their errors are yielded right after the directory itself.
+    pending_directory: Option<PendingDirectory>,
+
+    /// Entries of each directory we're currently inside of, innermost last.
+    entry_stack: Vec<std::vec::IntoIter<WalkEntry>>,
+}
+
+impl DirectoryWalk {
+    /// Reads the entries of the given directory and sorts them according to `self.options.sort`.
+    fn read_directory_entries(
+        &self,
+        pending_directory: &PendingDirectory,
+    ) -> Result<Vec<WalkEntry>, DirectoryError> {
+        let directory_path = &pending_directory.directory_path;
+
+        // Only the root directory has entries at depth 0.
+        if pending_directory.depth == 0 {
+            match directory_path.try_exists() {
+                Ok(true) if !directory_path.is_dir() => {
+                    return Err(DirectoryError::SourceDirectoryIsNotADirectory);
+                }
+                Ok(true) => {}
+                Ok(false) => return Err(DirectoryError::SourceDirectoryNotFound),
+                Err(error) => return Err(DirectoryError::UnableToAccessSource { error }),
+            }
+        }
+
+        let read_source_error =
+            |path: &Path, error: std::io::Error| DirectoryError::IoDuringOperation {
+                path: path.to_path_buf(),
+                operation: IoOperation::ReadSource,
+                source: error,
+            };
+
+        let directory_iterator = fs::read_dir(directory_path)
+            .map_err(|error| read_source_error(directory_path, error))?;
+
+        let mut entries = Vec::new();
+
+        for directory_item in directory_iterator {
+            let directory_item =
+                directory_item.map_err(|error| read_source_error(directory_path, error))?;
+
+            let entry_path = directory_item.path();
+
+            let file_type = directory_item
+                .file_type()
+                .map_err(|error| read_source_error(&entry_path, error))?;
+
+            let size_in_bytes = if self.options.sort == WalkSortOrder::BySizeDesc {
+                directory_item
+                    .metadata()
+                    .map_err(|error| read_source_error(&entry_path, error))?
+                    .len()
+            } else {
+                0
+            };
+
+            entries.push((
+                WalkEntry {
+                    path: entry_path,
+                    depth: pending_directory.depth,
+                    file_type,
+                },
+                size_in_bytes,
+            ));
+        }
+
+        match self.options.sort {
+            WalkSortOrder::None => {}
+            WalkSortOrder::ByName => {
+                entries.sort_by(|(first, _), (second, _)| first.path.cmp(&second.path));
+            }
+            WalkSortOrder::BySizeDesc => {
+                entries.sort_by_key(|(_, size_in_bytes)| Reverse(*size_in_bytes));
+            }
+        }
+
+        Ok(entries.into_iter().map(|(entry, _)| entry).collect())
+    }
+
+    /// Returns `true` if the walk should descend into the given entry.
+    fn should_descend_into(&self, entry: &WalkEntry) -> bool {
+        if let Some(maximum_depth) = self.options.maximum_depth {
+            if entry.depth >= maximum_depth {
+                return false;
+            }
+        }
+
+        if entry.file_type.is_dir() {
+            true
+        } else if entry.file_type.is_symlink() && self.options.follow_symbolic_links {
+            // Unlike `DirEntry::file_type`, this *does* follow symbolic links.
+            entry.path.is_dir()
+        } else {
+            false
+        }
+    }
+}
+
+impl Iterator for DirectoryWalk {
+    type Item = Result<WalkEntry, DirectoryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(pending_directory) = self.pending_directory.take() {
+            match self.read_directory_entries(&pending_directory) {
+                Ok(entries) => self.entry_stack.push(entries.into_iter()),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+
+        while let Some(current_entries) = self.entry_stack.last_mut() {
+            let entry = match current_entries.next() {
+                Some(entry) => entry,
+                None => {
+                    self.entry_stack.pop();
+                    continue;
+                }
+            };
+
+            if self.should_descend_into(&entry) {
+                self.pending_directory = Some(PendingDirectory {
+                    directory_path: entry.path.clone(),
+                    depth: entry.depth + 1,
+                });
+            }
+
+            return Some(Ok(entry));
+        }
+
+        None
+    }
+}
+
+
+/// Walks the directory tree at `directory_path`, visiting its files, directories and other entries.
+///
+/// The walk is lazy and depth-first: each directory is visited before its contents,
+/// and the contents of a directory are visited before its next sibling. The root directory itself is not visited.
+///
+/// ### Walk depth
+/// Maximum walk depth can be configured by setting
+/// the [`maximum_depth`][WalkOptions::maximum_depth] option to:
+/// - `Some(0)` -- visits direct contents of the directory (a single layer of files and directories),
+/// - `Some(1+)` -- visits up to a certain subdirectory limit, or,
+/// - `None` -- visits the entire subtree, as deep as required.
+///
+/// ## Symbolic links
+/// Symbolic links are always visited as entries themselves. If the
+/// [`follow_symbolic_links`][WalkOptions::follow_symbolic_links] option is `true`,
+/// the walk also descends into symbolic links to directories. In that case,
+/// the contents are visited under the symbolic link's path, not the path it points to.
+///
+/// **Careful!** Following symbolic links that form a cycle makes the walk only end
+/// at the maximum depth (or never, if there is no depth limit).
+///
+/// ### Errors
+/// If `directory_path` does not exist or is not a directory, the first item is an error.
+/// If a subdirectory can't be read, an error is yielded in place of its contents
+/// and the walk continues with the next entry.
+pub fn walk<P>(directory_path: P, options: WalkOptions) -> DirectoryWalk
+where
+    P: AsRef<Path>,
+{
+    DirectoryWalk {
+        options,
+        pending_directory: Some(PendingDirectory {
+            directory_path: directory_path.as_ref().to_path_buf(),
+            depth: 0,
+        }),
+        entry_stack: Vec::new(),
+    }
+}
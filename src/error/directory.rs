@@ -1,7 +1,49 @@
-use std::path::PathBuf;
+use std::{fmt::Display, path::PathBuf};
 
 use thiserror::Error;
 
+use crate::directory::{DirectoryCopyProgress, DirectoryDeleteProgress};
+
+/// Describes the kind of filesystem operation that was being performed
+/// when an [`std::io::Error`] was encountered, see [`DirectoryError::IoDuringOperation`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IoOperation {
+    /// Reading a source directory, its entries or their metadata.
+    ReadSource,
+
+    /// Creating a target directory.
+    CreateDirectory,
+
+    /// Copying a file from the source to the target.
+    CopyFile,
+
+    /// Writing to or otherwise modifying a target file.
+    WriteTarget,
+
+    /// Reading a target file or its metadata (e.g. to verify it after copying).
+    ReadTarget,
+
+    /// Removing a directory.
+    RemoveDirectory,
+
+    /// Removing a file (or a symbolic link).
+    RemoveFile,
+}
+
+impl Display for IoOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IoOperation::ReadSource => write!(f, "reading source"),
+            IoOperation::CreateDirectory => write!(f, "creating directory"),
+            IoOperation::CopyFile => write!(f, "copying file"),
+            IoOperation::WriteTarget => write!(f, "writing target"),
+            IoOperation::ReadTarget => write!(f, "reading target"),
+            IoOperation::RemoveDirectory => write!(f, "removing directory"),
+            IoOperation::RemoveFile => write!(f, "removing file"),
+        }
+    }
+}
+
 /// Represents an error when copying or moving a directory.
 #[derive(Error, Debug)]
 pub enum DirectoryError {
@@ -18,13 +60,30 @@ pub enum DirectoryError {
     ///
     /// The inner [`std::io::Error`] will likely describe the real cause of this error.
     #[error("unable to access source directory or file")]
-    UnableToAccessSource { error: std::io::Error },
+    UnableToAccessSource {
+        #[source]
+        error: std::io::Error,
+    },
 
     /// A directory or file in the source directory
     /// has disappeared since being scanned by the same function.
     #[error("a directory or file inside the source directory has been removed mid-process")]
     SourceContentsInvalid,
 
+    /// A special file (FIFO, socket or device node) was found in the source directory
+    /// and the configured [`SpecialFileBehaviour`][crate::directory::SpecialFileBehaviour]
+    /// is [`Error`][crate::directory::SpecialFileBehaviour::Error].
+    /// The `path` field contains the path of the special file.
+    #[error("source directory contains a special file: {}", .path.display())]
+    SourceContainsSpecialFile { path: PathBuf },
+
+    /// A broken symbolic link (i.e. one whose destination does not exist) was found in the source directory
+    /// and the configured [`BrokenSymlinkBehaviour`][crate::directory::BrokenSymlinkBehaviour]
+    /// is [`Error`][crate::directory::BrokenSymlinkBehaviour::Error].
+    /// The `path` field contains the path of the symbolic link.
+    #[error("source directory contains a broken symbolic link: {}", .path.display())]
+    SourceContainsBrokenSymlink { path: PathBuf },
+
     /// The target directory path points to an invalid location, because (one of):
     /// - source and target directory are the same,
     /// - target directory is a subdirectory of the source directory, or,
@@ -32,6 +91,12 @@ pub enum DirectoryError {
     #[error("target directory path points to an invalid location")]
     InvalidTargetDirectoryPath,
 
+    /// An ancestor of the target directory path exists, but is not a directory
+    /// (e.g. the target path is `foo.txt/bar` and `foo.txt` is a file), so the target directory can't be created.
+    /// The `path` field contains the path of that ancestor.
+    #[error("parent of target directory is not a directory: {}", .path.display())]
+    TargetParentNotADirectory { path: PathBuf },
+
     /// Returned when the the target directory rule is set to
     /// [`TargetDirectoryRule::AllowEmpty`][crate::directory::TargetDirectoryRule::AllowEmpty],
     /// but the given target directory isn't empty.
@@ -43,20 +108,172 @@ pub enum DirectoryError {
     ///
     /// The inner [`std::io::Error`] will likely describe the real cause of this error.
     #[error("unable to access target directory or file")]
-    UnableToAccessTarget { error: std::io::Error },
+    UnableToAccessTarget {
+        #[source]
+        error: std::io::Error,
+    },
+
+    /// The filesystem of the target directory doesn't have enough free space
+    /// for the source directory's contents. This can only be returned if the `ensure_free_space`
+    /// option is enabled (see e.g. [`DirectoryCopyOptions`][crate::directory::DirectoryCopyOptions]).
+    #[error(
+        "not enough free space on target: {required} bytes required, {available} bytes available"
+    )]
+    InsufficientFreeSpace { required: u64, available: u64 },
+
+    /// The filesystem of the target directory doesn't have enough free inodes
+    /// for the entries that would be created. This can only be returned if the `ensure_free_inodes`
+    /// option is enabled (see e.g. [`DirectoryCopyOptions`][crate::directory::DirectoryCopyOptions]).
+    #[error(
+        "not enough free inodes on target: {required} inodes required, {available} inodes available"
+    )]
+    InsufficientInodes { required: u64, available: u64 },
+
+    /// A target directory or file could not be created or written to
+    /// because the target resides on a read-only filesystem.
+    /// The `path` field contains the path that could not be created or modified.
+    #[error("target filesystem is read-only: {}", .path.display())]
+    ReadOnlyFilesystem { path: PathBuf },
+
+    /// The staging directory (see [`move_directory_with_staging_directory`][crate::directory::move_directory_with_staging_directory])
+    /// does not exist, is not a directory or can't be written to.
+    /// The `path` field contains the staging directory path.
+    ///
+    /// The inner [`std::io::Error`] will likely describe the real cause of this error.
+    #[error("unable to use staging directory {}", .path.display())]
+    UnableToUseStagingDirectory {
+        path: PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
 
     /// A target directory or file already exists.
     /// The `path` field contains the path that already existed and caused this error.
     #[error("target directory or file already exists: {}", .path.display())]
     TargetItemAlreadyExists { path: PathBuf },
 
+    /// One or more target directories or files already exist. This is returned instead of
+    /// [`TargetItemAlreadyExists`][Self::TargetItemAlreadyExists] if the `collect_all_collisions` option
+    /// is enabled (see e.g. [`DirectoryCopyOptions`][crate::directory::DirectoryCopyOptions]).
+    /// The `paths` field contains all the paths that already existed.
+    #[error("{} target directories or files already exist", .paths.len())]
+    TargetItemsAlreadyExist { paths: Vec<PathBuf> },
+
+    /// A copied file doesn't match its source file. This can only be returned if the
+    /// [`verify_after_copy`][crate::directory::DirectoryCopyOptions::verify_after_copy] option is set.
+    /// The `path` field contains the path of the copied (target) file.
+    #[error("copied file does not match its source: {}", .path.display())]
+    VerificationFailed { path: PathBuf },
+
+    /// A path transform (see [`copy_directory_with_path_transform`][crate::directory::copy_directory_with_path_transform])
+    /// returned a path that is not a relative path inside the target directory
+    /// (e.g. an absolute path, an empty path or a path containing `..`).
+    /// The `path` field contains the returned path.
+    #[error("path transform returned an invalid relative path: {}", .path.display())]
+    InvalidTransformedPath { path: PathBuf },
+
+    /// A path transform (see [`copy_directory_with_path_transform`][crate::directory::copy_directory_with_path_transform])
+    /// mapped two source entries to the same target path.
+    /// The `path` field contains the target path both entries mapped to.
+    #[error("multiple source entries map to the same target path: {}", .path.display())]
+    DuplicateTransformedPath { path: PathBuf },
+
+    /// The configured minimum copy depth is not smaller than the maximum copy depth
+    /// (see [`DirectoryCopyOptions::maximum_copy_depth`][crate::directory::DirectoryCopyOptions::maximum_copy_depth]),
+    /// meaning no file could ever be copied.
+    #[error("minimum copy depth ({minimum}) is not smaller than maximum copy depth ({maximum})")]
+    InvalidCopyDepthRange { minimum: usize, maximum: usize },
+
+    /// A source path was expected to be inside the source root directory, but it isn't
+    /// (see e.g. [`map_source_to_target`][crate::directory::map_source_to_target]).
+    /// The `path` field contains the offending source path.
+    #[error("source path is not inside the source root directory: {}", .path.display())]
+    SourcePathEscapesRoot { path: PathBuf },
+
+    /// The directory could not be moved to the trash
+    /// (see [`trash_directory`][crate::directory::trash_directory]).
+    /// This usually means no trash is available, e.g. in headless environments
+    /// or on filesystems without a trash directory.
+    ///
+    /// The `reason` field contains the platform's description of the problem.
+    #[error("unable to move directory to the trash: {reason}")]
+    TrashUnavailable { reason: String },
+
+    /// Some of the paths the copy was restricted to do not exist in the source directory
+    /// (see [`copy_directory_only_paths`][crate::directory::copy_directory_only_paths]).
+    /// The `paths` field contains all such paths, sorted. Nothing is copied in this case.
+    #[error("{} of the listed paths were not found in the source directory", .paths.len())]
+    ListedPathsNotFound { paths: Vec<PathBuf> },
+
+    /// The owner and group of a target file or directory could not be changed to match its source
+    /// (see [`DirectoryCopyOptions::preserve_ownership`][crate::directory::DirectoryCopyOptions::preserve_ownership]).
+    /// The `path` field contains the target path whose ownership could not be changed.
+    ///
+    /// This usually happens when not running with elevated privileges, in which case
+    /// the inner [`std::io::Error`] is of the [`PermissionDenied`][std::io::ErrorKind::PermissionDenied] kind.
+    #[error("unable to preserve ownership of {}", .path.display())]
+    UnableToPreserveOwnership {
+        path: PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+
+    /// The copy did not finish within the configured timeout
+    /// (see [`DirectoryCopyWithProgressOptions::timeout`][crate::directory::DirectoryCopyWithProgressOptions::timeout]).
+    /// The `progress` field contains the progress at the time the copy was stopped;
+    /// everything that was copied until then is left in place.
+    #[error(
+        "copy timed out after copying {} of {} bytes",
+        .progress.bytes_finished,
+        .progress.bytes_total
+    )]
+    TimedOut {
+        progress: Box<DirectoryCopyProgress>,
+    },
+
+    /// The removal was cancelled by its progress handler
+    /// (see [`delete_directory_with_progress`][crate::directory::delete_directory_with_progress]).
+    /// The `progress` field contains the progress at the time the removal was cancelled;
+    /// everything that was removed until then stays removed.
+    #[error(
+        "removal cancelled after removing {} files and {} directories",
+        .progress.files_deleted,
+        .progress.directories_deleted
+    )]
+    Cancelled {
+        progress: Box<DirectoryDeleteProgress>,
+    },
+
+    /// An [`std::io::Error`] was encountered while performing an operation on a specific path
+    /// inside the source or target directory.
+    ///
+    /// The `operation` field describes what was being done at the time (see [`IoOperation`]),
+    /// and the `path` field contains the path that was being operated on.
+    #[error("i/o error while {operation} ({})", .path.display())]
+    IoDuringOperation {
+        path: PathBuf,
+        operation: IoOperation,
+        source: std::io::Error,
+    },
+
     /// Some other unrecoverable error with some `reason`.
     #[error("an unrecoverable error has been encountered: {reason}")]
     OtherReason { reason: String },
 
     /// Some other [`std::io::Error`] was encountered.
-    #[error("other std::io::Error: {error}")]
-    OtherIoError { error: std::io::Error },
+    #[error("other std::io::Error")]
+    OtherIoError {
+        #[source]
+        error: std::io::Error,
+    },
+}
+
+/// Wraps the [`std::io::Error`] into [`DirectoryError::OtherIoError`],
+/// which allows using `?` on `std::fs` calls in functions returning a [`DirectoryError`].
+impl From<std::io::Error> for DirectoryError {
+    fn from(error: std::io::Error) -> Self {
+        Self::OtherIoError { error }
+    }
 }
 
 /// Represents an error when scanning a directory.
@@ -68,11 +285,17 @@ pub enum DirectoryScanError {
     #[error("the root directory path doesn't lead to a directory")]
     NotADirectory,
 
-    #[error("unable to read directory: {error}")]
-    UnableToReadDirectory { error: std::io::Error },
+    #[error("unable to read directory")]
+    UnableToReadDirectory {
+        #[source]
+        error: std::io::Error,
+    },
 
-    #[error("unable to read directory item: {error}")]
-    UnableToReadDirectoryItem { error: std::io::Error },
+    #[error("unable to read directory item")]
+    UnableToReadDirectoryItem {
+        #[source]
+        error: std::io::Error,
+    },
 }
 
 /// Represents an error when querying size of a scanned directory.
@@ -95,17 +318,26 @@ pub enum DirectorySizeScanError {
     ///
     /// The inner [`std::io::Error`] will likely describe the real cause of this error.
     #[error("unable to access file")]
-    UnableToAccessFile { error: std::io::Error },
+    UnableToAccessFile {
+        #[source]
+        error: std::io::Error,
+    },
 
     /// The directory cannot be accessed (e.g. due to missing permissions).
     ///
     /// The inner [`std::io::Error`] will likely describe the real cause of this error.
     #[error("unable to access file")]
-    UnableToAccessDirectory { error: std::io::Error },
+    UnableToAccessDirectory {
+        #[source]
+        error: std::io::Error,
+    },
 
     /// Some other [`std::io::Error`] was encountered.
-    #[error("other std::io::Error: {error}")]
-    OtherIoError { error: std::io::Error },
+    #[error("other std::io::Error")]
+    OtherIoError {
+        #[source]
+        error: std::io::Error,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -116,6 +348,9 @@ pub enum DirectoryIsEmptyError {
     #[error("given path does not lead to a directory")]
     NotADirectory,
 
-    #[error("unable to read contents of directory due to an std::io::Error: {error}")]
-    UnableToReadDirectory { error: std::io::Error },
+    #[error("unable to read contents of directory")]
+    UnableToReadDirectory {
+        #[source]
+        error: std::io::Error,
+    },
 }
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use thiserror::Error;
 
 /// Represents an error when copying or moving a file.
@@ -15,7 +17,10 @@ pub enum FileError {
     ///
     /// The inner [`std::io::Error`] will likely describe the real cause of this error.
     #[error("unable to access source file")]
-    UnableToAccessSourceFile { error: std::io::Error },
+    UnableToAccessSourceFile {
+        #[source]
+        error: std::io::Error,
+    },
 
     /// The target file already exists. Some copy/move options disable this error:
     /// - [`FileCopyOptions.overwrite_existing`][crate::file::FileCopyOptions],
@@ -29,15 +34,108 @@ pub enum FileError {
     ///
     /// The inner [`std::io::Error`] will likely describe the real cause of this error.
     #[error("unable to access target file")]
-    UnableToAccessTargetFile { error: std::io::Error },
+    UnableToAccessTargetFile {
+        #[source]
+        error: std::io::Error,
+    },
+
+    /// An ancestor of the target file path exists, but is not a directory
+    /// (e.g. the target path is `foo.txt/bar.txt` and `foo.txt` is a file), so the target file can't be created.
+    /// The `path` field contains the path of that ancestor.
+    #[error("parent of target file is not a directory: {}", .path.display())]
+    TargetParentNotADirectory { path: PathBuf },
+
+    /// The target file name returned by the closure passed to [`copy_file_as`][crate::file::copy_file_as]
+    /// is not a plain file name (e.g. it is empty, contains a path separator or is `..`).
+    /// The `path` field contains the returned file name.
+    #[error("invalid target file name: {}", .path.display())]
+    InvalidTargetFileName { path: PathBuf },
 
     /// The source and target file paths point to the same file.
     #[error("source and target file path are the same file")]
     SourceAndTargetAreTheSameFile,
 
+    /// A temporary file could not be created in the target file's directory
+    /// (e.g. due to missing permissions). This can only happen when copying atomically,
+    /// see [`FileCopyOptions.atomic`][crate::file::FileCopyOptions].
+    ///
+    /// The inner [`std::io::Error`] will likely describe the real cause of this error.
+    #[error("unable to create temporary file in target directory")]
+    UnableToCreateTemporaryFile {
+        #[source]
+        error: std::io::Error,
+    },
+
+    /// The files are not on the same filesystem, which is required
+    /// when swapping them (see [`swap_files`][crate::file::swap_files]).
+    #[error("files are not on the same filesystem")]
+    NotOnSameFilesystem,
+
+    /// The copied file's checksum doesn't match the source file's checksum.
+    /// This can only happen when verification is enabled,
+    /// see [`FileMoveOptions.verify_before_delete`][crate::file::FileMoveOptions::verify_before_delete].
+    #[error("copied file does not match the source file")]
+    VerificationFailed,
+
+    /// The file could not be written to or removed because it resides on a read-only filesystem.
+    /// The `path` field contains the path that could not be modified.
+    #[error("filesystem is read-only: {}", .path.display())]
+    ReadOnlyFilesystem { path: PathBuf },
+
+    /// The alternate data streams of the source file could not be enumerated.
+    /// This can only happen on Windows when preserving alternate data streams,
+    /// see [`FileCopyOptions.preserve_alternate_data_streams`][crate::file::FileCopyOptions::preserve_alternate_data_streams].
+    ///
+    /// The inner [`std::io::Error`] will likely describe the real cause of this error.
+    #[error("unable to enumerate alternate data streams of source file")]
+    UnableToEnumerateAlternateDataStreams {
+        #[source]
+        error: std::io::Error,
+    },
+
+    /// The owner and group of the target file could not be changed to match the source file
+    /// (see [`FileCopyOptions::preserve_ownership`][crate::file::FileCopyOptions::preserve_ownership]).
+    ///
+    /// This usually happens when not running with elevated privileges, in which case
+    /// the inner [`std::io::Error`] is of the [`PermissionDenied`][std::io::ErrorKind::PermissionDenied] kind.
+    #[error("unable to preserve ownership of target file")]
+    UnableToPreserveOwnership {
+        #[source]
+        error: std::io::Error,
+    },
+
+    /// The source file was modified (its size or modification time changed) while it was being copied,
+    /// see [`FileCopyWithProgressOptions::detect_source_modification`][crate::file::FileCopyWithProgressOptions::detect_source_modification].
+    /// The target file is removed before this error is returned.
+    #[error("source file was modified during copy")]
+    SourceModifiedDuringCopy,
+
+    /// The file is larger than the given limit, see [`read_to_string_limited`][crate::file::read_to_string_limited].
+    /// The `size` field contains the size of the file in bytes and `limit` the maximum allowed size.
+    #[error("file is too large: {size} bytes (limit is {limit} bytes)")]
+    FileTooLarge { size: u64, limit: u64 },
+
+    /// The file's contents are not valid UTF-8, see [`read_to_string_limited`][crate::file::read_to_string_limited].
+    #[error("file contents are not valid UTF-8")]
+    InvalidUtf8 {
+        #[source]
+        error: std::str::Utf8Error,
+    },
+
     /// Some other [`std::io::Error`] was encountered.
-    #[error("other std::io::Error: {error}")]
-    OtherIoError { error: std::io::Error },
+    #[error("other std::io::Error")]
+    OtherIoError {
+        #[source]
+        error: std::io::Error,
+    },
+}
+
+/// Wraps the [`std::io::Error`] into [`FileError::OtherIoError`],
+/// which allows using `?` on `std::fs` calls in functions returning a [`FileError`].
+impl From<std::io::Error> for FileError {
+    fn from(error: std::io::Error) -> Self {
+        Self::OtherIoError { error }
+    }
 }
 
 
@@ -52,15 +150,29 @@ pub enum FileRemoveError {
     #[error("provided file path is not a file")]
     NotAFile,
 
+    /// The file could not be moved to the trash (see [`trash_file`][crate::file::trash_file]).
+    /// This usually means no trash is available, e.g. in headless environments
+    /// or on filesystems without a trash directory.
+    ///
+    /// The `reason` field contains the platform's description of the problem.
+    #[error("unable to move file to the trash: {reason}")]
+    TrashUnavailable { reason: String },
+
     /// The file cannot be accessed (e.g. due to missing permissions).
     ///
     /// The inner [`std::io::Error`] will likely describe the real cause of this error.
     #[error("unable to access file")]
-    UnableToAccessFile { error: std::io::Error },
+    UnableToAccessFile {
+        #[source]
+        error: std::io::Error,
+    },
 
     /// Some other [`std::io::Error`] was encountered.
-    #[error("other std::io::Error: {error}")]
-    OtherIoError { error: std::io::Error },
+    #[error("other std::io::Error")]
+    OtherIoError {
+        #[source]
+        error: std::io::Error,
+    },
 }
 
 /// Represents an error when querying the size of a file.
@@ -78,9 +190,15 @@ pub enum FileSizeError {
     ///
     /// The inner [`std::io::Error`] will likely describe the real cause of this error.
     #[error("unable to access file")]
-    UnableToAccessFile { error: std::io::Error },
+    UnableToAccessFile {
+        #[source]
+        error: std::io::Error,
+    },
 
     /// Some other [`std::io::Error`] was encountered.
-    #[error("other std::io::Error: {error}")]
-    OtherIoError { error: std::io::Error },
+    #[error("other std::io::Error")]
+    OtherIoError {
+        #[source]
+        error: std::io::Error,
+    },
 }
@@ -2,6 +2,62 @@
 
 mod directory;
 mod file;
+mod path;
 
 pub use directory::*;
 pub use file::*;
+pub use path::*;
+
+
+/// Returns `true` if the given error indicates that the filesystem is mounted as read-only
+/// (`EROFS` on Unix, `ERROR_WRITE_PROTECT` on Windows).
+pub(crate) fn is_read_only_filesystem_error(error: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        error.raw_os_error() == Some(libc::EROFS)
+    }
+
+    #[cfg(windows)]
+    {
+        // ERROR_WRITE_PROTECT
+        error.raw_os_error() == Some(19)
+    }
+}
+
+
+/// Returns `true` if the given error indicates that a file could not be accessed
+/// because another process has it open or locked (`ERROR_SHARING_VIOLATION` or
+/// `ERROR_LOCK_VIOLATION` on Windows).
+///
+/// File locks are only advisory on Unix, so this always returns `false` there.
+pub(crate) fn is_locked_file_error(error: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        let _ = error;
+        false
+    }
+
+    #[cfg(windows)]
+    {
+        // ERROR_SHARING_VIOLATION or ERROR_LOCK_VIOLATION
+        matches!(error.raw_os_error(), Some(32) | Some(33))
+    }
+}
+
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_read_only_filesystem_error() {
+        let read_only_error = std::io::Error::from_raw_os_error(libc::EROFS);
+        assert!(is_read_only_filesystem_error(&read_only_error));
+
+        let permission_error = std::io::Error::from_raw_os_error(libc::EACCES);
+        assert!(!is_read_only_filesystem_error(&permission_error));
+
+        let custom_error = std::io::Error::new(std::io::ErrorKind::Other, "not an OS error");
+        assert!(!is_read_only_filesystem_error(&custom_error));
+    }
+}
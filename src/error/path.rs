@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Represents a problem with the relationship between a source and a target path,
+/// see [`validate_copy_pair`][crate::path::validate_copy_pair].
+#[derive(Error, Debug)]
+pub enum PathValidationError {
+    /// The source path does not exist (or is a broken symbolic link).
+    #[error("source path does not exist")]
+    SourceNotFound,
+
+    /// The source path cannot be accessed (e.g. due to missing permissions).
+    ///
+    /// The inner [`std::io::Error`] will likely describe the real cause of this error.
+    #[error("unable to access source path")]
+    UnableToAccessSource {
+        #[source]
+        error: std::io::Error,
+    },
+
+    /// The target path (or one of its ancestors) cannot be accessed (e.g. due to missing permissions).
+    ///
+    /// The inner [`std::io::Error`] will likely describe the real cause of this error.
+    #[error("unable to access target path")]
+    UnableToAccessTarget {
+        #[source]
+        error: std::io::Error,
+    },
+
+    /// The source path is neither a file nor a directory (e.g. a FIFO or a socket),
+    /// so it can't be copied with [`copy_file`][crate::file::copy_file]
+    /// or [`copy_directory`][crate::directory::copy_directory].
+    #[error("source path is neither a file nor a directory")]
+    UnsupportedSource,
+
+    /// The source is a directory, but the target path exists and is not a directory.
+    #[error("target path is not a directory")]
+    TargetNotADirectory,
+
+    /// The source and target paths point to the same file or directory
+    /// (possibly through symbolic links).
+    #[error("source and target path are the same")]
+    SourceAndTargetAreTheSame,
+
+    /// The source is a directory and the target path is inside of it.
+    #[error("target path is inside the source directory")]
+    TargetInsideSource,
+
+    /// An ancestor of the target path exists, but is not a directory
+    /// (e.g. the target path is `foo.txt/bar` and `foo.txt` is a file).
+    /// The `path` field contains the path of that ancestor.
+    #[error("parent of target path is not a directory: {}", .path.display())]
+    TargetParentNotADirectory { path: PathBuf },
+}
@@ -0,0 +1,85 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::{io::Read, path::Path};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+use sha2::{Digest, Sha256};
+
+use super::DEFAULT_COPY_BUFFER_SIZE;
+
+
+/// Checksum algorithm used to verify file contents
+/// (see e.g. [`FileMoveOptions::verify_before_delete`][super::FileMoveOptions::verify_before_delete]).
+///
+/// ## Defaults
+/// The default algorithm is [`ChecksumAlgorithm::Sha256`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ChecksumAlgorithm {
+    /// CRC-32 (IEEE). Very fast, but only suitable for detecting accidental corruption.
+    Crc32,
+
+    /// SHA-256. Slower than [`Crc32`][ChecksumAlgorithm::Crc32],
+    /// but practically guarantees that two files with the same checksum have the same contents.
+    #[default]
+    Sha256,
+}
+
+
+/// Incrementally computes a checksum with the given [`ChecksumAlgorithm`],
+/// e.g. from the chunks of a file as it is being copied.
+pub(crate) enum ChecksumHasher {
+    Crc32(crc32fast::Hasher),
+    Sha256(Sha256),
+}
+
+impl ChecksumHasher {
+    pub(crate) fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Crc32 => Self::Crc32(crc32fast::Hasher::new()),
+            ChecksumAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Crc32(hasher) => hasher.update(data),
+            Self::Sha256(hasher) => hasher.update(data),
+        }
+    }
+
+    /// Returns the checksum as a lowercase hexadecimal string.
+    pub(crate) fn finalize(self) -> String {
+        match self {
+            Self::Crc32(hasher) => format!("{:08x}", hasher.finalize()),
+            Self::Sha256(hasher) => hasher
+                .finalize()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+        }
+    }
+}
+
+
+/// Reads the entire file at `file_path` and returns its checksum as a lowercase hexadecimal string.
+pub(crate) fn compute_file_checksum(
+    file_path: &Path,
+    algorithm: ChecksumAlgorithm,
+) -> std::io::Result<String> {
+    let mut file = fs::File::open(file_path)?;
+    let mut buffer = vec![0u8; DEFAULT_COPY_BUFFER_SIZE];
+    let mut hasher = ChecksumHasher::new(algorithm);
+
+    loop {
+        let num_bytes_read = file.read(&mut buffer)?;
+        if num_bytes_read == 0 {
+            break;
+        }
+
+        hasher.update(&buffer[..num_bytes_read]);
+    }
+
+    Ok(hasher.finalize())
+}
@@ -1,23 +1,98 @@
 #[cfg(not(feature = "fs-err"))]
 use std::fs;
 use std::{
-    io::{BufReader, BufWriter, Write},
-    path::Path,
+    fs::Metadata,
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
 #[cfg(feature = "fs-err")]
 use fs_err as fs;
 
 use super::{
+    checksum::ChecksumHasher,
+    classify_read_only_filesystem_error,
     progress::{FileProgress, ProgressWriter},
+    sparse::copy_file_punching_zero_runs_unchecked,
+    validate_existing_target_directory,
     validate_source_file_path,
+    validate_target_file_parent,
+    ChecksumAlgorithm,
     ValidatedSourceFilePath,
 };
-use crate::error::FileError;
+use crate::{error::FileError, path::is_same_file};
+
+
+/// Size (in bytes) of the buffer used to copy file contents, unless configured otherwise
+/// (see e.g. [`FileCopyWithProgressOptions::buffer_strategy`]). This is 64 KiB.
+///
+/// All options with a buffer size default to this value.
+pub const DEFAULT_COPY_BUFFER_SIZE: usize = 1024 * 64;
+
+
+/// Specifies how sparse files (files with holes, i.e. unallocated ranges that read as zeros)
+/// are handled when copying.
+///
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`SparseMode::Never`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SparseMode {
+    /// Indicates holes in the source file should be detected and recreated on the target.
+    ///
+    /// Holes are detected using `lseek` with `SEEK_DATA` and `SEEK_HOLE`. This is currently only
+    /// supported on Linux. On other platforms or filesystems that don't support it,
+    /// this behaves the same as [`SparseMode::Never`].
+    Auto,
+
+    /// Indicates the file should be copied verbatim, i.e. any holes in the source file
+    /// will take up space on the target.
+    #[default]
+    Never,
+
+    /// Indicates every (4 KiB) block of zero bytes in the source file, whether it is a hole or not,
+    /// should be turned into a hole on the target.
+    ///
+    /// Note that this requires reading the entire source file,
+    /// including its holes, and checking every block for zero bytes.
+    Always,
+}
+
+
+/// Specifies how the contents of a file are read when copying.
+///
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`CopyMethod::Standard`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CopyMethod {
+    /// Indicates the copying should be left to the standard library (see [`std::fs::copy`]),
+    /// which uses the fastest method the platform provides (e.g. `copy_file_range` on Linux).
+    #[default]
+    Standard,
+
+    /// Indicates source files of at least `minimum_size_in_bytes` bytes should be mapped into memory
+    /// (using `mmap`) and written to the target file directly from the mapping.
+    /// Smaller files are copied as with [`CopyMethod::Standard`].
+    ///
+    /// If the source file can't be mapped (e.g. because it is a special file or resides on a filesystem
+    /// that doesn't support mapping), it is copied as with [`CopyMethod::Standard`] instead.
+    /// Whether the mapping was used is reported in [`FinishedFileCopy::was_memory_mapped`].
+    ///
+    /// This is currently only supported on Unix. It is not used with [`SparseMode::Always`],
+    /// nor with [`SparseMode::Auto`] for source files that contain holes; source files without holes
+    /// are still memory-mapped. On other platforms, this behaves the same as [`CopyMethod::Standard`].
+    ///
+    /// *Careful:* the source file must not be truncated by another process while it is being copied,
+    /// as reading the no-longer-existing part of the mapping terminates the process with `SIGBUS`.
+    MemoryMapped { minimum_size_in_bytes: u64 },
+}
 
 
 /// Options that influence the [`copy_file`] function.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FileCopyOptions {
     /// Whether to overwrite an existing target file if it exists already.
     ///
@@ -28,6 +103,78 @@ pub struct FileCopyOptions {
     ///
     /// This takes precedence over `overwrite_existing`.
     pub skip_existing: bool,
+
+    /// If enabled along with `overwrite_existing`, an existing target file is only overwritten
+    /// if the source file's modification time is strictly newer than the target file's.
+    /// Otherwise, copying is skipped (as if `skip_existing` were enabled).
+    ///
+    /// This has no effect if `overwrite_existing` is disabled.
+    pub overwrite_only_if_source_is_newer: bool,
+
+    /// Whether to copy the file atomically.
+    ///
+    /// If enabled, the contents are first copied into a temporary file in the target file's directory,
+    /// which is then renamed onto the target file path once the copy has succeeded. If copying fails,
+    /// the temporary file is removed. This way the target file is never observed in a partially-written state.
+    ///
+    /// If the temporary file cannot be created (e.g. the target directory isn't writable),
+    /// [`FileError::UnableToCreateTemporaryFile`] is returned.
+    pub atomic: bool,
+
+    /// Specifies how sparse files are handled.
+    ///
+    /// See [`SparseMode`] for more details.
+    pub sparse: SparseMode,
+
+    /// Whether to also copy the alternate data streams (named streams) of the source file.
+    ///
+    /// On NTFS, files can carry additional named streams next to their main contents,
+    /// which are otherwise not guaranteed to be copied (e.g. when copying sparsely).
+    /// If enabled, all named streams of the source file are enumerated and copied onto the target file.
+    /// If they can't be enumerated, [`FileError::UnableToEnumerateAlternateDataStreams`] is returned.
+    ///
+    /// This has no effect on platforms other than Windows.
+    pub preserve_alternate_data_streams: bool,
+
+    /// Specifies how the contents of the file are read.
+    ///
+    /// See [`CopyMethod`] for more details.
+    pub copy_method: CopyMethod,
+
+    /// Whether to change the owner and group of the target file to match the source file.
+    ///
+    /// Changing the owner usually requires elevated privileges (e.g. running as root).
+    /// If it isn't permitted, `Err(`[`FileError::UnableToPreserveOwnership`]`)` is returned,
+    /// with the inner error being of the [`PermissionDenied`][std::io::ErrorKind::PermissionDenied] kind,
+    /// and the copied target file is removed.
+    ///
+    /// This has no effect on platforms other than Unix.
+    pub preserve_ownership: bool,
+
+    /// Whether to set the creation time of the target file to the one of the source file.
+    ///
+    /// Support for this depends on the platform:
+    /// - on Windows, the creation time is set through `SetFileTime`,
+    /// - on macOS, the creation time is set through `setattrlist`,
+    /// - on Linux and other platforms, creation times can not be set, so this has no effect.
+    ///
+    /// If the creation time can't be read or set, `Err(`[`FileError::UnableToAccessTargetFile`]`)`
+    /// is returned.
+    pub preserve_creation_time: bool,
+
+    /// Permission mode (e.g. `0o640`) to set on the target file once its contents have been copied,
+    /// instead of keeping the permissions of the source file.
+    ///
+    /// On Unix, this is applied as the full mode of the target file.
+    /// On Windows, only the owner write bit (`0o200`) is considered:
+    /// if it isn't set, the target file is made read-only, otherwise it is made writable.
+    ///
+    /// This is the single-file counterpart of
+    /// [`DirectoryCopyOptions::force_mode`][crate::directory::DirectoryCopyOptions::force_mode].
+    /// If the permissions can't be set, `Err(`[`FileError::UnableToAccessTargetFile`]`)` is returned.
+    ///
+    /// Defaults to `None`.
+    pub target_permissions: Option<u32>,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -36,8 +183,443 @@ impl Default for FileCopyOptions {
         Self {
             overwrite_existing: false,
             skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::default(),
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::default(),
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
+        }
+    }
+}
+
+
+/// Describes the result of a successful [`copy_file_detailed`] call.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FinishedFileCopy {
+    /// Logical size of the copied file in bytes.
+    pub bytes_copied: u64,
+
+    /// Number of bytes that were physically written to the target file.
+    ///
+    /// This is less than [`bytes_copied`][Self::bytes_copied] if some parts of the file
+    /// were left as holes (see [`SparseMode`]).
+    pub bytes_written: u64,
+
+    /// Whether the source file was copied through a memory mapping (see [`CopyMethod::MemoryMapped`]).
+    pub was_memory_mapped: bool,
+}
+
+
+/// Creates a new empty temporary file in the same directory as `target_file_path`
+/// and returns its path.
+///
+/// Because the temporary file is a sibling of the target file, renaming it
+/// onto `target_file_path` never needs to cross a filesystem boundary.
+pub(crate) fn create_temporary_sibling_file(target_file_path: &Path) -> Result<PathBuf, FileError> {
+    static TEMPORARY_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let target_directory_path = match target_file_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let target_file_name =
+        target_file_path
+            .file_name()
+            .ok_or_else(|| FileError::UnableToAccessTargetFile {
+                error: std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "target file path does not have a file name",
+                ),
+            })?;
+
+    loop {
+        let temporary_file_path = target_directory_path.join(format!(
+            ".{}.{}-{}.fs-more-tmp",
+            target_file_name.to_string_lossy(),
+            std::process::id(),
+            TEMPORARY_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        // `create_new` ensures we never clobber an existing file with the same name.
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temporary_file_path)
+        {
+            Ok(_) => return Ok(temporary_file_path),
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(FileError::UnableToCreateTemporaryFile { error }),
+        }
+    }
+}
+
+
+/// Ensures the target file path doesn't exist yet (unless `options.overwrite_existing` is `true`)
+/// and that it isn't the source file itself.
+///
+/// Returns `Ok(true)` if the target file exists and should be skipped
+/// (i.e. `options.skip_existing` is `true`).
+pub(crate) fn should_skip_copying_to_target(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    options: &FileCopyOptions,
+) -> Result<bool, FileError> {
+    match target_file_path.try_exists() {
+        Ok(exists) => {
+            // Ensure we don't try to copy the file into itself.
+            if exists && is_same_file(source_file_path, target_file_path)? {
+                return Err(FileError::SourceAndTargetAreTheSameFile);
+            }
+
+            if exists && options.skip_existing {
+                return Ok(true);
+            }
+
+            if exists && !options.overwrite_existing {
+                return Err(FileError::AlreadyExists);
+            }
+
+            if exists
+                && options.overwrite_only_if_source_is_newer
+                && !is_source_file_newer(source_file_path, target_file_path)?
+            {
+                return Ok(true);
+            }
+
+            Ok(false)
+        }
+        Err(error) => Err(FileError::UnableToAccessTargetFile { error }),
+    }
+}
+
+
+/// Returns `true` if the source file's modification time is strictly newer than the target file's.
+fn is_source_file_newer(
+    source_file_path: &Path,
+    target_file_path: &Path,
+) -> Result<bool, FileError> {
+    let source_modified_at = fs::metadata(source_file_path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+    let target_modified_at = fs::metadata(target_file_path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    Ok(source_modified_at > target_modified_at)
+}
+
+
+/// Copies the source file into a temporary file next to the target file,
+/// then renames the temporary file onto the target file path.
+///
+/// If any step fails, the temporary file is removed.
+///
+/// *Warning:* no checks are performed before copying
+/// (e.g. whether source exists or whether target is a directory or already exists).
+fn copy_file_atomically_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    options: &FileCopyOptions,
+) -> Result<FinishedFileCopy, FileError> {
+    let temporary_file_path = create_temporary_sibling_file(target_file_path)?;
+
+    let finished_copy =
+        match copy_file_contents_unchecked(source_file_path, &temporary_file_path, options) {
+            Ok(finished_copy) => finished_copy,
+            Err(error) => {
+                let _ = fs::remove_file(&temporary_file_path);
+                return Err(error);
+            }
+        };
+
+    if let Err(error) = fs::rename(&temporary_file_path, target_file_path) {
+        let _ = fs::remove_file(&temporary_file_path);
+        return Err(FileError::UnableToAccessTargetFile { error });
+    }
+
+    Ok(finished_copy)
+}
+
+
+/// Copies the contents of the source file to the target file,
+/// handling holes in the source file according to `options.sparse` (see [`SparseMode`])
+/// and reading the source file according to `options.copy_method` (see [`CopyMethod`]).
+///
+/// If `options.preserve_alternate_data_streams` is `true`, the named streams of the source file
+/// are copied as well (on Windows only), and if `options.preserve_ownership` is `true`,
+/// the owner and group of the source file are applied to the target file (on Unix only).
+/// If `options.preserve_creation_time` is `true`, the creation time of the source file
+/// is applied to the target file (on Windows and macOS only).
+/// Finally, `options.target_permissions` is applied to the target file, if set.
+///
+/// *Warning:* no checks are performed before copying
+/// (e.g. whether source exists or whether target is a directory or already exists).
+fn copy_file_contents_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    options: &FileCopyOptions,
+) -> Result<FinishedFileCopy, FileError> {
+    let finished_copy = copy_main_stream_unchecked(
+        source_file_path,
+        target_file_path,
+        options.sparse,
+        options.copy_method,
+    )?;
+
+    #[cfg(windows)]
+    {
+        if options.preserve_alternate_data_streams {
+            super::streams::copy_alternate_data_streams_unchecked(
+                source_file_path,
+                target_file_path,
+            )?;
         }
     }
+
+    apply_target_file_attributes_unchecked(source_file_path, target_file_path, options)?;
+
+    Ok(finished_copy)
+}
+
+
+/// Applies `options.preserve_ownership`, `options.preserve_creation_time` and
+/// `options.target_permissions` to the already copied target file (see [`FileCopyOptions`]).
+///
+/// *Warning:* no checks are performed (e.g. whether the target file exists).
+fn apply_target_file_attributes_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    options: &FileCopyOptions,
+) -> Result<(), FileError> {
+    if options.preserve_ownership {
+        super::copy_ownership(source_file_path, target_file_path)
+            .map_err(|error| FileError::UnableToPreserveOwnership { error })?;
+    }
+
+    if options.preserve_creation_time {
+        super::metadata::copy_creation_time(source_file_path, target_file_path)
+            .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+    }
+
+    // Permissions are applied last, so that a read-only mode doesn't prevent the steps above.
+    if let Some(target_permissions) = options.target_permissions {
+        apply_target_permissions(target_file_path, target_permissions)
+            .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+    }
+
+    Ok(())
+}
+
+
+/// Sets the permissions of the file at `target_file_path` (see [`FileCopyOptions::target_permissions`]).
+#[cfg(unix)]
+fn apply_target_permissions(target_file_path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(
+        target_file_path,
+        std::fs::Permissions::from_mode(mode),
+    )
+}
+
+/// Sets the read-only attribute of the file at `target_file_path` according to the owner write bit of `mode`
+/// (see [`FileCopyOptions::target_permissions`]).
+#[cfg(not(unix))]
+fn apply_target_permissions(target_file_path: &Path, mode: u32) -> std::io::Result<()> {
+    let mut permissions = fs::metadata(target_file_path)?.permissions();
+    permissions.set_readonly(mode & 0o200 == 0);
+
+    fs::set_permissions(target_file_path, permissions)
+}
+
+
+/// Copies the main contents of the source file to the target file
+/// (see [`copy_file_contents_unchecked`]).
+fn copy_main_stream_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    sparse: SparseMode,
+    copy_method: CopyMethod,
+) -> Result<FinishedFileCopy, FileError> {
+    match sparse {
+        SparseMode::Auto => {
+            // Files without holes are copied as with `SparseMode::Never`, so the copy method still applies.
+            #[cfg(target_os = "linux")]
+            {
+                if super::sparse::file_has_holes(source_file_path)? != Some(false) {
+                    if let Some(finished_copy) =
+                        super::sparse::copy_file_preserving_holes_unchecked(
+                            source_file_path,
+                            target_file_path,
+                        )?
+                    {
+                        return Ok(finished_copy);
+                    }
+                }
+            }
+        }
+        SparseMode::Never => {}
+        SparseMode::Always => {
+            return copy_file_punching_zero_runs_unchecked(source_file_path, target_file_path);
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        if let CopyMethod::MemoryMapped {
+            minimum_size_in_bytes,
+        } = copy_method
+        {
+            if let Some(finished_copy) = super::mmap::copy_file_memory_mapped_unchecked(
+                source_file_path,
+                target_file_path,
+                minimum_size_in_bytes,
+            )? {
+                return Ok(finished_copy);
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = copy_method;
+    }
+
+    // Pass the copying onto Rust's standard library.
+    let num_bytes_copied = fs::copy(source_file_path, target_file_path)
+        .map_err(|error| FileError::OtherIoError { error })?;
+
+    Ok(FinishedFileCopy {
+        bytes_copied: num_bytes_copied,
+        bytes_written: num_bytes_copied,
+        was_memory_mapped: false,
+    })
+}
+
+
+/// Copies the contents (and permissions) of `source_file_path` to `target_file_path`, computing
+/// the checksum of the contents with the given `algorithm` as they are copied.
+///
+/// Returns the number of bytes copied and the checksum as a lowercase hexadecimal string.
+///
+/// The same checks as in [`copy_file`] are performed before the target file is opened:
+/// notably, if the target path resolves to the source file itself (e.g. through a symbolic link),
+/// `Err(`[`FileError::SourceAndTargetAreTheSameFile`]`)` is returned. If the target file exists and
+/// `overwrite_existing` is `false`, `Err(`[`FileError::AlreadyExists`]`)` is returned.
+pub(crate) fn copy_file_with_checksum(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    overwrite_existing: bool,
+    algorithm: ChecksumAlgorithm,
+) -> Result<(u64, String), FileError> {
+    let ValidatedSourceFilePath {
+        source_file_path, ..
+    } = validate_source_file_path(source_file_path)?;
+
+    validate_target_file_parent(target_file_path)?;
+
+    // Skipping is disabled in these options, so this only ever returns `Ok(false)` or an error.
+    should_skip_copying_to_target(
+        &source_file_path,
+        target_file_path,
+        &FileCopyOptions {
+            overwrite_existing,
+            ..Default::default()
+        },
+    )?;
+
+    copy_file_with_checksum_unchecked(&source_file_path, target_file_path, algorithm)
+}
+
+
+/// Copies the contents (and permissions) of `source_file_path` to `target_file_path`, computing
+/// the checksum of the contents with the given `algorithm` as they are copied
+/// (see [`copy_file_with_checksum`]).
+///
+/// This performs no validation: the target file is created or truncated, so the caller
+/// must have made sure it is allowed to be written to.
+fn copy_file_with_checksum_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    algorithm: ChecksumAlgorithm,
+) -> Result<(u64, String), FileError> {
+    let mut source_file = fs::File::open(source_file_path)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+    let source_permissions = source_file
+        .metadata()
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?
+        .permissions();
+
+    let mut target_file = fs::File::create(target_file_path)
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    let mut hasher = ChecksumHasher::new(algorithm);
+
+    let num_bytes_copied =
+        copy_contents_updating_hasher(&mut source_file, &mut target_file, &mut hasher)
+            .and_then(|num_bytes_copied| {
+                fs::set_permissions(target_file_path, source_permissions)
+                    .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+                Ok(num_bytes_copied)
+            })
+            .map_err(|error| remove_partially_copied_target_file(target_file_path, error))?;
+
+    Ok((num_bytes_copied, hasher.finalize()))
+}
+
+
+/// Copies everything from `reader` (the source) to `writer` (the target),
+/// passing each copied chunk to `hasher` as well.
+///
+/// Returns the number of bytes copied.
+fn copy_contents_updating_hasher<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    hasher: &mut ChecksumHasher,
+) -> Result<u64, FileError>
+where
+    R: Read,
+    W: Write,
+{
+    let mut buffer = vec![0u8; DEFAULT_COPY_BUFFER_SIZE];
+    let mut num_bytes_copied: u64 = 0;
+
+    loop {
+        let num_bytes_read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(num_bytes_read) => num_bytes_read,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(FileError::UnableToAccessSourceFile { error }),
+        };
+
+        hasher.update(&buffer[..num_bytes_read]);
+        writer
+            .write_all(&buffer[..num_bytes_read])
+            .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+        num_bytes_copied += num_bytes_read as u64;
+    }
+
+    writer
+        .flush()
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    Ok(num_bytes_copied)
+}
+
+
+/// Removes the partially written target file after a failed copy and returns the original `error`.
+///
+/// This must only be called once we've been allowed to write to the target file, i.e. either it didn't exist
+/// or we were allowed to overwrite it.
+fn remove_partially_copied_target_file(target_file_path: &Path, error: FileError) -> FileError {
+    // The original error is more useful to the caller than an error encountered while cleaning up after it.
+    let _ = fs::remove_file(target_file_path);
+
+    error
 }
 
 
@@ -53,18 +635,52 @@ impl Default for FileCopyOptions {
 /// return `Err` with [`FileError::AlreadyExists`][crate::error::FileError::AlreadyExists],
 /// unless `options.skip_existing` is `true`, in which case `Ok(0)` is returned.
 ///
+/// If both `options.overwrite_existing` and `options.overwrite_only_if_source_is_newer` are `true`,
+/// an existing target file is only overwritten if the source file was modified more recently,
+/// otherwise copying is skipped and `Ok(0)` is returned.
+///
+/// If `options.atomic` is `true`, the file is first copied into a temporary file next to the target
+/// and then renamed into place, see [`FileCopyOptions::atomic`].
+///
+/// Holes in sparse source files are handled according to `options.sparse`, see [`SparseMode`].
+/// To find out how many bytes were physically written, use [`copy_file_detailed`].
+///
+/// ## Partial copies
+/// If an error occurs while copying the file contents, the partially written target file is removed
+/// before the error is returned. Note that this includes a pre-existing target file
+/// that was being overwritten (`options.overwrite_existing`).
+///
 /// ## Symbolic links
 /// If `source_file_path` is a symbolic link to a file, the contents of the file it points to will be copied to `target_file_path`
 /// (same behaviour as `cp` without `-P` on Unix).
 ///
 /// ## Internals
-/// This function internally delegates copying to [`std::fs::copy`] from the standard library
+/// Unless `options.sparse` is set to something else than [`SparseMode::Never`],
+/// this function internally delegates copying to [`std::fs::copy`] from the standard library
 /// (but note that [`copy_file_with_progress`] does not).
 pub fn copy_file<P, T>(
     source_file_path: P,
     target_file_path: T,
     options: FileCopyOptions,
 ) -> Result<u64, FileError>
+where
+    P: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    copy_file_detailed(source_file_path, target_file_path, options)
+        .map(|finished_copy| finished_copy.bytes_copied)
+}
+
+
+/// Copy a single file from the `source_file_path` to the `target_file_path`.
+///
+/// This function behaves exactly like [`copy_file`], but returns more details about the copy,
+/// such as the number of bytes that were physically written (see [`FinishedFileCopy`]).
+pub fn copy_file_detailed<P, T>(
+    source_file_path: P,
+    target_file_path: T,
+    options: FileCopyOptions,
+) -> Result<FinishedFileCopy, FileError>
 where
     P: AsRef<Path>,
     T: AsRef<Path>,
@@ -76,46 +692,347 @@ where
         source_file_path, ..
     } = validate_source_file_path(source_file_path)?;
 
-    // Ensure the target file path doesn't exist yet
-    // (unless `overwrite_existing` is `true`)
-    // and that it isn't already a directory path.
-    match target_file_path.try_exists() {
-        Ok(exists) => {
-            if exists {
-                // Ensure we don't try to copy the file into itself.
-                let canonicalized_source_path = source_file_path
-                    .canonicalize()
-                    .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
-                let canonicalized_target_path = target_file_path
-                    .canonicalize()
-                    .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+    validate_target_file_parent(target_file_path)?;
 
-                if canonicalized_source_path.eq(&canonicalized_target_path) {
-                    return Err(FileError::SourceAndTargetAreTheSameFile);
-                }
-            }
+    if should_skip_copying_to_target(&source_file_path, target_file_path, &options)? {
+        return Ok(FinishedFileCopy {
+            bytes_copied: 0,
+            bytes_written: 0,
+            was_memory_mapped: false,
+        });
+    }
 
-            if exists && options.skip_existing {
-                return Ok(0);
-            }
+    if options.atomic {
+        return copy_file_atomically_unchecked(&source_file_path, target_file_path, &options)
+            .map_err(|error| classify_read_only_filesystem_error(error, target_file_path));
+    }
 
-            if exists && !options.overwrite_existing {
-                return Err(FileError::AlreadyExists);
+    // Make sure the source file can be opened before the target file is touched: only once the target
+    // has been created or truncated by the copy may it be removed if the copy fails.
+    fs::File::open(&source_file_path)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    // All checks have passed, copy the file.
+    copy_file_contents_unchecked(&source_file_path, target_file_path, &options)
+        .map_err(|error| remove_partially_copied_target_file(target_file_path, error))
+        .map_err(|error| classify_read_only_filesystem_error(error, target_file_path))
+}
+
+
+/// Copy a single file from the `source_file_path` into the `target_directory_path` directory,
+/// naming the copy with the file name returned by `target_file_name`.
+///
+/// `target_file_name` is called with the `source_file_path` (as provided) and must return
+/// a plain file name, such as `report-2024.txt`. Otherwise,
+/// `Err(`[`FileError::InvalidTargetFileName`]`)` is returned.
+///
+/// The target directory must already exist. If it doesn't, `Err(`[`FileError::UnableToAccessTargetFile`]`)`
+/// is returned, and if it exists, but is not a directory, `Err(`[`FileError::TargetParentNotADirectory`]`)`.
+///
+/// Apart from that, this behaves exactly like [`copy_file`] with the `target_directory_path/<target file name>`
+/// target path, including the options and the checks for existing target files.
+pub fn copy_file_as<P, T, F, N>(
+    source_file_path: P,
+    target_directory_path: T,
+    target_file_name: F,
+    options: FileCopyOptions,
+) -> Result<u64, FileError>
+where
+    P: AsRef<Path>,
+    T: AsRef<Path>,
+    F: FnOnce(&Path) -> N,
+    N: AsRef<Path>,
+{
+    let source_file_path = source_file_path.as_ref();
+    let target_directory_path = target_directory_path.as_ref();
+
+    let target_file_name = target_file_name(source_file_path);
+    let target_file_name = target_file_name.as_ref();
+
+    let mut target_file_name_components = target_file_name.components();
+    let is_plain_file_name = matches!(
+        (
+            target_file_name_components.next(),
+            target_file_name_components.next()
+        ),
+        (Some(Component::Normal(_)), None)
+    );
+
+    if !is_plain_file_name {
+        return Err(FileError::InvalidTargetFileName {
+            path: target_file_name.to_path_buf(),
+        });
+    }
+
+    validate_existing_target_directory(target_directory_path)?;
+
+    copy_file(
+        source_file_path,
+        target_directory_path.join(target_file_name),
+        options,
+    )
+}
+
+
+/// Copies `num_bytes` bytes from `source_file`, starting at its offset `start`,
+/// to the current position of `target_file` using the `copy_file_range(2)` system call.
+///
+/// Returns `Ok(None)` if the system call is not supported for these files
+/// (e.g. on older kernels or across filesystems) and nothing has been copied yet,
+/// in which case the caller should fall back to copying in userspace.
+#[cfg(target_os = "linux")]
+fn copy_range_with_system_call(
+    source_file: &fs::File,
+    target_file: &fs::File,
+    start: u64,
+    num_bytes: u64,
+) -> std::io::Result<Option<u64>> {
+    use std::os::unix::io::AsRawFd;
+
+    let mut source_offset = start as libc::loff_t;
+    let mut num_bytes_copied: u64 = 0;
+
+    while num_bytes_copied < num_bytes {
+        let remaining_bytes = (num_bytes - num_bytes_copied).min(isize::MAX as u64) as libc::size_t;
+
+        // SAFETY: both file descriptors are valid for the duration of the call and `source_offset`
+        // is a valid pointer to an offset. A null target offset means the target file's position is used (and updated).
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_copy_file_range,
+                source_file.as_raw_fd(),
+                &mut source_offset as *mut libc::loff_t,
+                target_file.as_raw_fd(),
+                std::ptr::null_mut::<libc::loff_t>(),
+                remaining_bytes,
+                0u32,
+            )
+        };
+
+        if result < 0 {
+            let error = std::io::Error::last_os_error();
+
+            let is_unsupported = matches!(
+                error.raw_os_error(),
+                Some(libc::ENOSYS)
+                    | Some(libc::EXDEV)
+                    | Some(libc::EINVAL)
+                    | Some(libc::EOPNOTSUPP)
+                    | Some(libc::EPERM)
+            );
+
+            if is_unsupported && num_bytes_copied == 0 {
+                return Ok(None);
             }
+
+            return Err(error);
         }
-        Err(error) => return Err(FileError::UnableToAccessTargetFile { error }),
+
+        if result == 0 {
+            // Reached the end of the source file.
+            break;
+        }
+
+        num_bytes_copied += result as u64;
     }
 
-    // All checks have passed, pass the copying onto Rust's standard library.
-    let num_bytes_copied = fs::copy(source_file_path, target_file_path)
+    Ok(Some(num_bytes_copied))
+}
+
+
+/// Copies `len` bytes (or everything up to the end of the file, if `len` is `None`)
+/// of the source file, starting at byte `start`, into the (newly created or truncated) target file,
+/// then applies the target file attributes from `options` (see [`apply_target_file_attributes_unchecked`]).
+///
+/// *Warning:* no checks are performed before copying
+/// (e.g. whether source exists or whether target is a directory or already exists).
+fn copy_file_range_with_attributes_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    start: u64,
+    len: Option<u64>,
+    options: &FileCopyOptions,
+) -> Result<u64, FileError> {
+    let num_bytes_copied =
+        copy_file_range_unchecked(source_file_path, target_file_path, start, len)?;
+
+    apply_target_file_attributes_unchecked(source_file_path, target_file_path, options)?;
+
+    Ok(num_bytes_copied)
+}
+
+
+/// Copies `len` bytes (or everything up to the end of the file, if `len` is `None`)
+/// of the source file, starting at byte `start`, into the (newly created or truncated) target file.
+///
+/// *Warning:* no checks are performed before copying
+/// (e.g. whether source exists or whether target is a directory or already exists).
+fn copy_file_range_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    start: u64,
+    len: Option<u64>,
+) -> Result<u64, FileError> {
+    let mut source_file = fs::File::open(source_file_path)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    let source_file_size_in_bytes = source_file
+        .metadata()
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?
+        .len();
+
+    let bytes_until_end_of_file = source_file_size_in_bytes.saturating_sub(start);
+    let num_bytes_to_copy = match len {
+        Some(len) => len.min(bytes_until_end_of_file),
+        None => bytes_until_end_of_file,
+    };
+
+    let mut target_file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(target_file_path)
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    #[cfg(target_os = "linux")]
+    {
+        let system_call_result = copy_range_with_system_call(
+            &source_file,
+            &target_file,
+            start,
+            num_bytes_to_copy,
+        )
         .map_err(|error| FileError::OtherIoError { error })?;
 
+        if let Some(num_bytes_copied) = system_call_result {
+            return Ok(num_bytes_copied);
+        }
+    }
+
+    source_file
+        .seek(SeekFrom::Start(start))
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    std::io::copy(
+        &mut (&mut source_file).take(num_bytes_to_copy),
+        &mut target_file,
+    )
+    .map_err(|error| FileError::OtherIoError { error })
+}
+
+
+/// Copy a byte range of the file at `source_file_path` to the `target_file_path`.
+///
+/// The range starts at byte `start` of the source file and is `len` bytes long
+/// (or reaches to the end of the source file, if `len` is `None`). The target file
+/// will contain only the copied range. Returns the number of bytes actually copied,
+/// which can be less than `len` if the source file ends before the range does
+/// (and is `0` if `start` is past the end of the source file).
+///
+/// The target path must be the actual target file path and cannot be a directory.
+///
+/// ## Options
+/// The following options are respected in the same way as in [`copy_file`]:
+/// - `options.overwrite_existing`, `options.skip_existing` and `options.overwrite_only_if_source_is_newer`
+///   control what happens if the target file exists,
+/// - if `options.atomic` is `true`, the range is first copied into a temporary file next to the target
+///   and then renamed into place,
+/// - `options.preserve_ownership`, `options.preserve_creation_time` and `options.target_permissions`
+///   are applied to the target file once the range has been copied.
+///
+/// The remaining options only apply to copying whole files and are **ignored** here:
+/// `options.sparse`, `options.copy_method` and `options.preserve_alternate_data_streams`.
+/// Note that, unlike [`copy_file`], the permissions of the source file are not copied.
+///
+/// ## Partial copies
+/// If an error occurs while copying the range, the partially written target file is removed
+/// before the error is returned, as in [`copy_file`].
+///
+/// ## Symbolic links
+/// If `source_file_path` is a symbolic link to a file, the contents of the file it points to will be copied to `target_file_path`.
+///
+/// ## Internals
+/// On Linux, this function uses the `copy_file_range(2)` system call when the kernel and filesystems support it,
+/// and otherwise falls back to copying through userspace.
+pub fn copy_file_range<P, T>(
+    source_file_path: P,
+    target_file_path: T,
+    start: u64,
+    len: Option<u64>,
+    options: FileCopyOptions,
+) -> Result<u64, FileError>
+where
+    P: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    let source_file_path = source_file_path.as_ref();
+    let target_file_path = target_file_path.as_ref();
+
+    let ValidatedSourceFilePath {
+        source_file_path, ..
+    } = validate_source_file_path(source_file_path)?;
+
+    validate_target_file_parent(target_file_path)?;
+
+    if should_skip_copying_to_target(&source_file_path, target_file_path, &options)? {
+        return Ok(0);
+    }
+
+    if !options.atomic {
+        // Make sure the source file can be opened before the target file is touched: only once the target
+        // has been created or truncated by the copy may it be removed if the copy fails.
+        fs::File::open(&source_file_path)
+            .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+        return copy_file_range_with_attributes_unchecked(
+            &source_file_path,
+            target_file_path,
+            start,
+            len,
+            &options,
+        )
+        .map_err(|error| remove_partially_copied_target_file(target_file_path, error))
+        .map_err(|error| classify_read_only_filesystem_error(error, target_file_path));
+    }
+
+    let temporary_file_path = create_temporary_sibling_file(target_file_path)
+        .map_err(|error| classify_read_only_filesystem_error(error, target_file_path))?;
+
+    let num_bytes_copied = match copy_file_range_with_attributes_unchecked(
+        &source_file_path,
+        &temporary_file_path,
+        start,
+        len,
+        &options,
+    ) {
+        Ok(num_bytes_copied) => num_bytes_copied,
+        Err(error) => {
+            let _ = fs::remove_file(&temporary_file_path);
+            return Err(classify_read_only_filesystem_error(
+                error,
+                target_file_path,
+            ));
+        }
+    };
+
+    if let Err(error) = fs::rename(&temporary_file_path, target_file_path) {
+        let _ = fs::remove_file(&temporary_file_path);
+        return Err(classify_read_only_filesystem_error(
+            FileError::UnableToAccessTargetFile { error },
+            target_file_path,
+        ));
+    }
+
     Ok(num_bytes_copied)
 }
 
 
 /// Options that influence the [`copy_file_with_progress`] function.
+///
+/// These contain no closures: the progress handler is the last argument
+/// of [`copy_file_with_progress`].
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FileCopyWithProgressOptions {
     /// Whether to overwrite an existing target file if it exists already.
     ///
@@ -126,15 +1043,50 @@ pub struct FileCopyWithProgressOptions {
     /// This takes precedence over `overwrite_existing`.
     pub skip_existing: bool,
 
-    /// Internal buffer size (for both reading and writing) when copying the file,
-    /// defaults to 64 KiB.
-    pub buffer_size: usize,
+    /// How large the internal buffer (for both reading and writing) used when copying the file is.
+    ///
+    /// Defaults to a [fixed][BufferStrategy::Fixed] buffer of [`DEFAULT_COPY_BUFFER_SIZE`] bytes (64 KiB).
+    pub buffer_strategy: BufferStrategy,
 
     /// *Minimum* amount of bytes written between two consecutive progress reports.
     /// Defaults to 64 KiB.
     ///
     /// *Note that the interval can be larger.*
     pub progress_update_byte_interval: u64,
+
+    /// Whether to reserve space for the entire target file before writing to it,
+    /// which can reduce fragmentation of large files.
+    ///
+    /// Pre-allocation is currently only performed on Linux (using `fallocate`). If it fails
+    /// (e.g. because the filesystem doesn't support it), the file is simply written as usual.
+    ///
+    /// Defaults to `false`.
+    pub preallocate_target: bool,
+
+    /// Whether to bypass the operating system's page cache when reading and writing,
+    /// so that copying huge files (e.g. backups) doesn't evict more useful data from the cache.
+    ///
+    /// Files are opened with `O_DIRECT` on Linux and `FILE_FLAG_NO_BUFFERING` on Windows.
+    /// The alignment this requires is handled internally: the buffer size (see [`buffer_strategy`][Self::buffer_strategy]) is rounded up
+    /// to a multiple of 4 KiB, and if the file size is not a multiple of that, the final partial block
+    /// is written normally. If the filesystem doesn't support unbuffered IO, the file is copied normally.
+    ///
+    /// This has no effect on other platforms. If enabled, [`preallocate_target`][Self::preallocate_target]
+    /// is ignored.
+    ///
+    /// Defaults to `false`.
+    pub unbuffered: bool,
+
+    /// Whether to fail the copy if the source file was modified while it was being copied
+    /// (e.g. a log file that is being appended to).
+    ///
+    /// The size and modification time of the source file are recorded before copying and checked
+    /// again afterwards. If either of them changed, the (possibly inconsistent) target file is removed
+    /// and `Err(`[`FileError::SourceModifiedDuringCopy`]`)` is returned.
+    /// On platforms that don't provide modification times, only the size is compared.
+    ///
+    /// Defaults to `false`.
+    pub detect_source_modification: bool,
 }
 
 impl Default for FileCopyWithProgressOptions {
@@ -142,15 +1094,69 @@ impl Default for FileCopyWithProgressOptions {
         Self {
             overwrite_existing: false,
             skip_existing: false,
-            // 64 KiB
-            buffer_size: 1024 * 64,
-            // 64 KiB
-            progress_update_byte_interval: 1024 * 64,
+            preallocate_target: false,
+            unbuffered: false,
+            detect_source_modification: false,
+            buffer_strategy: BufferStrategy::default(),
+            progress_update_byte_interval: DEFAULT_COPY_BUFFER_SIZE as u64,
         }
     }
 }
 
 
+/// Determines the size of the buffer used when copying a file with progress,
+/// see [`FileCopyWithProgressOptions::buffer_strategy`].
+///
+/// Large buffers mean fewer system calls and are faster for big files, but allocating them
+/// is pure overhead when copying many small files. [`Adaptive`][BufferStrategy::Adaptive]
+/// picks a buffer size based on the size of each file to get the best of both.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum BufferStrategy {
+    /// Always uses a buffer of the given size (in bytes).
+    Fixed(usize),
+
+    /// Uses a buffer of `small` bytes for files smaller than `threshold` bytes,
+    /// and a buffer of `large` bytes for all other files.
+    Adaptive {
+        /// Buffer size (in bytes) for files smaller than `threshold`.
+        small: usize,
+
+        /// Buffer size (in bytes) for files of at least `threshold` bytes.
+        large: usize,
+
+        /// Size of the source file (in bytes) from which on the `large` buffer is used.
+        threshold: u64,
+    },
+}
+
+impl BufferStrategy {
+    /// Returns the size of the buffer (in bytes) to use when copying a file of `file_size_bytes` bytes.
+    pub fn buffer_size_for(&self, file_size_bytes: u64) -> usize {
+        match *self {
+            BufferStrategy::Fixed(buffer_size) => buffer_size,
+            BufferStrategy::Adaptive {
+                small,
+                large,
+                threshold,
+            } => {
+                if file_size_bytes < threshold {
+                    small
+                } else {
+                    large
+                }
+            }
+        }
+    }
+}
+
+impl Default for BufferStrategy {
+    fn default() -> Self {
+        Self::Fixed(DEFAULT_COPY_BUFFER_SIZE)
+    }
+}
+
+
 /// Copies the specified file from the source to the target with the specified options.
 ///
 /// This is done by opening both files (one for reading, another for writing) and wrapping
@@ -168,9 +1174,75 @@ pub(crate) fn copy_file_with_progress_unchecked<F>(
 where
     F: FnMut(&FileProgress),
 {
-    let bytes_total = fs::metadata(source_file_path)
-        .map_err(|error| FileError::OtherIoError { error })?
-        .len();
+    let source_metadata =
+        fs::metadata(source_file_path).map_err(|error| FileError::OtherIoError { error })?;
+
+    let bytes_copied = copy_file_contents_with_progress_unchecked(
+        source_file_path,
+        target_file_path,
+        source_metadata.len(),
+        options,
+        progress_handler,
+    )?;
+
+    if options.detect_source_modification {
+        ensure_source_file_unmodified(source_file_path, &source_metadata)
+            .map_err(|error| remove_partially_copied_target_file(target_file_path, error))?;
+    }
+
+    Ok(bytes_copied)
+}
+
+
+/// Returns `Err(`[`FileError::SourceModifiedDuringCopy`]`)` if the size or modification time
+/// of the file at `source_file_path` differ from `original_metadata`,
+/// see [`FileCopyWithProgressOptions::detect_source_modification`].
+fn ensure_source_file_unmodified(
+    source_file_path: &Path,
+    original_metadata: &Metadata,
+) -> Result<(), FileError> {
+    let current_metadata = fs::metadata(source_file_path)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    // If modification times aren't available on this platform, both of these are `None`.
+    let was_modified = current_metadata.len() != original_metadata.len()
+        || current_metadata.modified().ok() != original_metadata.modified().ok();
+
+    if was_modified {
+        return Err(FileError::SourceModifiedDuringCopy);
+    }
+
+    Ok(())
+}
+
+
+/// Copies the contents of the file at `source_file_path` (which is `bytes_total` bytes large)
+/// to `target_file_path`, reporting progress along the way.
+///
+/// For more details, see [`copy_file_with_progress_unchecked`].
+fn copy_file_contents_with_progress_unchecked<F>(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    bytes_total: u64,
+    options: FileCopyWithProgressOptions,
+    progress_handler: F,
+) -> Result<u64, FileError>
+where
+    F: FnMut(&FileProgress),
+{
+    #[cfg(any(target_os = "linux", windows))]
+    {
+        if options.unbuffered {
+            return super::unbuffered::copy_file_unbuffered_with_progress_unchecked(
+                source_file_path,
+                target_file_path,
+                bytes_total,
+                options,
+                progress_handler,
+            )
+            .map_err(|error| remove_partially_copied_target_file(target_file_path, error));
+        }
+    }
 
     // Open a file for reading and a file for writing,
     // wrap them in buffers and progress monitors, then copy the file.
@@ -179,7 +1251,10 @@ where
         .open(source_file_path)
         .map_err(|error| FileError::OtherIoError { error })?;
 
-    let mut input_file_buffered = BufReader::with_capacity(options.buffer_size, input_file);
+    let input_file_buffered = BufReader::with_capacity(
+        options.buffer_strategy.buffer_size_for(bytes_total),
+        input_file,
+    );
 
 
     let output_file = fs::OpenOptions::new()
@@ -189,14 +1264,76 @@ where
         .open(target_file_path)
         .map_err(|error| FileError::OtherIoError { error })?;
 
+    if options.preallocate_target {
+        // Pre-allocation is only an optimization, so we fall back to normal writes if it fails.
+        let _ = preallocate_file(&output_file, bytes_total);
+    }
+
+    write_file_with_progress(
+        input_file_buffered,
+        output_file,
+        bytes_total,
+        options,
+        progress_handler,
+    )
+    .map_err(|error| remove_partially_copied_target_file(target_file_path, error))
+}
+
+
+/// Reserves `length` bytes of disk space for the given (empty) file without changing its size.
+#[cfg(target_os = "linux")]
+fn preallocate_file(file: &fs::File, length: u64) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    if length == 0 {
+        return Ok(());
+    }
+
+    // SAFETY: the file descriptor is valid for the duration of the call.
+    let result = unsafe {
+        libc::fallocate(
+            file.as_raw_fd(),
+            libc::FALLOC_FL_KEEP_SIZE,
+            0,
+            length as libc::off_t,
+        )
+    };
+
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn preallocate_file(_file: &fs::File, _length: u64) -> std::io::Result<()> {
+    Ok(())
+}
+
+
+/// Copies the contents of the opened `input_file_buffered` into `output_file`, reporting progress along the way.
+///
+/// For more details, see [`copy_file_with_progress_unchecked`].
+fn write_file_with_progress<F>(
+    mut input_file_buffered: BufReader<fs::File>,
+    output_file: fs::File,
+    bytes_total: u64,
+    options: FileCopyWithProgressOptions,
+    progress_handler: F,
+) -> Result<u64, FileError>
+where
+    F: FnMut(&FileProgress),
+{
     let output_file_progress_monitored = ProgressWriter::new(
         output_file,
         progress_handler,
         options.progress_update_byte_interval,
         bytes_total,
     );
+    // The writer uses the same buffer size as the reader (see `BufferStrategy`).
     let mut output_file_buffered = BufWriter::with_capacity(
-        options.buffer_size,
+        input_file_buffered.capacity(),
         output_file_progress_monitored,
     );
 
@@ -260,10 +1397,15 @@ where
 /// If `source_file_path` is a symbolic link to a file, the contents of the file it points to will be copied to `target_file_path`
 /// (same behaviour as `cp` without `-P` on Unix).
 ///
+/// ## Partial copies
+/// If an error occurs while copying the file contents, the partially written target file is removed
+/// before the error is returned. Note that this includes a pre-existing target file
+/// that was being overwritten ([`options.overwrite_existing`][FileCopyWithProgressOptions::overwrite_existing]).
+///
 ///
 /// ## Internals
 /// This function handles copying itself by opening handles of both files itself
-/// and buffering reads and writes (see the [`option.buffer_size`][FileCopyWithProgressOptions::buffer_size] option).
+/// and buffering reads and writes (see the [`option.buffer_strategy`][FileCopyWithProgressOptions::buffer_strategy] option).
 pub fn copy_file_with_progress<P, T, F>(
     source_file_path: P,
     target_file_path: T,
@@ -282,23 +1424,16 @@ where
         source_file_path, ..
     } = validate_source_file_path(source_file_path)?;
 
+    validate_target_file_parent(target_file_path)?;
+
     // Ensure the target file path doesn't exist yet
     // (unless `overwrite_existing` is `true`)
     // and that it isn't already a directory path.
     match target_file_path.try_exists() {
         Ok(exists) => {
-            if exists {
-                // Ensure we don't try to copy the file into itself.
-                let canonicalized_source_path = source_file_path
-                    .canonicalize()
-                    .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
-                let canonicalized_target_path = target_file_path
-                    .canonicalize()
-                    .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
-
-                if canonicalized_source_path.eq(&canonicalized_target_path) {
-                    return Err(FileError::SourceAndTargetAreTheSameFile);
-                }
+            // Ensure we don't try to copy the file into itself.
+            if exists && is_same_file(&source_file_path, target_file_path)? {
+                return Err(FileError::SourceAndTargetAreTheSameFile);
             }
 
             if exists && options.skip_existing {
@@ -322,4 +1457,5 @@ where
         options,
         progress_handler,
     )
+    .map_err(|error| classify_read_only_filesystem_error(error, target_file_path))
 }
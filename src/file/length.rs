@@ -0,0 +1,77 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use super::{validate_source_file_path, ValidatedSourceFilePath};
+use crate::error::FileError;
+
+
+/// Describes how the length of a file was changed by [`set_file_length`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FileLengthChange {
+    /// The file already had the requested length.
+    Unchanged,
+
+    /// The file was extended with zero bytes.
+    Grew {
+        /// Length of the file, in bytes, before it was extended.
+        previous_length: u64,
+    },
+
+    /// The file was truncated.
+    Shrank {
+        /// Length of the file, in bytes, before it was truncated.
+        previous_length: u64,
+    },
+}
+
+
+/// Set the length of the file at `file_path` to exactly `length` bytes.
+///
+/// If the file is longer than `length`, it is truncated. If it is shorter,
+/// it is extended with zero bytes (on most filesystems, the extended part will not take up any space).
+///
+/// `file_path` must point to an existing file, otherwise
+/// `Err(`[`FileError::NotFound`]`)` or `Err(`[`FileError::NotAFile`]`)` is returned.
+///
+/// ## Symbolic links
+/// If `file_path` is a symbolic link to a file, the length of the file it points to is changed.
+///
+/// ### Return value
+/// Upon success, the function returns whether the file grew, shrank, or stayed the same,
+/// see [`FileLengthChange`].
+pub fn set_file_length<P>(file_path: P, length: u64) -> Result<FileLengthChange, FileError>
+where
+    P: AsRef<Path>,
+{
+    let ValidatedSourceFilePath {
+        source_file_path: file_path,
+        ..
+    } = validate_source_file_path(file_path.as_ref())?;
+
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .open(&file_path)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    let previous_length = file
+        .metadata()
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?
+        .len();
+
+    if previous_length == length {
+        return Ok(FileLengthChange::Unchanged);
+    }
+
+    file.set_len(length)
+        .map_err(|error| FileError::OtherIoError { error })?;
+
+    if length > previous_length {
+        Ok(FileLengthChange::Grew { previous_length })
+    } else {
+        Ok(FileLengthChange::Shrank { previous_length })
+    }
+}
@@ -0,0 +1,590 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use super::{validate_source_file_path, ValidatedSourceFilePath};
+use crate::error::FileError;
+
+
+/// Specifies whose metadata is copied when the source path of [`copy_metadata`] is a symbolic link.
+///
+/// ## Defaults
+/// [`Default`] is implemented for this enum. The default value is [`MetadataSource::Target`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum MetadataSource {
+    /// Indicates the metadata of the file the symbolic link points to should be copied.
+    #[default]
+    Target,
+
+    /// Indicates the timestamps of the symbolic link itself should be copied.
+    ///
+    /// Permissions and extended attributes are still copied from the file the symbolic link points to,
+    /// as symbolic links don't carry meaningful ones of their own on most platforms.
+    Link,
+}
+
+
+/// Options that influence the [`copy_metadata`] function.
+///
+/// Each field enables copying one class of metadata from the source file onto the target file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MetadataCopyOptions {
+    /// Whether to copy the permissions of the source file
+    /// (the mode bits on Unix, the read-only attribute on Windows).
+    ///
+    /// Defaults to `true`.
+    pub permissions: bool,
+
+    /// Whether to copy the last access and last modification time of the source file.
+    ///
+    /// Defaults to `true`.
+    pub timestamps: bool,
+
+    /// Whether to copy the extended attributes of the source file.
+    ///
+    /// Attributes in privileged namespaces (i.e. anything other than `user.*`, such as `security.*`)
+    /// that we aren't permitted to set on the target are skipped.
+    ///
+    /// This is currently only supported on Linux and has no effect on other platforms.
+    ///
+    /// Defaults to `false`, as not every filesystem supports extended attributes.
+    pub extended_attributes: bool,
+
+    /// If the source path is a symbolic link, specifies whether the timestamps are copied
+    /// from the symbolic link itself or from the file it points to.
+    ///
+    /// This has no effect if the source path is not a symbolic link or if `timestamps` is disabled.
+    ///
+    /// Defaults to [`MetadataSource::Target`].
+    pub symlink_metadata_source: MetadataSource,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for MetadataCopyOptions {
+    fn default() -> Self {
+        Self {
+            permissions: true,
+            timestamps: true,
+            extended_attributes: false,
+            symlink_metadata_source: MetadataSource::default(),
+        }
+    }
+}
+
+
+/// Copies the selected metadata (see [`MetadataCopyOptions`]) from the file at `source_file_path`
+/// onto the existing file at `target_file_path`, without touching the contents of either file.
+///
+/// This composes with a plain content copy (e.g. [`copy_file`][super::copy_file]),
+/// which is useful when contents and metadata are synchronized separately.
+///
+/// `source_file_path` must point to an existing file, otherwise `Err(`[`FileError::NotFound`]`)`
+/// or `Err(`[`FileError::NotAFile`]`)` is returned. `target_file_path` must point to an existing file
+/// as well, otherwise `Err(`[`FileError::UnableToAccessTargetFile`]`)` is returned.
+///
+/// ## Symbolic links
+/// If either path is a symbolic link to a file, the metadata of the file it points to is read or modified,
+/// unless [`symlink_metadata_source`][MetadataCopyOptions::symlink_metadata_source] is set to
+/// [`MetadataSource::Link`], in which case the timestamps are read from the source symbolic link itself.
+///
+/// Permissions are applied last, so that a read-only source file doesn't prevent
+/// the other metadata from being copied.
+pub fn copy_metadata<S, T>(
+    source_file_path: S,
+    target_file_path: T,
+    options: MetadataCopyOptions,
+) -> Result<(), FileError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    let original_source_file_path = source_file_path.as_ref();
+
+    let ValidatedSourceFilePath {
+        source_file_path,
+        original_was_symlink_to_file,
+    } = validate_source_file_path(original_source_file_path)?;
+
+    let target_file_path = target_file_path.as_ref();
+
+    let target_metadata = fs::metadata(target_file_path)
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    if !target_metadata.is_file() {
+        return Err(FileError::UnableToAccessTargetFile {
+            error: std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "target path is not a file",
+            ),
+        });
+    }
+
+    let source_metadata = fs::metadata(&source_file_path)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    if options.extended_attributes {
+        copy_extended_attributes(&source_file_path, target_file_path)?;
+    }
+
+    if options.timestamps {
+        let link_metadata = if original_was_symlink_to_file
+            && options.symlink_metadata_source == MetadataSource::Link
+        {
+            Some(
+                fs::symlink_metadata(original_source_file_path)
+                    .map_err(|error| FileError::UnableToAccessSourceFile { error })?,
+            )
+        } else {
+            None
+        };
+
+        copy_timestamps(
+            link_metadata.as_ref().unwrap_or(&source_metadata),
+            target_file_path,
+        )
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+    }
+
+    if options.permissions {
+        fs::set_permissions(target_file_path, source_metadata.permissions())
+            .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+    }
+
+    Ok(())
+}
+
+
+/// Sets the last access and last modification time of the file at `target_file_path`
+/// to the ones in `source_metadata`.
+fn copy_timestamps(
+    source_metadata: &std::fs::Metadata,
+    target_file_path: &Path,
+) -> std::io::Result<()> {
+    set_file_times(
+        target_file_path,
+        source_metadata.accessed()?,
+        source_metadata.modified()?,
+    )
+}
+
+
+/// Converts `time` into a `timespec`, as expected by e.g. `utimensat`.
+#[cfg(unix)]
+fn timespec_from_system_time(time: SystemTime) -> libc::timespec {
+    let (seconds, nanoseconds) = match time.duration_since(UNIX_EPOCH) {
+        Ok(since_epoch) => (
+            since_epoch.as_secs() as i64,
+            since_epoch.subsec_nanos() as i64,
+        ),
+        Err(error) => {
+            // Times before the epoch are represented with a negative number of seconds
+            // and a (positive) number of nanoseconds on top of that.
+            let before_epoch = error.duration();
+            let seconds = -(before_epoch.as_secs() as i64);
+
+            match before_epoch.subsec_nanos() {
+                0 => (seconds, 0),
+                nanoseconds => (seconds - 1, 1_000_000_000 - nanoseconds as i64),
+            }
+        }
+    };
+
+    libc::timespec {
+        tv_sec: seconds as libc::time_t,
+        tv_nsec: nanoseconds as _,
+    }
+}
+
+/// Sets the last access and last modification time of the file at `file_path`.
+#[cfg(unix)]
+pub(super) fn set_file_times(
+    file_path: &Path,
+    last_access_time: SystemTime,
+    last_modification_time: SystemTime,
+) -> std::io::Result<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let file_path_c = CString::new(file_path.as_os_str().as_bytes())?;
+
+    let times = [
+        timespec_from_system_time(last_access_time),
+        timespec_from_system_time(last_modification_time),
+    ];
+
+    // SAFETY: the path is a valid NUL-terminated string and `times` contains exactly two timespecs.
+    let result = unsafe {
+        libc::utimensat(
+            libc::AT_FDCWD,
+            file_path_c.as_ptr(),
+            times.as_ptr(),
+            0,
+        )
+    };
+
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// Converts the time to a `FILETIME`, i.e. the number of 100-nanosecond intervals since January 1, 1601 (UTC).
+#[cfg(windows)]
+fn file_time_from_system_time(
+    time: SystemTime,
+) -> std::io::Result<windows_sys::Win32::Foundation::FILETIME> {
+    use std::time::Duration;
+
+    /// Number of seconds between January 1, 1601 and the Unix epoch.
+    const SECONDS_FROM_1601_TO_UNIX_EPOCH: u64 = 11_644_473_600;
+
+    let since_1601 = UNIX_EPOCH
+        .checked_sub(Duration::from_secs(
+            SECONDS_FROM_1601_TO_UNIX_EPOCH,
+        ))
+        .and_then(|windows_epoch| time.duration_since(windows_epoch).ok())
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "time is before January 1, 1601",
+            )
+        })?;
+
+    let intervals = since_1601.as_secs() * 10_000_000 + u64::from(since_1601.subsec_nanos()) / 100;
+
+    Ok(windows_sys::Win32::Foundation::FILETIME {
+        dwLowDateTime: intervals as u32,
+        dwHighDateTime: (intervals >> 32) as u32,
+    })
+}
+
+/// Sets the last access and last modification time of the file at `file_path`.
+#[cfg(windows)]
+pub(super) fn set_file_times(
+    file_path: &Path,
+    last_access_time: SystemTime,
+    last_modification_time: SystemTime,
+) -> std::io::Result<()> {
+    use std::os::windows::{fs::OpenOptionsExt, io::AsRawHandle};
+
+    use windows_sys::Win32::{
+        Foundation::HANDLE,
+        Storage::FileSystem::{SetFileTime, FILE_WRITE_ATTRIBUTES},
+    };
+
+    let file = std::fs::OpenOptions::new()
+        .access_mode(FILE_WRITE_ATTRIBUTES)
+        .open(file_path)?;
+
+    let last_access_time = file_time_from_system_time(last_access_time)?;
+    let last_write_time = file_time_from_system_time(last_modification_time)?;
+
+    // SAFETY: the handle is valid for as long as `file` is alive, and a null
+    // creation time pointer instructs `SetFileTime` to leave the creation time unchanged.
+    let result = unsafe {
+        SetFileTime(
+            file.as_raw_handle() as HANDLE,
+            std::ptr::null(),
+            &last_access_time,
+            &last_write_time,
+        )
+    };
+
+    if result == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// Sets the creation time of the file at `target_file_path` to the one of the file at `source_file_path`
+/// (see [`FileCopyOptions::preserve_creation_time`][super::FileCopyOptions::preserve_creation_time]).
+#[cfg(windows)]
+pub(super) fn copy_creation_time(
+    source_file_path: &Path,
+    target_file_path: &Path,
+) -> std::io::Result<()> {
+    use std::os::windows::{fs::OpenOptionsExt, io::AsRawHandle};
+
+    use windows_sys::Win32::{
+        Foundation::HANDLE,
+        Storage::FileSystem::{SetFileTime, FILE_WRITE_ATTRIBUTES},
+    };
+
+    let creation_time = file_time_from_system_time(fs::metadata(source_file_path)?.created()?)?;
+
+    let file = std::fs::OpenOptions::new()
+        .access_mode(FILE_WRITE_ATTRIBUTES)
+        .open(target_file_path)?;
+
+    // SAFETY: the handle is valid for as long as `file` is alive, and null last access and
+    // last write time pointers instruct `SetFileTime` to leave those times unchanged.
+    let result = unsafe {
+        SetFileTime(
+            file.as_raw_handle() as HANDLE,
+            &creation_time,
+            std::ptr::null(),
+            std::ptr::null(),
+        )
+    };
+
+    if result == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// Sets the creation time of the file at `target_file_path` to the one of the file at `source_file_path`
+/// (see [`FileCopyOptions::preserve_creation_time`][super::FileCopyOptions::preserve_creation_time]).
+#[cfg(target_os = "macos")]
+pub(super) fn copy_creation_time(
+    source_file_path: &Path,
+    target_file_path: &Path,
+) -> std::io::Result<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let mut creation_time = timespec_from_system_time(fs::metadata(source_file_path)?.created()?);
+
+    let target_file_path_c = CString::new(target_file_path.as_os_str().as_bytes())?;
+
+    // SAFETY: `attrlist` is a plain C struct, for which all-zeroes is a valid value.
+    let mut attributes: libc::attrlist = unsafe { std::mem::zeroed() };
+    attributes.bitmapcount = libc::ATTR_BIT_MAP_COUNT;
+    attributes.commonattr = libc::ATTR_CMN_CRTIME;
+
+    // SAFETY: the path is a valid NUL-terminated string, and the attribute buffer
+    // consists of exactly one timespec, as requested by `ATTR_CMN_CRTIME`.
+    let result = unsafe {
+        libc::setattrlist(
+            target_file_path_c.as_ptr(),
+            &mut attributes as *mut libc::attrlist as *mut libc::c_void,
+            &mut creation_time as *mut libc::timespec as *mut libc::c_void,
+            std::mem::size_of::<libc::timespec>(),
+            0,
+        )
+    };
+
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// Creation times can not be set on this platform, so this does nothing
+/// (see [`FileCopyOptions::preserve_creation_time`][super::FileCopyOptions::preserve_creation_time]).
+#[cfg(not(any(windows, target_os = "macos")))]
+pub(super) fn copy_creation_time(
+    _source_file_path: &Path,
+    _target_file_path: &Path,
+) -> std::io::Result<()> {
+    Ok(())
+}
+
+
+/// Copies all extended attributes of the file at `source_file_path` onto the file at `target_file_path`
+/// (see [`MetadataCopyOptions::extended_attributes`]).
+#[cfg(target_os = "linux")]
+fn copy_extended_attributes(
+    source_file_path: &Path,
+    target_file_path: &Path,
+) -> Result<(), FileError> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let source_error = |error: std::io::Error| FileError::UnableToAccessSourceFile { error };
+    let target_error = |error: std::io::Error| FileError::UnableToAccessTargetFile { error };
+
+    let source_file_path_c = CString::new(source_file_path.as_os_str().as_bytes())
+        .map_err(|error| source_error(error.into()))?;
+    let target_file_path_c = CString::new(target_file_path.as_os_str().as_bytes())
+        .map_err(|error| target_error(error.into()))?;
+
+    // The list of attribute names can change between querying its size and reading it,
+    // in which case `ERANGE` is returned and we simply try again.
+    let attribute_names = loop {
+        // SAFETY: the path is a valid NUL-terminated string and a zero size only queries the required size.
+        let names_length = unsafe {
+            libc::listxattr(
+                source_file_path_c.as_ptr(),
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+
+        if names_length < 0 {
+            let error = std::io::Error::last_os_error();
+
+            // The filesystem doesn't support extended attributes, so there is nothing to copy.
+            if error.raw_os_error() == Some(libc::ENOTSUP) {
+                return Ok(());
+            }
+
+            return Err(source_error(error));
+        }
+
+        let mut attribute_names = vec![0u8; names_length as usize];
+
+        // SAFETY: `attribute_names` is valid for writes of its entire length.
+        let names_length = unsafe {
+            libc::listxattr(
+                source_file_path_c.as_ptr(),
+                attribute_names.as_mut_ptr() as *mut _,
+                attribute_names.len(),
+            )
+        };
+
+        if names_length < 0 {
+            let error = std::io::Error::last_os_error();
+
+            if error.raw_os_error() == Some(libc::ERANGE) {
+                continue;
+            }
+
+            return Err(source_error(error));
+        }
+
+        attribute_names.truncate(names_length as usize);
+        break attribute_names;
+    };
+
+    // Names are stored one after another, each terminated by a NUL byte.
+    for attribute_name in attribute_names
+        .split(|byte| *byte == 0)
+        .filter(|name| !name.is_empty())
+    {
+        let attribute_name_c =
+            CString::new(attribute_name).map_err(|error| source_error(error.into()))?;
+
+        let attribute_value = loop {
+            // SAFETY: both strings are valid and NUL-terminated; a zero size only queries the required size.
+            let value_length = unsafe {
+                libc::getxattr(
+                    source_file_path_c.as_ptr(),
+                    attribute_name_c.as_ptr(),
+                    std::ptr::null_mut(),
+                    0,
+                )
+            };
+
+            if value_length < 0 {
+                return Err(source_error(std::io::Error::last_os_error()));
+            }
+
+            let mut attribute_value = vec![0u8; value_length as usize];
+
+            // SAFETY: `attribute_value` is valid for writes of its entire length.
+            let value_length = unsafe {
+                libc::getxattr(
+                    source_file_path_c.as_ptr(),
+                    attribute_name_c.as_ptr(),
+                    attribute_value.as_mut_ptr() as *mut _,
+                    attribute_value.len(),
+                )
+            };
+
+            if value_length < 0 {
+                let error = std::io::Error::last_os_error();
+
+                if error.raw_os_error() == Some(libc::ERANGE) {
+                    continue;
+                }
+
+                return Err(source_error(error));
+            }
+
+            attribute_value.truncate(value_length as usize);
+            break attribute_value;
+        };
+
+        // SAFETY: both strings are valid and NUL-terminated, and `attribute_value` is valid for reads of its entire length.
+        let result = unsafe {
+            libc::setxattr(
+                target_file_path_c.as_ptr(),
+                attribute_name_c.as_ptr(),
+                attribute_value.as_ptr() as *const _,
+                attribute_value.len(),
+                0,
+            )
+        };
+
+        if result != 0 {
+            let error = std::io::Error::last_os_error();
+
+            let is_privileged_attribute = !attribute_name.starts_with(b"user.");
+            let is_permission_error = matches!(
+                error.raw_os_error(),
+                Some(libc::EPERM) | Some(libc::EACCES) | Some(libc::ENOTSUP)
+            );
+
+            if is_privileged_attribute && is_permission_error {
+                continue;
+            }
+
+            return Err(target_error(error));
+        }
+    }
+
+    Ok(())
+}
+
+/// Changes the owner and group of the entry at `target_path` to match the ones of `source_path`
+/// (see e.g. [`FileCopyOptions::preserve_ownership`][super::FileCopyOptions::preserve_ownership]).
+///
+/// Changing the owner usually requires elevated privileges, so this commonly fails with
+/// a [`PermissionDenied`][std::io::ErrorKind::PermissionDenied] error when run as a regular user.
+/// As changing the owner clears the set-user-ID and set-group-ID bits, they are restored afterwards.
+#[cfg(unix)]
+pub(crate) fn copy_ownership(source_path: &Path, target_path: &Path) -> std::io::Result<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt, os::unix::fs::MetadataExt};
+
+    let source_metadata = fs::metadata(source_path)?;
+    let target_permissions = fs::metadata(target_path)?.permissions();
+
+    let target_path_c = CString::new(target_path.as_os_str().as_bytes())?;
+
+    // SAFETY: the path is a valid NUL-terminated string.
+    let result = unsafe {
+        libc::chown(
+            target_path_c.as_ptr(),
+            source_metadata.uid(),
+            source_metadata.gid(),
+        )
+    };
+
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if target_permissions.mode() & 0o6000 != 0 {
+            fs::set_permissions(target_path, target_permissions)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn copy_ownership(_source_path: &Path, _target_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+
+#[cfg(not(target_os = "linux"))]
+fn copy_extended_attributes(
+    _source_file_path: &Path,
+    _target_file_path: &Path,
+) -> Result<(), FileError> {
+    Ok(())
+}
@@ -0,0 +1,124 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::{io::Write, os::unix::io::AsRawFd, path::Path};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use super::FinishedFileCopy;
+use crate::error::FileError;
+
+
+/// A read-only memory mapping of an entire file, which is unmapped when dropped.
+struct MappedFile {
+    pointer: *mut libc::c_void,
+    length: usize,
+}
+
+impl MappedFile {
+    /// Maps the first `length` bytes of the given file into memory.
+    ///
+    /// Returns `None` if the file can't be mapped (e.g. because the filesystem doesn't support it).
+    fn map(file: &fs::File, length: usize) -> Option<Self> {
+        // SAFETY: we request a new private, read-only mapping of a valid file descriptor.
+        let pointer = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                length,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if pointer == libc::MAP_FAILED {
+            return None;
+        }
+
+        // The file is read front to back, which the kernel can use to read ahead more aggressively.
+        // This is only a hint, so a failure is not a problem.
+        // SAFETY: the range is exactly the mapping we just created.
+        unsafe { libc::madvise(pointer, length, libc::MADV_SEQUENTIAL) };
+
+        Some(Self { pointer, length })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        // SAFETY: the mapping is valid, readable and `length` bytes long until `self` is dropped.
+        unsafe { std::slice::from_raw_parts(self.pointer as *const u8, self.length) }
+    }
+}
+
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        // SAFETY: the mapping was created by `mmap` with this exact length and is not used after this.
+        unsafe { libc::munmap(self.pointer, self.length) };
+    }
+}
+
+
+/// Copies the file by mapping the source file into memory and writing the mapped contents to the target file,
+/// then copies the source file's permissions to the target file.
+///
+/// Returns `Ok(None)` if the source file is smaller than `minimum_size_in_bytes`, isn't a regular file,
+/// or can't be mapped into memory, in which case the target file hasn't been touched
+/// and the caller should copy the file some other way.
+///
+/// *Warning:* no checks are performed before copying
+/// (e.g. whether source exists or whether target is a directory or already exists).
+pub(super) fn copy_file_memory_mapped_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    minimum_size_in_bytes: u64,
+) -> Result<Option<FinishedFileCopy>, FileError> {
+    let source_file = fs::File::open(source_file_path)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    let source_file_metadata = source_file
+        .metadata()
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    let source_file_size_in_bytes = source_file_metadata.len();
+
+    // Empty files can't be mapped, and mapping small files isn't worth it.
+    if !source_file_metadata.is_file()
+        || source_file_size_in_bytes == 0
+        || source_file_size_in_bytes < minimum_size_in_bytes
+    {
+        return Ok(None);
+    }
+
+    let mapped_length = match usize::try_from(source_file_size_in_bytes) {
+        Ok(mapped_length) => mapped_length,
+        Err(_) => return Ok(None),
+    };
+
+    let mapped_source_file = match MappedFile::map(&source_file, mapped_length) {
+        Some(mapped_source_file) => mapped_source_file,
+        None => return Ok(None),
+    };
+
+    let mut target_file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(target_file_path)
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    target_file
+        .write_all(mapped_source_file.as_slice())
+        .map_err(|error| FileError::OtherIoError { error })?;
+
+    fs::set_permissions(
+        target_file_path,
+        source_file_metadata.permissions(),
+    )
+    .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    Ok(Some(FinishedFileCopy {
+        bytes_copied: source_file_size_in_bytes,
+        bytes_written: source_file_size_in_bytes,
+        was_memory_mapped: true,
+    }))
+}
@@ -1,4 +1,4 @@
-//! File sizing, copying, moving and removal operations. Includes progress monitoring variants.
+//! File sizing, resizing, copying, moving, swapping and removal operations. Includes progress monitoring variants.
 
 #[cfg(not(feature = "fs-err"))]
 use std::fs;
@@ -7,19 +7,41 @@ use std::path::{Path, PathBuf};
 #[cfg(feature = "fs-err")]
 use fs_err as fs;
 
+mod checksum;
 mod copy;
+mod length;
+mod metadata;
+#[cfg(unix)]
+mod mmap;
 mod r#move;
 mod progress;
+mod read;
 mod remove;
 mod size;
+mod sparse;
+#[cfg(windows)]
+mod streams;
+mod swap;
+mod touch;
+#[cfg(any(target_os = "linux", windows))]
+mod unbuffered;
 
+pub use checksum::*;
 pub use copy::*;
+pub use length::*;
+pub use metadata::*;
 pub use progress::*;
 pub use r#move::*;
+pub use read::*;
 pub use remove::*;
 pub use size::*;
+pub use swap::*;
+pub use touch::*;
 
-use crate::error::FileError;
+use crate::{
+    error::{is_read_only_filesystem_error, FileError},
+    path::{find_non_directory_ancestor, probe, PathKind},
+};
 
 pub(crate) struct ValidatedSourceFilePath {
     pub(crate) source_file_path: PathBuf,
@@ -36,37 +58,81 @@ pub(crate) struct ValidatedSourceFilePath {
 
 /// Given a `&Path`, validate that it exists and is a file.
 /// If the given path is a symlink to a file, the returned path will be a resolved one, i.e. pointing to the real file.
-fn validate_source_file_path(
+pub(crate) fn validate_source_file_path(
     source_file_path: &Path,
 ) -> Result<ValidatedSourceFilePath, FileError> {
-    // Ensure the source file path exists. We use `try_exists`
-    // instead of `exists` to catch permission and other IO errors
-    // as distinct from the `FileError::NotFound` error.
-    match source_file_path.try_exists() {
-        Ok(exists) => {
-            if !exists {
-                return Err(FileError::NotFound);
-            }
-
-            if !source_file_path.is_file() {
-                return Err(FileError::NotAFile);
-            }
-
-            if source_file_path.is_symlink() {
-                let canonicalized_path = fs::canonicalize(source_file_path)
-                    .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
-
-                return Ok(ValidatedSourceFilePath {
-                    source_file_path: canonicalized_path,
-                    original_was_symlink_to_file: true,
-                });
-            }
+    // Ensure the source file path exists. Unlike `exists`, `probe` returns
+    // permission and other IO errors, which we keep distinct from the `FileError::NotFound` error.
+    let path_kind =
+        probe(source_file_path).map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    match path_kind {
+        PathKind::Missing | PathKind::BrokenSymlink => Err(FileError::NotFound),
+        PathKind::File => Ok(ValidatedSourceFilePath {
+            source_file_path: source_file_path.to_path_buf(),
+            original_was_symlink_to_file: false,
+        }),
+        PathKind::SymlinkToFile => {
+            let canonicalized_path = fs::canonicalize(source_file_path)
+                .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
 
             Ok(ValidatedSourceFilePath {
-                source_file_path: source_file_path.to_path_buf(),
-                original_was_symlink_to_file: false,
+                source_file_path: canonicalized_path,
+                original_was_symlink_to_file: true,
             })
         }
-        Err(error) => Err(FileError::UnableToAccessSourceFile { error }),
+        PathKind::Directory | PathKind::SymlinkToDirectory | PathKind::Other => {
+            Err(FileError::NotAFile)
+        }
+    }
+}
+
+
+/// Ensures the target file's parent directory is not a file (or anything else that isn't a directory),
+/// see [`FileError::TargetParentNotADirectory`].
+pub(crate) fn validate_target_file_parent(target_file_path: &Path) -> Result<(), FileError> {
+    match find_non_directory_ancestor(target_file_path) {
+        Some(path) => Err(FileError::TargetParentNotADirectory { path }),
+        None => Ok(()),
+    }
+}
+
+
+/// Ensures the directory a file is being copied or moved into exists and is a directory
+/// (see e.g. [`copy_file_as`]).
+fn validate_existing_target_directory(target_directory_path: &Path) -> Result<(), FileError> {
+    match fs::metadata(target_directory_path) {
+        Ok(metadata) if metadata.is_dir() => Ok(()),
+        Ok(_) => Err(FileError::TargetParentNotADirectory {
+            path: target_directory_path.to_path_buf(),
+        }),
+        Err(error) => {
+            // The target directory path might also be e.g. a path below another file,
+            // which is reported with a more precise error.
+            validate_target_file_parent(target_directory_path)?;
+
+            Err(FileError::UnableToAccessTargetFile { error })
+        }
+    }
+}
+
+
+/// Returns [`FileError::ReadOnlyFilesystem`] instead of the given `error`
+/// if it was caused by `path` residing on a read-only filesystem.
+fn classify_read_only_filesystem_error(error: FileError, path: &Path) -> FileError {
+    let io_error = match &error {
+        FileError::UnableToAccessSourceFile { error }
+        | FileError::UnableToAccessTargetFile { error }
+        | FileError::UnableToCreateTemporaryFile { error }
+        | FileError::OtherIoError { error } => error,
+        _ => return error,
+    };
+
+    if is_read_only_filesystem_error(io_error) {
+        FileError::ReadOnlyFilesystem {
+            path: path.to_path_buf(),
+        }
+    } else {
+        error
     }
 }
@@ -6,14 +6,22 @@ use std::path::Path;
 use fs_err as fs;
 
 use super::{
+    checksum::compute_file_checksum,
+    classify_read_only_filesystem_error,
     copy::copy_file_with_progress_unchecked,
+    validate_existing_target_directory,
     validate_source_file_path,
+    validate_target_file_parent,
+    BufferStrategy,
+    ChecksumAlgorithm,
     FileCopyWithProgressOptions,
     FileProgress,
+    DEFAULT_COPY_BUFFER_SIZE,
 };
 use crate::{
     error::{FileError, FileRemoveError},
     file::ValidatedSourceFilePath,
+    path::is_same_file,
 };
 
 /// Options that influence the [`move_file`] function.
@@ -21,6 +29,19 @@ use crate::{
 pub struct FileMoveOptions {
     /// Whether to allow overwriting the target file if it already exists.
     pub overwrite_existing: bool,
+
+    /// If the file can't simply be renamed and is copied instead, this enables verifying
+    /// the checksum of the copy against the source file before the source file is removed.
+    ///
+    /// If the checksums don't match, the copy is removed, the source file is kept and
+    /// [`FileError::VerificationFailed`] is returned. Defaults to `None` (no verification).
+    pub verify_before_delete: Option<ChecksumAlgorithm>,
+
+    /// Whether to create the target file's parent directory (and any of its missing ancestors)
+    /// if it doesn't exist yet. Defaults to `false`.
+    ///
+    /// For [`move_file_into_directory`], this means creating the target directory.
+    pub create_missing_parent_directories: bool,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -28,11 +49,40 @@ impl Default for FileMoveOptions {
     fn default() -> Self {
         Self {
             overwrite_existing: false,
+            verify_before_delete: None,
+            create_missing_parent_directories: false,
         }
     }
 }
 
 
+/// Compares the checksums of the source file and its copy (the target file).
+///
+/// If the checksums don't match or either of the files can't be read, the target file is removed.
+fn verify_copied_file(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    algorithm: ChecksumAlgorithm,
+) -> Result<(), FileError> {
+    let source_checksum = compute_file_checksum(source_file_path, algorithm)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error });
+    let target_checksum = compute_file_checksum(target_file_path, algorithm)
+        .map_err(|error| FileError::UnableToAccessTargetFile { error });
+
+    let verification_result = match (source_checksum, target_checksum) {
+        (Ok(source_checksum), Ok(target_checksum)) if source_checksum == target_checksum => Ok(()),
+        (Ok(_), Ok(_)) => Err(FileError::VerificationFailed),
+        (Err(error), _) | (_, Err(error)) => Err(error),
+    };
+
+    if verification_result.is_err() {
+        let _ = fs::remove_file(target_file_path);
+    }
+
+    verification_result
+}
+
+
 /// Moves a single file from the `source_file_path` to the `target_file_path`.
 ///
 /// The target path must be the actual target file path and cannot be a directory.
@@ -44,11 +94,19 @@ impl Default for FileMoveOptions {
 /// If `options.overwrite_existing` is `false` and the target file exists, this function will
 /// return `Err` with [`FileError::AlreadyExists`][crate::error::FileError::AlreadyExists].
 ///
+/// If [`options.create_missing_parent_directories`][FileMoveOptions::create_missing_parent_directories]
+/// is `true`, the target file's parent directories are created if they don't exist yet.
+///
 /// ## Symbolic links
 /// If the `source_file_path` is a symbolic link to a file, the contents of the file that the link points to
 /// will be copied to the `target_file_path` and the original `source_file_path` symbolic link will be removed
 /// (i.e. the link destination will be untouched, but we won't preserve the link on the target file).
 ///
+/// If [`options.verify_before_delete`][FileMoveOptions::verify_before_delete] is set and the file
+/// had to be copied, the copy is verified before the source file is removed.
+/// If verification fails, the source file is kept and
+/// `Err(`[`FileError::VerificationFailed`][crate::error::FileError::VerificationFailed]`)` is returned.
+///
 /// ## Internals
 /// This function will first attempt to move the file with [`std::fs::rename`].
 /// If that fails (you can't rename files across filesystems), a copy-and-delete will be performed.
@@ -69,23 +127,16 @@ where
         original_was_symlink_to_file,
     } = validate_source_file_path(source_file_path)?;
 
+    validate_target_file_parent(target_file_path)?;
+
     // Ensure the target file path doesn't exist yet
     // (unless `overwrite_existing` is `true`)
     // and that it isn't already a directory path.
     match target_file_path.try_exists() {
         Ok(exists) => {
-            if exists {
-                // Ensure we don't try to copy the file into itself.
-                let canonicalized_source_path = validated_source_file_path
-                    .canonicalize()
-                    .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
-                let canonicalized_target_path = target_file_path
-                    .canonicalize()
-                    .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
-
-                if canonicalized_source_path.eq(&canonicalized_target_path) {
-                    return Err(FileError::SourceAndTargetAreTheSameFile);
-                }
+            // Ensure we don't try to copy the file into itself.
+            if exists && is_same_file(&validated_source_file_path, target_file_path)? {
+                return Err(FileError::SourceAndTargetAreTheSameFile);
             }
 
             if exists && !options.overwrite_existing {
@@ -95,6 +146,17 @@ where
         Err(error) => return Err(FileError::UnableToAccessTargetFile { error }),
     }
 
+    if options.create_missing_parent_directories {
+        if let Some(target_parent_directory_path) = target_file_path.parent() {
+            fs::create_dir_all(target_parent_directory_path).map_err(|error| {
+                classify_read_only_filesystem_error(
+                    FileError::UnableToAccessTargetFile { error },
+                    target_parent_directory_path,
+                )
+            })?;
+        }
+    }
+
     // All checks have passed. Now we do the following:
     // - if both paths reside on the same filesystem
     //   (as indicated by std::fs::rename succeeding) that's nice (and fast),
@@ -112,8 +174,21 @@ where
         Ok(target_file_path_metadata.len())
     } else {
         // Copy, then delete original.
-        let num_bytes_copied = fs::copy(&validated_source_file_path, target_file_path)
-            .map_err(|error| FileError::OtherIoError { error })?;
+        let num_bytes_copied =
+            fs::copy(&validated_source_file_path, target_file_path).map_err(|error| {
+                classify_read_only_filesystem_error(
+                    FileError::OtherIoError { error },
+                    target_file_path,
+                )
+            })?;
+
+        if let Some(algorithm) = options.verify_before_delete {
+            verify_copied_file(
+                &validated_source_file_path,
+                target_file_path,
+                algorithm,
+            )?;
+        }
 
         let file_path_to_remove = if original_was_symlink_to_file {
             source_file_path
@@ -121,28 +196,77 @@ where
             validated_source_file_path.as_path()
         };
 
-        super::remove_file(file_path_to_remove).map_err(|error| match error {
-            FileRemoveError::NotFound => FileError::NotFound,
-            FileRemoveError::NotAFile => FileError::NotAFile,
-            FileRemoveError::UnableToAccessFile { error } => {
-                FileError::UnableToAccessSourceFile { error }
-            }
-            FileRemoveError::OtherIoError { error } => FileError::OtherIoError { error },
-        })?;
+        super::remove_file(file_path_to_remove)
+            .map_err(|error| match error {
+                FileRemoveError::NotFound => FileError::NotFound,
+                FileRemoveError::NotAFile => FileError::NotAFile,
+                FileRemoveError::UnableToAccessFile { error } => {
+                    FileError::UnableToAccessSourceFile { error }
+                }
+                FileRemoveError::OtherIoError { error } => FileError::OtherIoError { error },
+                // `remove_file` doesn't use the trash, so this can't actually happen.
+                FileRemoveError::TrashUnavailable { reason } => FileError::OtherIoError {
+                    error: std::io::Error::new(std::io::ErrorKind::Other, reason),
+                },
+            })
+            .map_err(|error| classify_read_only_filesystem_error(error, file_path_to_remove))?;
 
         Ok(num_bytes_copied)
     }
 }
 
 
+/// Moves a single file from the `source_file_path` into the `target_directory_path` directory,
+/// keeping its file name (i.e. the file is moved to `target_directory_path/<source file name>`).
+///
+/// Returns the number of bytes moved (i.e. the file size).
+///
+/// If the target directory doesn't exist, it is created if
+/// [`options.create_missing_parent_directories`][FileMoveOptions::create_missing_parent_directories]
+/// is `true`, otherwise `Err(`[`FileError::UnableToAccessTargetFile`]`)` is returned.
+/// If the target directory path exists, but is not a directory,
+/// `Err(`[`FileError::TargetParentNotADirectory`]`)` is returned.
+///
+/// Apart from that, this behaves exactly like [`move_file`], including the options,
+/// symbolic link handling and the fallback to copying when the file can't simply be renamed.
+/// If the `source_file_path` is a symbolic link, the name of the link is used.
+pub fn move_file_into_directory<P, T>(
+    source_file_path: P,
+    target_directory_path: T,
+    options: FileMoveOptions,
+) -> Result<u64, FileError>
+where
+    P: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    let source_file_path = source_file_path.as_ref();
+    let target_directory_path = target_directory_path.as_ref();
+
+    let source_file_name = source_file_path.file_name().ok_or(FileError::NotAFile)?;
+
+    if !options.create_missing_parent_directories {
+        validate_existing_target_directory(target_directory_path)?;
+    }
+
+    move_file(
+        source_file_path,
+        target_directory_path.join(source_file_name),
+        options,
+    )
+}
+
+
 /// Options that influence the [`move_file_with_progress`] function.
+///
+/// The progress handler is passed to [`move_file_with_progress`] separately,
+/// so the same options can be reused for any number of moves.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct FileMoveWithProgressOptions {
     /// Whether to allow overwriting the target file if it already exists.
     pub overwrite_existing: bool,
 
     /// Internal buffer size (for both reading and writing) when copying the file,
-    /// defaults to 64 KiB.
+    /// defaults to [`DEFAULT_COPY_BUFFER_SIZE`] (64 KiB).
     pub buffer_size: usize,
 
     /// *Minimum* amount of bytes written between two consecutive progress reports.
@@ -156,10 +280,8 @@ impl Default for FileMoveWithProgressOptions {
     fn default() -> Self {
         Self {
             overwrite_existing: false,
-            // 64 KiB
-            buffer_size: 1024 * 64,
-            // 64 KiB
-            progress_update_byte_interval: 1024 * 64,
+            buffer_size: DEFAULT_COPY_BUFFER_SIZE,
+            progress_update_byte_interval: DEFAULT_COPY_BUFFER_SIZE as u64,
         }
     }
 }
@@ -213,23 +335,16 @@ where
         original_was_symlink_to_file,
     } = validate_source_file_path(source_file_path)?;
 
+    validate_target_file_parent(target_file_path)?;
+
     // Ensure the target file path doesn't exist yet
     // (unless `overwrite_existing` is `true`)
     // and that it isn't already a directory path.
     match target_file_path.try_exists() {
         Ok(exists) => {
-            if exists {
-                // Ensure we don't try to copy the file into itself.
-                let canonicalized_source_path = validated_source_file_path
-                    .canonicalize()
-                    .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
-                let canonicalized_target_path = target_file_path
-                    .canonicalize()
-                    .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
-
-                if canonicalized_source_path.eq(&canonicalized_target_path) {
-                    return Err(FileError::SourceAndTargetAreTheSameFile);
-                }
+            // Ensure we don't try to copy the file into itself.
+            if exists && is_same_file(&validated_source_file_path, target_file_path)? {
+                return Err(FileError::SourceAndTargetAreTheSameFile);
             }
 
             if exists && !options.overwrite_existing {
@@ -267,11 +382,15 @@ where
             FileCopyWithProgressOptions {
                 overwrite_existing: options.overwrite_existing,
                 skip_existing: false,
-                buffer_size: options.buffer_size,
+                preallocate_target: false,
+                unbuffered: false,
+                detect_source_modification: false,
+                buffer_strategy: BufferStrategy::Fixed(options.buffer_size),
                 progress_update_byte_interval: options.progress_update_byte_interval,
             },
             progress_handler,
-        )?;
+        )
+        .map_err(|error| classify_read_only_filesystem_error(error, target_file_path))?;
 
         let file_path_to_remove = if original_was_symlink_to_file {
             source_file_path
@@ -279,14 +398,20 @@ where
             validated_source_file_path.as_path()
         };
 
-        super::remove_file(file_path_to_remove).map_err(|error| match error {
-            FileRemoveError::NotFound => FileError::NotFound,
-            FileRemoveError::NotAFile => FileError::NotAFile,
-            FileRemoveError::UnableToAccessFile { error } => {
-                FileError::UnableToAccessSourceFile { error }
-            }
-            FileRemoveError::OtherIoError { error } => FileError::OtherIoError { error },
-        })?;
+        super::remove_file(file_path_to_remove)
+            .map_err(|error| match error {
+                FileRemoveError::NotFound => FileError::NotFound,
+                FileRemoveError::NotAFile => FileError::NotAFile,
+                FileRemoveError::UnableToAccessFile { error } => {
+                    FileError::UnableToAccessSourceFile { error }
+                }
+                FileRemoveError::OtherIoError { error } => FileError::OtherIoError { error },
+                // `remove_file` doesn't use the trash, so this can't actually happen.
+                FileRemoveError::TrashUnavailable { reason } => FileError::OtherIoError {
+                    error: std::io::Error::new(std::io::ErrorKind::Other, reason),
+                },
+            })
+            .map_err(|error| classify_read_only_filesystem_error(error, file_path_to_remove))?;
 
         Ok(bytes_written)
     }
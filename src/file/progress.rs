@@ -2,6 +2,7 @@ use std::io::Write;
 
 /// File copying or moving progress.
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FileProgress {
     /// Current number of bytes copied or moved to the destination.
     pub bytes_finished: u64,
@@ -10,6 +11,34 @@ pub struct FileProgress {
     pub bytes_total: u64,
 }
 
+impl FileProgress {
+    /// Returns the completed fraction of the copy or move, between `0.0` and `1.0`.
+    ///
+    /// An empty file (`bytes_total` is `0`) is always considered complete, i.e. this returns `1.0`.
+    /// If the file grew while it was being copied and more bytes than expected have been written,
+    /// this is capped at `1.0` as well.
+    pub fn fraction_complete(&self) -> f64 {
+        if self.bytes_total == 0 {
+            return 1.0;
+        }
+
+        (self.bytes_finished as f64 / self.bytes_total as f64).min(1.0)
+    }
+}
+
+/// Progress is ordered by [`bytes_finished`][FileProgress::bytes_finished], but only
+/// when comparing progress with the same [`bytes_total`][FileProgress::bytes_total]
+/// (e.g. two reports of the same copy). Otherwise, the two are not comparable.
+impl PartialOrd for FileProgress {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self.bytes_total != other.bytes_total {
+            return None;
+        }
+
+        Some(self.bytes_finished.cmp(&other.bytes_finished))
+    }
+}
+
 
 /// A file write progress handler that implements `Write` and just passes data through.
 pub(crate) struct ProgressWriter<W: Write, F: FnMut(&FileProgress)> {
@@ -0,0 +1,65 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::{io::Read, path::Path};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use super::{validate_source_file_path, ValidatedSourceFilePath};
+use crate::error::FileError;
+
+
+/// Reads the entire file at `file_path` into a string, but only if it is at most `max_bytes` long.
+///
+/// This makes it safe to read files whose size is not under your control (e.g. user-provided
+/// configuration files), as at most `max_bytes` bytes are ever held in memory.
+///
+/// If the given path is a symbolic link to a file, the file it points to is read.
+///
+/// ### Errors
+/// - The size of the file is checked before reading it. If it is larger than `max_bytes`,
+///   `Err(`[`FileError::FileTooLarge`]`)` is returned without reading anything.
+///   The same error is returned if the file grows past the limit while it is being read.
+/// - If the contents are not valid UTF-8, `Err(`[`FileError::InvalidUtf8`]`)` is returned.
+/// - If nothing exists at `file_path`, `Err(`[`FileError::NotFound`]`)` is returned,
+///   and if it is not a file, `Err(`[`FileError::NotAFile`]`)` is returned.
+pub fn read_to_string_limited<P>(file_path: P, max_bytes: u64) -> Result<String, FileError>
+where
+    P: AsRef<Path>,
+{
+    let ValidatedSourceFilePath {
+        source_file_path, ..
+    } = validate_source_file_path(file_path.as_ref())?;
+
+    let file = fs::File::open(&source_file_path)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    let file_size_bytes = file
+        .metadata()
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?
+        .len();
+
+    if file_size_bytes > max_bytes {
+        return Err(FileError::FileTooLarge {
+            size: file_size_bytes,
+            limit: max_bytes,
+        });
+    }
+
+    // Reading one byte past the limit tells us whether the file has grown since we checked its size.
+    let mut contents = Vec::with_capacity(file_size_bytes as usize);
+    file.take(max_bytes.saturating_add(1))
+        .read_to_end(&mut contents)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    if contents.len() as u64 > max_bytes {
+        return Err(FileError::FileTooLarge {
+            size: contents.len() as u64,
+            limit: max_bytes,
+        });
+    }
+
+    String::from_utf8(contents).map_err(|error| FileError::InvalidUtf8 {
+        error: error.utf8_error(),
+    })
+}
@@ -40,3 +40,45 @@ where
 
     Ok(())
 }
+
+
+/// Moves a single file to the trash (recycle bin) instead of removing it permanently.
+///
+/// If the file can't be moved to the trash (e.g. because no trash is available),
+/// `Err(`[`FileRemoveError::TrashUnavailable`]`)` is returned and the file is left in place.
+///
+/// ## Symbolic links
+/// If `file_path` is a symbolic link to a file, the link itself is moved to the trash,
+/// not the file it points to.
+///
+/// ## Internals
+/// This function uses the [`trash`](https://docs.rs/trash) crate internally,
+/// which uses the platform's trash facilities (the freedesktop.org trash on Linux and other Unixes,
+/// the Recycle Bin on Windows and the Finder on macOS).
+/// It is only available with the `trash` feature enabled.
+#[cfg(feature = "trash")]
+pub fn trash_file<P>(file_path: P) -> Result<(), FileRemoveError>
+where
+    P: AsRef<Path>,
+{
+    let file_path = file_path.as_ref();
+
+    match file_path.try_exists() {
+        Ok(exists) => {
+            if !exists {
+                return Err(FileRemoveError::NotFound);
+            }
+        }
+        Err(error) => {
+            return Err(FileRemoveError::UnableToAccessFile { error });
+        }
+    }
+
+    if !file_path.is_file() {
+        return Err(FileRemoveError::NotAFile);
+    }
+
+    trash::delete(file_path).map_err(|error| FileRemoveError::TrashUnavailable {
+        reason: error.to_string(),
+    })
+}
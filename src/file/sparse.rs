@@ -0,0 +1,248 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::{
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use super::FinishedFileCopy;
+use crate::error::FileError;
+
+
+/// Size of the blocks that are checked for being entirely zero when punching holes
+/// (see [`copy_file_punching_zero_runs_unchecked`]). This matches the most common filesystem block size.
+const HOLE_BLOCK_SIZE: usize = 4096;
+
+
+/// Opens the source file for reading and creates (or truncates) the target file for writing.
+fn open_source_and_target_files(
+    source_file_path: &Path,
+    target_file_path: &Path,
+) -> Result<(fs::File, fs::File), FileError> {
+    let source_file = fs::File::open(source_file_path)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    let target_file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(target_file_path)
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    Ok((source_file, target_file))
+}
+
+
+/// Returns whether the source file contains any holes, using `lseek` with `SEEK_HOLE`.
+///
+/// Returns `Ok(None)` if the source filesystem doesn't support seeking for holes.
+#[cfg(target_os = "linux")]
+pub(super) fn file_has_holes(source_file_path: &Path) -> Result<Option<bool>, FileError> {
+    use std::os::unix::io::AsRawFd;
+
+    let source_file = fs::File::open(source_file_path)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    let source_file_size_in_bytes = source_file
+        .metadata()
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?
+        .len();
+
+    if source_file_size_in_bytes == 0 {
+        return Ok(Some(false));
+    }
+
+    // SAFETY: the file descriptor is valid for the duration of the call.
+    let first_hole_offset = unsafe { libc::lseek(source_file.as_raw_fd(), 0, libc::SEEK_HOLE) };
+
+    if first_hole_offset < 0 {
+        let error = std::io::Error::last_os_error();
+
+        if matches!(
+            error.raw_os_error(),
+            Some(libc::EINVAL) | Some(libc::EOPNOTSUPP)
+        ) {
+            return Ok(None);
+        }
+
+        return Err(FileError::UnableToAccessSourceFile { error });
+    }
+
+    // There is always an implicit hole at the end of the file.
+    Ok(Some(
+        (first_hole_offset as u64) < source_file_size_in_bytes,
+    ))
+}
+
+
+/// Copies the file while recreating the holes of the source file on the target,
+/// using `lseek` with `SEEK_DATA` and `SEEK_HOLE` to find them.
+/// The source file's permissions are copied as well.
+///
+/// Returns `Ok(None)` if the source filesystem doesn't support seeking for holes,
+/// in which case nothing has been copied yet and the caller should copy the file verbatim instead.
+///
+/// *Warning:* no checks are performed before copying
+/// (e.g. whether source exists or whether target is a directory or already exists).
+#[cfg(target_os = "linux")]
+pub(super) fn copy_file_preserving_holes_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+) -> Result<Option<FinishedFileCopy>, FileError> {
+    use std::os::unix::io::AsRawFd;
+
+    /// Calls `lseek` on the given file, returning `Ok(None)` if there is
+    /// no more data (or no more holes) after `offset`.
+    fn seek_for(file: &fs::File, offset: u64, whence: i32) -> std::io::Result<Option<u64>> {
+        // SAFETY: the file descriptor is valid for the duration of the call.
+        let result = unsafe { libc::lseek(file.as_raw_fd(), offset as libc::off_t, whence) };
+
+        if result < 0 {
+            let error = std::io::Error::last_os_error();
+
+            if error.raw_os_error() == Some(libc::ENXIO) {
+                return Ok(None);
+            }
+
+            return Err(error);
+        }
+
+        Ok(Some(result as u64))
+    }
+
+    let (mut source_file, mut target_file) =
+        open_source_and_target_files(source_file_path, target_file_path)?;
+
+    let source_file_metadata = source_file
+        .metadata()
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+    let source_file_size_in_bytes = source_file_metadata.len();
+
+    let mut offset: u64 = 0;
+    let mut bytes_written: u64 = 0;
+
+    while offset < source_file_size_in_bytes {
+        let data_start = match seek_for(&source_file, offset, libc::SEEK_DATA) {
+            Ok(Some(data_start)) => data_start,
+            Ok(None) => break,
+            Err(error)
+                if offset == 0
+                    && matches!(
+                        error.raw_os_error(),
+                        Some(libc::EINVAL) | Some(libc::EOPNOTSUPP)
+                    ) =>
+            {
+                return Ok(None);
+            }
+            Err(error) => return Err(FileError::UnableToAccessSourceFile { error }),
+        };
+
+        // There is always an implicit hole at the end of the file.
+        let data_end = seek_for(&source_file, data_start, libc::SEEK_HOLE)
+            .map_err(|error| FileError::UnableToAccessSourceFile { error })?
+            .unwrap_or(source_file_size_in_bytes);
+
+        source_file
+            .seek(SeekFrom::Start(data_start))
+            .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+        target_file
+            .seek(SeekFrom::Start(data_start))
+            .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+        bytes_written += std::io::copy(
+            &mut (&mut source_file).take(data_end - data_start),
+            &mut target_file,
+        )
+        .map_err(|error| FileError::OtherIoError { error })?;
+
+        offset = data_end;
+    }
+
+    // Recreates any trailing hole.
+    target_file
+        .set_len(source_file_size_in_bytes)
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    fs::set_permissions(
+        target_file_path,
+        source_file_metadata.permissions(),
+    )
+    .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    Ok(Some(FinishedFileCopy {
+        bytes_copied: source_file_size_in_bytes,
+        bytes_written,
+        was_memory_mapped: false,
+    }))
+}
+
+
+/// Copies the file, skipping over (instead of writing) every block that consists only of zero bytes,
+/// which leaves holes in the target file on filesystems that support them.
+/// The source file's permissions are copied as well.
+///
+/// *Warning:* no checks are performed before copying
+/// (e.g. whether source exists or whether target is a directory or already exists).
+pub(super) fn copy_file_punching_zero_runs_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+) -> Result<FinishedFileCopy, FileError> {
+    let (mut source_file, mut target_file) =
+        open_source_and_target_files(source_file_path, target_file_path)?;
+
+    let mut buffer = vec![0u8; HOLE_BLOCK_SIZE * 16];
+
+    let mut bytes_copied: u64 = 0;
+    let mut bytes_written: u64 = 0;
+
+    loop {
+        let num_bytes_read = match source_file.read(&mut buffer) {
+            Ok(num_bytes_read) => num_bytes_read,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(FileError::UnableToAccessSourceFile { error }),
+        };
+
+        if num_bytes_read == 0 {
+            break;
+        }
+
+        for block in buffer[..num_bytes_read].chunks(HOLE_BLOCK_SIZE) {
+            if block.iter().all(|byte| *byte == 0) {
+                target_file
+                    .seek(SeekFrom::Current(block.len() as i64))
+                    .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+            } else {
+                target_file
+                    .write_all(block)
+                    .map_err(|error| FileError::OtherIoError { error })?;
+
+                bytes_written += block.len() as u64;
+            }
+        }
+
+        bytes_copied += num_bytes_read as u64;
+    }
+
+    // Seeking past the end of the file doesn't extend it, so if the file ends
+    // with a run of zero blocks, we need to set its length explicitly.
+    target_file
+        .set_len(bytes_copied)
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    let source_permissions = source_file
+        .metadata()
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?
+        .permissions();
+
+    fs::set_permissions(target_file_path, source_permissions)
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    Ok(FinishedFileCopy {
+        bytes_copied,
+        bytes_written,
+        was_memory_mapped: false,
+    })
+}
@@ -0,0 +1,141 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::{
+    ffi::{OsStr, OsString},
+    os::windows::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+use windows_sys::Win32::{
+    Foundation::{ERROR_HANDLE_EOF, INVALID_HANDLE_VALUE},
+    Storage::FileSystem::{
+        FindClose,
+        FindFirstStreamW,
+        FindNextStreamW,
+        FindStreamInfoStandard,
+        WIN32_FIND_STREAM_DATA,
+    },
+};
+
+use crate::error::FileError;
+
+
+/// Returns the name of the stream in `find_stream_data`, e.g. `:metadata:$DATA`.
+fn stream_name_from_find_data(find_stream_data: &WIN32_FIND_STREAM_DATA) -> OsString {
+    let name_length = find_stream_data
+        .cStreamName
+        .iter()
+        .position(|character| *character == 0)
+        .unwrap_or(find_stream_data.cStreamName.len());
+
+    OsString::from_wide(&find_stream_data.cStreamName[..name_length])
+}
+
+
+/// Returns the names of all named data streams of the given file (e.g. `:metadata`).
+///
+/// The unnamed main stream (`::$DATA`) is not included.
+fn list_alternate_data_streams(file_path: &Path) -> std::io::Result<Vec<OsString>> {
+    let wide_file_path: Vec<u16> = OsStr::new(file_path)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    // SAFETY: an all-zero `WIN32_FIND_STREAM_DATA` is a valid value.
+    let mut find_stream_data: WIN32_FIND_STREAM_DATA = unsafe { std::mem::zeroed() };
+
+    // SAFETY: `wide_file_path` is NUL-terminated and `find_stream_data` is a valid, writable struct.
+    let find_handle = unsafe {
+        FindFirstStreamW(
+            wide_file_path.as_ptr(),
+            FindStreamInfoStandard,
+            &mut find_stream_data as *mut WIN32_FIND_STREAM_DATA as *mut _,
+            0,
+        )
+    };
+
+    if find_handle == INVALID_HANDLE_VALUE {
+        let error = std::io::Error::last_os_error();
+
+        // The file has no streams at all (e.g. it's on a filesystem without stream support).
+        if error.raw_os_error() == Some(ERROR_HANDLE_EOF as i32) {
+            return Ok(Vec::new());
+        }
+
+        return Err(error);
+    }
+
+    let mut stream_names = Vec::new();
+
+    let result = loop {
+        let stream_name = stream_name_from_find_data(&find_stream_data);
+
+        // Stream names have the form `:<name>:<type>`. We only copy named data streams.
+        if let Some(stream_name) = stream_name
+            .to_str()
+            .and_then(|stream_name| stream_name.strip_suffix(":$DATA"))
+        {
+            if stream_name != ":" {
+                stream_names.push(OsString::from(stream_name));
+            }
+        }
+
+        // SAFETY: `find_handle` is a valid stream search handle.
+        if unsafe {
+            FindNextStreamW(
+                find_handle,
+                &mut find_stream_data as *mut WIN32_FIND_STREAM_DATA as *mut _,
+            )
+        } == 0
+        {
+            let error = std::io::Error::last_os_error();
+
+            if error.raw_os_error() == Some(ERROR_HANDLE_EOF as i32) {
+                break Ok(stream_names);
+            }
+
+            break Err(error);
+        }
+    };
+
+    // SAFETY: `find_handle` is a valid stream search handle and is not used after this.
+    unsafe { FindClose(find_handle) };
+
+    result
+}
+
+
+/// Returns the path of the named stream `stream_name` (e.g. `:metadata`) of the given file.
+fn stream_path(file_path: &Path, stream_name: &OsStr) -> PathBuf {
+    let mut stream_path = file_path.as_os_str().to_os_string();
+    stream_path.push(stream_name);
+
+    PathBuf::from(stream_path)
+}
+
+
+/// Copies all named data streams of the source file onto the target file.
+///
+/// *Warning:* no checks are performed before copying
+/// (e.g. whether source exists or whether target is a directory or already exists).
+pub(super) fn copy_alternate_data_streams_unchecked(
+    source_file_path: &Path,
+    target_file_path: &Path,
+) -> Result<(), FileError> {
+    let stream_names = list_alternate_data_streams(source_file_path)
+        .map_err(|error| FileError::UnableToEnumerateAlternateDataStreams { error })?;
+
+    for stream_name in stream_names {
+        let mut source_stream = fs::File::open(stream_path(source_file_path, &stream_name))
+            .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+        let mut target_stream = fs::File::create(stream_path(target_file_path, &stream_name))
+            .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+        std::io::copy(&mut source_stream, &mut target_stream)
+            .map_err(|error| FileError::OtherIoError { error })?;
+    }
+
+    Ok(())
+}
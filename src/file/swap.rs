@@ -0,0 +1,222 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use super::copy::create_temporary_sibling_file;
+use crate::{
+    error::FileError,
+    path::{is_same_file, probe, PathKind},
+};
+
+
+/// Ensures the given path exists and is a file (or a symbolic link to one).
+///
+/// `access_error` is used to construct the error if the path can't be accessed, so this
+/// can be used to validate both the first and the second file path of [`swap_files`].
+fn validate_file_path_to_swap<E>(file_path: &Path, access_error: E) -> Result<(), FileError>
+where
+    E: FnOnce(std::io::Error) -> FileError,
+{
+    let path_kind = probe(file_path).map_err(access_error)?;
+
+    match path_kind {
+        PathKind::File | PathKind::SymlinkToFile => Ok(()),
+        PathKind::Missing | PathKind::BrokenSymlink => Err(FileError::NotFound),
+        PathKind::Directory | PathKind::SymlinkToDirectory | PathKind::Other => {
+            Err(FileError::NotAFile)
+        }
+    }
+}
+
+
+/// Returns `true` if the given error indicates a rename across filesystems was attempted.
+fn is_cross_filesystem_error(error: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        error.raw_os_error() == Some(libc::EXDEV)
+    }
+
+    #[cfg(windows)]
+    {
+        // ERROR_NOT_SAME_DEVICE
+        error.raw_os_error() == Some(17)
+    }
+}
+
+
+/// Ensures both files are on the same filesystem (i.e. can be renamed onto each other).
+///
+/// This can only be checked up front on Unix, on other platforms the rename itself will fail.
+fn ensure_files_are_on_same_filesystem(
+    first_file_path: &Path,
+    second_file_path: &Path,
+) -> Result<(), FileError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        // We're renaming the directory entries themselves, so symbolic links must not be followed.
+        let first_file_device = fs::symlink_metadata(first_file_path)
+            .map_err(|error| FileError::UnableToAccessSourceFile { error })?
+            .dev();
+        let second_file_device = fs::symlink_metadata(second_file_path)
+            .map_err(|error| FileError::UnableToAccessTargetFile { error })?
+            .dev();
+
+        if first_file_device != second_file_device {
+            return Err(FileError::NotOnSameFilesystem);
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (first_file_path, second_file_path);
+    }
+
+    Ok(())
+}
+
+
+/// Atomically exchanges the two paths using `renameat2` with the `RENAME_EXCHANGE` flag.
+///
+/// Returns `Ok(false)` if the kernel or filesystem doesn't support exchanging paths,
+/// in which case nothing has been changed.
+#[cfg(target_os = "linux")]
+fn exchange_paths_with_system_call(
+    first_file_path: &Path,
+    second_file_path: &Path,
+) -> std::io::Result<bool> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let to_c_string = |path: &Path| {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidInput, error))
+    };
+
+    let first_file_path_c = to_c_string(first_file_path)?;
+    let second_file_path_c = to_c_string(second_file_path)?;
+
+    // SAFETY: both paths are valid NUL-terminated strings for the duration of the call.
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_renameat2,
+            libc::AT_FDCWD,
+            first_file_path_c.as_ptr(),
+            libc::AT_FDCWD,
+            second_file_path_c.as_ptr(),
+            libc::RENAME_EXCHANGE,
+        )
+    };
+
+    if result != 0 {
+        let error = std::io::Error::last_os_error();
+
+        if matches!(
+            error.raw_os_error(),
+            Some(libc::ENOSYS) | Some(libc::EINVAL)
+        ) {
+            return Ok(false);
+        }
+
+        return Err(error);
+    }
+
+    Ok(true)
+}
+
+
+/// Exchanges the two paths with three renames, through a temporary file next to the first file.
+///
+/// If a rename fails, the renames that have already been performed are reverted (as far as possible).
+fn exchange_paths_with_renames(
+    first_file_path: &Path,
+    second_file_path: &Path,
+) -> Result<(), FileError> {
+    let map_rename_error = |error: std::io::Error| {
+        if is_cross_filesystem_error(&error) {
+            FileError::NotOnSameFilesystem
+        } else {
+            FileError::OtherIoError { error }
+        }
+    };
+
+    let temporary_file_path = create_temporary_sibling_file(first_file_path)?;
+
+    if let Err(error) = fs::rename(first_file_path, &temporary_file_path) {
+        let _ = fs::remove_file(&temporary_file_path);
+        return Err(map_rename_error(error));
+    }
+
+    if let Err(error) = fs::rename(second_file_path, first_file_path) {
+        let _ = fs::rename(&temporary_file_path, first_file_path);
+        return Err(map_rename_error(error));
+    }
+
+    if let Err(error) = fs::rename(&temporary_file_path, second_file_path) {
+        let _ = fs::rename(first_file_path, second_file_path);
+        let _ = fs::rename(&temporary_file_path, first_file_path);
+        return Err(map_rename_error(error));
+    }
+
+    Ok(())
+}
+
+
+/// Swaps two existing files, i.e. after this function returns, `first_file_path` contains
+/// what was previously at `second_file_path` and vice versa.
+///
+/// Both files must exist and be on the same filesystem. Otherwise, `Err(`[`FileError::NotFound`]`)`
+/// (if either of the files doesn't exist), `Err(`[`FileError::NotAFile`]`)` (if either of the paths
+/// isn't a file) or `Err(`[`FileError::NotOnSameFilesystem`]`)` is returned. If both paths refer
+/// to the same file (see [`is_same_file`][crate::path::is_same_file]),
+/// `Err(`[`FileError::SourceAndTargetAreTheSameFile`]`)` is returned.
+///
+/// ## Atomicity
+/// On Linux, the files are swapped atomically using `renameat2` with the `RENAME_EXCHANGE` flag,
+/// meaning both paths always exist and there is no moment at which either of them is missing.
+///
+/// On other platforms (or filesystems that don't support `RENAME_EXCHANGE`), the files are swapped
+/// with three renames through a temporary file next to `first_file_path`. In that case,
+/// `first_file_path` briefly does not exist, but if any of the renames fails, the previous ones are reverted.
+///
+/// ## Symbolic links
+/// If either path is a symbolic link to a file, the symbolic link itself is swapped,
+/// not the file it points to.
+pub fn swap_files<A, B>(first_file_path: A, second_file_path: B) -> Result<(), FileError>
+where
+    A: AsRef<Path>,
+    B: AsRef<Path>,
+{
+    let first_file_path = first_file_path.as_ref();
+    let second_file_path = second_file_path.as_ref();
+
+    validate_file_path_to_swap(first_file_path, |error| {
+        FileError::UnableToAccessSourceFile { error }
+    })?;
+    validate_file_path_to_swap(second_file_path, |error| {
+        FileError::UnableToAccessTargetFile { error }
+    })?;
+
+    if is_same_file(first_file_path, second_file_path)? {
+        return Err(FileError::SourceAndTargetAreTheSameFile);
+    }
+
+    ensure_files_are_on_same_filesystem(first_file_path, second_file_path)?;
+
+    #[cfg(target_os = "linux")]
+    {
+        match exchange_paths_with_system_call(first_file_path, second_file_path) {
+            Ok(true) => return Ok(()),
+            Ok(false) => {}
+            Err(error) if is_cross_filesystem_error(&error) => {
+                return Err(FileError::NotOnSameFilesystem);
+            }
+            Err(error) => return Err(FileError::OtherIoError { error }),
+        }
+    }
+
+    exchange_paths_with_renames(first_file_path, second_file_path)
+}
@@ -0,0 +1,106 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::{path::Path, time::SystemTime};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+use super::{metadata::set_file_times, validate_source_file_path, ValidatedSourceFilePath};
+use crate::error::FileError;
+
+
+/// Options that influence the [`touch`] function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TouchOptions {
+    /// Whether to create an empty file if nothing exists at the given path.
+    ///
+    /// If this is `false` and the file doesn't exist, `Err(`[`FileError::NotFound`]`)` is returned.
+    ///
+    /// Defaults to `true`.
+    pub create: bool,
+
+    /// The time to set as the file's last access and last modification time.
+    ///
+    /// `None` means the current time.
+    ///
+    /// Defaults to `None`.
+    pub time: Option<SystemTime>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TouchOptions {
+    fn default() -> Self {
+        Self {
+            create: true,
+            time: None,
+        }
+    }
+}
+
+
+/// Describes what [`touch`] did to the file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TouchOutcome {
+    /// The file didn't exist and was created empty.
+    Created,
+
+    /// The file already existed and its timestamps were updated.
+    Updated,
+}
+
+
+/// Updates the last access and last modification time of the file at `file_path`,
+/// creating an empty file first if it doesn't exist (like the `touch` command does).
+///
+/// See [`TouchOptions`] for whether to create missing files and which time to set.
+///
+/// If `file_path` exists, but is not a file, `Err(`[`FileError::NotAFile`]`)` is returned.
+///
+/// ## Symbolic links
+/// If `file_path` is a symbolic link to a file, the timestamps of the file it points to are updated.
+///
+/// ### Return value
+/// Upon success, the function returns whether the file was created or just updated,
+/// see [`TouchOutcome`].
+pub fn touch<P>(file_path: P, options: TouchOptions) -> Result<TouchOutcome, FileError>
+where
+    P: AsRef<Path>,
+{
+    let file_path = file_path.as_ref();
+    let time = options.time.unwrap_or_else(SystemTime::now);
+
+    let existing_file_path = match validate_source_file_path(file_path) {
+        Ok(ValidatedSourceFilePath {
+            source_file_path, ..
+        }) => source_file_path,
+        Err(FileError::NotFound) if options.create => {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(file_path)
+            {
+                Ok(_) => {
+                    // A freshly created file already has the current time.
+                    if options.time.is_some() {
+                        set_file_times(file_path, time, time)
+                            .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+                    }
+
+                    return Ok(TouchOutcome::Created);
+                }
+                // The file has been created in the meantime, so we just update it.
+                Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => {
+                    file_path.to_path_buf()
+                }
+                Err(error) => return Err(FileError::UnableToAccessSourceFile { error }),
+            }
+        }
+        Err(error) => return Err(error),
+    };
+
+    set_file_times(&existing_file_path, time, time)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    Ok(TouchOutcome::Updated)
+}
@@ -0,0 +1,206 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+#[cfg(all(target_os = "linux", not(feature = "fs-err")))]
+use std::os::unix::fs::OpenOptionsExt;
+#[cfg(all(windows, not(feature = "fs-err")))]
+use std::os::windows::fs::OpenOptionsExt;
+use std::{
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+#[cfg(all(target_os = "linux", feature = "fs-err"))]
+use fs_err::os::unix::fs::OpenOptionsExt;
+#[cfg(all(windows, feature = "fs-err"))]
+use fs_err::os::windows::fs::OpenOptionsExt;
+
+use super::{
+    progress::{FileProgress, ProgressWriter},
+    FileCopyWithProgressOptions,
+};
+use crate::error::FileError;
+
+
+/// Flags that make reads and writes bypass the page cache (`O_DIRECT`).
+#[cfg(target_os = "linux")]
+const UNBUFFERED_OPEN_FLAGS: i32 = libc::O_DIRECT;
+
+/// Flags that make reads and writes bypass the system cache (`FILE_FLAG_NO_BUFFERING`).
+#[cfg(windows)]
+const UNBUFFERED_OPEN_FLAGS: u32 = 0x2000_0000;
+
+/// Alignment of buffer addresses, file offsets and transfer sizes for unbuffered IO.
+///
+/// The actual requirement is the logical block (sector) size of the underlying device,
+/// which is at most 4 KiB on virtually all current hardware.
+const UNBUFFERED_IO_ALIGNMENT: usize = 4096;
+
+
+/// A heap-allocated buffer whose start address is aligned to [`UNBUFFERED_IO_ALIGNMENT`].
+struct AlignedBuffer {
+    storage: Vec<u8>,
+    offset: usize,
+    length: usize,
+}
+
+impl AlignedBuffer {
+    /// Allocates a new zeroed buffer of at least `minimum_length` bytes.
+    /// The length is rounded up to a multiple of [`UNBUFFERED_IO_ALIGNMENT`].
+    fn new(minimum_length: usize) -> Self {
+        let num_blocks =
+            (minimum_length.max(1) + UNBUFFERED_IO_ALIGNMENT - 1) / UNBUFFERED_IO_ALIGNMENT;
+        let length = num_blocks * UNBUFFERED_IO_ALIGNMENT;
+
+        let storage = vec![0; length + UNBUFFERED_IO_ALIGNMENT];
+        let offset = storage.as_ptr().align_offset(UNBUFFERED_IO_ALIGNMENT);
+
+        Self {
+            storage,
+            offset,
+            length,
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.storage[self.offset..self.offset + self.length]
+    }
+}
+
+
+/// Opens the given file for reading, or creates (or truncates) it for writing, bypassing the page cache.
+///
+/// If the filesystem doesn't support unbuffered IO (e.g. `tmpfs` on Linux),
+/// the file is opened normally instead.
+fn open_file_unbuffered(file_path: &Path, for_writing: bool) -> std::io::Result<fs::File> {
+    let open_with_flags = |flags| {
+        let mut open_options = fs::OpenOptions::new();
+
+        if for_writing {
+            open_options.create(true).write(true).truncate(true);
+        } else {
+            open_options.read(true);
+        }
+
+        open_options.custom_flags(flags).open(file_path)
+    };
+
+    match open_with_flags(UNBUFFERED_OPEN_FLAGS) {
+        Err(error) if error.kind() == std::io::ErrorKind::InvalidInput => open_with_flags(0),
+        result => result,
+    }
+}
+
+
+/// Reads from `file` until `buffer` is full or the end of the file is reached,
+/// returning the number of bytes read.
+fn read_until_full(file: &mut fs::File, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut bytes_read = 0;
+
+    while bytes_read < buffer.len() {
+        match file.read(&mut buffer[bytes_read..]) {
+            Ok(0) => break,
+            Ok(num_bytes) => bytes_read += num_bytes,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(bytes_read)
+}
+
+
+/// Copies the file while bypassing the page cache for both reading and writing,
+/// reporting progress along the way (see [`FileCopyWithProgressOptions::unbuffered`]).
+///
+/// Unbuffered IO can only transfer whole aligned blocks, so if the file size is not a multiple
+/// of the block size, the final partial block is written through a normal (buffered) handle.
+///
+/// *Warning:* no checks are performed before copying
+/// (e.g. whether source exists or whether target is a directory or already exists).
+pub(super) fn copy_file_unbuffered_with_progress_unchecked<F>(
+    source_file_path: &Path,
+    target_file_path: &Path,
+    bytes_total: u64,
+    options: FileCopyWithProgressOptions,
+    progress_handler: F,
+) -> Result<u64, FileError>
+where
+    F: FnMut(&FileProgress),
+{
+    let mut source_file = open_file_unbuffered(source_file_path, false)
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?;
+
+    let target_file = open_file_unbuffered(target_file_path, true)
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+    let mut target_file_progress_monitored = ProgressWriter::new(
+        target_file,
+        progress_handler,
+        options.progress_update_byte_interval,
+        bytes_total,
+    );
+
+    let mut buffer = AlignedBuffer::new(options.buffer_strategy.buffer_size_for(bytes_total));
+    let mut bytes_copied: u64 = 0;
+    let mut unaligned_tail = Vec::new();
+
+    loop {
+        let buffer = buffer.as_mut_slice();
+
+        let bytes_read = read_until_full(&mut source_file, buffer)
+            .map_err(|error| FileError::OtherIoError { error })?;
+
+        if bytes_read == 0 {
+            break;
+        }
+
+        // Only a short read at the end of the file can produce a length that isn't block-aligned.
+        let aligned_length = bytes_read - bytes_read % UNBUFFERED_IO_ALIGNMENT;
+
+        target_file_progress_monitored
+            .write_all(&buffer[..aligned_length])
+            .map_err(|error| FileError::OtherIoError { error })?;
+
+        bytes_copied += aligned_length as u64;
+
+        if aligned_length < bytes_read {
+            unaligned_tail.extend_from_slice(&buffer[aligned_length..bytes_read]);
+            break;
+        }
+    }
+
+    let (target_file, mut copy_progress, mut progress_handler) =
+        target_file_progress_monitored.into_inner();
+
+    // The unbuffered handle can't write the final partial block, so we use a normal one for it.
+    drop(target_file);
+
+    if !unaligned_tail.is_empty() {
+        let mut target_file = fs::OpenOptions::new()
+            .write(true)
+            .open(target_file_path)
+            .map_err(|error| FileError::UnableToAccessTargetFile { error })?;
+
+        target_file
+            .seek(SeekFrom::Start(bytes_copied))
+            .map_err(|error| FileError::OtherIoError { error })?;
+
+        target_file
+            .write_all(&unaligned_tail)
+            .map_err(|error| FileError::OtherIoError { error })?;
+
+        target_file
+            .flush()
+            .map_err(|error| FileError::OtherIoError { error })?;
+
+        bytes_copied += unaligned_tail.len() as u64;
+    }
+
+    // Perform one last progress update.
+    copy_progress.bytes_finished = bytes_copied;
+    progress_handler(&copy_progress);
+
+    Ok(bytes_copied)
+}
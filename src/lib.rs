@@ -40,6 +40,9 @@
 //! </details>
 //!
 
+#[cfg(feature = "async")]
+pub mod asynchronous;
 pub mod directory;
 pub mod error;
 pub mod file;
+pub mod path;
@@ -0,0 +1,9 @@
+//! Path inspection utilities.
+
+mod probe;
+mod same_file;
+mod validate;
+
+pub use probe::*;
+pub use same_file::*;
+pub use validate::*;
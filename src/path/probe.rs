@@ -0,0 +1,186 @@
+#[cfg(not(feature = "fs-err"))]
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(feature = "fs-err")]
+use fs_err as fs;
+
+/// Describes what a path points to, see [`probe`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PathKind {
+    /// Nothing exists at the path.
+    Missing,
+
+    /// The path points to a file.
+    File,
+
+    /// The path points to a directory.
+    Directory,
+
+    /// The path is a symbolic link that (possibly through other symbolic links) points to a file.
+    SymlinkToFile,
+
+    /// The path is a symbolic link that (possibly through other symbolic links) points to a directory.
+    SymlinkToDirectory,
+
+    /// The path is a symbolic link that points to a path that does not exist.
+    BrokenSymlink,
+
+    /// The path points to something else, e.g. a FIFO, socket or device node
+    /// (or a symbolic link to one).
+    Other,
+}
+
+
+/// Determines whether `path` is missing, a file, a directory, or a symbolic link to one of those,
+/// without having to query both the symbolic link metadata and the metadata of its destination.
+///
+/// ## Symbolic links
+/// Unlike [`Path::is_file`] and [`Path::is_dir`], this function distinguishes between
+/// files or directories and symbolic links to them (see [`PathKind::SymlinkToFile`] and [`PathKind::SymlinkToDirectory`]).
+/// A symbolic link whose destination does not exist is reported as [`PathKind::BrokenSymlink`].
+///
+/// ### Return value
+/// If nothing exists at `path`, `Ok(`[`PathKind::Missing`]`)` is returned.
+/// Any other IO error (e.g. due to missing permissions) is returned as-is, meaning that, unlike
+/// [`Path::exists`], an inaccessible path is not reported as missing.
+pub fn probe<P>(path: P) -> std::io::Result<PathKind>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+
+    let symlink_metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(PathKind::Missing);
+        }
+        Err(error) => return Err(error),
+    };
+
+    let file_type = symlink_metadata.file_type();
+
+    if file_type.is_file() {
+        return Ok(PathKind::File);
+    } else if file_type.is_dir() {
+        return Ok(PathKind::Directory);
+    } else if !file_type.is_symlink() {
+        return Ok(PathKind::Other);
+    }
+
+    // The path is a symbolic link, which means we need to follow it
+    // (unlike `symlink_metadata`, `metadata` *does* follow symbolic links).
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => Ok(PathKind::SymlinkToFile),
+        Ok(metadata) if metadata.is_dir() => Ok(PathKind::SymlinkToDirectory),
+        Ok(_) => Ok(PathKind::Other),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(PathKind::BrokenSymlink),
+        Err(error) => Err(error),
+    }
+}
+
+
+/// Finds the nearest existing ancestor of `path` and returns it if it is not a directory
+/// (e.g. when `path` is `foo/bar.txt/baz` and `foo/bar.txt` is a file).
+///
+/// Returns `None` if the nearest existing ancestor is a directory (or a symbolic link to one),
+/// or if no ancestor could be accessed at all.
+pub(crate) fn find_non_directory_ancestor(path: &Path) -> Option<PathBuf> {
+    for ancestor in path.ancestors().skip(1) {
+        // An empty parent means the path is relative to the current directory.
+        if ancestor.as_os_str().is_empty() {
+            return None;
+        }
+
+        // Missing (or otherwise inaccessible) ancestors are skipped;
+        // if one of them really is a problem, the operation itself will report it.
+        if let Ok(metadata) = fs::metadata(ancestor) {
+            if metadata.is_dir() {
+                return None;
+            }
+
+            return Some(ancestor.to_path_buf());
+        }
+    }
+
+    None
+}
+
+
+/// Resolves `path` through its nearest existing ancestor: that ancestor is canonicalized
+/// (following any symbolic links) and the remaining, not yet existing components are appended to it.
+///
+/// This makes it possible to compare paths that don't exist yet with canonicalized ones,
+/// e.g. to detect that `link/new-directory` would end up inside some directory
+/// when `link` is a symbolic link pointing into it.
+pub(crate) fn resolve_through_existing_ancestor(path: &Path) -> std::io::Result<PathBuf> {
+    let clean_path = path_clean::clean(path);
+    let mut missing_components = Vec::new();
+
+    for ancestor in clean_path.ancestors() {
+        // An empty ancestor means the path is relative to the current directory.
+        let ancestor_to_resolve = if ancestor.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            ancestor
+        };
+
+        match fs::canonicalize(ancestor_to_resolve) {
+            Ok(canonical_ancestor) => {
+                let mut resolved_path = dunce::simplified(&canonical_ancestor).to_path_buf();
+                resolved_path.extend(missing_components.iter().rev());
+
+                return Ok(resolved_path);
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                if let Some(file_name) = ancestor.file_name() {
+                    missing_components.push(file_name.to_os_string());
+                }
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(clean_path)
+}
+
+
+/// Returns a relative path that leads from `base_directory_path` to `path`, e.g. `../c`
+/// from `/a/b` to `/a/c`.
+///
+/// Both paths must be absolute and clean (without `.` or `..` components), as they are compared lexically.
+/// Returns `None` if either path is relative or they share no common prefix (e.g. different drives on Windows).
+pub(crate) fn relative_path_from(path: &Path, base_directory_path: &Path) -> Option<PathBuf> {
+    if !path.is_absolute() || !base_directory_path.is_absolute() {
+        return None;
+    }
+
+    let path_components: Vec<Component> = path.components().collect();
+    let base_directory_components: Vec<Component> = base_directory_path.components().collect();
+
+    let num_common_components = path_components
+        .iter()
+        .zip(base_directory_components.iter())
+        .take_while(|(path_component, base_directory_component)| {
+            path_component == base_directory_component
+        })
+        .count();
+
+    if num_common_components == 0 {
+        return None;
+    }
+
+    let mut relative_path = PathBuf::new();
+
+    for _ in num_common_components..base_directory_components.len() {
+        relative_path.push("..");
+    }
+
+    relative_path.extend(&path_components[num_common_components..]);
+
+    if relative_path.as_os_str().is_empty() {
+        relative_path.push(".");
+    }
+
+    Some(relative_path)
+}
@@ -0,0 +1,120 @@
+// Only needed on Unix, as the file identity is queried through a file handle on Windows.
+#[cfg(all(unix, not(feature = "fs-err")))]
+use std::fs;
+use std::path::Path;
+
+#[cfg(all(unix, feature = "fs-err"))]
+use fs_err as fs;
+
+use crate::error::FileError;
+
+
+/// Uniquely identifies a file (or directory) on the system for as long as it exists.
+#[derive(PartialEq, Eq)]
+struct FileIdentity {
+    device: u64,
+    index: u64,
+}
+
+/// Returns the identity of the file at `path` (see [`FileIdentity`]), following symbolic links,
+/// or `None` if nothing exists at `path`.
+#[cfg(unix)]
+fn file_identity(path: &Path) -> std::io::Result<Option<FileIdentity>> {
+    use std::os::unix::fs::MetadataExt;
+
+    match fs::metadata(path) {
+        Ok(metadata) => Ok(Some(FileIdentity {
+            device: metadata.dev(),
+            index: metadata.ino(),
+        })),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Returns the identity of the file at `path` (see [`FileIdentity`]), following symbolic links,
+/// or `None` if nothing exists at `path`.
+#[cfg(windows)]
+fn file_identity(path: &Path) -> std::io::Result<Option<FileIdentity>> {
+    use std::os::windows::{fs::OpenOptionsExt, io::AsRawHandle};
+
+    use windows_sys::Win32::{
+        Foundation::HANDLE,
+        Storage::FileSystem::{
+            GetFileInformationByHandle,
+            BY_HANDLE_FILE_INFORMATION,
+            FILE_FLAG_BACKUP_SEMANTICS,
+        },
+    };
+
+    // No access rights are needed to query the file information,
+    // and `FILE_FLAG_BACKUP_SEMANTICS` is required to open directories.
+    let file = match std::fs::OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+    {
+        Ok(file) => file,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+
+    // SAFETY: an all-zero `BY_HANDLE_FILE_INFORMATION` is a valid value.
+    let mut file_information: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+
+    // SAFETY: the handle is valid for as long as `file` is alive
+    // and `file_information` is a valid, writable struct.
+    let result = unsafe {
+        GetFileInformationByHandle(
+            file.as_raw_handle() as HANDLE,
+            &mut file_information,
+        )
+    };
+
+    if result == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(Some(FileIdentity {
+        device: u64::from(file_information.dwVolumeSerialNumber),
+        index: (u64::from(file_information.nFileIndexHigh) << 32)
+            | u64::from(file_information.nFileIndexLow),
+    }))
+}
+
+
+/// Returns `true` if `first_path` and `second_path` refer to the same file (or directory).
+///
+/// Unlike comparing the (canonicalized) paths, this compares the identity of the files themselves:
+/// the device and inode number on Unix, and the volume serial number and file index on Windows.
+/// This means that e.g. hard links to the same file are reported as the same file, as are
+/// differently-cased paths on case-insensitive filesystems.
+///
+/// ## Symbolic links
+/// Symbolic links are followed, i.e. a symbolic link and the file it points to are the same file.
+///
+/// ### Return value
+/// If nothing exists at either of the paths, `Ok(false)` is returned.
+/// If the first path can't be accessed, `Err(`[`FileError::UnableToAccessSourceFile`]`)` is returned,
+/// and if the second path can't be accessed, `Err(`[`FileError::UnableToAccessTargetFile`]`)` is returned.
+pub fn is_same_file<A, B>(first_path: A, second_path: B) -> Result<bool, FileError>
+where
+    A: AsRef<Path>,
+    B: AsRef<Path>,
+{
+    let first_identity = match file_identity(first_path.as_ref())
+        .map_err(|error| FileError::UnableToAccessSourceFile { error })?
+    {
+        Some(identity) => identity,
+        None => return Ok(false),
+    };
+
+    let second_identity = match file_identity(second_path.as_ref())
+        .map_err(|error| FileError::UnableToAccessTargetFile { error })?
+    {
+        Some(identity) => identity,
+        None => return Ok(false),
+    };
+
+    Ok(first_identity == second_identity)
+}
@@ -0,0 +1,173 @@
+use std::path::Path;
+
+use super::{probe, resolve_through_existing_ancestor, PathKind};
+use crate::{
+    directory::{
+        validate_source_directory_path,
+        validate_source_target_directory_pair,
+        validate_target_directory_path,
+        TargetDirectoryRule,
+    },
+    error::{DirectoryError, FileError, PathValidationError},
+    file::{
+        should_skip_copying_to_target,
+        validate_source_file_path,
+        validate_target_file_parent,
+        FileCopyOptions,
+        ValidatedSourceFilePath,
+    },
+};
+
+
+/// Converts an error of the file validators (see [`validate_file_copy_pair`]) into a [`PathValidationError`].
+fn path_validation_error_from_file_error(error: FileError) -> PathValidationError {
+    match error {
+        FileError::NotFound => PathValidationError::SourceNotFound,
+        FileError::NotAFile => PathValidationError::UnsupportedSource,
+        FileError::UnableToAccessSourceFile { error } => {
+            PathValidationError::UnableToAccessSource { error }
+        }
+        FileError::UnableToAccessTargetFile { error } => {
+            PathValidationError::UnableToAccessTarget { error }
+        }
+        FileError::TargetParentNotADirectory { path } => {
+            PathValidationError::TargetParentNotADirectory { path }
+        }
+        FileError::SourceAndTargetAreTheSameFile => PathValidationError::SourceAndTargetAreTheSame,
+        // The validators don't return any other errors.
+        error => PathValidationError::UnableToAccessTarget {
+            error: std::io::Error::new(std::io::ErrorKind::Other, error),
+        },
+    }
+}
+
+
+/// Converts an error of the directory validators (see [`validate_directory_copy_pair`])
+/// into a [`PathValidationError`].
+fn path_validation_error_from_directory_error(error: DirectoryError) -> PathValidationError {
+    match error {
+        DirectoryError::SourceDirectoryNotFound => PathValidationError::SourceNotFound,
+        DirectoryError::SourceDirectoryIsNotADirectory => PathValidationError::UnsupportedSource,
+        DirectoryError::UnableToAccessSource { error } => {
+            PathValidationError::UnableToAccessSource { error }
+        }
+        DirectoryError::UnableToAccessTarget { error } | DirectoryError::OtherIoError { error } => {
+            PathValidationError::UnableToAccessTarget { error }
+        }
+        DirectoryError::TargetParentNotADirectory { path } => {
+            PathValidationError::TargetParentNotADirectory { path }
+        }
+        DirectoryError::InvalidTargetDirectoryPath => PathValidationError::TargetNotADirectory,
+        // The validators don't return any other errors.
+        error => PathValidationError::UnableToAccessTarget {
+            error: std::io::Error::new(std::io::ErrorKind::Other, error),
+        },
+    }
+}
+
+
+/// Validates a file copy pair with the same validators [`copy_file`][crate::file::copy_file] uses.
+fn validate_file_copy_pair(source_path: &Path, target_path: &Path) -> Result<(), FileError> {
+    let ValidatedSourceFilePath {
+        source_file_path, ..
+    } = validate_source_file_path(source_path)?;
+
+    validate_target_file_parent(target_path)?;
+
+    // Existing targets are allowed here, so this only checks whether the target is the source file itself.
+    should_skip_copying_to_target(
+        &source_file_path,
+        target_path,
+        &FileCopyOptions {
+            overwrite_existing: true,
+            ..Default::default()
+        },
+    )?;
+
+    Ok(())
+}
+
+
+/// Validates a directory copy pair with the same validators
+/// [`copy_directory`][crate::directory::copy_directory] uses.
+fn validate_directory_copy_pair(
+    source_path: &Path,
+    target_path: &Path,
+) -> Result<(), PathValidationError> {
+    let source_directory_path = validate_source_directory_path(source_path)
+        .map_err(path_validation_error_from_directory_error)?;
+
+    // Existing targets are allowed here, so this only checks whether the target is a directory.
+    let validated_target_path = validate_target_directory_path(
+        target_path,
+        &TargetDirectoryRule::AllowNonEmpty {
+            overwrite_existing_files: true,
+            overwrite_existing_subdirectories: true,
+        },
+    )
+    .map_err(path_validation_error_from_directory_error)?;
+
+    match validate_source_target_directory_pair(
+        &source_directory_path,
+        &validated_target_path.target_directory_path,
+    ) {
+        Ok(()) => Ok(()),
+        // The pair validator doesn't distinguish between the target being the source directory or inside it.
+        Err(DirectoryError::InvalidTargetDirectoryPath) => {
+            let resolved_target_path = resolve_through_existing_ancestor(target_path)
+                .map_err(|error| PathValidationError::UnableToAccessTarget { error })?;
+
+            if resolved_target_path == source_directory_path {
+                Err(PathValidationError::SourceAndTargetAreTheSame)
+            } else {
+                Err(PathValidationError::TargetInsideSource)
+            }
+        }
+        Err(error) => Err(path_validation_error_from_directory_error(error)),
+    }
+}
+
+
+/// Ensures `source_path` can be copied or moved to `target_path`, performing the same path checks
+/// [`copy_file`][crate::file::copy_file] (if the source is a file) or
+/// [`copy_directory`][crate::directory::copy_directory] (if the source is a directory) perform before copying.
+///
+/// Both paths are resolved before being compared, meaning relative paths and
+/// symbolic links are taken into account. The target path doesn't have to exist.
+///
+/// ### Return value
+/// If the paths are a valid source-target pair, `Ok(())` is returned. Otherwise:
+/// - `Err(`[`PathValidationError::SourceNotFound`]`)` if the source path doesn't exist,
+/// - `Err(`[`PathValidationError::UnsupportedSource`]`)` if the source path is neither a file nor a directory,
+/// - `Err(`[`PathValidationError::SourceAndTargetAreTheSame`]`)` if both paths point to the same file or directory,
+/// - `Err(`[`PathValidationError::TargetInsideSource`]`)` if the source is a directory and the target path is inside of it,
+/// - `Err(`[`PathValidationError::TargetNotADirectory`]`)` if the source is a directory and the target path
+///   exists, but is not a directory,
+/// - `Err(`[`PathValidationError::TargetParentNotADirectory`]`)` if an ancestor of the target path is a file, or,
+/// - `Err(`[`PathValidationError::UnableToAccessSource`]`)` or `Err(`[`PathValidationError::UnableToAccessTarget`]`)`
+///   if either of the paths can't be accessed.
+///
+/// Note that this does not check whether the target already exists, as that is configured
+/// separately for each operation (e.g. [`FileCopyOptions::overwrite_existing`][crate::file::FileCopyOptions::overwrite_existing]).
+pub fn validate_copy_pair<S, T>(source_path: S, target_path: T) -> Result<(), PathValidationError>
+where
+    S: AsRef<Path>,
+    T: AsRef<Path>,
+{
+    let source_path = source_path.as_ref();
+    let target_path = target_path.as_ref();
+
+    let source_path_kind =
+        probe(source_path).map_err(|error| PathValidationError::UnableToAccessSource { error })?;
+
+    match source_path_kind {
+        PathKind::Missing | PathKind::BrokenSymlink => Err(PathValidationError::SourceNotFound),
+        PathKind::Directory | PathKind::SymlinkToDirectory => {
+            validate_directory_copy_pair(source_path, target_path)
+        }
+        PathKind::File | PathKind::SymlinkToFile | PathKind::Other => {
+            validate_file_copy_pair(source_path, target_path)
+                .map_err(path_validation_error_from_file_error)
+        }
+    }
+}
@@ -706,7 +706,7 @@ fn generate_impl(
 /// This attribute macro is a handy way of initializing directory trees for testing `fs-more`.
 ///
 /// ## Example
-/// ```rust
+/// ```ignore
 /// use fs_more_test_harness_macros::fs_harness_tree;
 /// use fs_more_test_harness::assertable::{AssertableRootDirectory, AssertableFilePath, AssertableDirectoryPath};
 ///
@@ -409,7 +409,7 @@ impl AssertableDirectoryPath {
 
         #[cfg(unix)]
         {
-            std::os::unix::fs::symlink(target_path.as_ref(), &self.file_path)
+            std::os::unix::fs::symlink(target_path.as_ref(), &self.directory_path)
                 .map_err(|error| AssertableFilePathError::OtherIoError { error })?;
         }
 
@@ -588,7 +588,7 @@ impl AssertableFilePath {
         let parent_directory = self
             .file_path
             .parent()
-            .ok_or_else(|| AssertableFilePathError::NoParentDirectory)?;
+            .ok_or(AssertableFilePathError::NoParentDirectory)?;
 
         std::fs::create_dir_all(parent_directory)
             .map_err(|error| AssertableFilePathError::OtherIoError { error })
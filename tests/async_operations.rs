@@ -0,0 +1,163 @@
+#![cfg(feature = "async")]
+
+use fs_more::{
+    directory::{DirectoryCopyOptions, DirectoryCopyWithProgressOptions},
+    file::{FileCopyOptions, FileCopyWithProgressOptions, FileMoveOptions},
+};
+use fs_more_test_harness::{
+    assertable::AssertableFilePath,
+    error::TestResult,
+    trees::{DeepTreeHarness, EmptyTreeHarness, SimpleFileHarness},
+};
+
+/// Builds a single-threaded Tokio runtime (which still has a blocking thread pool).
+fn build_runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+}
+
+
+#[test]
+pub fn async_copy_and_move_file() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+    let runtime = build_runtime();
+
+    let copied_file = AssertableFilePath::from_path(
+        harness
+            .test_file
+            .path()
+            .with_file_name("test_file-copy.txt"),
+    );
+    copied_file.assert_not_exists();
+
+    let bytes_copied = runtime
+        .block_on(fs_more::asynchronous::copy_file(
+            harness.test_file.path(),
+            copied_file.path(),
+            FileCopyOptions::default(),
+        ))
+        .unwrap();
+
+    assert_eq!(
+        bytes_copied,
+        harness.test_file.file_size_in_bytes()?
+    );
+    copied_file.assert_content_matches_file(harness.test_file.path());
+
+
+    let moved_file_path = harness
+        .test_file
+        .path()
+        .with_file_name("test_file-moved.txt");
+
+    let bytes_moved = runtime
+        .block_on(fs_more::asynchronous::move_file(
+            copied_file.path(),
+            &moved_file_path,
+            FileMoveOptions::default(),
+        ))
+        .unwrap();
+
+    assert_eq!(bytes_moved, bytes_copied);
+    copied_file.assert_not_exists();
+    assert!(moved_file_path.is_file());
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn async_copy_file_with_progress_forwards_progress_into_channel() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+    let runtime = build_runtime();
+
+    let copied_file_path = harness
+        .test_file
+        .path()
+        .with_file_name("test_file-copy.txt");
+    let (progress_sender, mut progress_receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    let bytes_copied = runtime
+        .block_on(fs_more::asynchronous::copy_file_with_progress(
+            harness.test_file.path(),
+            &copied_file_path,
+            FileCopyWithProgressOptions::default(),
+            move |progress| {
+                progress_sender.send(progress.clone()).unwrap();
+            },
+        ))
+        .unwrap();
+
+    let mut last_progress = None;
+    while let Ok(progress) = progress_receiver.try_recv() {
+        last_progress = Some(progress);
+    }
+
+    let last_progress = last_progress.unwrap();
+    assert_eq!(last_progress.bytes_finished, bytes_copied);
+    assert_eq!(last_progress.bytes_total, bytes_copied);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn async_copy_directory() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+    let runtime = build_runtime();
+
+    let finished_copy = runtime
+        .block_on(fs_more::asynchronous::copy_directory(
+            harness.root.path(),
+            empty_harness.root.path(),
+            DirectoryCopyOptions::default(),
+        ))
+        .unwrap();
+
+    assert_eq!(finished_copy.num_files_copied, 6);
+    empty_harness
+        .root
+        .assert_directory_contents_match_directory(harness.root.path());
+
+    empty_harness.destroy()?;
+
+
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let (finished_copy_with_progress, num_progress_updates) = runtime.block_on(async {
+        let (progress_sender, mut progress_receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let finished_copy = fs_more::asynchronous::copy_directory_with_progress(
+            harness.root.path(),
+            empty_harness.root.path(),
+            DirectoryCopyWithProgressOptions::default(),
+            move |progress| {
+                progress_sender.send(progress.clone()).unwrap();
+            },
+        )
+        .await
+        .unwrap();
+
+        // The progress handler (and with it, the sender) is dropped once the copy finishes.
+        let mut num_progress_updates = 0;
+        while progress_receiver.recv().await.is_some() {
+            num_progress_updates += 1;
+        }
+
+        (finished_copy, num_progress_updates)
+    });
+
+    assert_eq!(finished_copy_with_progress.num_files_copied, 6);
+    assert!(num_progress_updates > 0);
+
+
+    empty_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
@@ -1,14 +1,30 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
 use assert_matches::assert_matches;
 use fs_more::{
     directory::{
+        DirectoryCopyOperation,
         DirectoryCopyOptions,
         DirectoryCopyProgress,
         DirectoryCopyWithProgressOptions,
         DirectoryScan,
+        DirectoryScanOptions,
+        FileEvent,
+        FileSkipReason,
+        FinishedDirectoryCopy,
+        JunctionBehaviour,
+        LockedFileBehaviour,
+        PlannedOperation,
+        PlannedTargetAction,
         TargetDirectoryRule,
+        TraversalOrder,
+        VerifyMode,
     },
-    error::DirectoryError,
-    file::FileCopyOptions,
+    error::{DirectoryError, IoOperation},
+    file::{ChecksumAlgorithm, CopyMethod, FileCopyOptions, SparseMode},
 };
 use fs_more_test_harness::{
     assertable::{AssertableDirectoryPath, AssertableFilePath},
@@ -21,8 +37,11 @@ pub fn copy_directory() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    let source_scan = DirectoryScan::scan_with_options(harness.root.path(), None, false)
-        .expect("failed to scan temporary directory");
+    let source_scan = DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .expect("failed to scan temporary directory");
     let source_full_size = source_scan
         .total_size_in_bytes()
         .expect("failed to compute size of source directory in bytes");
@@ -51,13 +70,13 @@ pub fn copy_directory() -> TestResult<()> {
     );
 
     assert_eq!(
-        source_scan.files.len(),
+        source_scan.files().len(),
         finished_copy.num_files_copied,
         "DirectoryScan and copy_directory report different number of files"
     );
 
     assert_eq!(
-        source_scan.directories.len(),
+        source_scan.directories().len(),
         finished_copy.num_directories_created,
         "DirectoryScan and copy_directory report different number of directories"
     );
@@ -78,10 +97,19 @@ pub fn copy_directory_respect_maximum_depth_option() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    const MAXIMUM_DEPTH: Option<usize> = Some(2);
+    // A copy depth counts one level more than a scan depth (entries in the root are at copy depth 1).
+    const MAXIMUM_SCAN_DEPTH: Option<usize> = Some(2);
+    const MAXIMUM_COPY_DEPTH: Option<usize> = Some(3);
 
-    let source_scan = DirectoryScan::scan_with_options(harness.root.path(), MAXIMUM_DEPTH, false)
-        .expect("failed to scan temporary directory");
+    let source_scan = DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            maximum_scan_depth: MAXIMUM_SCAN_DEPTH,
+            follow_symbolic_links: false,
+            ..Default::default()
+        },
+    )
+    .expect("failed to scan temporary directory");
     let source_full_size = source_scan
         .total_size_in_bytes()
         .expect("failed to compute size of source directory in bytes");
@@ -93,7 +121,8 @@ pub fn copy_directory_respect_maximum_depth_option() -> TestResult<()> {
         empty_harness.root.path(),
         DirectoryCopyOptions {
             target_directory_rule: TargetDirectoryRule::AllowEmpty,
-            maximum_copy_depth: MAXIMUM_DEPTH,
+            maximum_copy_depth: MAXIMUM_COPY_DEPTH,
+            ..Default::default()
         },
     )
     .unwrap_or_else(|error| {
@@ -109,13 +138,13 @@ pub fn copy_directory_respect_maximum_depth_option() -> TestResult<()> {
     );
 
     assert_eq!(
-        source_scan.files.len(),
+        source_scan.files().len(),
         finished_copy.num_files_copied,
         "DirectoryScan and copy_directory report different number of files"
     );
 
     assert_eq!(
-        source_scan.directories.len(),
+        source_scan.directories().len(),
         finished_copy.num_directories_created,
         "DirectoryScan and copy_directory report different number of directories"
     );
@@ -128,113 +157,38 @@ pub fn copy_directory_respect_maximum_depth_option() -> TestResult<()> {
 
 
 #[test]
-pub fn copy_directory_with_progress() -> TestResult<()> {
+pub fn copy_directory_into_subdirectory() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    let source_scan = DirectoryScan::scan_with_options(harness.root.path(), None, false)
-        .expect("failed to scan temporary directory");
-    let source_full_size = source_scan
-        .total_size_in_bytes()
-        .expect("failed to compute size of source directory in bytes");
-
     empty_harness.root.assert_is_empty();
 
-    let mut last_progress: Option<DirectoryCopyProgress> = None;
-
-    let finished_copy = fs_more::directory::copy_directory_with_progress(
+    fs_more::directory::copy_directory(
         harness.root.path(),
         empty_harness.root.path(),
-        DirectoryCopyWithProgressOptions {
-            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+        DirectoryCopyOptions {
+            copy_into_subdirectory: true,
             ..Default::default()
         },
-        |progress| {
-            if last_progress.is_none() {
-                last_progress = Some(progress.clone());
-                return;
-            };
-
-            let previous_progress = last_progress.as_ref().unwrap();
-            let progress_operation_index_delta = progress.current_operation_index - previous_progress.current_operation_index;
-
-            if progress_operation_index_delta != 0 && progress_operation_index_delta != 1 {
-                panic!(
-                    "copy_directory_with_progress reported non-consecutive operation indexes: {} and {}",
-                    previous_progress.current_operation_index,
-                    progress.current_operation_index
-                );
-            }
-
-            assert!(
-                progress.current_operation_index >= 0,
-                "copy_directory_with_progress reported a negative operation index: {}",
-                progress.current_operation_index
-            );
-
-            last_progress = Some(progress.clone());
-        },
     )
     .unwrap_or_else(|error| {
         panic!(
-            "copy_directory_with_progress unexpectedly failed with Err: {}",
+            "copy_directory unexpectedly failed with Err: {}",
             error
         );
     });
 
 
-    assert!(
-        last_progress.is_some(),
-        "copy_directory_with_progress did not report progress at all"
-    );
-
-    let last_progress = last_progress.unwrap();
-
-    assert_eq!(
-        last_progress.current_operation_index + 1,
-        last_progress.total_operations,
-        "copy_directory_with_progress's last progress reported inconsistent operation indexes"
-    );
-
-    assert_eq!(
-        last_progress.bytes_finished, last_progress.bytes_total,
-        "copy_directory_with_progress's last progress message was an unfinished copy"
-    );
-    assert_eq!(
-        source_full_size,
-        last_progress.bytes_total,
-        "DirectoryScan and copy_directory_with_progress's last progress reported different amount of total bytes"
-    );
-    assert_eq!(
-        source_full_size, finished_copy.total_bytes_copied,
-        "DirectoryScan and copy_directory_with_progress report different amount of total bytes"
-    );
-
-    assert_eq!(
-        source_scan.files.len(),
-        last_progress.files_copied,
-        "copy_directory_with_progress's last progress did not report all files"
-    );
-    assert_eq!(
-        source_scan.files.len(),
-        finished_copy.num_files_copied,
-        "DirectoryScan and copy_directory_with_progress report different number of files"
-    );
-
-    assert_eq!(
-        source_scan.directories.len(),
-        last_progress.directories_created,
-        "copy_directory_with_progress's last progress did not report all directories"
-    );
-    assert_eq!(
-        source_scan.directories.len(),
-        finished_copy.num_directories_created,
-        "DirectoryScan and copy_directory_with_progress report different number of directories"
+    let target_subdirectory = AssertableDirectoryPath::from_path(
+        empty_harness
+            .root
+            .child_path(harness.root.path().file_name().unwrap()),
     );
 
+    target_subdirectory.assert_is_directory();
     harness
         .root
-        .assert_directory_contents_match_directory(empty_harness.root.path());
+        .assert_directory_contents_match_directory(target_subdirectory.path());
 
 
     harness.destroy()?;
@@ -244,48 +198,60 @@ pub fn copy_directory_with_progress() -> TestResult<()> {
 
 
 #[test]
-pub fn copy_directory_with_progress_respect_depth_option() -> TestResult<()> {
+pub fn copy_directory_respect_minimum_depth_option() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    const MAXIMUM_DEPTH: Option<usize> = Some(2);
-
-    let source_scan = DirectoryScan::scan_with_options(harness.root.path(), MAXIMUM_DEPTH, false)
-        .expect("failed to scan temporary directory");
-    let source_full_size = source_scan
-        .total_size_in_bytes()
-        .expect("failed to compute size of source directory in bytes");
-
-    empty_harness.root.assert_is_empty();
+    let target_file_path = |source_file: &AssertableFilePath| {
+        AssertableFilePath::from_path(
+            empty_harness.root.path().join(
+                source_file
+                    .path()
+                    .strip_prefix(harness.root.path())
+                    .unwrap(),
+            ),
+        )
+    };
 
-    fs_more::directory::copy_directory_with_progress(
+    let finished_copy = fs_more::directory::copy_directory(
         harness.root.path(),
         empty_harness.root.path(),
-        DirectoryCopyWithProgressOptions {
-            target_directory_rule: TargetDirectoryRule::AllowEmpty,
-            maximum_copy_depth: MAXIMUM_DEPTH,
+        DirectoryCopyOptions {
+            minimum_copy_depth: Some(1),
             ..Default::default()
         },
-        |_| {},
     )
     .unwrap_or_else(|error| {
         panic!(
-            "copy_directory_with_progress unexpectedly failed with Err: {}",
+            "copy_directory unexpectedly failed with Err: {}",
             error
         );
     });
 
-    let target_scan = DirectoryScan::scan_with_options(empty_harness.root.path(), None, false)
-        .expect("failed to scan target temporary directory");
-    let target_full_size = target_scan
-        .total_size_in_bytes()
-        .expect("failed to compute size of target directory in bytes");
+
+    // `file_a` is directly in the root directory, so it must not be copied.
+    target_file_path(&harness.file_a).assert_not_exists();
+
+    // Deeper files keep their relative paths.
+    target_file_path(&harness.file_b).assert_content_matches_file(harness.file_b.path());
+    target_file_path(&harness.file_c).assert_content_matches_file(harness.file_c.path());
 
     assert_eq!(
-        source_full_size, target_full_size,
-        "copy_directory_with_progress did not create an equally-sized directory copy"
+        finished_copy.num_files_copied,
+        DirectoryScan::scan_with_options(
+            harness.root.path(),
+            DirectoryScanOptions {
+                minimum_scan_depth: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .files()
+        .len(),
+        "copy_directory and DirectoryScan report different number of files"
     );
 
+
     harness.destroy()?;
     empty_harness.destroy()?;
     Ok(())
@@ -293,63 +259,57 @@ pub fn copy_directory_with_progress_respect_depth_option() -> TestResult<()> {
 
 
 #[test]
-pub fn error_on_copy_directory_with_progress_on_existing_file_without_option() -> TestResult<()> {
+pub fn copy_directory_skips_empty_directories() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    // Still the harness setup.
-    let file_a_filename = harness.file_a.path().file_name().unwrap();
-    let test_file_path = empty_harness.root.child_path(file_a_filename);
-    fs_more::file::copy_file(
-        harness.file_a.path(),
-        &test_file_path,
-        FileCopyOptions {
-            overwrite_existing: false,
-            skip_existing: false,
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            skip_empty_directories: true,
+            ..Default::default()
         },
     )
     .unwrap();
 
-    let test_file = AssertableFilePath::from_path_with_captured_content(test_file_path)?;
+    // `dir_foo2` and `dir_foo3` are empty in the source directory.
+    assert_eq!(finished_copy.num_empty_directories_skipped, 2);
 
-    test_file.assert_exists();
-    test_file.assert_content_unchanged();
-    // End of setup, we have now pre-copied a single file to test our overwriting options.
+    AssertableDirectoryPath::from_path(empty_harness.root.child_path("dir_foo2"))
+        .assert_not_exists();
+    AssertableDirectoryPath::from_path(empty_harness.root.child_path("dir_foo3"))
+        .assert_not_exists();
+    AssertableFilePath::from_path(empty_harness.root.child_path("dir_foo/dir_bar/file_c.bin"))
+        .assert_content_matches_file(harness.file_c.path());
+
+    empty_harness.destroy()?;
 
 
-    empty_harness.root.assert_is_not_empty();
+    // With a maximum depth of 2, the contents of `dir_foo/dir_bar` are not copied,
+    // so it becomes empty and is skipped as well.
+    let empty_harness = EmptyTreeHarness::new()?;
 
-    let copy_result = fs_more::directory::copy_directory_with_progress(
+    let finished_copy = fs_more::directory::copy_directory_with_progress(
         harness.root.path(),
         empty_harness.root.path(),
         DirectoryCopyWithProgressOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_files: false,
-                overwrite_existing_subdirectories: true,
-            },
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            maximum_copy_depth: Some(2),
+            skip_empty_directories: true,
             ..Default::default()
         },
         |_| {},
-    );
+    )
+    .unwrap();
 
-    assert!(
-        copy_result.is_err(),
-        "copy_directory_with_progress should have errored due to existing target file"
-    );
+    assert_eq!(finished_copy.num_empty_directories_skipped, 3);
 
-    let copy_err = copy_result.unwrap_err();
-    match &copy_err {
-        DirectoryError::TargetItemAlreadyExists { path } => {
-            assert_eq!(
-                path,
-                test_file.path(),
-                "copy_directory_with_progress returned TargetItemAlreadyExists with incorrect inner path"
-            );
-        }
-        _ => {
-            panic!("copy_directory_with_progress should have errored with TargetItemAlreadyExists")
-        }
-    }
+    AssertableDirectoryPath::from_path(empty_harness.root.child_path("dir_foo/dir_bar"))
+        .assert_not_exists();
+    AssertableFilePath::from_path(empty_harness.root.child_path("dir_foo/file_b.bin"))
+        .assert_content_matches_file(harness.file_b.path());
 
 
     harness.destroy()?;
@@ -357,52 +317,45 @@ pub fn error_on_copy_directory_with_progress_on_existing_file_without_option() -
     Ok(())
 }
 
+
 #[test]
-pub fn error_on_copy_directory_with_progress_on_existing_directory_without_option() -> TestResult<()>
-{
+pub fn copy_directory_with_file_filter_copies_only_matching_files() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    // Still the harness setup.
-    let replicated_foo_dir_name = harness.dir_foo.path().file_name().unwrap();
-    let replicated_foo_dir_path = empty_harness.root.child_path(replicated_foo_dir_name);
-    std::fs::create_dir_all(&replicated_foo_dir_path)?;
-    // End of setup, we have now pre-copied a single file to test our overwriting options.
-
-
-    empty_harness.root.assert_is_not_empty();
-
-    let copy_result = fs_more::directory::copy_directory_with_progress(
+    let finished_copy = fs_more::directory::copy_directory_with_file_filter(
         harness.root.path(),
         empty_harness.root.path(),
-        DirectoryCopyWithProgressOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_files: true,
-                overwrite_existing_subdirectories: false,
-            },
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            pre_scan: false,
             ..Default::default()
         },
-        |_| {},
-    );
+        |_, metadata| metadata.len() >= 1024 * 128,
+    )
+    .unwrap();
 
-    assert!(
-        copy_result.is_err(),
-        "copy_directory_with_progress should have errored due to existing target file"
-    );
+    // Only `file_c.bin`, `file_d.bin` and `file_f.bin` are at least 128 KiB large.
+    assert_eq!(finished_copy.num_files_copied, 3);
+    assert_eq!(finished_copy.num_files_filtered_out, 3);
 
-    let copy_err = copy_result.unwrap_err();
-    match &copy_err {
-        DirectoryError::TargetItemAlreadyExists { path } => {
-            assert_eq!(
-                path,
-                &replicated_foo_dir_path,
-                "copy_directory_with_progress returned TargetItemAlreadyExists with incorrect inner path"
-            );
-        }
-        _ => {
-            panic!("copy_directory_with_progress should have errored with TargetItemAlreadyExists")
-        }
-    }
+    AssertableFilePath::from_path(empty_harness.root.child_path("file_a.bin")).assert_not_exists();
+    AssertableFilePath::from_path(empty_harness.root.child_path("dir_foo/file_b.bin"))
+        .assert_not_exists();
+    AssertableFilePath::from_path(empty_harness.root.child_path("dir_foo/dir_bar/file_c.bin"))
+        .assert_content_matches_file(harness.file_c.path());
+    AssertableFilePath::from_path(
+        empty_harness
+            .root
+            .child_path("dir_foo/dir_bar/hello/world/file_e.bin"),
+    )
+    .assert_not_exists();
+    AssertableFilePath::from_path(
+        empty_harness
+            .root
+            .child_path("dir_foo/dir_bar/hello/world/file_f.bin"),
+    )
+    .assert_content_matches_file(harness.file_f.path());
 
 
     harness.destroy()?;
@@ -411,269 +364,382 @@ pub fn error_on_copy_directory_with_progress_on_existing_directory_without_optio
 }
 
 
-
 #[test]
-pub fn disallow_copy_directory_into_itself() -> TestResult<()> {
+pub fn copy_directory_with_path_transform() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
 
-    let copy_result = fs_more::directory::copy_directory(
-        harness.root.path(),
+    let finished_copy = fs_more::directory::copy_directory_with_path_transform(
         harness.root.path(),
+        empty_harness.root.path(),
         DirectoryCopyOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_subdirectories: true,
-                overwrite_existing_files: true,
-            },
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
             ..Default::default()
         },
-    );
+        |relative_path| {
+            // Skip the `dir_foo/dir_bar` subtree and strip the `dir_` prefix from directory names.
+            if relative_path.ends_with("dir_bar") {
+                return None;
+            }
+
+            let relative_path_string = relative_path.to_str().unwrap();
+            Some(PathBuf::from(
+                relative_path_string.replace("dir_", ""),
+            ))
+        },
+    )
+    .unwrap();
+
+    // `file_a`, `file_b` as well as `foo`, `foo2` and `foo3`.
+    assert_eq!(finished_copy.num_files_copied, 2);
+    assert_eq!(finished_copy.num_directories_created, 3);
+
+    AssertableFilePath::from_path(empty_harness.root.child_path("file_a.bin"))
+        .assert_content_matches_file(harness.file_a.path());
+    AssertableFilePath::from_path(empty_harness.root.child_path("foo/file_b.bin"))
+        .assert_content_matches_file(harness.file_b.path());
+    AssertableDirectoryPath::from_path(empty_harness.root.child_path("foo2")).assert_is_directory();
+    AssertableDirectoryPath::from_path(empty_harness.root.child_path("foo/bar"))
+        .assert_not_exists();
+    AssertableDirectoryPath::from_path(empty_harness.root.child_path("dir_foo"))
+        .assert_not_exists();
 
-    assert_matches!(
-        copy_result,
-        Err(DirectoryError::InvalidTargetDirectoryPath),
-        "copy_directory should have errored when trying to copy a directory into itself"
-    );
 
     harness.destroy()?;
+    empty_harness.destroy()?;
     Ok(())
 }
 
+
 #[test]
-pub fn disallow_copy_directory_into_subdirectory_of_itself() -> TestResult<()> {
+pub fn copy_directory_with_path_transform_creates_missing_parent_directories() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
 
-    let copy_result = fs_more::directory::copy_directory(
+    // Flatten the directory tree by copying each file into `files/`.
+    fs_more::directory::copy_directory_with_path_transform(
         harness.root.path(),
-        harness.dir_world.path(),
+        empty_harness.root.path(),
         DirectoryCopyOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_subdirectories: true,
-                overwrite_existing_files: true,
-            },
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
             ..Default::default()
         },
-    );
+        |relative_path| {
+            if relative_path.extension().is_none() {
+                return Some(relative_path.to_path_buf());
+            }
+
+            Some(PathBuf::from("files").join(relative_path.file_name().unwrap()))
+        },
+    )
+    .unwrap();
+
+    AssertableFilePath::from_path(empty_harness.root.child_path("files/file_a.bin"))
+        .assert_content_matches_file(harness.file_a.path());
+    AssertableFilePath::from_path(empty_harness.root.child_path("files/file_d.bin"))
+        .assert_content_matches_file(harness.file_d.path());
+    AssertableDirectoryPath::from_path(empty_harness.root.child_path("dir_foo/dir_bar/hello"))
+        .assert_is_directory();
 
-    assert_matches!(
-        copy_result,
-        Err(DirectoryError::InvalidTargetDirectoryPath),
-        "copy_directory should have errored when trying to \
-        copy a directory into a subdirectory of itself"
-    );
 
     harness.destroy()?;
+    empty_harness.destroy()?;
     Ok(())
 }
 
+
 #[test]
-pub fn disallow_copy_directory_with_progress_into_itself() -> TestResult<()> {
+pub fn copy_directory_with_path_transform_errors_on_duplicate_target_paths() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
 
-    let copy_result = fs_more::directory::copy_directory_with_progress(
-        harness.root.path(),
+    let copy_result = fs_more::directory::copy_directory_with_path_transform(
         harness.root.path(),
-        DirectoryCopyWithProgressOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_subdirectories: true,
-                overwrite_existing_files: true,
-            },
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
             ..Default::default()
         },
-        |_| {},
+        |relative_path| {
+            if relative_path.extension().is_none() {
+                return Some(relative_path.to_path_buf());
+            }
+
+            Some(PathBuf::from("same.bin"))
+        },
     );
 
     assert_matches!(
         copy_result,
-        Err(DirectoryError::InvalidTargetDirectoryPath),
-        "copy_directory_with_progress should have errored when trying to \
-        copy a directory into itself"
+        Err(DirectoryError::DuplicateTransformedPath { path })
+            if path == empty_harness.root.child_path("same.bin")
     );
 
-    harness.destroy()?;
-    Ok(())
-}
+    // Nothing should have been copied.
+    empty_harness.root.assert_is_empty();
 
-#[test]
-pub fn disallow_copy_directory_with_progress_into_subdirectory_of_itself() -> TestResult<()> {
-    let harness = DeepTreeHarness::new()?;
 
-    let copy_result = fs_more::directory::copy_directory_with_progress(
+    let escaping_copy_result = fs_more::directory::copy_directory_with_path_transform(
         harness.root.path(),
-        harness.dir_world.path(),
-        DirectoryCopyWithProgressOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_subdirectories: true,
-                overwrite_existing_files: true,
-            },
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
             ..Default::default()
         },
-        |_| {},
+        |relative_path| Some(PathBuf::from("..").join(relative_path)),
     );
 
     assert_matches!(
-        copy_result,
-        Err(DirectoryError::InvalidTargetDirectoryPath),
-        "copy_directory_with_progress should have errored when trying to \
-        copy a directory into a subdirectory of itself"
+        escaping_copy_result,
+        Err(DirectoryError::InvalidTransformedPath { .. })
     );
 
+    empty_harness.root.assert_is_empty();
+
+
     harness.destroy()?;
+    empty_harness.destroy()?;
     Ok(())
 }
 
+
+#[cfg(unix)]
 #[test]
-pub fn error_on_copy_directory_on_existing_target_directory_without_option() -> TestResult<()> {
+pub fn copy_directory_preserves_root_metadata() -> TestResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    empty_harness.root.assert_is_empty();
+    std::fs::set_permissions(
+        harness.root.path(),
+        std::fs::Permissions::from_mode(0o750),
+    )?;
 
-    let copy_result = fs_more::directory::copy_directory(
+    let target_directory_path = empty_harness.root.child_path("copied");
+
+    fs_more::directory::copy_directory(
         harness.root.path(),
-        empty_harness.root.path(),
+        &target_directory_path,
         DirectoryCopyOptions {
-            target_directory_rule: TargetDirectoryRule::DisallowExisting,
+            preserve_root_metadata: true,
             ..Default::default()
         },
+    )
+    .unwrap();
+
+    let source_metadata = std::fs::metadata(harness.root.path())?;
+    let target_metadata = std::fs::metadata(&target_directory_path)?;
+
+    assert_eq!(
+        target_metadata.permissions().mode() & 0o777,
+        0o750
+    );
+    assert_eq!(
+        target_metadata.modified()?,
+        source_metadata.modified()?
     );
 
-    let copy_err = copy_result.unwrap_err();
-    match &copy_err {
-        DirectoryError::TargetItemAlreadyExists { path } => {
-            assert_eq!(
-                path,
-                empty_harness.root.path(),
-                "copy_directory did not return the correct path \
-                inside the TargetItemAlreadyExists error"
-            );
-        }
-        _ => panic!("Unexpected Err value: {}", copy_err),
-    }
 
-    empty_harness.root.assert_is_empty();
+    std::fs::set_permissions(
+        harness.root.path(),
+        std::fs::Permissions::from_mode(0o755),
+    )?;
 
     harness.destroy()?;
     empty_harness.destroy()?;
     Ok(())
 }
 
+
+#[cfg(unix)]
 #[test]
-pub fn error_on_copy_directory_on_existing_file_without_option() -> TestResult<()> {
+pub fn copy_directory_applies_forced_mode() -> TestResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    // Still the harness setup.
-    let file_a_filename = harness.file_a.path().file_name().unwrap();
-    let test_file_path = empty_harness.root.child_path(file_a_filename);
-    fs_more::file::copy_file(
-        harness.file_a.path(),
-        &test_file_path,
-        FileCopyOptions {
-            overwrite_existing: false,
-            skip_existing: false,
+    let mode_of = |path: PathBuf| -> TestResult<u32> {
+        Ok(std::fs::metadata(path)?.permissions().mode() & 0o777)
+    };
+
+    let target_directory_path = empty_harness.root.child_path("copied");
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        &target_directory_path,
+        DirectoryCopyOptions {
+            preserve_root_metadata: true,
+            force_mode: Some(0o700),
+            ..Default::default()
         },
     )
     .unwrap();
 
-    let test_file = AssertableFilePath::from_path_with_captured_content(test_file_path)?;
-
-    test_file.assert_exists();
-    test_file.assert_content_unchanged();
-    // End of setup, we have now pre-copied a single file to test our overwriting options.
+    assert_eq!(mode_of(target_directory_path.clone())?, 0o700);
+    assert_eq!(
+        mode_of(target_directory_path.join("file_a.bin"))?,
+        0o700
+    );
+    assert_eq!(
+        mode_of(target_directory_path.join("dir_foo"))?,
+        0o700
+    );
+    assert_eq!(
+        mode_of(target_directory_path.join("dir_foo").join("file_b.bin"))?,
+        0o700
+    );
+    assert_eq!(
+        mode_of(target_directory_path.join("dir_foo").join("dir_bar"))?,
+        0o700
+    );
 
 
-    empty_harness.root.assert_is_not_empty();
+    // A mode without the write bit must not prevent the directory contents from being copied.
+    let target_directory_path = empty_harness.root.child_path("copied-with-progress");
 
-    let copy_result = fs_more::directory::copy_directory(
+    fs_more::directory::copy_directory_with_progress(
         harness.root.path(),
-        empty_harness.root.path(),
-        DirectoryCopyOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_files: false,
-                overwrite_existing_subdirectories: false,
-            },
+        &target_directory_path,
+        DirectoryCopyWithProgressOptions {
+            force_mode: Some(0o555),
             ..Default::default()
         },
-    );
+        |_| {},
+    )
+    .unwrap();
 
-    let copy_err = copy_result.unwrap_err();
-    match &copy_err {
-        DirectoryError::TargetItemAlreadyExists { path } => {
-            assert_eq!(
-                path,
-                test_file.path(),
-                "copy_directory did not return the correct path \
-                inside the TargetItemAlreadyExists error"
-            );
-        }
-        _ => panic!("Unexpected Err value: {}", copy_err),
+    assert_eq!(mode_of(target_directory_path.clone())?, 0o555);
+    assert_eq!(
+        mode_of(target_directory_path.join("dir_foo"))?,
+        0o555
+    );
+    assert_eq!(
+        mode_of(target_directory_path.join("dir_foo").join("file_b.bin"))?,
+        0o555
+    );
+    harness
+        .file_b
+        .assert_content_matches_file(target_directory_path.join("dir_foo").join("file_b.bin"));
+
+    // Make the copy removable again.
+    for entry_path in [
+        target_directory_path.clone(),
+        target_directory_path.join("dir_foo"),
+        target_directory_path.join("dir_foo").join("dir_bar"),
+        target_directory_path
+            .join("dir_foo")
+            .join("dir_bar")
+            .join("hello"),
+        target_directory_path
+            .join("dir_foo")
+            .join("dir_bar")
+            .join("hello")
+            .join("world"),
+    ] {
+        std::fs::set_permissions(entry_path, std::fs::Permissions::from_mode(0o755))?;
     }
 
-    empty_harness.root.assert_is_not_empty();
 
     harness.destroy()?;
     empty_harness.destroy()?;
     Ok(())
 }
 
+
+#[cfg(unix)]
 #[test]
-pub fn error_on_copy_directory_on_existing_subdirectory_without_option() -> TestResult<()> {
+pub fn copy_directory_does_not_preserve_root_metadata_onto_non_empty_target() -> TestResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    // Still the harness setup.
-    let replicated_foo_dir_name = harness.dir_foo.path().file_name().unwrap();
-    let replicated_foo_dir_path = empty_harness.root.child_path(replicated_foo_dir_name);
-    std::fs::create_dir_all(&replicated_foo_dir_path)?;
+    std::fs::set_permissions(
+        harness.root.path(),
+        std::fs::Permissions::from_mode(0o750),
+    )?;
+    std::fs::set_permissions(
+        empty_harness.root.path(),
+        std::fs::Permissions::from_mode(0o700),
+    )?;
+    std::fs::write(
+        empty_harness.root.child_path("unrelated.txt"),
+        "unrelated",
+    )?;
 
-    let file_b_filename = harness.file_b.path().file_name().unwrap();
-    let replicated_file_b_path = empty_harness.root.child_path(file_b_filename);
-    fs_more::file::copy_file(
-        harness.file_b.path(),
-        &replicated_file_b_path,
-        FileCopyOptions {
-            overwrite_existing: false,
-            skip_existing: false,
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: false,
+                overwrite_existing_files: false,
+            },
+            preserve_root_metadata: true,
+            ..Default::default()
         },
     )
     .unwrap();
 
-    let replicated_file_b =
-        AssertableFilePath::from_path_with_captured_content(replicated_file_b_path)?;
+    let target_metadata = std::fs::metadata(empty_harness.root.path())?;
+    assert_eq!(
+        target_metadata.permissions().mode() & 0o777,
+        0o700
+    );
 
-    replicated_file_b.assert_exists();
-    replicated_file_b.assert_content_unchanged();
-    // End of setup, we have now pre-copied a single directory containing
-    // a single file to test our overwriting options.
 
+    std::fs::set_permissions(
+        harness.root.path(),
+        std::fs::Permissions::from_mode(0o755),
+    )?;
+    std::fs::set_permissions(
+        empty_harness.root.path(),
+        std::fs::Permissions::from_mode(0o755),
+    )?;
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn copy_directory_errors_on_insufficient_free_space() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    // A sparse file that is much larger than the free space of
+    // any filesystem the tests could reasonably be running on (8 TiB).
+    let huge_file = std::fs::File::create(harness.root.child_path("huge-sparse-file.bin"))?;
+    if huge_file.set_len(1 << 43).is_err() {
+        // The filesystem doesn't support sparse files this large, so there is nothing to test.
+        harness.destroy()?;
+        empty_harness.destroy()?;
+        return Ok(());
+    }
+    drop(huge_file);
 
-    empty_harness.root.assert_is_not_empty();
 
     let copy_result = fs_more::directory::copy_directory(
         harness.root.path(),
         empty_harness.root.path(),
         DirectoryCopyOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_files: true,
-                overwrite_existing_subdirectories: false,
-            },
+            ensure_free_space: true,
             ..Default::default()
         },
     );
 
-    let copy_err = copy_result.unwrap_err();
-    match &copy_err {
-        DirectoryError::TargetItemAlreadyExists { path } => {
-            assert_eq!(
-                path, &replicated_foo_dir_path,
-                "copy_directory did not return the correct path \
-                inside the TargetItemAlreadyExists error"
-            );
-        }
-        _ => panic!("Unexpected Err value: {}", copy_err),
-    }
+    assert_matches!(
+        copy_result,
+        Err(DirectoryError::InsufficientFreeSpace { required, available }) if required > available,
+        "copy_directory should have errored due to insufficient free space"
+    );
+
+    empty_harness.root.assert_is_empty();
 
-    empty_harness.root.assert_is_not_empty();
 
     harness.destroy()?;
     empty_harness.destroy()?;
@@ -682,214 +748,3577 @@ pub fn error_on_copy_directory_on_existing_subdirectory_without_option() -> Test
 
 
 #[test]
-pub fn copy_directory_symbolic_link_to_file_behaviour() -> TestResult<()> {
+pub fn copy_directory_with_free_inode_check() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    let symlinked_file =
-        AssertableFilePath::from_path(harness.root.child_path("file_a-symlinked.bin"));
-    symlinked_file.assert_not_exists();
-    symlinked_file.symlink_to_file(harness.file_a.path())?;
-    symlinked_file.assert_is_symlink_to_file();
-
     fs_more::directory::copy_directory(
         harness.root.path(),
         empty_harness.root.path(),
-        DirectoryCopyOptions::default(),
+        DirectoryCopyOptions {
+            ensure_free_inodes: true,
+            ..Default::default()
+        },
     )
     .unwrap();
 
-    let previously_symlinked_file_in_target =
-        AssertableFilePath::from_path(empty_harness.root.child_path("file_a-symlinked.bin"));
-    previously_symlinked_file_in_target.assert_exists();
-    previously_symlinked_file_in_target.assert_is_file();
-
-    empty_harness.destroy()?;
-    harness.destroy()?;
-    Ok(())
-}
+    empty_harness
+        .root
+        .assert_directory_contents_match_directory(harness.root.path());
 
-#[test]
-pub fn copy_directory_with_progress_symbolic_link_to_file_behaviour() -> TestResult<()> {
-    let harness = DeepTreeHarness::new()?;
-    let empty_harness = EmptyTreeHarness::new()?;
 
-    let symlinked_file =
-        AssertableFilePath::from_path(harness.root.child_path("file_a-symlinked.bin"));
-    symlinked_file.assert_not_exists();
-    symlinked_file.symlink_to_file(harness.file_a.path())?;
-    symlinked_file.assert_is_symlink_to_file();
+    let empty_harness_with_progress = EmptyTreeHarness::new()?;
 
     fs_more::directory::copy_directory_with_progress(
         harness.root.path(),
-        empty_harness.root.path(),
-        DirectoryCopyWithProgressOptions::default(),
+        empty_harness_with_progress.root.path(),
+        DirectoryCopyWithProgressOptions {
+            ensure_free_inodes: true,
+            ..Default::default()
+        },
         |_| {},
     )
     .unwrap();
 
-    let previously_symlinked_file_in_target =
-        AssertableFilePath::from_path(empty_harness.root.child_path("file_a-symlinked.bin"));
-    previously_symlinked_file_in_target.assert_exists();
-    previously_symlinked_file_in_target.assert_is_file();
+    empty_harness_with_progress
+        .root
+        .assert_directory_contents_match_directory(harness.root.path());
 
+
+    harness.destroy()?;
     empty_harness.destroy()?;
+    empty_harness_with_progress.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_errors_when_target_parent_is_a_file() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = DeepTreeHarness::new()?;
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.dir_foo.path(),
+        target_harness.file_a.path().join("foo"),
+        DirectoryCopyOptions::default(),
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(DirectoryError::TargetParentNotADirectory { path }) if path == target_harness.file_a.path(),
+        "copy_directory should have errored because the target's parent is a file"
+    );
+
+    target_harness.file_a.assert_content_unchanged();
+
+
     harness.destroy()?;
+    target_harness.destroy()?;
     Ok(())
 }
 
 
 #[test]
-pub fn copy_directory_symbolic_link_to_directory_behaviour() -> TestResult<()> {
+pub fn copy_directory_with_progress() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    let symlinked_dir =
-        AssertableDirectoryPath::from_path(harness.root.child_path("symlinked-directory"));
-    symlinked_dir.assert_not_exists();
-    symlinked_dir.symlink_to_directory(harness.dir_foo.path())?;
-    symlinked_dir.assert_is_symlink_to_directory();
+    let source_scan = DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .expect("failed to scan temporary directory");
+    let source_full_size = source_scan
+        .total_size_in_bytes()
+        .expect("failed to compute size of source directory in bytes");
 
-    fs_more::directory::copy_directory(
+    empty_harness.root.assert_is_empty();
+
+    let mut last_progress: Option<DirectoryCopyProgress> = None;
+
+    let finished_copy = fs_more::directory::copy_directory_with_progress(
         harness.root.path(),
         empty_harness.root.path(),
-        DirectoryCopyOptions::default(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            ..Default::default()
+        },
+        |progress| {
+            if last_progress.is_none() {
+                last_progress = Some(progress.clone());
+                return;
+            };
+
+            let previous_progress = last_progress.as_ref().unwrap();
+            let progress_operation_index_delta = progress.current_operation_index - previous_progress.current_operation_index;
+
+            if progress_operation_index_delta != 0 && progress_operation_index_delta != 1 {
+                panic!(
+                    "copy_directory_with_progress reported non-consecutive operation indexes: {} and {}",
+                    previous_progress.current_operation_index,
+                    progress.current_operation_index
+                );
+            }
+
+            assert!(
+                progress.current_operation_index >= 0,
+                "copy_directory_with_progress reported a negative operation index: {}",
+                progress.current_operation_index
+            );
+
+            last_progress = Some(progress.clone());
+        },
     )
-    .unwrap();
+    .unwrap_or_else(|error| {
+        panic!(
+            "copy_directory_with_progress unexpectedly failed with Err: {}",
+            error
+        );
+    });
+
+
+    assert!(
+        last_progress.is_some(),
+        "copy_directory_with_progress did not report progress at all"
+    );
+
+    let last_progress = last_progress.unwrap();
+
+    assert_eq!(
+        last_progress.current_operation_index + 1,
+        last_progress.total_operations,
+        "copy_directory_with_progress's last progress reported inconsistent operation indexes"
+    );
+
+    assert_eq!(
+        last_progress.bytes_finished, last_progress.bytes_total,
+        "copy_directory_with_progress's last progress message was an unfinished copy"
+    );
+    assert!(last_progress.is_finished());
+    assert_eq!(last_progress.fraction_complete(), 1.0);
+    assert_eq!(
+        source_full_size,
+        last_progress.bytes_total,
+        "DirectoryScan and copy_directory_with_progress's last progress reported different amount of total bytes"
+    );
+    assert_eq!(
+        source_full_size, finished_copy.total_bytes_copied,
+        "DirectoryScan and copy_directory_with_progress report different amount of total bytes"
+    );
+
+    assert_eq!(
+        source_scan.files().len(),
+        last_progress.files_copied,
+        "copy_directory_with_progress's last progress did not report all files"
+    );
+    assert_eq!(
+        source_scan.files().len(),
+        finished_copy.num_files_copied,
+        "DirectoryScan and copy_directory_with_progress report different number of files"
+    );
+
+    assert_eq!(
+        source_scan.directories().len(),
+        last_progress.directories_created,
+        "copy_directory_with_progress's last progress did not report all directories"
+    );
+    assert_eq!(
+        last_progress.total_files, last_progress.files_copied,
+        "copy_directory_with_progress's last progress reported an inconsistent total number of files"
+    );
+    assert_eq!(
+        last_progress.total_directories, last_progress.directories_created,
+        "copy_directory_with_progress's last progress reported an inconsistent total number of directories"
+    );
+    assert_eq!(
+        source_scan.directories().len(),
+        finished_copy.num_directories_created,
+        "DirectoryScan and copy_directory_with_progress report different number of directories"
+    );
+
+    harness
+        .root
+        .assert_directory_contents_match_directory(empty_harness.root.path());
 
-    let previously_symlinked_dir_in_target =
-        AssertableDirectoryPath::from_path(empty_harness.root.child_path("symlinked-directory"));
-    previously_symlinked_dir_in_target.assert_exists();
-    previously_symlinked_dir_in_target
-        .assert_directory_contents_match_directory(harness.dir_foo.path());
 
-    empty_harness.destroy()?;
     harness.destroy()?;
+    empty_harness.destroy()?;
     Ok(())
 }
 
+
 #[test]
-pub fn copy_directory_with_progress_symbolic_link_to_directory_behaviour() -> TestResult<()> {
+pub fn copy_directory_links_unchanged_files_from_previous_copy() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
+    let previous_copy_harness = EmptyTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    let symlinked_dir =
-        AssertableDirectoryPath::from_path(harness.root.child_path("symlinked-directory"));
-    symlinked_dir.assert_not_exists();
-    symlinked_dir.symlink_to_directory(harness.dir_foo.path())?;
-    symlinked_dir.assert_is_symlink_to_directory();
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        previous_copy_harness.root.path(),
+        DirectoryCopyOptions::default(),
+    )
+    .unwrap();
 
-    fs_more::directory::copy_directory_with_progress(
+    // Only `file_a.bin` changes between the two copies.
+    std::fs::write(harness.file_a.path(), "changed contents")?;
+
+    let finished_copy = fs_more::directory::copy_directory_incremental(
         harness.root.path(),
         empty_harness.root.path(),
-        DirectoryCopyWithProgressOptions::default(),
-        |_| {},
+        previous_copy_harness.root.path(),
+        DirectoryCopyOptions::default(),
     )
     .unwrap();
 
-    let previously_symlinked_dir_in_target =
-        AssertableDirectoryPath::from_path(empty_harness.root.child_path("symlinked-directory"));
-    previously_symlinked_dir_in_target.assert_exists();
-    previously_symlinked_dir_in_target
-        .assert_directory_contents_match_directory(harness.dir_foo.path());
+    assert_eq!(finished_copy.num_files_copied, 1);
+    assert_eq!(finished_copy.num_files_linked, 5);
+
+    AssertableFilePath::from_path(empty_harness.root.child_path("file_a.bin"))
+        .assert_content_matches_str("changed contents");
+    AssertableFilePath::from_path(previous_copy_harness.root.child_path("file_a.bin"))
+        .assert_content_matches_expected_value_of_assertable(&harness.file_a);
+
+    harness
+        .root
+        .assert_directory_contents_match_directory(empty_harness.root.path());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let linked_file_metadata =
+            std::fs::metadata(empty_harness.root.child_path("dir_foo/file_b.bin"))?;
+        let previous_file_metadata =
+            std::fs::metadata(previous_copy_harness.root.child_path("dir_foo/file_b.bin"))?;
+
+        assert_eq!(
+            linked_file_metadata.ino(),
+            previous_file_metadata.ino()
+        );
+    }
+
 
-    empty_harness.destroy()?;
     harness.destroy()?;
+    previous_copy_harness.destroy()?;
+    empty_harness.destroy()?;
     Ok(())
 }
 
+
 #[test]
-pub fn copy_directory_symbolic_link_to_directory_respect_depth_limit() -> TestResult<()> {
+pub fn copy_directory_reports_plan_before_copying() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    let symlinked_dir =
-        AssertableDirectoryPath::from_path(harness.root.child_path("symlinked-directory"));
-    symlinked_dir.assert_not_exists();
-    symlinked_dir.symlink_to_directory(harness.dir_foo.path())?;
-    symlinked_dir.assert_is_symlink_to_directory();
+    let existing_target_file_path = empty_harness.root.child_path("file_a.bin");
+    std::fs::write(&existing_target_file_path, "existing")?;
 
-    fs_more::directory::copy_directory(
+    let mut reported_plan = None;
+
+    let finished_copy = fs_more::directory::copy_directory_with_plan(
         harness.root.path(),
         empty_harness.root.path(),
         DirectoryCopyOptions {
-            maximum_copy_depth: Some(1),
+            target_directory_rule: TargetDirectoryRule::Merge {
+                overwrite_existing_files: false,
+            },
             ..Default::default()
         },
+        |plan| {
+            // Nothing may have been copied yet.
+            assert!(!plan.target_directory_path.join("dir_foo").exists());
+
+            reported_plan = Some(plan.clone());
+        },
     )
     .unwrap();
 
-    let previously_symlinked_dir_in_target =
-        AssertableDirectoryPath::from_path(empty_harness.root.child_path("symlinked-directory"));
-    previously_symlinked_dir_in_target.assert_exists();
+    let plan = reported_plan.unwrap();
+
+    let planned_file_copies: Vec<_> = plan
+        .operations
+        .iter()
+        .filter_map(|operation| match operation {
+            PlannedOperation::CopyFile {
+                target_file_path,
+                target_action,
+                ..
+            } => Some((target_file_path.clone(), *target_action)),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(planned_file_copies.len(), 6);
+    assert!(planned_file_copies.contains(&(
+        existing_target_file_path.clone(),
+        PlannedTargetAction::Skip
+    )));
+    assert_eq!(
+        planned_file_copies
+            .iter()
+            .filter(|(_, target_action)| *target_action == PlannedTargetAction::Create)
+            .count(),
+        5
+    );
 
-    let previously_symlinked_file_b = AssertableFilePath::from_path(
-        empty_harness
-            .root
-            .child_path("symlinked-directory")
-            .join(harness.file_b.path().file_name().unwrap()),
+    assert!(plan.operations.iter().any(|operation| matches!(
+        operation,
+        PlannedOperation::CreateDirectory {
+            target_action: PlannedTargetAction::Create,
+            ..
+        }
+    )));
+
+    let expected_bytes_to_copy = [
+        &harness.file_b,
+        &harness.file_c,
+        &harness.file_d,
+        &harness.file_e,
+        &harness.file_f,
+    ]
+    .iter()
+    .map(|file| std::fs::metadata(file.path()).unwrap().len())
+    .sum::<u64>();
+
+    assert_eq!(plan.total_bytes_to_copy, expected_bytes_to_copy);
+    assert_eq!(finished_copy.num_existing_files_skipped, 1);
+
+    AssertableFilePath::from_path(existing_target_file_path).assert_content_matches_str("existing");
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_plan_does_not_report_plan_on_collision() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let existing_target_file_path = empty_harness.root.child_path("file_a.bin");
+    std::fs::write(&existing_target_file_path, "existing")?;
+
+    let mut plan_was_reported = false;
+
+    let copy_result = fs_more::directory::copy_directory_with_plan(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_files: false,
+                overwrite_existing_subdirectories: false,
+            },
+            ..Default::default()
+        },
+        |_| plan_was_reported = true,
     );
-    previously_symlinked_file_b.assert_is_file();
-    previously_symlinked_file_b.assert_content_matches_file(harness.file_b.path());
 
-    let previously_symlinked_file_c = AssertableFilePath::from_path(
-        empty_harness
-            .root
-            .child_path("symlinked-directory")
-            .join(harness.dir_bar.path().file_name().unwrap())
-            .join(harness.file_c.path().file_name().unwrap()),
+    assert_matches!(
+        copy_result.unwrap_err(),
+        DirectoryError::TargetItemAlreadyExists { path }
+            if path == existing_target_file_path
     );
-    previously_symlinked_file_c.assert_not_exists();
+    assert!(!plan_was_reported);
+
+    assert!(!empty_harness.root.child_path("dir_foo").exists());
+    AssertableFilePath::from_path(existing_target_file_path).assert_content_matches_str("existing");
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_progress_times_out_between_operations() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let copy_result = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            timeout: Some(Duration::ZERO),
+            ..Default::default()
+        },
+        |_| {},
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(DirectoryError::TimedOut { progress })
+        if progress.files_copied == 0 && progress.bytes_finished == 0
+    );
+
+    empty_harness.root.assert_is_empty();
+
+
+    // A slow operation exceeds the timeout, but is still allowed to finish.
+    let empty_harness_slow = EmptyTreeHarness::new()?;
+
+    let copy_result = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness_slow.root.path(),
+        DirectoryCopyWithProgressOptions {
+            timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        },
+        |progress| {
+            if progress.current_operation_index == 0 {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        },
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(DirectoryError::TimedOut { progress })
+        if progress.current_operation_index == 0
+            && progress.files_copied + progress.directories_created == 1
+    );
+
+
+    let empty_harness_unlimited = EmptyTreeHarness::new()?;
+
+    fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness_unlimited.root.path(),
+        DirectoryCopyWithProgressOptions {
+            timeout: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        },
+        |_| {},
+    )
+    .unwrap();
+
+    empty_harness_unlimited
+        .root
+        .assert_directory_contents_match_directory(harness.root.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    empty_harness_slow.destroy()?;
+    empty_harness_unlimited.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_progress_in_sorted_order() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let mut copied_file_paths: Vec<PathBuf> = Vec::new();
+
+    fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            traversal_order: TraversalOrder::ByNameAscending,
+            ..Default::default()
+        },
+        |progress| {
+            if let DirectoryCopyOperation::CopyingFile { target_path, .. } =
+                &progress.current_operation
+            {
+                if copied_file_paths.last() != Some(target_path) {
+                    copied_file_paths.push(target_path.clone());
+                }
+            }
+        },
+    )
+    .unwrap();
+
+    let expected_file_paths: Vec<PathBuf> = [
+        "file_a.bin",
+        "dir_foo/file_b.bin",
+        "dir_foo/dir_bar/file_c.bin",
+        "dir_foo/dir_bar/hello/world/file_d.bin",
+        "dir_foo/dir_bar/hello/world/file_e.bin",
+        "dir_foo/dir_bar/hello/world/file_f.bin",
+    ]
+    .iter()
+    .map(|relative_path| empty_harness.root.child_path(relative_path))
+    .collect();
+
+    assert_eq!(copied_file_paths, expected_file_paths);
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_progress_creates_directories_before_their_contents() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let mut created_directories: Vec<PathBuf> = Vec::new();
+
+    fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions::default(),
+        |progress| {
+            let target_path = match &progress.current_operation {
+                DirectoryCopyOperation::CreatingDirectory { target_path } => target_path,
+                DirectoryCopyOperation::CopyingFile { target_path, .. } => target_path,
+                DirectoryCopyOperation::RecreatingSpecialFile { target_path } => target_path,
+                DirectoryCopyOperation::RecreatingBrokenSymlink { target_path } => target_path,
+                DirectoryCopyOperation::RecreatingSymlink { target_path } => target_path,
+                DirectoryCopyOperation::RecreatingJunction { target_path } => target_path,
+            };
+
+            let parent_directory = target_path.parent().unwrap();
+            assert!(
+                parent_directory == empty_harness.root.path()
+                    || created_directories
+                        .iter()
+                        .any(|directory| directory == parent_directory),
+                "copy_directory_with_progress did not create {} before its contents",
+                parent_directory.display()
+            );
+
+            if let DirectoryCopyOperation::CreatingDirectory { target_path } =
+                &progress.current_operation
+            {
+                created_directories.push(target_path.clone());
+            }
+        },
+    )
+    .unwrap_or_else(|error| {
+        panic!(
+            "copy_directory_with_progress unexpectedly failed with Err: {}",
+            error
+        );
+    });
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_progress_respect_depth_option() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    // A copy depth counts one level more than a scan depth (entries in the root are at copy depth 1).
+    const MAXIMUM_SCAN_DEPTH: Option<usize> = Some(2);
+    const MAXIMUM_COPY_DEPTH: Option<usize> = Some(3);
+
+    let source_scan = DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            maximum_scan_depth: MAXIMUM_SCAN_DEPTH,
+            follow_symbolic_links: false,
+            ..Default::default()
+        },
+    )
+    .expect("failed to scan temporary directory");
+    let source_full_size = source_scan
+        .total_size_in_bytes()
+        .expect("failed to compute size of source directory in bytes");
+
+    empty_harness.root.assert_is_empty();
+
+    fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            maximum_copy_depth: MAXIMUM_COPY_DEPTH,
+            ..Default::default()
+        },
+        |_| {},
+    )
+    .unwrap_or_else(|error| {
+        panic!(
+            "copy_directory_with_progress unexpectedly failed with Err: {}",
+            error
+        );
+    });
+
+    let target_scan = DirectoryScan::scan_with_options(
+        empty_harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .expect("failed to scan target temporary directory");
+    let target_full_size = target_scan
+        .total_size_in_bytes()
+        .expect("failed to compute size of target directory in bytes");
+
+    assert_eq!(
+        source_full_size, target_full_size,
+        "copy_directory_with_progress did not create an equally-sized directory copy"
+    );
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn error_on_copy_directory_with_progress_on_existing_file_without_option() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    // Still the harness setup.
+    let file_a_filename = harness.file_a.path().file_name().unwrap();
+    let test_file_path = empty_harness.root.child_path(file_a_filename);
+    fs_more::file::copy_file(
+        harness.file_a.path(),
+        &test_file_path,
+        FileCopyOptions {
+            overwrite_existing: false,
+            skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
+        },
+    )
+    .unwrap();
+
+    let test_file = AssertableFilePath::from_path_with_captured_content(test_file_path)?;
+
+    test_file.assert_exists();
+    test_file.assert_content_unchanged();
+    // End of setup, we have now pre-copied a single file to test our overwriting options.
+
+
+    empty_harness.root.assert_is_not_empty();
+
+    let copy_result = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_files: false,
+                overwrite_existing_subdirectories: true,
+            },
+            ..Default::default()
+        },
+        |_| {},
+    );
+
+    assert!(
+        copy_result.is_err(),
+        "copy_directory_with_progress should have errored due to existing target file"
+    );
+
+    let copy_err = copy_result.unwrap_err();
+    match &copy_err {
+        DirectoryError::TargetItemAlreadyExists { path } => {
+            assert_eq!(
+                path,
+                test_file.path(),
+                "copy_directory_with_progress returned TargetItemAlreadyExists with incorrect inner path"
+            );
+        }
+        _ => {
+            panic!("copy_directory_with_progress should have errored with TargetItemAlreadyExists")
+        }
+    }
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn error_on_copy_directory_with_progress_on_existing_directory_without_option() -> TestResult<()>
+{
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    // Still the harness setup.
+    let replicated_foo_dir_name = harness.dir_foo.path().file_name().unwrap();
+    let replicated_foo_dir_path = empty_harness.root.child_path(replicated_foo_dir_name);
+    std::fs::create_dir_all(&replicated_foo_dir_path)?;
+    // End of setup, we have now pre-copied a single file to test our overwriting options.
+
+
+    empty_harness.root.assert_is_not_empty();
+
+    let copy_result = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_files: true,
+                overwrite_existing_subdirectories: false,
+            },
+            ..Default::default()
+        },
+        |_| {},
+    );
+
+    assert!(
+        copy_result.is_err(),
+        "copy_directory_with_progress should have errored due to existing target file"
+    );
+
+    let copy_err = copy_result.unwrap_err();
+    match &copy_err {
+        DirectoryError::TargetItemAlreadyExists { path } => {
+            assert_eq!(
+                path,
+                &replicated_foo_dir_path,
+                "copy_directory_with_progress returned TargetItemAlreadyExists with incorrect inner path"
+            );
+        }
+        _ => {
+            panic!("copy_directory_with_progress should have errored with TargetItemAlreadyExists")
+        }
+    }
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+
+#[test]
+pub fn disallow_copy_directory_into_itself() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: true,
+                overwrite_existing_files: true,
+            },
+            ..Default::default()
+        },
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(DirectoryError::InvalidTargetDirectoryPath),
+        "copy_directory should have errored when trying to copy a directory into itself"
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn disallow_copy_directory_into_subdirectory_of_itself() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        harness.dir_world.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: true,
+                overwrite_existing_files: true,
+            },
+            ..Default::default()
+        },
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(DirectoryError::InvalidTargetDirectoryPath),
+        "copy_directory should have errored when trying to \
+        copy a directory into a subdirectory of itself"
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn disallow_copy_directory_with_progress_into_itself() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let copy_result = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: true,
+                overwrite_existing_files: true,
+            },
+            ..Default::default()
+        },
+        |_| {},
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(DirectoryError::InvalidTargetDirectoryPath),
+        "copy_directory_with_progress should have errored when trying to \
+        copy a directory into itself"
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn disallow_copy_directory_with_progress_into_subdirectory_of_itself() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let copy_result = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        harness.dir_world.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: true,
+                overwrite_existing_files: true,
+            },
+            ..Default::default()
+        },
+        |_| {},
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(DirectoryError::InvalidTargetDirectoryPath),
+        "copy_directory_with_progress should have errored when trying to \
+        copy a directory into a subdirectory of itself"
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn error_on_copy_directory_on_existing_target_directory_without_option() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    empty_harness.root.assert_is_empty();
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::DisallowExisting,
+            ..Default::default()
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+    match &copy_err {
+        DirectoryError::TargetItemAlreadyExists { path } => {
+            assert_eq!(
+                path,
+                empty_harness.root.path(),
+                "copy_directory did not return the correct path \
+                inside the TargetItemAlreadyExists error"
+            );
+        }
+        _ => panic!("Unexpected Err value: {}", copy_err),
+    }
+
+    empty_harness.root.assert_is_empty();
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn error_on_copy_directory_on_existing_file_without_option() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    // Still the harness setup.
+    let file_a_filename = harness.file_a.path().file_name().unwrap();
+    let test_file_path = empty_harness.root.child_path(file_a_filename);
+    fs_more::file::copy_file(
+        harness.file_a.path(),
+        &test_file_path,
+        FileCopyOptions {
+            overwrite_existing: false,
+            skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
+        },
+    )
+    .unwrap();
+
+    let test_file = AssertableFilePath::from_path_with_captured_content(test_file_path)?;
+
+    test_file.assert_exists();
+    test_file.assert_content_unchanged();
+    // End of setup, we have now pre-copied a single file to test our overwriting options.
+
+
+    empty_harness.root.assert_is_not_empty();
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_files: false,
+                overwrite_existing_subdirectories: false,
+            },
+            ..Default::default()
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+    match &copy_err {
+        DirectoryError::TargetItemAlreadyExists { path } => {
+            assert_eq!(
+                path,
+                test_file.path(),
+                "copy_directory did not return the correct path \
+                inside the TargetItemAlreadyExists error"
+            );
+        }
+        _ => panic!("Unexpected Err value: {}", copy_err),
+    }
+
+    empty_harness.root.assert_is_not_empty();
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn error_on_copy_directory_on_existing_subdirectory_without_option() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    // Still the harness setup.
+    let replicated_foo_dir_name = harness.dir_foo.path().file_name().unwrap();
+    let replicated_foo_dir_path = empty_harness.root.child_path(replicated_foo_dir_name);
+    std::fs::create_dir_all(&replicated_foo_dir_path)?;
+
+    let file_b_filename = harness.file_b.path().file_name().unwrap();
+    let replicated_file_b_path = empty_harness.root.child_path(file_b_filename);
+    fs_more::file::copy_file(
+        harness.file_b.path(),
+        &replicated_file_b_path,
+        FileCopyOptions {
+            overwrite_existing: false,
+            skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
+        },
+    )
+    .unwrap();
+
+    let replicated_file_b =
+        AssertableFilePath::from_path_with_captured_content(replicated_file_b_path)?;
+
+    replicated_file_b.assert_exists();
+    replicated_file_b.assert_content_unchanged();
+    // End of setup, we have now pre-copied a single directory containing
+    // a single file to test our overwriting options.
+
+
+    empty_harness.root.assert_is_not_empty();
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_files: true,
+                overwrite_existing_subdirectories: false,
+            },
+            ..Default::default()
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+    match &copy_err {
+        DirectoryError::TargetItemAlreadyExists { path } => {
+            assert_eq!(
+                path, &replicated_foo_dir_path,
+                "copy_directory did not return the correct path \
+                inside the TargetItemAlreadyExists error"
+            );
+        }
+        _ => panic!("Unexpected Err value: {}", copy_err),
+    }
+
+    empty_harness.root.assert_is_not_empty();
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_symbolic_link_to_file_behaviour() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let symlinked_file =
+        AssertableFilePath::from_path(harness.root.child_path("file_a-symlinked.bin"));
+    symlinked_file.assert_not_exists();
+    symlinked_file.symlink_to_file(harness.file_a.path())?;
+    symlinked_file.assert_is_symlink_to_file();
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions::default(),
+    )
+    .unwrap();
+
+    let previously_symlinked_file_in_target =
+        AssertableFilePath::from_path(empty_harness.root.child_path("file_a-symlinked.bin"));
+    previously_symlinked_file_in_target.assert_exists();
+    previously_symlinked_file_in_target.assert_is_file();
+
+    empty_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn copy_directory_with_progress_symbolic_link_to_file_behaviour() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let symlinked_file =
+        AssertableFilePath::from_path(harness.root.child_path("file_a-symlinked.bin"));
+    symlinked_file.assert_not_exists();
+    symlinked_file.symlink_to_file(harness.file_a.path())?;
+    symlinked_file.assert_is_symlink_to_file();
+
+    fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions::default(),
+        |_| {},
+    )
+    .unwrap();
+
+    let previously_symlinked_file_in_target =
+        AssertableFilePath::from_path(empty_harness.root.child_path("file_a-symlinked.bin"));
+    previously_symlinked_file_in_target.assert_exists();
+    previously_symlinked_file_in_target.assert_is_file();
+
+    empty_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_symbolic_link_to_directory_behaviour() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let symlinked_dir =
+        AssertableDirectoryPath::from_path(harness.root.child_path("symlinked-directory"));
+    symlinked_dir.assert_not_exists();
+    symlinked_dir.symlink_to_directory(harness.dir_foo.path())?;
+    symlinked_dir.assert_is_symlink_to_directory();
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions::default(),
+    )
+    .unwrap();
+
+    let previously_symlinked_dir_in_target =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("symlinked-directory"));
+    previously_symlinked_dir_in_target.assert_exists();
+    previously_symlinked_dir_in_target
+        .assert_directory_contents_match_directory(harness.dir_foo.path());
+
+    empty_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn copy_directory_with_progress_symbolic_link_to_directory_behaviour() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let symlinked_dir =
+        AssertableDirectoryPath::from_path(harness.root.child_path("symlinked-directory"));
+    symlinked_dir.assert_not_exists();
+    symlinked_dir.symlink_to_directory(harness.dir_foo.path())?;
+    symlinked_dir.assert_is_symlink_to_directory();
+
+    fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions::default(),
+        |_| {},
+    )
+    .unwrap();
+
+    let previously_symlinked_dir_in_target =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("symlinked-directory"));
+    previously_symlinked_dir_in_target.assert_exists();
+    previously_symlinked_dir_in_target
+        .assert_directory_contents_match_directory(harness.dir_foo.path());
+
+    empty_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn copy_directory_symbolic_link_to_directory_respect_depth_limit() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let symlinked_dir =
+        AssertableDirectoryPath::from_path(harness.root.child_path("symlinked-directory"));
+    symlinked_dir.assert_not_exists();
+    symlinked_dir.symlink_to_directory(harness.dir_foo.path())?;
+    symlinked_dir.assert_is_symlink_to_directory();
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            maximum_copy_depth: Some(2),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let previously_symlinked_dir_in_target =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("symlinked-directory"));
+    previously_symlinked_dir_in_target.assert_exists();
+
+    let previously_symlinked_file_b = AssertableFilePath::from_path(
+        empty_harness
+            .root
+            .child_path("symlinked-directory")
+            .join(harness.file_b.path().file_name().unwrap()),
+    );
+    previously_symlinked_file_b.assert_is_file();
+    previously_symlinked_file_b.assert_content_matches_file(harness.file_b.path());
+
+    let previously_symlinked_file_c = AssertableFilePath::from_path(
+        empty_harness
+            .root
+            .child_path("symlinked-directory")
+            .join(harness.dir_bar.path().file_name().unwrap())
+            .join(harness.file_c.path().file_name().unwrap()),
+    );
+    previously_symlinked_file_c.assert_not_exists();
+
+    empty_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn copy_directory_with_progress_symbolic_link_to_directory_respect_depth_limit(
+) -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let symlinked_dir =
+        AssertableDirectoryPath::from_path(harness.root.child_path("symlinked-directory"));
+    symlinked_dir.assert_not_exists();
+    symlinked_dir.symlink_to_directory(harness.dir_foo.path())?;
+    symlinked_dir.assert_is_symlink_to_directory();
+
+    fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            maximum_copy_depth: Some(2),
+            ..Default::default()
+        },
+        |_| {},
+    )
+    .unwrap();
+
+    let previously_symlinked_dir_in_target =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("symlinked-directory"));
+    previously_symlinked_dir_in_target.assert_exists();
+
+    let previously_symlinked_file_b = AssertableFilePath::from_path(
+        empty_harness
+            .root
+            .child_path("symlinked-directory")
+            .join(harness.file_b.path().file_name().unwrap()),
+    );
+    previously_symlinked_file_b.assert_is_file();
+    previously_symlinked_file_b.assert_content_matches_file(harness.file_b.path());
+
+    let previously_symlinked_file_c = AssertableFilePath::from_path(
+        empty_harness
+            .root
+            .child_path("symlinked-directory")
+            .join(harness.dir_bar.path().file_name().unwrap())
+            .join(harness.file_c.path().file_name().unwrap()),
+    );
+    previously_symlinked_file_c.assert_not_exists();
+
+    empty_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_preemptively_check_for_directory_collisions() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+    empty_harness.root.assert_is_empty();
+
+    // Target directory preparation.
+    let existing_target_file = AssertableFilePath::from_path(
+        empty_harness.root.path().join(
+            harness
+                .file_d
+                .path()
+                .strip_prefix(harness.root.path())
+                .unwrap(),
+        ),
+    );
+
+    let non_existing_target_file = AssertableFilePath::from_path(
+        empty_harness.root.path().join(
+            harness
+                .file_a
+                .path()
+                .strip_prefix(harness.root.path())
+                .unwrap(),
+        ),
+    );
+
+    std::fs::create_dir_all(existing_target_file.path().parent().unwrap()).unwrap();
+    std::fs::copy(harness.file_d.path(), existing_target_file.path()).unwrap();
+
+    existing_target_file.assert_content_matches_file(harness.file_d.path());
+    non_existing_target_file.assert_not_exists();
+
+    empty_harness.root.assert_is_not_empty();
+    // END of preparation
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: false,
+                overwrite_existing_files: false,
+            },
+            ..Default::default()
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+
+    match copy_err {
+        DirectoryError::TargetItemAlreadyExists { path } => {
+            assert_eq!(
+                path.as_path(),
+                empty_harness.root.path()
+                    .join(
+                        harness.dir_foo.path()
+                            .strip_prefix(harness.root.path())
+                            .unwrap()
+                    ),
+                "copy_directory did not return the proper directory collision in the target directory"
+            );
+        }
+        _ => panic!("Unexpected Err: {}", copy_err),
+    }
+
+    empty_harness.root.assert_is_not_empty();
+
+    non_existing_target_file.assert_not_exists();
+    existing_target_file.assert_content_matches_file(harness.file_d.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn copy_directory_preemptively_check_for_file_collisions() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+    empty_harness.root.assert_is_empty();
+
+    // Target directory preparation.
+    let existing_target_file = AssertableFilePath::from_path(
+        empty_harness.root.path().join(
+            harness
+                .file_d
+                .path()
+                .strip_prefix(harness.root.path())
+                .unwrap(),
+        ),
+    );
+
+    let non_existing_target_file = AssertableFilePath::from_path(
+        empty_harness.root.path().join(
+            harness
+                .file_a
+                .path()
+                .strip_prefix(harness.root.path())
+                .unwrap(),
+        ),
+    );
+
+    std::fs::create_dir_all(existing_target_file.path().parent().unwrap()).unwrap();
+    std::fs::copy(harness.file_d.path(), existing_target_file.path()).unwrap();
+
+    existing_target_file.assert_content_matches_file(harness.file_d.path());
+    non_existing_target_file.assert_not_exists();
+
+    empty_harness.root.assert_is_not_empty();
+    // END of preparation
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: true,
+                overwrite_existing_files: false,
+            },
+            ..Default::default()
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+
+    match copy_err {
+        DirectoryError::TargetItemAlreadyExists { path } => {
+            assert_eq!(
+                path.as_path(),
+                existing_target_file.path(),
+                "copy_directory did not return the proper file collision in the target directory"
+            );
+        }
+        _ => panic!("Unexpected Err: {}", copy_err),
+    }
+
+    empty_harness.root.assert_is_not_empty();
+
+    non_existing_target_file.assert_not_exists();
+    existing_target_file.assert_content_matches_file(harness.file_d.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_without_pre_scan() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let source_scan = DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .expect("failed to scan temporary directory");
+    let source_full_size = source_scan
+        .total_size_in_bytes()
+        .expect("failed to compute size of source directory in bytes");
+
+    empty_harness.root.assert_is_empty();
+
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            pre_scan: false,
+            ..Default::default()
+        },
+    )
+    .unwrap_or_else(|error| {
+        panic!(
+            "copy_directory unexpectedly failed with Err: {}",
+            error
+        );
+    });
+
+
+    assert_eq!(
+        source_full_size, finished_copy.total_bytes_copied,
+        "DirectoryScan and copy_directory report different amount of bytes"
+    );
+
+    assert_eq!(
+        source_scan.files().len(),
+        finished_copy.num_files_copied,
+        "DirectoryScan and copy_directory report different number of files"
+    );
+
+    harness
+        .root
+        .assert_directory_contents_match_directory(empty_harness.root.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_without_pre_scan_leaves_earlier_files_on_late_collision() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+    empty_harness.root.assert_is_empty();
+
+    // Target directory preparation.
+    let existing_target_file = AssertableFilePath::from_path(
+        empty_harness.root.path().join(
+            harness
+                .file_d
+                .path()
+                .strip_prefix(harness.root.path())
+                .unwrap(),
+        ),
+    );
+
+    // Files directly in the source root are always copied before
+    // any file in a subdirectory is even discovered.
+    let earlier_target_file = AssertableFilePath::from_path(
+        empty_harness.root.path().join(
+            harness
+                .file_a
+                .path()
+                .strip_prefix(harness.root.path())
+                .unwrap(),
+        ),
+    );
+
+    std::fs::create_dir_all(existing_target_file.path().parent().unwrap()).unwrap();
+    std::fs::copy(harness.file_d.path(), existing_target_file.path()).unwrap();
+
+    existing_target_file.assert_content_matches_file(harness.file_d.path());
+    earlier_target_file.assert_not_exists();
+    // END of preparation
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: true,
+                overwrite_existing_files: false,
+            },
+            pre_scan: false,
+            ..Default::default()
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+
+    match copy_err {
+        DirectoryError::TargetItemAlreadyExists { path } => {
+            assert_eq!(
+                path.as_path(),
+                existing_target_file.path(),
+                "copy_directory did not return the proper file collision in the target directory"
+            );
+        }
+        _ => panic!("Unexpected Err: {}", copy_err),
+    }
+
+    earlier_target_file.assert_content_matches_file(harness.file_a.path());
+    existing_target_file.assert_content_matches_file(harness.file_d.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_progress_preemptively_check_for_directory_collisions() -> TestResult<()>
+{
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+    empty_harness.root.assert_is_empty();
+
+    // Target directory preparation.
+    let existing_target_file = AssertableFilePath::from_path(
+        empty_harness.root.path().join(
+            harness
+                .file_d
+                .path()
+                .strip_prefix(harness.root.path())
+                .unwrap(),
+        ),
+    );
+
+    let non_existing_target_file = AssertableFilePath::from_path(
+        empty_harness.root.path().join(
+            harness
+                .file_a
+                .path()
+                .strip_prefix(harness.root.path())
+                .unwrap(),
+        ),
+    );
+
+    std::fs::create_dir_all(existing_target_file.path().parent().unwrap()).unwrap();
+    std::fs::copy(harness.file_d.path(), existing_target_file.path()).unwrap();
+
+    existing_target_file.assert_content_matches_file(harness.file_d.path());
+    non_existing_target_file.assert_not_exists();
+
+    empty_harness.root.assert_is_not_empty();
+    // END of preparation
+
+    let mut last_progress: Option<DirectoryCopyProgress> = None;
+
+    let copy_result = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: false,
+                overwrite_existing_files: false,
+            },
+            ..Default::default()
+        },
+        |progress| {
+            last_progress = Some(progress.clone());
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+
+    assert!(
+        last_progress.is_none(),
+        "copy_directory_with_progress did not check for directory collisions before starting copy"
+    );
+
+    match copy_err {
+        DirectoryError::TargetItemAlreadyExists { path } => {
+            assert_eq!(
+                path.as_path(),
+                empty_harness.root.path()
+                    .join(
+                        harness.dir_foo.path()
+                            .strip_prefix(harness.root.path())
+                            .unwrap()
+                    ),
+                "copy_directory did not return the proper directory collision in the target directory"
+            );
+        }
+        _ => panic!("Unexpected Err: {}", copy_err),
+    }
+
+    empty_harness.root.assert_is_not_empty();
+
+    non_existing_target_file.assert_not_exists();
+    existing_target_file.assert_content_matches_file(harness.file_d.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn copy_directory_with_progress_preemptively_check_for_file_collisions() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+    empty_harness.root.assert_is_empty();
+
+    // Target directory preparation.
+    let existing_target_file = AssertableFilePath::from_path(
+        empty_harness.root.path().join(
+            harness
+                .file_d
+                .path()
+                .strip_prefix(harness.root.path())
+                .unwrap(),
+        ),
+    );
+
+    let non_existing_target_file = AssertableFilePath::from_path(
+        empty_harness.root.path().join(
+            harness
+                .file_a
+                .path()
+                .strip_prefix(harness.root.path())
+                .unwrap(),
+        ),
+    );
+
+    std::fs::create_dir_all(existing_target_file.path().parent().unwrap()).unwrap();
+    std::fs::copy(harness.file_d.path(), existing_target_file.path()).unwrap();
+
+    existing_target_file.assert_content_matches_file(harness.file_d.path());
+    non_existing_target_file.assert_not_exists();
+
+    empty_harness.root.assert_is_not_empty();
+    // END of preparation
+
+    let mut last_progress: Option<DirectoryCopyProgress> = None;
+
+    let copy_result = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: true,
+                overwrite_existing_files: false,
+            },
+            ..Default::default()
+        },
+        |progress| {
+            last_progress = Some(progress.clone());
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+
+    assert!(
+        last_progress.is_none(),
+        "copy_directory_with_progress did not check for directory collisions before starting copy"
+    );
+
+    match copy_err {
+        DirectoryError::TargetItemAlreadyExists { path } => {
+            assert_eq!(
+                path.as_path(),
+                existing_target_file.path(),
+                "copy_directory did not return the proper file collision in the target directory"
+            );
+        }
+        _ => panic!("Unexpected Err: {}", copy_err),
+    }
+
+    empty_harness.root.assert_is_not_empty();
+
+    non_existing_target_file.assert_not_exists();
+    existing_target_file.assert_content_matches_file(harness.file_d.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn disallow_copy_directory_when_source_is_symlink_to_target() -> TestResult<()> {
+    // Tests behaviour when copying "symlink to directory A" to "A".
+    // This should fail.
+
+    let harness_for_comparison = DeepTreeHarness::new()?;
+    let harness = DeepTreeHarness::new()?;
+    let intermediate_harness = EmptyTreeHarness::new()?;
+
+    // Directory symlink preparation
+    let symlink_to_directory = AssertableDirectoryPath::from_path(
+        intermediate_harness.root.child_path("symlinked-directory"),
+    );
+
+    symlink_to_directory.assert_not_exists();
+    symlink_to_directory
+        .symlink_to_directory(harness.root.path())
+        .unwrap();
+    // END of preparation
+
+    let copy_result = fs_more::directory::copy_directory(
+        symlink_to_directory.path(),
+        harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: true,
+                overwrite_existing_files: true,
+            },
+            ..Default::default()
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+
+    match &copy_err {
+        DirectoryError::InvalidTargetDirectoryPath => {
+            // This is the expected error value.
+        }
+        _ => panic!("Unexpected Err: {}", copy_err),
+    }
+
+    harness
+        .root
+        .assert_directory_contents_match_directory(harness_for_comparison.root.path());
+
+
+    harness_for_comparison.destroy()?;
+    harness.destroy()?;
+    intermediate_harness.destroy()?;
+
+    Ok(())
+}
+
+
+#[test]
+pub fn disallow_copy_directory_with_progress_when_source_is_symlink_to_target() -> TestResult<()> {
+    // Tests behaviour when copying "symlink to directory A" to "A".
+    // This should fail.
+
+    let harness_for_comparison = DeepTreeHarness::new()?;
+    let harness = DeepTreeHarness::new()?;
+    let intermediate_harness = EmptyTreeHarness::new()?;
+
+    // Directory symlink preparation
+    let symlink_to_directory = AssertableDirectoryPath::from_path(
+        intermediate_harness.root.child_path("symlinked-directory"),
+    );
+
+    symlink_to_directory.assert_not_exists();
+    symlink_to_directory
+        .symlink_to_directory(harness.root.path())
+        .unwrap();
+    // END of preparation
+
+    let mut last_progress: Option<DirectoryCopyProgress> = None;
+
+    let copy_result = fs_more::directory::copy_directory_with_progress(
+        symlink_to_directory.path(),
+        harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: true,
+                overwrite_existing_files: true,
+            },
+            ..Default::default()
+        },
+        |progress| {
+            last_progress = Some(progress.clone());
+        },
+    );
+
+    assert!(last_progress.is_none());
+
+    let copy_err = copy_result.unwrap_err();
+
+    match &copy_err {
+        DirectoryError::InvalidTargetDirectoryPath => {
+            // This is the expected error value.
+        }
+        _ => panic!("Unexpected Err: {}", copy_err),
+    }
+
+    harness
+        .root
+        .assert_directory_contents_match_directory(harness_for_comparison.root.path());
+
+
+    harness_for_comparison.destroy()?;
+    harness.destroy()?;
+    intermediate_harness.destroy()?;
+
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn copy_directory_skips_special_files_by_default() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    // Binding a Unix domain socket leaves a socket file behind, which is a special file.
+    let socket_path = harness.root.child_path("socket");
+    let _listener = std::os::unix::net::UnixListener::bind(&socket_path)?;
+
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_special_files_skipped, 1);
+    assert_eq!(finished_copy.num_special_files_recreated, 0);
+
+    assert!(!empty_harness.root.child_path("socket").exists());
+
+    harness
+        .root
+        .assert_directory_contents_match_directory(empty_harness.root.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+pub fn copy_directory_errors_on_special_file_when_configured() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let socket_path = harness.root.child_path("socket");
+    let _listener = std::os::unix::net::UnixListener::bind(&socket_path)?;
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            special_file_behaviour: fs_more::directory::SpecialFileBehaviour::Error,
+            ..Default::default()
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+    assert_matches!(
+        &copy_err,
+        DirectoryError::SourceContainsSpecialFile { path } if path.ends_with("socket"),
+        "copy_directory should have errored with SourceContainsSpecialFile, got {} instead",
+        copy_err
+    );
+
+    empty_harness.root.assert_is_empty();
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+pub fn copy_directory_recreates_special_files_when_configured() -> TestResult<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let socket_path = harness.root.child_path("socket");
+    let _listener = std::os::unix::net::UnixListener::bind(&socket_path)?;
+
+    let finished_copy = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            special_file_behaviour: fs_more::directory::SpecialFileBehaviour::Recreate,
+            ..Default::default()
+        },
+        |_| {},
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_special_files_recreated, 1);
+    assert_eq!(finished_copy.num_special_files_skipped, 0);
+
+    let recreated_socket_metadata =
+        std::fs::symlink_metadata(empty_harness.root.child_path("socket"))?;
+    assert!(recreated_socket_metadata.file_type().is_socket());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+pub fn copy_directory_errors_on_broken_symlink_by_default() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    std::os::unix::fs::symlink(
+        harness.root.child_path("missing.bin"),
+        harness.root.child_path("broken-link"),
+    )?;
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions::default(),
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(DirectoryError::SourceContainsBrokenSymlink { path }) if path.ends_with("broken-link"),
+        "copy_directory should have errored with SourceContainsBrokenSymlink"
+    );
+
+    empty_harness.root.assert_is_empty();
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+pub fn copy_directory_skips_or_recreates_broken_symlinks_when_configured() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let missing_destination_path = harness.root.child_path("missing.bin");
+
+    std::os::unix::fs::symlink(
+        &missing_destination_path,
+        harness.root.child_path("broken-link"),
+    )?;
+
+
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            broken_symlink_behaviour: fs_more::directory::BrokenSymlinkBehaviour::Skip,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_broken_symlinks_skipped, 1);
+    assert_eq!(finished_copy.num_broken_symlinks_recreated, 0);
+    assert!(std::fs::symlink_metadata(empty_harness.root.child_path("broken-link")).is_err());
+
+    empty_harness.destroy()?;
+
+
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let finished_copy = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            broken_symlink_behaviour: fs_more::directory::BrokenSymlinkBehaviour::CopyAsSymlink,
+            ..Default::default()
+        },
+        |_| {},
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_broken_symlinks_skipped, 0);
+    assert_eq!(finished_copy.num_broken_symlinks_recreated, 1);
+    assert_eq!(
+        std::fs::read_link(empty_harness.root.child_path("broken-link"))?,
+        missing_destination_path
+    );
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn copy_directory_follows_only_root_symlink_when_configured() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    std::os::unix::fs::symlink(
+        "file_a.bin",
+        harness.root.child_path("file_a-link"),
+    )?;
+    std::os::unix::fs::symlink(
+        harness.dir_foo.path(),
+        harness.root.child_path("dir_foo-link"),
+    )?;
+
+    let link_harness = EmptyTreeHarness::new()?;
+    let linked_source_path = link_harness.root.child_path("linked-source");
+    std::os::unix::fs::symlink(harness.root.path(), &linked_source_path)?;
+
+
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let finished_copy = fs_more::directory::copy_directory(
+        &linked_source_path,
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            follow_root_symlink_only: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_symlinks_recreated, 2);
+
+    let empty_harness_with_progress = EmptyTreeHarness::new()?;
+    let mut num_recreating_operations = 0;
+
+    let finished_copy_with_progress = fs_more::directory::copy_directory_with_progress(
+        &linked_source_path,
+        empty_harness_with_progress.root.path(),
+        DirectoryCopyWithProgressOptions {
+            follow_root_symlink_only: true,
+            ..Default::default()
+        },
+        |progress| {
+            if let DirectoryCopyOperation::RecreatingSymlink { .. } = progress.current_operation {
+                num_recreating_operations += 1;
+            }
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        finished_copy_with_progress.num_symlinks_recreated,
+        2
+    );
+    assert!(num_recreating_operations >= 2);
+    assert_eq!(
+        std::fs::read_link(empty_harness_with_progress.root.child_path("file_a-link"))?,
+        PathBuf::from("file_a.bin")
+    );
+
+    empty_harness_with_progress.destroy()?;
+
+    // The root link was followed, so the directory contents were copied.
+    assert!(empty_harness.root.child_path("file_a.bin").is_file());
+    assert!(empty_harness
+        .root
+        .child_path("dir_foo")
+        .join("file_b.bin")
+        .is_file());
+
+    // Nested links were recreated as they were.
+    assert_eq!(
+        std::fs::read_link(empty_harness.root.child_path("file_a-link"))?,
+        PathBuf::from("file_a.bin")
+    );
+    assert_eq!(
+        std::fs::read_link(empty_harness.root.child_path("dir_foo-link"))?,
+        harness.dir_foo.path()
+    );
+
+    empty_harness.destroy()?;
+
+
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let finished_copy = fs_more::directory::copy_directory(
+        &linked_source_path,
+        empty_harness.root.path(),
+        DirectoryCopyOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_symlinks_recreated, 0);
+    assert!(empty_harness
+        .root
+        .child_path("file_a-link")
+        .symlink_metadata()?
+        .is_file());
+    assert!(empty_harness
+        .root
+        .child_path("dir_foo-link")
+        .symlink_metadata()?
+        .is_dir());
+
+
+    harness.destroy()?;
+    link_harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn map_source_to_target_matches_copied_paths() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let mapped_file_b_path = fs_more::directory::map_source_to_target(
+        harness.root.path(),
+        empty_harness.root.path(),
+        harness.file_b.path(),
+    )
+    .unwrap();
+
+    assert!(!mapped_file_b_path.exists());
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions::default(),
+    )
+    .unwrap();
+
+    assert!(mapped_file_b_path.is_file());
+    harness
+        .file_b
+        .assert_content_matches_file(&mapped_file_b_path);
+
+    assert_eq!(
+        fs_more::directory::map_source_to_target(
+            harness.root.path(),
+            empty_harness.root.path(),
+            harness.root.path(),
+        )
+        .unwrap(),
+        empty_harness.root.path()
+    );
+
+    let escaping_path = empty_harness.root.child_path("file.bin");
+    let mapping_result = fs_more::directory::map_source_to_target(
+        harness.root.path(),
+        empty_harness.root.path(),
+        &escaping_path,
+    );
+
+    assert_matches!(
+        mapping_result.unwrap_err(),
+        DirectoryError::SourcePathEscapesRoot { path }
+        if path == escaping_path
+    );
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn io_errors_convert_into_directory_error() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let missing_file_path = harness.root.child_path("missing.bin");
+
+    let copy_then_read_missing = || -> Result<FinishedDirectoryCopy, DirectoryError> {
+        let finished_copy = fs_more::directory::copy_directory(
+            harness.root.path(),
+            empty_harness.root.path(),
+            DirectoryCopyOptions::default(),
+        )?;
+
+        std::fs::metadata(&missing_file_path)?;
+
+        Ok(finished_copy)
+    };
+
+    assert_matches!(
+        copy_then_read_missing(),
+        Err(DirectoryError::OtherIoError { error })
+        if error.kind() == std::io::ErrorKind::NotFound
+    );
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn directory_errors_expose_io_error_as_source() -> TestResult<()> {
+    use std::error::Error;
+
+    let directory_errors = [
+        DirectoryError::UnableToAccessSource {
+            error: std::io::Error::new(std::io::ErrorKind::NotFound, "source"),
+        },
+        DirectoryError::UnableToAccessTarget {
+            error: std::io::Error::new(std::io::ErrorKind::NotFound, "target"),
+        },
+        DirectoryError::IoDuringOperation {
+            path: PathBuf::from("some/path"),
+            operation: IoOperation::CopyFile,
+            source: std::io::Error::new(std::io::ErrorKind::NotFound, "operation"),
+        },
+        DirectoryError::from(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "other",
+        )),
+    ];
+
+    for directory_error in directory_errors {
+        let source = directory_error
+            .source()
+            .expect("DirectoryError should expose the underlying IO error as its source");
+
+        let io_error = source
+            .downcast_ref::<std::io::Error>()
+            .expect("DirectoryError source should be an IO error");
+        assert_eq!(io_error.kind(), std::io::ErrorKind::NotFound);
+
+        // The source is reported by the error chain, so it isn't repeated in the message.
+        assert!(!directory_error.to_string().ends_with(&io_error.to_string()));
+    }
+
+    assert!(DirectoryError::SourceDirectoryNotFound.source().is_none());
+
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn copy_directory_preserves_ownership_when_configured() -> TestResult<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let is_privileged = std::fs::metadata(harness.root.path())?.uid() == 0;
+
+    // Only a privileged user can give away files, so we can only use a foreign owner when running as root.
+    if is_privileged {
+        std::os::unix::fs::chown(harness.dir_foo.path(), Some(1234), Some(1234))?;
+        std::os::unix::fs::chown(harness.file_b.path(), Some(1234), Some(1234))?;
+    }
+
+    let target_directory_path = empty_harness.root.child_path("copied");
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        &target_directory_path,
+        DirectoryCopyOptions {
+            preserve_ownership: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    for (source_path, target_path) in [
+        (
+            harness.root.path().to_path_buf(),
+            target_directory_path.clone(),
+        ),
+        (
+            harness.dir_foo.path().to_path_buf(),
+            target_directory_path.join("dir_foo"),
+        ),
+        (
+            harness.file_b.path().to_path_buf(),
+            target_directory_path.join("dir_foo").join("file_b.bin"),
+        ),
+        (
+            harness.file_a.path().to_path_buf(),
+            target_directory_path.join("file_a.bin"),
+        ),
+    ] {
+        let source_metadata = std::fs::metadata(source_path)?;
+        let target_metadata = std::fs::metadata(target_path)?;
+
+        assert_eq!(target_metadata.uid(), source_metadata.uid());
+        assert_eq!(target_metadata.gid(), source_metadata.gid());
+    }
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_options_from_builder() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let options = DirectoryCopyOptions::builder()
+        .with_target_rule(TargetDirectoryRule::AllowEmpty)
+        .with_max_depth(1)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        options,
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            maximum_copy_depth: Some(1),
+            ..Default::default()
+        }
+    );
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        options,
+    )
+    .unwrap();
+
+    AssertableFilePath::from_path(empty_harness.root.child_path("file_a.bin"))
+        .assert_content_matches_file(harness.file_a.path());
+    AssertableFilePath::from_path(empty_harness.root.child_path("dir_foo/file_b.bin"))
+        .assert_not_exists();
+
+
+    let invalid_options = DirectoryCopyOptions::builder()
+        .with_min_depth(2)
+        .with_max_depth(1)
+        .build();
+
+    assert_matches!(
+        invalid_options,
+        Err(DirectoryError::InvalidCopyDepthRange {
+            minimum: 2,
+            maximum: 1
+        })
+    );
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_maximum_depth_zero_copies_nothing() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let target_directory =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("target"));
+    target_directory.assert_not_exists();
+
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        target_directory.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            maximum_copy_depth: Some(0),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Only the target directory itself is created.
+    target_directory.assert_is_directory();
+    target_directory.assert_is_empty();
+
+    assert_eq!(finished_copy.num_files_copied, 0);
+    assert_eq!(finished_copy.num_directories_created, 1);
+    assert_eq!(finished_copy.total_bytes_copied, 0);
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_progress_with_maximum_depth_zero_copies_nothing() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let finished_copy = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            maximum_copy_depth: Some(0),
+            ..Default::default()
+        },
+        |_| {},
+    )
+    .unwrap();
+
+    empty_harness.root.assert_is_empty();
+    assert_eq!(finished_copy.num_files_copied, 0);
+    assert_eq!(finished_copy.total_bytes_copied, 0);
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_maximum_depth_one_copies_only_direct_entries() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            maximum_copy_depth: Some(1),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    AssertableFilePath::from_path(empty_harness.root.child_path("file_a.bin"))
+        .assert_content_matches_file(harness.file_a.path());
+
+    let target_dir_foo =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("dir_foo"));
+    target_dir_foo.assert_is_directory();
+    target_dir_foo.assert_is_empty();
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_maximum_depth_two_copies_one_level_of_subdirectories() -> TestResult<()>
+{
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            maximum_copy_depth: Some(2),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    AssertableFilePath::from_path(empty_harness.root.child_path("file_a.bin"))
+        .assert_content_matches_file(harness.file_a.path());
+    AssertableFilePath::from_path(empty_harness.root.child_path("dir_foo/file_b.bin"))
+        .assert_content_matches_file(harness.file_b.path());
+
+    let target_dir_bar =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("dir_foo/dir_bar"));
+    target_dir_bar.assert_is_directory();
+    target_dir_bar.assert_is_empty();
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_maximum_depth_larger_than_tree_copies_everything() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            maximum_copy_depth: Some(100),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    harness
+        .root
+        .assert_directory_contents_match_directory(empty_harness.root.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_rejects_minimum_depth_larger_than_maximum_depth() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            minimum_copy_depth: Some(2),
+            maximum_copy_depth: Some(1),
+            ..Default::default()
+        },
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(DirectoryError::InvalidCopyDepthRange {
+            minimum: 2,
+            maximum: 1
+        })
+    );
+
+    let copy_with_progress_result = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            minimum_copy_depth: Some(2),
+            maximum_copy_depth: Some(1),
+            ..Default::default()
+        },
+        |_| {},
+    );
+
+    assert_matches!(
+        copy_with_progress_result,
+        Err(DirectoryError::InvalidCopyDepthRange { .. })
+    );
+
+    // Files at the minimum depth are not copied, so a minimum equal to the maximum can't copy anything either.
+    let equal_depths_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            minimum_copy_depth: Some(1),
+            maximum_copy_depth: Some(1),
+            ..Default::default()
+        },
+    );
+
+    assert_matches!(
+        equal_depths_result,
+        Err(DirectoryError::InvalidCopyDepthRange {
+            minimum: 1,
+            maximum: 1
+        })
+    );
+
+    empty_harness.root.assert_is_empty();
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_progress_reports_skipped_bytes() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let existing_target_file_path = empty_harness.root.child_path("file_a.bin");
+    std::fs::write(&existing_target_file_path, "existing")?;
+
+    let file_a_size_bytes = std::fs::metadata(harness.file_a.path())?.len();
+
+    let mut last_progress: Option<DirectoryCopyProgress> = None;
+
+    let finished_copy = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::Merge {
+                overwrite_existing_files: false,
+            },
+            ..Default::default()
+        },
+        |progress| {
+            assert_eq!(progress.bytes_skipped, file_a_size_bytes);
+            last_progress = Some(progress.clone());
+        },
+    )
+    .unwrap();
+
+    let last_progress = last_progress.unwrap();
+
+    assert_eq!(finished_copy.num_existing_files_skipped, 1);
+    assert_eq!(
+        last_progress.bytes_finished,
+        last_progress.bytes_total
+    );
+    assert_eq!(
+        finished_copy.total_bytes_copied,
+        last_progress.bytes_finished
+    );
+
+    AssertableFilePath::from_path(existing_target_file_path).assert_content_matches_str("existing");
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_follows_symlinked_target_directory() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let real_target_directory =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("real-target"));
+    std::fs::create_dir(real_target_directory.path())?;
+
+    let symlinked_target_directory =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("symlinked-target"));
+    symlinked_target_directory.symlink_to_directory(real_target_directory.path())?;
+
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        symlinked_target_directory.path(),
+        DirectoryCopyOptions::default(),
+    )
+    .unwrap();
+
+    symlinked_target_directory.assert_is_symlink_to_directory();
+    harness
+        .root
+        .assert_directory_contents_match_directory(real_target_directory.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_rejects_symlinked_target_inside_source() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let symlink_to_source =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("link-to-source"));
+    symlink_to_source.symlink_to_directory(harness.root.path())?;
+
+    let symlink_to_source_subdirectory =
+        AssertableDirectoryPath::from_path(empty_harness.root.child_path("link-to-dir-foo"));
+    symlink_to_source_subdirectory.symlink_to_directory(harness.dir_foo.path())?;
+
+    for target_directory_path in [
+        symlink_to_source.path().to_path_buf(),
+        symlink_to_source_subdirectory.path().to_path_buf(),
+        symlink_to_source_subdirectory.path().join("new-directory"),
+    ] {
+        let copy_result = fs_more::directory::copy_directory(
+            harness.root.path(),
+            &target_directory_path,
+            DirectoryCopyOptions {
+                target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                    overwrite_existing_subdirectories: true,
+                    overwrite_existing_files: true,
+                },
+                ..Default::default()
+            },
+        );
+
+        assert_matches!(
+            copy_result,
+            Err(DirectoryError::InvalidTargetDirectoryPath)
+        );
+    }
+
+    AssertableDirectoryPath::from_path(harness.dir_foo.child_path("new-directory"))
+        .assert_not_exists();
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+#[cfg(not(windows))]
+pub fn copy_directory_junction_behaviour_does_not_affect_symlinks() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let symlinked_dir =
+        AssertableDirectoryPath::from_path(harness.root.child_path("symlinked-directory"));
+    symlinked_dir.symlink_to_directory(harness.dir_foo.path())?;
+
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            junction_behaviour: JunctionBehaviour::Skip,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_junctions_skipped, 0);
+    assert_eq!(finished_copy.num_junctions_recreated, 0);
+
+    AssertableDirectoryPath::from_path(empty_harness.root.child_path("symlinked-directory"))
+        .assert_directory_contents_match_directory(harness.dir_foo.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+#[cfg(windows)]
+pub fn copy_directory_recreates_or_skips_junctions() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let junction_path = harness.root.child_path("junction-to-dir-foo");
+    let mklink_status = std::process::Command::new("cmd")
+        .arg("/C")
+        .arg("mklink")
+        .arg("/J")
+        .arg(&junction_path)
+        .arg(harness.dir_foo.path())
+        .status()?;
+    assert!(mklink_status.success());
+
+
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            junction_behaviour: JunctionBehaviour::Recreate,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_junctions_recreated, 1);
+
+    let recreated_junction_path = empty_harness.root.child_path("junction-to-dir-foo");
+    assert!(
+        std::fs::symlink_metadata(&recreated_junction_path)?
+            .file_type()
+            .is_symlink()
+    );
+    assert_eq!(
+        std::fs::canonicalize(&recreated_junction_path)?,
+        std::fs::canonicalize(harness.dir_foo.path())?
+    );
+
+    empty_harness.destroy()?;
+
+
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            junction_behaviour: JunctionBehaviour::Skip,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_junctions_skipped, 1);
+    AssertableDirectoryPath::from_path(empty_harness.root.child_path("junction-to-dir-foo"))
+        .assert_not_exists();
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_progress_reports_file_events() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let existing_target_file_path = empty_harness.root.child_path("file_a.bin");
+    std::fs::write(&existing_target_file_path, "existing")?;
+
+    let mut file_events = Vec::new();
+    let mut last_progress: Option<DirectoryCopyProgress> = None;
+
+    let finished_copy = fs_more::directory::copy_directory_with_progress_and_file_events(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::Merge {
+                overwrite_existing_files: false,
+            },
+            ..Default::default()
+        },
+        |progress| last_progress = Some(progress.clone()),
+        |file_event| file_events.push(file_event),
+    )
+    .unwrap();
+
+    assert_eq!(
+        file_events.first(),
+        Some(&FileEvent::Skipped {
+            path: existing_target_file_path,
+            reason: FileSkipReason::TargetAlreadyExists,
+        })
+    );
+
+    // Each copied file must be started and then finished before the next one starts.
+    let copy_events = &file_events[1..];
+    assert_eq!(
+        copy_events.len(),
+        finished_copy.num_files_copied * 2
+    );
+
+    for started_and_finished in copy_events.chunks(2) {
+        match started_and_finished {
+            [FileEvent::Started {
+                path: started_path,
+                size,
+            }, FileEvent::Finished {
+                path: finished_path,
+                bytes,
+            }] => {
+                assert_eq!(started_path, finished_path);
+                assert_eq!(size, bytes);
+                assert_eq!(std::fs::metadata(finished_path)?.len(), *bytes);
+            }
+            _ => panic!(
+                "unexpected file events: {:?}",
+                started_and_finished
+            ),
+        }
+    }
+
+    assert_eq!(
+        last_progress.unwrap().bytes_finished,
+        finished_copy.total_bytes_copied
+    );
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_rejects_missing_or_non_directory_source() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let missing_source_path = harness.root.child_path("missing-directory");
+
+    assert_matches!(
+        fs_more::directory::copy_directory(
+            &missing_source_path,
+            empty_harness.root.path(),
+            DirectoryCopyOptions::default(),
+        ),
+        Err(DirectoryError::SourceDirectoryNotFound)
+    );
+    assert_matches!(
+        fs_more::directory::copy_directory_with_progress(
+            &missing_source_path,
+            empty_harness.root.path(),
+            DirectoryCopyWithProgressOptions::default(),
+            |_| {},
+        ),
+        Err(DirectoryError::SourceDirectoryNotFound)
+    );
+
+    assert_matches!(
+        fs_more::directory::copy_directory(
+            harness.file_a.path(),
+            empty_harness.root.path(),
+            DirectoryCopyOptions::default(),
+        ),
+        Err(DirectoryError::SourceDirectoryIsNotADirectory)
+    );
+    assert_matches!(
+        fs_more::directory::copy_directory_with_progress(
+            harness.file_a.path(),
+            empty_harness.root.path(),
+            DirectoryCopyWithProgressOptions::default(),
+            |_| {},
+        ),
+        Err(DirectoryError::SourceDirectoryIsNotADirectory)
+    );
+
+    empty_harness.root.assert_is_empty();
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_locked_file_skip_copies_unlocked_files() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            locked_file_behaviour: LockedFileBehaviour::Skip,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_locked_files_skipped, 0);
+
+    harness
+        .root
+        .assert_directory_contents_match_directory(empty_harness.root.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+#[cfg(windows)]
+pub fn copy_directory_skips_or_retries_locked_files() -> TestResult<()> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    let harness = DeepTreeHarness::new()?;
+
+    // Opening the file without any sharing prevents other handles from reading it.
+    let locked_file = std::fs::OpenOptions::new()
+        .read(true)
+        .share_mode(0)
+        .open(harness.file_a.path())?;
+
+
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let copy_error = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            locked_file_behaviour: LockedFileBehaviour::Retry {
+                retries: 2,
+                delay: Duration::from_millis(10),
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+
+    assert_matches!(
+        copy_error,
+        DirectoryError::IoDuringOperation { .. }
+    );
+
+    empty_harness.destroy()?;
+
+
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let finished_copy = fs_more::directory::copy_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyWithProgressOptions {
+            locked_file_behaviour: LockedFileBehaviour::Skip,
+            ..Default::default()
+        },
+        |_| {},
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_locked_files_skipped, 1);
+
+    AssertableFilePath::from_path(empty_harness.root.child_path("file_a.bin")).assert_not_exists();
+
+    drop(locked_file);
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_directories_only_copies_no_files() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    for pre_scan in [true, false] {
+        let empty_harness = EmptyTreeHarness::new()?;
+
+        let finished_copy = fs_more::directory::copy_directory(
+            harness.root.path(),
+            empty_harness.root.path(),
+            DirectoryCopyOptions {
+                directories_only: true,
+                pre_scan,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(finished_copy.num_files_copied, 0);
+        assert!(finished_copy.num_directories_created > 0);
+
+        let target_path_of = |source_path: &std::path::Path| {
+            empty_harness
+                .root
+                .child_path(source_path.strip_prefix(harness.root.path()).unwrap())
+        };
+
+        assert!(target_path_of(harness.dir_world.path()).is_dir());
+        assert!(target_path_of(harness.dir_foo_2.path()).is_dir());
+        assert!(!target_path_of(harness.file_a.path()).exists());
+        assert!(!target_path_of(harness.file_f.path()).exists());
+
+        empty_harness.destroy()?;
+    }
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_deletes_extraneous_target_entries() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = DeepTreeHarness::new()?;
+
+    let extraneous_file_path = target_harness.root.child_path("extraneous-file.txt");
+    std::fs::write(&extraneous_file_path, "extraneous")?;
+
+    let extraneous_directory_path = target_harness.dir_foo.path().join("extraneous-directory");
+    std::fs::create_dir(&extraneous_directory_path)?;
+    std::fs::write(
+        extraneous_directory_path.join("nested-file.txt"),
+        "extraneous",
+    )?;
+
+    for pre_scan in [true, false] {
+        let finished_copy = fs_more::directory::copy_directory(
+            harness.root.path(),
+            target_harness.root.path(),
+            DirectoryCopyOptions {
+                target_directory_rule: TargetDirectoryRule::Merge {
+                    overwrite_existing_files: true,
+                },
+                delete_extraneous_from_target: true,
+                pre_scan,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // The second copy has nothing left to delete.
+        let expected_deleted_entries = if pre_scan { 3 } else { 0 };
+        assert_eq!(
+            finished_copy.num_extraneous_entries_deleted,
+            expected_deleted_entries
+        );
+    }
+
+    assert!(!extraneous_file_path.exists());
+    assert!(!extraneous_directory_path.exists());
+
+    harness
+        .root
+        .assert_directory_contents_match_directory(target_harness.root.path());
+
+
+    harness.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_keeps_target_entries_of_filtered_out_files() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = DeepTreeHarness::new()?;
+
+    let extraneous_file_path = target_harness.root.child_path("extraneous-file.txt");
+
+    for pre_scan in [true, false] {
+        std::fs::write(&extraneous_file_path, "extraneous")?;
+
+        let finished_copy = fs_more::directory::copy_directory_with_file_filter(
+            harness.root.path(),
+            target_harness.root.path(),
+            DirectoryCopyOptions {
+                target_directory_rule: TargetDirectoryRule::Merge {
+                    overwrite_existing_files: true,
+                },
+                delete_extraneous_from_target: true,
+                pre_scan,
+                ..Default::default()
+            },
+            |_, metadata| metadata.len() >= 1024 * 128,
+        )
+        .unwrap();
+
+        assert_eq!(finished_copy.num_extraneous_entries_deleted, 1);
+    }
+
+    assert!(!extraneous_file_path.exists());
+
+    // Target files whose source files were filtered out are not extraneous.
+    harness
+        .root
+        .assert_directory_contents_match_directory(target_harness.root.path());
+
+
+    harness.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_keeps_target_entries_of_oversized_files() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = DeepTreeHarness::new()?;
+
+    let extraneous_file_path = target_harness.root.child_path("extraneous-file.txt");
+
+    for pre_scan in [true, false] {
+        std::fs::write(&extraneous_file_path, "extraneous")?;
+
+        let finished_copy = fs_more::directory::copy_directory(
+            harness.root.path(),
+            target_harness.root.path(),
+            DirectoryCopyOptions {
+                target_directory_rule: TargetDirectoryRule::Merge {
+                    overwrite_existing_files: true,
+                },
+                delete_extraneous_from_target: true,
+                max_file_size: Some(1024),
+                pre_scan,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(finished_copy.num_extraneous_entries_deleted, 1);
+    }
+
+    assert!(!extraneous_file_path.exists());
+
+    // Target files whose source files were too large to be copied are not extraneous.
+    harness
+        .root
+        .assert_directory_contents_match_directory(target_harness.root.path());
+
+
+    harness.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_keeps_target_entries_outside_of_copy_depth() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = DeepTreeHarness::new()?;
+
+    let extraneous_file_path = target_harness.root.child_path("extraneous-file.txt");
+
+    for pre_scan in [true, false] {
+        std::fs::write(&extraneous_file_path, "extraneous")?;
+
+        let finished_copy = fs_more::directory::copy_directory(
+            harness.root.path(),
+            target_harness.root.path(),
+            DirectoryCopyOptions {
+                target_directory_rule: TargetDirectoryRule::Merge {
+                    overwrite_existing_files: true,
+                },
+                delete_extraneous_from_target: true,
+                minimum_copy_depth: Some(1),
+                maximum_copy_depth: Some(2),
+                pre_scan,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(finished_copy.num_extraneous_entries_deleted, 1);
+    }
+
+    assert!(!extraneous_file_path.exists());
+
+    // Target entries whose source entries are outside of the copied depth range are not extraneous.
+    harness
+        .root
+        .assert_directory_contents_match_directory(target_harness.root.path());
+
+
+    harness.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_only_paths_keeps_target_entries_of_unlisted_paths() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = DeepTreeHarness::new()?;
+
+    let extraneous_file_path = target_harness.root.child_path("extraneous-file.txt");
+
+    for pre_scan in [true, false] {
+        std::fs::write(&extraneous_file_path, "extraneous")?;
+
+        let finished_copy = fs_more::directory::copy_directory_only_paths(
+            harness.root.path(),
+            target_harness.root.path(),
+            ["dir_foo/file_b.bin"],
+            DirectoryCopyOptions {
+                target_directory_rule: TargetDirectoryRule::Merge {
+                    overwrite_existing_files: true,
+                },
+                delete_extraneous_from_target: true,
+                pre_scan,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(finished_copy.num_extraneous_entries_deleted, 1);
+    }
+
+    assert!(!extraneous_file_path.exists());
+
+    // Target entries whose source entries were not listed are not extraneous.
+    harness
+        .root
+        .assert_directory_contents_match_directory(target_harness.root.path());
+
+
+    harness.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_directories_only_keeps_target_files() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = DeepTreeHarness::new()?;
+
+    let extraneous_file_path = target_harness.root.child_path("extraneous-file.txt");
+
+    for pre_scan in [true, false] {
+        std::fs::write(&extraneous_file_path, "extraneous")?;
+
+        let finished_copy = fs_more::directory::copy_directory(
+            harness.root.path(),
+            target_harness.root.path(),
+            DirectoryCopyOptions {
+                target_directory_rule: TargetDirectoryRule::Merge {
+                    overwrite_existing_files: true,
+                },
+                delete_extraneous_from_target: true,
+                directories_only: true,
+                pre_scan,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(finished_copy.num_extraneous_entries_deleted, 1);
+    }
+
+    assert!(!extraneous_file_path.exists());
+
+    // Target files are not extraneous just because only directories were copied.
+    harness
+        .root
+        .assert_directory_contents_match_directory(target_harness.root.path());
+
+
+    harness.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_with_plan_lists_extraneous_target_entries() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = DeepTreeHarness::new()?;
+
+    let extraneous_directory_path = target_harness.dir_foo.path().join("extraneous-directory");
+    std::fs::create_dir(&extraneous_directory_path)?;
+
+    let extraneous_file_path = extraneous_directory_path.join("nested-file.txt");
+    std::fs::write(&extraneous_file_path, "extraneous")?;
+
+    let options = DirectoryCopyOptions {
+        target_directory_rule: TargetDirectoryRule::Merge {
+            overwrite_existing_files: true,
+        },
+        delete_extraneous_from_target: true,
+        max_file_size: Some(1024),
+        ..Default::default()
+    };
+
+    // Entries are listed in the order they would be deleted in, i.e. contents before their directory.
+    let expected_extraneous_target_paths = vec![
+        extraneous_file_path.clone(),
+        extraneous_directory_path.clone(),
+    ];
+
+    let merge_plan = fs_more::directory::plan_merge(
+        harness.root.path(),
+        target_harness.root.path(),
+        options,
+    )
+    .unwrap();
+
+    assert_eq!(
+        merge_plan.extraneous_target_paths,
+        expected_extraneous_target_paths
+    );
+
+    let mut reported_plan = None;
+
+    let finished_copy = fs_more::directory::copy_directory_with_plan(
+        harness.root.path(),
+        target_harness.root.path(),
+        options,
+        |plan| {
+            // Nothing may have been deleted yet.
+            assert!(extraneous_file_path.exists());
+
+            reported_plan = Some(plan.clone());
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        reported_plan.unwrap().extraneous_target_paths,
+        expected_extraneous_target_paths
+    );
+    assert_eq!(finished_copy.num_extraneous_entries_deleted, 2);
+    assert!(!extraneous_directory_path.exists());
+
+
+    harness.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn copy_directory_rewrites_relative_symlinks_leading_outside_of_source() -> TestResult<()> {
+    use fs_more::directory::SymlinkRewrite;
+
+    let harness = DeepTreeHarness::new()?;
+
+    // Copying `dir_foo` means this link leads outside of the copied tree, while the other one stays inside.
+    std::os::unix::fs::symlink(
+        "../file_a.bin",
+        harness.dir_foo.path().join("file_a-link"),
+    )?;
+    std::os::unix::fs::symlink(
+        "file_b.bin",
+        harness.dir_foo.path().join("file_b-link"),
+    )?;
+
+
+    let verbatim_harness = EmptyTreeHarness::new()?;
+
+    fs_more::directory::copy_directory(
+        harness.dir_foo.path(),
+        verbatim_harness.root.path(),
+        DirectoryCopyOptions {
+            follow_root_symlink_only: true,
+            symlink_target_rewrite: SymlinkRewrite::Verbatim,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read_link(verbatim_harness.root.child_path("file_a-link"))?,
+        PathBuf::from("../file_a.bin")
+    );
+
+    verbatim_harness.destroy()?;
+
+
+    let rewriting_harness = EmptyTreeHarness::new()?;
+
+    fs_more::directory::copy_directory(
+        harness.dir_foo.path(),
+        rewriting_harness.root.path(),
+        DirectoryCopyOptions {
+            follow_root_symlink_only: true,
+            symlink_target_rewrite: SymlinkRewrite::RewriteRelative,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let rewritten_link_path = rewriting_harness.root.child_path("file_a-link");
+    assert!(std::fs::read_link(&rewritten_link_path)?.is_relative());
+    assert_eq!(
+        std::fs::read(&rewritten_link_path)?,
+        std::fs::read(harness.file_a.path())?
+    );
+
+    assert_eq!(
+        std::fs::read_link(rewriting_harness.root.child_path("file_b-link"))?,
+        PathBuf::from("file_b.bin")
+    );
+
+
+    rewriting_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_produces_manifest_when_requested() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+    let manifest_target_harness = EmptyTreeHarness::new()?;
+
+    let hello_file_path = harness.root.child_path("hello.txt");
+    std::fs::write(&hello_file_path, b"hello")?;
+
+
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions::default(),
+    )
+    .unwrap();
+
+    assert!(finished_copy.manifest.is_none());
+
+
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        manifest_target_harness.root.path(),
+        DirectoryCopyOptions::builder()
+            .with_target_rule(TargetDirectoryRule::AllowEmpty)
+            .with_produce_manifest(ChecksumAlgorithm::Sha256)
+            .build()
+            .unwrap(),
+    )
+    .unwrap();
+
+    harness
+        .root
+        .assert_directory_contents_match_directory(manifest_target_harness.root.path());
+
+    let manifest = finished_copy.manifest.unwrap();
+    assert_eq!(manifest.len(), finished_copy.num_files_copied);
+
+    for entry in &manifest {
+        let target_file_path = manifest_target_harness
+            .root
+            .path()
+            .join(&entry.relative_path);
+
+        assert!(entry.relative_path.is_relative());
+        assert_eq!(
+            entry.size_bytes,
+            std::fs::metadata(&target_file_path)?.len()
+        );
+        assert_eq!(entry.checksum.len(), 64);
+    }
+
+    let hello_entry = manifest
+        .iter()
+        .find(|entry| entry.relative_path == Path::new("hello.txt"))
+        .unwrap();
+
+    assert_eq!(hello_entry.size_bytes, 5);
+    assert_eq!(
+        hello_entry.checksum,
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+
 
-    empty_harness.destroy()?;
     harness.destroy()?;
+    empty_harness.destroy()?;
+    manifest_target_harness.destroy()?;
     Ok(())
 }
 
+
+#[cfg(unix)]
 #[test]
-pub fn copy_directory_with_progress_symbolic_link_to_directory_respect_depth_limit(
-) -> TestResult<()> {
+pub fn copy_directory_with_manifest_refuses_to_copy_file_onto_itself() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    let symlinked_dir =
-        AssertableDirectoryPath::from_path(harness.root.child_path("symlinked-directory"));
-    symlinked_dir.assert_not_exists();
-    symlinked_dir.symlink_to_directory(harness.dir_foo.path())?;
-    symlinked_dir.assert_is_symlink_to_directory();
+    // The target file resolves to the source file itself.
+    std::os::unix::fs::symlink(
+        harness.file_a.path(),
+        empty_harness.root.child_path("file_a.bin"),
+    )?;
 
-    fs_more::directory::copy_directory_with_progress(
+    let copy_result = fs_more::directory::copy_directory(
         harness.root.path(),
         empty_harness.root.path(),
-        DirectoryCopyWithProgressOptions {
-            maximum_copy_depth: Some(1),
-            ..Default::default()
-        },
-        |_| {},
-    )
-    .unwrap();
-
-    let previously_symlinked_dir_in_target =
-        AssertableDirectoryPath::from_path(empty_harness.root.child_path("symlinked-directory"));
-    previously_symlinked_dir_in_target.assert_exists();
-
-    let previously_symlinked_file_b = AssertableFilePath::from_path(
-        empty_harness
-            .root
-            .child_path("symlinked-directory")
-            .join(harness.file_b.path().file_name().unwrap()),
+        DirectoryCopyOptions::builder()
+            .with_target_rule(TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: false,
+                overwrite_existing_files: true,
+            })
+            .with_produce_manifest(ChecksumAlgorithm::Sha256)
+            .build()
+            .unwrap(),
     );
-    previously_symlinked_file_b.assert_is_file();
-    previously_symlinked_file_b.assert_content_matches_file(harness.file_b.path());
 
-    let previously_symlinked_file_c = AssertableFilePath::from_path(
-        empty_harness
-            .root
-            .child_path("symlinked-directory")
-            .join(harness.dir_bar.path().file_name().unwrap())
-            .join(harness.file_c.path().file_name().unwrap()),
+    assert_matches!(
+        copy_result.unwrap_err(),
+        DirectoryError::InvalidTargetDirectoryPath
     );
-    previously_symlinked_file_c.assert_not_exists();
+
+    // The source file must not have been truncated.
+    harness.file_a.assert_content_unchanged();
+
 
     empty_harness.destroy()?;
     harness.destroy()?;
@@ -898,75 +4327,78 @@ pub fn copy_directory_with_progress_symbolic_link_to_directory_respect_depth_lim
 
 
 #[test]
-pub fn copy_directory_preemptively_check_for_directory_collisions() -> TestResult<()> {
+pub fn copy_directory_copies_only_listed_paths() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
-    let empty_harness = EmptyTreeHarness::new()?;
-    empty_harness.root.assert_is_empty();
 
-    // Target directory preparation.
-    let existing_target_file = AssertableFilePath::from_path(
-        empty_harness.root.path().join(
-            harness
-                .file_d
-                .path()
-                .strip_prefix(harness.root.path())
-                .unwrap(),
-        ),
-    );
+    for pre_scan in [true, false] {
+        let empty_harness = EmptyTreeHarness::new()?;
+
+        let finished_copy = fs_more::directory::copy_directory_only_paths(
+            harness.root.path(),
+            empty_harness.root.path(),
+            ["dir_foo/file_b.bin", "dir_foo/dir_bar/hello"],
+            DirectoryCopyOptions {
+                target_directory_rule: TargetDirectoryRule::AllowEmpty,
+                pre_scan,
+                ..Default::default()
+            },
+        )
+        .unwrap();
 
-    let non_existing_target_file = AssertableFilePath::from_path(
-        empty_harness.root.path().join(
-            harness
-                .file_a
-                .path()
-                .strip_prefix(harness.root.path())
-                .unwrap(),
-        ),
-    );
+        // Only file_b and the contents of the `hello` directory are copied.
+        assert_eq!(finished_copy.num_files_copied, 4);
+
+        let target_root = empty_harness.root.path();
+
+        assert!(target_root.join("dir_foo/file_b.bin").is_file());
+        assert!(target_root
+            .join("dir_foo/dir_bar/hello/world/file_d.bin")
+            .is_file());
+        assert!(target_root
+            .join("dir_foo/dir_bar/hello/world/file_e.bin")
+            .is_file());
+        assert!(target_root
+            .join("dir_foo/dir_bar/hello/world/file_f.bin")
+            .is_file());
+
+        // Ancestors of the listed paths are created, but not copied in full.
+        assert!(target_root.join("dir_foo/dir_bar").is_dir());
+        assert!(!target_root.join("dir_foo/dir_bar/file_c.bin").exists());
+        assert!(!target_root.join("file_a.bin").exists());
+        assert!(!target_root.join("dir_foo2").exists());
+
+        empty_harness.destroy()?;
+    }
 
-    std::fs::create_dir_all(existing_target_file.path().parent().unwrap()).unwrap();
-    std::fs::copy(harness.file_d.path(), existing_target_file.path()).unwrap();
 
-    existing_target_file.assert_content_matches_file(harness.file_d.path());
-    non_existing_target_file.assert_not_exists();
+    harness.destroy()?;
+    Ok(())
+}
 
-    empty_harness.root.assert_is_not_empty();
-    // END of preparation
 
-    let copy_result = fs_more::directory::copy_directory(
+#[test]
+pub fn copy_directory_errors_on_missing_listed_paths() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let copy_result = fs_more::directory::copy_directory_only_paths(
         harness.root.path(),
         empty_harness.root.path(),
-        DirectoryCopyOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_subdirectories: false,
-                overwrite_existing_files: false,
-            },
-            ..Default::default()
-        },
+        ["file_a.bin", "missing.bin", "../file_a.bin"],
+        DirectoryCopyOptions::builder()
+            .with_target_rule(TargetDirectoryRule::AllowEmpty)
+            .build()
+            .unwrap(),
     );
 
-    let copy_err = copy_result.unwrap_err();
-
-    match copy_err {
-        DirectoryError::TargetItemAlreadyExists { path } => {
-            assert_eq!(
-                path.as_path(),
-                empty_harness.root.path()
-                    .join(
-                        harness.dir_foo.path()
-                            .strip_prefix(harness.root.path())
-                            .unwrap()
-                    ),
-                "copy_directory did not return the proper directory collision in the target directory"
-            );
-        }
-        _ => panic!("Unexpected Err: {}", copy_err),
-    }
-
-    empty_harness.root.assert_is_not_empty();
+    assert_matches!(
+        copy_result.unwrap_err(),
+        DirectoryError::ListedPathsNotFound { paths }
+            if paths == [PathBuf::from("../file_a.bin"), PathBuf::from("missing.bin")]
+    );
 
-    non_existing_target_file.assert_not_exists();
-    existing_target_file.assert_content_matches_file(harness.file_d.path());
+    // Nothing is copied if any listed path is missing.
+    empty_harness.root.assert_is_empty();
 
 
     harness.destroy()?;
@@ -974,360 +4406,496 @@ pub fn copy_directory_preemptively_check_for_directory_collisions() -> TestResul
     Ok(())
 }
 
+
 #[test]
-pub fn copy_directory_preemptively_check_for_file_collisions() -> TestResult<()> {
+pub fn plan_merge_reports_changes_without_copying() -> TestResult<()> {
+    use fs_more::directory::MergeFileReason;
+
     let harness = DeepTreeHarness::new()?;
-    let empty_harness = EmptyTreeHarness::new()?;
-    empty_harness.root.assert_is_empty();
+    let target_harness = EmptyTreeHarness::new()?;
 
-    // Target directory preparation.
-    let existing_target_file = AssertableFilePath::from_path(
-        empty_harness.root.path().join(
-            harness
-                .file_d
-                .path()
-                .strip_prefix(harness.root.path())
-                .unwrap(),
-        ),
-    );
+    fs_more::directory::copy_directory(
+        harness.root.path(),
+        target_harness.root.path(),
+        DirectoryCopyOptions::default(),
+    )
+    .unwrap();
 
-    let non_existing_target_file = AssertableFilePath::from_path(
-        empty_harness.root.path().join(
-            harness
-                .file_a
-                .path()
-                .strip_prefix(harness.root.path())
-                .unwrap(),
-        ),
-    );
+    let target_root = target_harness.root.path();
 
-    std::fs::create_dir_all(existing_target_file.path().parent().unwrap()).unwrap();
-    std::fs::copy(harness.file_d.path(), existing_target_file.path()).unwrap();
+    // `file_a` is missing from the target and `new_directory` doesn't exist there yet.
+    std::fs::remove_file(target_root.join("file_a.bin"))?;
+    std::fs::create_dir(harness.dir_foo.path().join("new_directory"))?;
+    std::fs::write(
+        harness.dir_foo.path().join("new_directory/new-file.txt"),
+        b"new",
+    )?;
 
-    existing_target_file.assert_content_matches_file(harness.file_d.path());
-    non_existing_target_file.assert_not_exists();
+    // `file_c` is changed in the source after its copy, while `file_b` is changed in the target.
+    std::thread::sleep(Duration::from_millis(50));
+    std::fs::write(harness.file_c.path(), b"changed in the source")?;
+    std::thread::sleep(Duration::from_millis(50));
+    std::fs::write(
+        target_root.join("dir_foo/file_b.bin"),
+        b"changed in the target",
+    )?;
 
-    empty_harness.root.assert_is_not_empty();
-    // END of preparation
 
-    let copy_result = fs_more::directory::copy_directory(
+    let merge_plan = fs_more::directory::plan_merge(
         harness.root.path(),
-        empty_harness.root.path(),
+        target_root,
         DirectoryCopyOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_subdirectories: true,
+            target_directory_rule: TargetDirectoryRule::Merge {
                 overwrite_existing_files: false,
             },
             ..Default::default()
         },
+    )
+    .unwrap();
+
+    let reason_of = |file_name: &str| {
+        merge_plan
+            .files
+            .iter()
+            .find(|file| file.source_file_path.file_name().unwrap() == file_name)
+            .unwrap()
+            .reason
+    };
+
+    assert_eq!(reason_of("file_a.bin"), MergeFileReason::New);
+    assert_eq!(reason_of("new-file.txt"), MergeFileReason::New);
+    assert_eq!(
+        reason_of("file_b.bin"),
+        MergeFileReason::TargetIsNewer
+    );
+    assert_eq!(
+        reason_of("file_c.bin"),
+        MergeFileReason::SourceIsNewer
+    );
+    assert_eq!(
+        reason_of("file_d.bin"),
+        MergeFileReason::Identical
     );
 
-    let copy_err = copy_result.unwrap_err();
+    // Without overwriting, only new files are created and everything else is skipped.
+    assert_eq!(merge_plan.files_to_create().count(), 2);
+    assert_eq!(merge_plan.files_to_overwrite().count(), 0);
+    assert_eq!(
+        merge_plan.files_to_skip().count(),
+        merge_plan.files.len() - 2
+    );
+    assert_eq!(
+        merge_plan.directories_to_create,
+        vec![target_root.join("dir_foo/new_directory")]
+    );
 
-    match copy_err {
-        DirectoryError::TargetItemAlreadyExists { path } => {
-            assert_eq!(
-                path.as_path(),
-                existing_target_file.path(),
-                "copy_directory did not return the proper file collision in the target directory"
-            );
-        }
-        _ => panic!("Unexpected Err: {}", copy_err),
-    }
+    // Nothing was copied.
+    assert!(!target_root.join("file_a.bin").exists());
+    assert!(!target_root.join("dir_foo/new_directory").exists());
 
-    empty_harness.root.assert_is_not_empty();
 
-    non_existing_target_file.assert_not_exists();
-    existing_target_file.assert_content_matches_file(harness.file_d.path());
+    let overwriting_merge_plan = fs_more::directory::plan_merge(
+        harness.root.path(),
+        target_root,
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::Merge {
+                overwrite_existing_files: true,
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        overwriting_merge_plan.files_to_create().count(),
+        2
+    );
+    assert_eq!(overwriting_merge_plan.files_to_skip().count(), 0);
+    assert_eq!(
+        overwriting_merge_plan.files_to_overwrite().count(),
+        overwriting_merge_plan.files.len() - 2
+    );
 
 
     harness.destroy()?;
-    empty_harness.destroy()?;
+    target_harness.destroy()?;
     Ok(())
 }
 
 
 #[test]
-pub fn copy_directory_with_progress_preemptively_check_for_directory_collisions() -> TestResult<()>
-{
+pub fn copy_directory_with_fast_enumeration() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
-    empty_harness.root.assert_is_empty();
 
-    // Target directory preparation.
-    let existing_target_file = AssertableFilePath::from_path(
-        empty_harness.root.path().join(
-            harness
-                .file_d
-                .path()
-                .strip_prefix(harness.root.path())
-                .unwrap(),
-        ),
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            fast_enumeration: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let source_scan = DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        finished_copy.num_files_copied,
+        source_scan.files().len()
+    );
+    assert_eq!(
+        finished_copy.num_directories_created,
+        source_scan.directories().len()
     );
 
-    let non_existing_target_file = AssertableFilePath::from_path(
-        empty_harness.root.path().join(
-            harness
-                .file_a
-                .path()
-                .strip_prefix(harness.root.path())
-                .unwrap(),
-        ),
+    harness
+        .root
+        .assert_directory_contents_match_directory(empty_harness.root.path());
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_directory_skips_files_above_max_file_size() -> TestResult<()> {
+    let source_harness = EmptyTreeHarness::new()?;
+    let target_harness = EmptyTreeHarness::new()?;
+
+    let small_file_path = source_harness.root.child_path("config.toml");
+    let large_file_path = source_harness.root.child_path("video.bin");
+    let nested_large_file_path = source_harness.root.child_path("media/photo.bin");
+
+    std::fs::write(&small_file_path, [1u8; 1024])?;
+    std::fs::write(&large_file_path, [2u8; 1025])?;
+    std::fs::create_dir(source_harness.root.child_path("media"))?;
+    std::fs::write(&nested_large_file_path, [3u8; 4096])?;
+
+    let finished_copy = fs_more::directory::copy_directory(
+        source_harness.root.path(),
+        target_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            max_file_size: Some(1024),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(finished_copy.num_files_copied, 1);
+    assert_eq!(finished_copy.num_directories_created, 1);
+
+    let mut skipped_paths = finished_copy.skipped_oversized_file_paths.clone();
+    skipped_paths.sort();
+    assert_eq!(
+        skipped_paths,
+        vec![nested_large_file_path, large_file_path]
     );
 
-    std::fs::create_dir_all(existing_target_file.path().parent().unwrap()).unwrap();
-    std::fs::copy(harness.file_d.path(), existing_target_file.path()).unwrap();
+    AssertableFilePath::from_path(target_harness.root.child_path("config.toml"))
+        .assert_content_matches_file(&small_file_path);
+    AssertableFilePath::from_path(target_harness.root.child_path("video.bin")).assert_not_exists();
+    AssertableFilePath::from_path(target_harness.root.child_path("media/photo.bin"))
+        .assert_not_exists();
+    AssertableDirectoryPath::from_path(target_harness.root.child_path("media")).assert_exists();
 
-    existing_target_file.assert_content_matches_file(harness.file_d.path());
-    non_existing_target_file.assert_not_exists();
 
-    empty_harness.root.assert_is_not_empty();
-    // END of preparation
+    source_harness.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
 
-    let mut last_progress: Option<DirectoryCopyProgress> = None;
 
-    let copy_result = fs_more::directory::copy_directory_with_progress(
-        harness.root.path(),
-        empty_harness.root.path(),
-        DirectoryCopyWithProgressOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_subdirectories: false,
-                overwrite_existing_files: false,
-            },
+#[test]
+pub fn finished_directory_copy_summary() -> TestResult<()> {
+    let source_harness = EmptyTreeHarness::new()?;
+    let target_harness = EmptyTreeHarness::new()?;
+
+    std::fs::write(
+        source_harness.root.child_path("notes.txt"),
+        [0u8; 1536],
+    )?;
+
+    let finished_copy = fs_more::directory::copy_directory(
+        source_harness.root.path(),
+        target_harness.root.path(),
+        DirectoryCopyOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
             ..Default::default()
         },
-        |progress| {
-            last_progress = Some(progress.clone());
-        },
-    );
-
-    let copy_err = copy_result.unwrap_err();
+    )
+    .unwrap();
 
-    assert!(
-        last_progress.is_none(),
-        "copy_directory_with_progress did not check for directory collisions before starting copy"
+    assert_eq!(
+        finished_copy.summary(),
+        "Copied 1 file (1.5 KiB) and created 0 directories"
     );
 
-    match copy_err {
-        DirectoryError::TargetItemAlreadyExists { path } => {
-            assert_eq!(
-                path.as_path(),
-                empty_harness.root.path()
-                    .join(
-                        harness.dir_foo.path()
-                            .strip_prefix(harness.root.path())
-                            .unwrap()
-                    ),
-                "copy_directory did not return the proper directory collision in the target directory"
-            );
-        }
-        _ => panic!("Unexpected Err: {}", copy_err),
-    }
-
-    empty_harness.root.assert_is_not_empty();
-
-    non_existing_target_file.assert_not_exists();
-    existing_target_file.assert_content_matches_file(harness.file_d.path());
-
 
-    harness.destroy()?;
-    empty_harness.destroy()?;
+    source_harness.destroy()?;
+    target_harness.destroy()?;
     Ok(())
 }
 
+
+#[cfg(unix)]
 #[test]
-pub fn copy_directory_with_progress_preemptively_check_for_file_collisions() -> TestResult<()> {
+pub fn copy_directory_stops_following_symlinks_after_limit() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
-    let empty_harness = EmptyTreeHarness::new()?;
-    empty_harness.root.assert_is_empty();
 
-    // Target directory preparation.
-    let existing_target_file = AssertableFilePath::from_path(
-        empty_harness.root.path().join(
-            harness
-                .file_d
-                .path()
-                .strip_prefix(harness.root.path())
-                .unwrap(),
-        ),
-    );
+    std::os::unix::fs::symlink(
+        harness.dir_foo_2.path(),
+        harness.root.child_path("dir_foo2-link"),
+    )?;
+    std::os::unix::fs::symlink(
+        harness.dir_foo_3.path(),
+        harness.root.child_path("dir_foo3-link"),
+    )?;
+    std::os::unix::fs::symlink(
+        "file_a.bin",
+        harness.root.child_path("file_a-link"),
+    )?;
 
-    let non_existing_target_file = AssertableFilePath::from_path(
-        empty_harness.root.path().join(
-            harness
-                .file_a
-                .path()
-                .strip_prefix(harness.root.path())
-                .unwrap(),
-        ),
-    );
+    let empty_harness = EmptyTreeHarness::new()?;
 
-    std::fs::create_dir_all(existing_target_file.path().parent().unwrap()).unwrap();
-    std::fs::copy(harness.file_d.path(), existing_target_file.path()).unwrap();
+    let finished_copy = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions::builder()
+            .with_max_symlinks_to_follow(1)
+            .build()
+            .unwrap(),
+    )
+    .unwrap();
 
-    existing_target_file.assert_content_matches_file(harness.file_d.path());
-    non_existing_target_file.assert_not_exists();
+    // Only one of the linked directories was followed, the other one was recreated as a link.
+    // Links to files don't count towards the limit.
+    assert_eq!(finished_copy.num_symlinks_recreated, 1);
+
+    let num_copied_links_to_directories = ["dir_foo2-link", "dir_foo3-link"]
+        .iter()
+        .filter(|link_name| {
+            empty_harness
+                .root
+                .child_path(link_name)
+                .symlink_metadata()
+                .unwrap()
+                .is_dir()
+        })
+        .count();
+    assert_eq!(num_copied_links_to_directories, 1);
+
+    assert!(empty_harness
+        .root
+        .child_path("file_a-link")
+        .symlink_metadata()?
+        .is_file());
 
-    empty_harness.root.assert_is_not_empty();
-    // END of preparation
+    empty_harness.destroy()?;
 
-    let mut last_progress: Option<DirectoryCopyProgress> = None;
 
-    let copy_result = fs_more::directory::copy_directory_with_progress(
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let finished_copy = fs_more::directory::copy_directory_with_progress(
         harness.root.path(),
         empty_harness.root.path(),
         DirectoryCopyWithProgressOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_subdirectories: true,
-                overwrite_existing_files: false,
-            },
+            max_symlinks_to_follow: Some(0),
             ..Default::default()
         },
-        |progress| {
-            last_progress = Some(progress.clone());
-        },
-    );
+        |_| {},
+    )
+    .unwrap();
 
-    let copy_err = copy_result.unwrap_err();
+    assert_eq!(finished_copy.num_symlinks_recreated, 2);
 
-    assert!(
-        last_progress.is_none(),
-        "copy_directory_with_progress did not check for directory collisions before starting copy"
-    );
+    empty_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
 
-    match copy_err {
-        DirectoryError::TargetItemAlreadyExists { path } => {
-            assert_eq!(
-                path.as_path(),
-                existing_target_file.path(),
-                "copy_directory did not return the proper file collision in the target directory"
-            );
-        }
-        _ => panic!("Unexpected Err: {}", copy_err),
-    }
 
-    empty_harness.root.assert_is_not_empty();
+#[test]
+pub fn copy_directory_with_progress_stream_delivers_progress_through_channel() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
 
-    non_existing_target_file.assert_not_exists();
-    existing_target_file.assert_content_matches_file(harness.file_d.path());
+    let (copy_thread_handle, progress_receiver) =
+        fs_more::directory::copy_directory_with_progress_stream(
+            harness.root.path(),
+            empty_harness.root.path(),
+            DirectoryCopyWithProgressOptions::default(),
+        );
+
+    // The receiver is disconnected once the copy finishes, which ends the iteration.
+    let received_progress: Vec<DirectoryCopyProgress> = progress_receiver.iter().collect();
+    let finished_copy = copy_thread_handle.join().unwrap().unwrap();
+
+    assert!(!received_progress.is_empty());
+
+    let last_progress = received_progress.last().unwrap();
+    assert_eq!(
+        last_progress.bytes_finished,
+        finished_copy.total_bytes_copied
+    );
+    assert_eq!(
+        last_progress.files_copied,
+        finished_copy.num_files_copied
+    );
+
+    empty_harness
+        .root
+        .assert_directory_contents_match_directory(harness.root.path());
 
 
-    harness.destroy()?;
     empty_harness.destroy()?;
+    harness.destroy()?;
     Ok(())
 }
 
 
 #[test]
-pub fn disallow_copy_directory_when_source_is_symlink_to_target() -> TestResult<()> {
-    // Tests behaviour when copying "symlink to directory A" to "A".
-    // This should fail.
-
-    let harness_for_comparison = DeepTreeHarness::new()?;
+pub fn copy_directory_verifies_copied_files_when_configured() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
-    let intermediate_harness = EmptyTreeHarness::new()?;
-
-    // Directory symlink preparation
-    let symlink_to_directory = AssertableDirectoryPath::from_path(
-        intermediate_harness.root.child_path("symlinked-directory"),
-    );
 
-    symlink_to_directory.assert_not_exists();
-    symlink_to_directory
-        .symlink_to_directory(harness.root.path())
+    for verify_mode in [
+        VerifyMode::SizeOnly,
+        VerifyMode::ContentHash(ChecksumAlgorithm::Crc32),
+    ] {
+        let empty_harness = EmptyTreeHarness::new()?;
+
+        let finished_copy = fs_more::directory::copy_directory(
+            harness.root.path(),
+            empty_harness.root.path(),
+            DirectoryCopyOptions::builder()
+                .with_verify_after_copy(verify_mode)
+                .build()
+                .unwrap(),
+        )
         .unwrap();
-    // END of preparation
 
-    let copy_result = fs_more::directory::copy_directory(
-        symlink_to_directory.path(),
+        assert_eq!(finished_copy.num_files_copied, 6);
+
+        empty_harness.destroy()?;
+    }
+
+
+    // Source files that change after being copied no longer match their copies.
+    let empty_harness = EmptyTreeHarness::new()?;
+    let mut previously_filtered_file_paths: Vec<PathBuf> = Vec::new();
+
+    let copy_result = fs_more::directory::copy_directory_with_file_filter(
         harness.root.path(),
+        empty_harness.root.path(),
         DirectoryCopyOptions {
-            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_subdirectories: true,
-                overwrite_existing_files: true,
-            },
+            pre_scan: false,
+            verify_after_copy: Some(VerifyMode::SizeOnly),
             ..Default::default()
         },
-    );
-
-    let copy_err = copy_result.unwrap_err();
+        |file_path, _| {
+            // When copying while traversing, the previously filtered files have already been copied.
+            for previous_file_path in &previously_filtered_file_paths {
+                use std::io::Write;
+
+                std::fs::OpenOptions::new()
+                    .append(true)
+                    .open(previous_file_path)
+                    .unwrap()
+                    .write_all(b"changed")
+                    .unwrap();
+            }
 
-    match &copy_err {
-        DirectoryError::InvalidTargetDirectoryPath => {
-            // This is the expected error value.
-        }
-        _ => panic!("Unexpected Err: {}", copy_err),
-    }
+            previously_filtered_file_paths.push(file_path.to_path_buf());
+            true
+        },
+    );
 
-    harness
-        .root
-        .assert_directory_contents_match_directory(harness_for_comparison.root.path());
+    assert_matches!(
+        copy_result.unwrap_err(),
+        DirectoryError::VerificationFailed { path }
+            if path.starts_with(empty_harness.root.path())
+    );
 
 
-    harness_for_comparison.destroy()?;
+    empty_harness.destroy()?;
     harness.destroy()?;
-    intermediate_harness.destroy()?;
-
     Ok(())
 }
 
 
 #[test]
-pub fn disallow_copy_directory_with_progress_when_source_is_symlink_to_target() -> TestResult<()> {
-    // Tests behaviour when copying "symlink to directory A" to "A".
-    // This should fail.
-
-    let harness_for_comparison = DeepTreeHarness::new()?;
+pub fn copy_directory_collects_all_collisions_when_configured() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
-    let intermediate_harness = EmptyTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
 
-    // Directory symlink preparation
-    let symlink_to_directory = AssertableDirectoryPath::from_path(
-        intermediate_harness.root.child_path("symlinked-directory"),
+    // Only `file_a.bin` and `dir_foo/file_b.bin` (along with `dir_foo`) exist in the target beforehand.
+    let existing_file_a = empty_harness.root.child_path("file_a.bin");
+    let existing_file_b = empty_harness.root.child_path("dir_foo/file_b.bin");
+    std::fs::create_dir(empty_harness.root.child_path("dir_foo"))?;
+    std::fs::write(&existing_file_a, b"existing")?;
+    std::fs::write(&existing_file_b, b"existing")?;
+
+    let copy_result = fs_more::directory::copy_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryCopyOptions::builder()
+            .with_target_rule(TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: true,
+                overwrite_existing_files: true,
+            })
+            .with_pre_scan(false)
+            .with_collect_all_collisions(true)
+            .build()
+            .unwrap(),
     );
 
-    symlink_to_directory.assert_not_exists();
-    symlink_to_directory
-        .symlink_to_directory(harness.root.path())
-        .unwrap();
-    // END of preparation
+    let existing_paths = match copy_result {
+        Err(DirectoryError::TargetItemsAlreadyExist { paths }) => paths,
+        other => panic!(
+            "expected TargetItemsAlreadyExist, got {:?}",
+            other
+        ),
+    };
 
-    let mut last_progress: Option<DirectoryCopyProgress> = None;
+    assert_eq!(existing_paths.len(), 3);
+    assert!(existing_paths.contains(&empty_harness.root.child_path("dir_foo")));
+    assert!(existing_paths.contains(&existing_file_a));
+    assert!(existing_paths.contains(&existing_file_b));
 
-    let copy_result = fs_more::directory::copy_directory_with_progress(
-        symlink_to_directory.path(),
+    // Nothing was copied.
+    assert_eq!(std::fs::read(&existing_file_a)?, b"existing");
+    assert!(!empty_harness.root.child_path("dir_foo2").exists());
+
+
+    let copy_with_progress_result = fs_more::directory::copy_directory_with_progress(
         harness.root.path(),
+        empty_harness.root.path(),
         DirectoryCopyWithProgressOptions {
             target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
-                overwrite_existing_subdirectories: true,
-                overwrite_existing_files: true,
+                overwrite_existing_subdirectories: false,
+                overwrite_existing_files: false,
             },
+            collect_all_collisions: true,
             ..Default::default()
         },
-        |progress| {
-            last_progress = Some(progress.clone());
-        },
+        |_| {},
     );
 
-    assert!(last_progress.is_none());
-
-    let copy_err = copy_result.unwrap_err();
-
-    match &copy_err {
-        DirectoryError::InvalidTargetDirectoryPath => {
-            // This is the expected error value.
-        }
-        _ => panic!("Unexpected Err: {}", copy_err),
-    }
-
-    harness
-        .root
-        .assert_directory_contents_match_directory(harness_for_comparison.root.path());
+    assert_matches!(
+        copy_with_progress_result,
+        Err(DirectoryError::TargetItemsAlreadyExist { paths }) if paths.len() == 3
+    );
 
 
-    harness_for_comparison.destroy()?;
+    empty_harness.destroy()?;
     harness.destroy()?;
-    intermediate_harness.destroy()?;
-
     Ok(())
 }
@@ -0,0 +1,111 @@
+use assert_matches::assert_matches;
+use fs_more::{directory::CreateDirectoryOptions, error::DirectoryError};
+use fs_more_test_harness::{error::TestResult, trees::SimpleTreeHarness};
+
+#[test]
+pub fn create_directory_reports_created_components() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let target_directory_path = harness.subdirectory_b.child_path("foo/bar/baz");
+
+    let created = fs_more::directory::create_directory(
+        &target_directory_path,
+        CreateDirectoryOptions::default(),
+    )
+    .unwrap();
+
+    assert!(target_directory_path.is_dir());
+    assert_eq!(
+        created.created_directories,
+        [
+            harness.subdirectory_b.child_path("foo"),
+            harness.subdirectory_b.child_path("foo/bar"),
+            target_directory_path.clone(),
+        ]
+    );
+
+
+    // Creating an existing directory is not an error, but nothing is reported as created.
+    let created_again = fs_more::directory::create_directory(
+        &target_directory_path,
+        CreateDirectoryOptions::default(),
+    )
+    .unwrap();
+
+    assert!(created_again.created_directories.is_empty());
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn create_directory_fails_if_exists_when_configured() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let creation_result = fs_more::directory::create_directory(
+        harness.subdirectory_b.path(),
+        CreateDirectoryOptions {
+            fail_if_exists: true,
+            ..Default::default()
+        },
+    );
+
+    assert_matches!(
+        creation_result,
+        Err(DirectoryError::TargetItemAlreadyExists { path })
+            if path == harness.subdirectory_b.path()
+    );
+
+
+    let creation_result = fs_more::directory::create_directory(
+        harness.binary_file_a.path().join("nested"),
+        CreateDirectoryOptions::default(),
+    );
+
+    assert_matches!(
+        creation_result,
+        Err(DirectoryError::TargetParentNotADirectory { path })
+            if path == harness.binary_file_a.path()
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn create_directory_applies_permissions_on_unix() -> TestResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let harness = SimpleTreeHarness::new()?;
+
+    let target_directory_path = harness.root.child_path("foo/bar");
+
+    fs_more::directory::create_directory(
+        &target_directory_path,
+        CreateDirectoryOptions {
+            unix_permissions: Some(0o750),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    for created_directory_path in [harness.root.child_path("foo"), target_directory_path] {
+        let mode = std::fs::metadata(created_directory_path)?
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o750);
+    }
+
+    // Existing directories are left untouched.
+    let root_mode = std::fs::metadata(harness.root.path())?.permissions().mode();
+    assert_ne!(root_mode & 0o777, 0o750);
+
+
+    harness.destroy()?;
+    Ok(())
+}
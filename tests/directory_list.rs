@@ -1,6 +1,14 @@
-use std::path::Path;
+use std::{path::Path, time::Duration};
 
-use fs_more_test_harness::{error::TestResult, trees::SimpleTreeHarness};
+use assert_matches::assert_matches;
+use fs_more::{
+    directory::{DirectoryScanOptions, ScanEntryRef, TraversalOrder},
+    error::{DirectoryScanError, DirectorySizeScanError},
+};
+use fs_more_test_harness::{
+    error::TestResult,
+    trees::{DeepTreeHarness, SimpleTreeHarness},
+};
 
 /// Returns `true` if the provided `Vec` of `AsRef<Path>`-implementing items
 /// contains at least one path matching the `target_path`.
@@ -19,8 +27,10 @@ where
 pub fn scan_directory() -> TestResult<()> {
     let harness = SimpleTreeHarness::new()?;
 
-    let scan_result =
-        fs_more::directory::DirectoryScan::scan_with_options(harness.root.path(), None, false);
+    let scan_result = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    );
 
     assert!(
         scan_result.is_ok(),
@@ -30,12 +40,12 @@ pub fn scan_directory() -> TestResult<()> {
     let scan = scan_result.unwrap();
 
     assert_eq!(
-        scan.directories.len(),
+        scan.directories().len(),
         1,
         "Unexpected amount of scanned directories."
     );
     assert_eq!(
-        scan.files.len(),
+        scan.files().len(),
         2,
         "Unexpected amount of scanned files."
     );
@@ -44,21 +54,21 @@ pub fn scan_directory() -> TestResult<()> {
 
 
     assert!(path_vec_contains_path(
-        &scan.files,
+        scan.files(),
         harness.binary_file_a.path()
     ));
     assert!(path_vec_contains_path(
-        &scan.files,
+        scan.files(),
         harness.binary_file_b.path()
     ));
 
 
     assert!(!path_vec_contains_path(
-        &scan.directories,
+        scan.directories(),
         harness.root.path(),
     ));
     assert!(path_vec_contains_path(
-        &scan.directories,
+        scan.directories(),
         harness.subdirectory_b.path(),
     ));
 
@@ -71,8 +81,14 @@ pub fn scan_directory() -> TestResult<()> {
 pub fn scan_directory_with_limited_depth() -> TestResult<()> {
     let harness = SimpleTreeHarness::new()?;
 
-    let scan_result =
-        fs_more::directory::DirectoryScan::scan_with_options(harness.root.path(), Some(0), false);
+    let scan_result = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            maximum_scan_depth: Some(0),
+            follow_symbolic_links: false,
+            ..Default::default()
+        },
+    );
 
     assert!(
         scan_result.is_ok(),
@@ -82,12 +98,12 @@ pub fn scan_directory_with_limited_depth() -> TestResult<()> {
     let scan = scan_result.unwrap();
 
     assert_eq!(
-        scan.directories.len(),
+        scan.directories().len(),
         1,
         "Unexpected amount of scanned directories."
     );
     assert_eq!(
-        scan.files.len(),
+        scan.files().len(),
         1,
         "Unexpected amount of scanned files."
     );
@@ -96,20 +112,20 @@ pub fn scan_directory_with_limited_depth() -> TestResult<()> {
 
 
     assert!(path_vec_contains_path(
-        &scan.files,
+        scan.files(),
         harness.binary_file_a.path()
     ));
     assert!(!path_vec_contains_path(
-        &scan.files,
+        scan.files(),
         harness.binary_file_b.path()
     ));
 
     assert!(!path_vec_contains_path(
-        &scan.directories,
+        scan.directories(),
         harness.root.path(),
     ));
     assert!(path_vec_contains_path(
-        &scan.directories,
+        scan.directories(),
         harness.subdirectory_b.path(),
     ));
 
@@ -128,8 +144,10 @@ pub fn directory_size_via_directory_scan() -> TestResult<()> {
         + harness.subdirectory_b.path().metadata().unwrap().len();
 
 
-    let scan_result =
-        fs_more::directory::DirectoryScan::scan_with_options(harness.root.path(), None, false);
+    let scan_result = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    );
 
 
     assert!(
@@ -164,8 +182,14 @@ pub fn directory_size_via_directory_scan_with_depth_limit() -> TestResult<()> {
         + harness.subdirectory_b.path().metadata().unwrap().len();
 
 
-    let scan_result =
-        fs_more::directory::DirectoryScan::scan_with_options(harness.root.path(), Some(0), false);
+    let scan_result = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            maximum_scan_depth: Some(0),
+            follow_symbolic_links: false,
+            ..Default::default()
+        },
+    );
 
 
     assert!(
@@ -193,6 +217,44 @@ pub fn directory_size_via_directory_scan_with_depth_limit() -> TestResult<()> {
 }
 
 
+#[test]
+pub fn directory_size_via_directory_scan_cached() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    // Nothing has changed since the scan, so the cached size must match.
+    let scanned_size_in_bytes = scan.total_size_in_bytes().unwrap();
+    assert_eq!(
+        scan.total_size_in_bytes_cached(),
+        scanned_size_in_bytes
+    );
+
+
+    // The cached size doesn't reflect changes made after the scan, not even removed files.
+    std::fs::write(harness.binary_file_a.path(), [1u8; 16])?;
+    std::fs::remove_file(harness.binary_file_b.path())?;
+
+    assert_matches!(
+        scan.total_size_in_bytes(),
+        Err(DirectorySizeScanError::EntryNoLongerExists { path })
+            if path == harness.binary_file_b.path()
+    );
+    assert_eq!(
+        scan.total_size_in_bytes_cached(),
+        scanned_size_in_bytes
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
 
 #[test]
 pub fn directory_size_via_size_function() -> TestResult<()> {
@@ -225,3 +287,823 @@ pub fn directory_size_via_size_function() -> TestResult<()> {
     harness.destroy()?;
     Ok(())
 }
+
+
+
+#[cfg(unix)]
+#[test]
+pub fn scan_directory_skips_unreadable_subdirectory() -> TestResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let harness = SimpleTreeHarness::new()?;
+
+    std::fs::set_permissions(
+        harness.subdirectory_b.path(),
+        std::fs::Permissions::from_mode(0o000),
+    )?;
+
+    // Privileged users (e.g. root) can read the directory regardless of its permissions,
+    // in which case there is nothing to test.
+    if std::fs::read_dir(harness.subdirectory_b.path()).is_ok() {
+        std::fs::set_permissions(
+            harness.subdirectory_b.path(),
+            std::fs::Permissions::from_mode(0o755),
+        )?;
+        harness.destroy()?;
+        return Ok(());
+    }
+
+    let aborting_scan_result = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        Default::default(),
+    );
+
+    assert!(
+        aborting_scan_result.is_err(),
+        "DirectoryScan::scan_with_options should have aborted on an unreadable subdirectory"
+    );
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            on_permission_denied: fs_more::directory::PermissionDeniedBehaviour::Skip,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(scan.files().len(), 1);
+    assert_eq!(scan.directories().len(), 1);
+    assert!(path_vec_contains_path(
+        scan.skipped_paths(),
+        harness.subdirectory_b.path()
+    ));
+
+
+    std::fs::set_permissions(
+        harness.subdirectory_b.path(),
+        std::fs::Permissions::from_mode(0o755),
+    )?;
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_with_minimum_depth() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            minimum_scan_depth: Some(1),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        scan.files().len(),
+        1,
+        "Unexpected amount of scanned files."
+    );
+    assert_eq!(scan.directories().len(), 0);
+
+    assert!(path_vec_contains_path(
+        scan.files(),
+        harness.binary_file_b.path()
+    ));
+    assert!(!path_vec_contains_path(
+        scan.files(),
+        harness.binary_file_a.path()
+    ));
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_empty_directories() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let empty_directory_path = harness.root.path().join("empty_directory");
+    let nested_empty_directory_path = empty_directory_path.join("nested_empty_directory");
+    std::fs::create_dir_all(&nested_empty_directory_path)?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    // Only the innermost directory is empty, as its parent contains a subdirectory.
+    let empty_directories = scan.empty_directories();
+
+    assert_eq!(empty_directories.len(), 1);
+    assert_eq!(
+        empty_directories[0].path,
+        nested_empty_directory_path
+    );
+    assert_eq!(empty_directories[0].depth, 1);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_reports_entry_depths() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(scan.file_entries().len(), scan.files().len());
+    assert_eq!(
+        scan.directory_entries().len(),
+        scan.directories().len()
+    );
+
+    let file_depth = |file_path: &Path| {
+        scan.file_entries()
+            .iter()
+            .find(|entry| entry.path == file_path)
+            .map(|entry| entry.depth)
+    };
+    let directory_depth = |directory_path: &Path| {
+        scan.directory_entries()
+            .iter()
+            .find(|entry| entry.path == directory_path)
+            .map(|entry| entry.depth)
+    };
+
+    assert_eq!(file_depth(harness.file_a.path()), Some(0));
+    assert_eq!(file_depth(harness.file_b.path()), Some(1));
+    assert_eq!(file_depth(harness.file_c.path()), Some(2));
+    assert_eq!(file_depth(harness.file_d.path()), Some(4));
+
+    assert_eq!(directory_depth(harness.dir_foo.path()), Some(0));
+    assert_eq!(directory_depth(harness.dir_bar.path()), Some(1));
+    assert_eq!(directory_depth(harness.dir_world.path()), Some(3));
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_in_sorted_order() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            traversal_order: TraversalOrder::ByNameAscending,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Each directory's files come before the contents of its subdirectories,
+    // which are in turn scanned in ascending order.
+    assert_eq!(
+        scan.files().collect::<Vec<_>>(),
+        [
+            harness.file_a.path(),
+            harness.file_b.path(),
+            harness.file_c.path(),
+            harness.file_d.path(),
+            harness.file_e.path(),
+            harness.file_f.path(),
+        ]
+    );
+
+    assert_eq!(
+        scan.directories().next(),
+        Some(harness.dir_foo.path())
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_stops_after_threshold() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let file_limited_scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            stop_after_files: Some(2),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(file_limited_scan.was_truncated);
+    assert_eq!(file_limited_scan.files().len(), 2);
+
+
+    // file_a.bin alone is 32 KiB.
+    let size_limited_scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            stop_after_bytes: Some(1024 * 32),
+            traversal_order: TraversalOrder::ByNameAscending,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(size_limited_scan.was_truncated);
+    assert_eq!(
+        size_limited_scan.files().collect::<Vec<_>>(),
+        [harness.file_a.path()]
+    );
+
+
+    // A threshold that is never reached doesn't truncate the scan.
+    let full_scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            stop_after_files: Some(7),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(!full_scan.was_truncated);
+    assert_eq!(full_scan.files().len(), 6);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_computes_recursive_directory_sizes() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    let directory_entry = |path: &Path| {
+        scan.directory_entries()
+            .iter()
+            .find(|entry| entry.path == path)
+            .expect("directory should be in the scan")
+    };
+
+    let dir_foo_entry = directory_entry(harness.dir_foo.path());
+    let dir_bar_entry = directory_entry(harness.dir_bar.path());
+    let dir_world_entry = directory_entry(harness.dir_world.path());
+
+    // dir_foo/dir_bar/hello/world contains only file_d, file_e and file_f.
+    assert_eq!(
+        dir_world_entry.recursive_size_in_bytes,
+        (256 + 16 + 1024) * 1024
+    );
+
+    // Everything beneath dir_foo (including its subdirectory entries), except for file_b.bin, is beneath dir_bar.
+    let dir_bar_subtree_size = dir_bar_entry.size_in_bytes + dir_bar_entry.recursive_size_in_bytes;
+    assert_eq!(
+        dir_foo_entry.recursive_size_in_bytes,
+        64 * 1024 + dir_bar_subtree_size
+    );
+
+    // The root directory isn't part of the scan, so its direct contents add up to the total size.
+    let total_size_of_top_level_entries: u64 = scan
+        .directory_entries()
+        .iter()
+        .filter(|entry| entry.depth == 0)
+        .map(|entry| entry.size_in_bytes + entry.recursive_size_in_bytes)
+        .chain(
+            scan.file_entries()
+                .iter()
+                .filter(|entry| entry.depth == 0)
+                .map(|entry| entry.size_in_bytes),
+        )
+        .sum();
+
+    assert_eq!(
+        total_size_of_top_level_entries,
+        scan.total_size_in_bytes_cached()
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn scan_directory_computes_recursive_directory_sizes_through_symlinks() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+    let linked_harness = DeepTreeHarness::new()?;
+
+    let container_directory_path = harness.root.child_path("container");
+    std::fs::create_dir(&container_directory_path)?;
+    std::os::unix::fs::symlink(
+        linked_harness.dir_world.path(),
+        container_directory_path.join("world-link"),
+    )?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            follow_symbolic_links: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let directory_entry = |path: &Path| {
+        scan.directory_entries()
+            .iter()
+            .find(|entry| entry.path == path)
+            .expect("directory should be in the scan")
+    };
+
+    // The linked directory is stored by its destination path, but still counts towards the directory containing the link.
+    let linked_directory_entry = directory_entry(linked_harness.dir_world.path());
+    assert_eq!(
+        linked_directory_entry.recursive_size_in_bytes,
+        (256 + 16 + 1024) * 1024
+    );
+
+    let container_directory_entry = directory_entry(&container_directory_path);
+    assert_eq!(
+        container_directory_entry.recursive_size_in_bytes,
+        linked_directory_entry.size_in_bytes + linked_directory_entry.recursive_size_in_bytes
+    );
+
+
+    linked_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_finds_scanned_paths() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    assert!(scan.contains(harness.file_f.path()));
+    assert!(scan.contains(harness.dir_world.path()));
+    assert!(!scan.contains(harness.root.path()));
+    assert!(!scan.contains(&harness.dir_world.path().join("missing.bin")));
+
+    match scan.find(harness.file_d.path()) {
+        Some(ScanEntryRef::File(file_entry)) => {
+            assert_eq!(file_entry.path, harness.file_d.path());
+            assert_eq!(file_entry.depth, 4);
+        }
+        other => panic!(
+            "expected file_d.bin to be found as a file, got {:?}",
+            other
+        ),
+    }
+
+    assert!(matches!(
+        scan.find(harness.dir_foo.path()),
+        Some(ScanEntryRef::Directory(_))
+    ));
+
+    // Entries that are removed after the first lookup must not be found anymore.
+    let mut modified_scan = scan.clone();
+    modified_scan
+        .file_entries
+        .retain(|entry| entry.path != harness.file_a.path());
+
+    assert!(!modified_scan.contains(harness.file_a.path()));
+    assert!(modified_scan.contains(harness.file_b.path()));
+
+    // The same goes for entries that are renamed in place, which keeps the number of entries the same.
+    let renamed_file_path = harness.root.child_path("renamed.bin");
+    let original_file_path = std::mem::replace(
+        &mut modified_scan.file_entries[0].path,
+        renamed_file_path.clone(),
+    );
+
+    assert!(modified_scan.contains(&renamed_file_path));
+    assert!(!modified_scan.contains(&original_file_path));
+    assert_eq!(scan, scan.clone());
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_groups_entries_by_parent() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    let entries_by_parent = scan.entries_by_parent();
+
+    let num_grouped_entries: usize = entries_by_parent.values().map(Vec::len).sum();
+    assert_eq!(
+        num_grouped_entries,
+        scan.files().len() + scan.directories().len()
+    );
+
+    let root_entries = &entries_by_parent[harness.root.path()];
+    assert!(root_entries
+        .iter()
+        .any(|entry| entry.path() == harness.file_a.path()));
+    assert!(root_entries
+        .iter()
+        .any(|entry| entry.path() == harness.dir_foo.path()));
+
+    let dir_foo_entries = &entries_by_parent[harness.dir_foo.path()];
+    assert!(dir_foo_entries
+        .iter()
+        .any(|entry| entry.path() == harness.file_b.path()));
+    assert!(dir_foo_entries
+        .iter()
+        .any(|entry| entry.path() == harness.dir_bar.path()));
+
+    // Directories come before files within each group.
+    for entries in entries_by_parent.values() {
+        let first_file_index = entries
+            .iter()
+            .position(|entry| matches!(entry, ScanEntryRef::File(_)))
+            .unwrap_or(entries.len());
+
+        assert!(entries[first_file_index..]
+            .iter()
+            .all(|entry| matches!(entry, ScanEntryRef::File(_))));
+    }
+
+    assert!(!entries_by_parent.contains_key(harness.file_a.path()));
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_rejects_missing_or_non_directory_root() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    assert_matches!(
+        fs_more::directory::DirectoryScan::scan_with_options(
+            harness.root.child_path("missing-directory"),
+            DirectoryScanOptions::default(),
+        ),
+        Err(DirectoryScanError::NotFound)
+    );
+
+    assert_matches!(
+        fs_more::directory::DirectoryScan::scan_with_options(
+            harness.file_a.path(),
+            DirectoryScanOptions::default(),
+        ),
+        Err(DirectoryScanError::NotADirectory)
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_estimates_copy_duration() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    let total_bytes = scan.total_size_in_bytes_cached();
+    assert!(total_bytes > 0);
+
+    assert_eq!(
+        scan.estimate_copy_duration(total_bytes),
+        Duration::from_secs(1)
+    );
+    assert_eq!(
+        scan.estimate_copy_duration(total_bytes * 2),
+        Duration::from_millis(500)
+    );
+    assert_eq!(scan.estimate_copy_duration(0), Duration::MAX);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_multiple_directories_aggregates_and_deduplicates_entries() -> TestResult<()> {
+    let deep_harness = DeepTreeHarness::new()?;
+    let simple_harness = SimpleTreeHarness::new()?;
+
+    let deep_scan = fs_more::directory::DirectoryScan::scan_with_options(
+        deep_harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+    let simple_scan = fs_more::directory::DirectoryScan::scan_with_options(
+        simple_harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    // `dir_foo` is inside the first root, so its entries must not be included twice.
+    let combined_scan = fs_more::directory::DirectoryScan::scan_multiple(
+        &[
+            deep_harness.root.path(),
+            simple_harness.root.path(),
+            deep_harness.dir_foo.path(),
+        ],
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(combined_scan.root_directory_paths().len(), 3);
+    assert_eq!(
+        combined_scan.files().len(),
+        deep_scan.files().len() + simple_scan.files().len()
+    );
+    assert_eq!(
+        combined_scan.directories().len(),
+        deep_scan.directories().len() + simple_scan.directories().len()
+    );
+    assert_eq!(
+        combined_scan.total_size_in_bytes_cached(),
+        deep_scan.total_size_in_bytes_cached() + simple_scan.total_size_in_bytes_cached()
+    );
+    assert_eq!(
+        combined_scan.total_size_in_bytes().unwrap(),
+        combined_scan.total_size_in_bytes_cached()
+    );
+
+    for file_entry in &combined_scan.file_entries {
+        let root_directory_path = &combined_scan.root_directory_paths()[file_entry.root_index];
+
+        assert!(file_entry.path.starts_with(root_directory_path));
+        assert_ne!(file_entry.root_index, 2);
+    }
+
+    let file_b_entry = combined_scan
+        .file_entries
+        .iter()
+        .find(|entry| entry.path.ends_with("dir_foo/file_b.bin"))
+        .unwrap();
+    assert_eq!(file_b_entry.root_index, 0);
+    assert_eq!(file_b_entry.depth, 1);
+
+    assert!(combined_scan
+        .file_entries
+        .iter()
+        .any(|entry| entry.root_index == 1));
+
+
+    let missing_root_result = fs_more::directory::DirectoryScan::scan_multiple(
+        &[
+            deep_harness.root.path().to_path_buf(),
+            deep_harness.root.path().join("non-existent"),
+        ],
+        DirectoryScanOptions::default(),
+    );
+    assert_matches!(
+        missing_root_result.unwrap_err(),
+        DirectoryScanError::NotFound
+    );
+
+
+    deep_harness.destroy()?;
+    simple_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+#[cfg(unix)]
+pub fn scan_directory_excludes_dotfiles_when_excluding_hidden() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let hidden_file_path = harness.root.child_path(".hidden-file");
+    std::fs::write(&hidden_file_path, b"hidden")?;
+
+    let hidden_directory_path = harness.root.child_path(".hidden-directory");
+    let file_in_hidden_directory_path = hidden_directory_path.join("visible-file.txt");
+    std::fs::create_dir(&hidden_directory_path)?;
+    std::fs::write(&file_in_hidden_directory_path, b"visible")?;
+
+
+    let full_scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
+
+    assert!(path_vec_contains_path(
+        full_scan.files(),
+        &hidden_file_path
+    ));
+    assert!(path_vec_contains_path(
+        full_scan.files(),
+        &file_in_hidden_directory_path
+    ));
+
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            exclude_hidden: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(!path_vec_contains_path(
+        scan.files(),
+        &hidden_file_path
+    ));
+    assert!(!path_vec_contains_path(
+        scan.directories(),
+        &hidden_directory_path
+    ));
+    assert!(!path_vec_contains_path(
+        scan.files(),
+        &file_in_hidden_directory_path
+    ));
+    assert!(path_vec_contains_path(
+        scan.files(),
+        harness.file_a.path()
+    ));
+    assert_eq!(scan.files().len() + 2, full_scan.files().len());
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+#[cfg(windows)]
+pub fn scan_directory_excludes_hidden_attribute_when_excluding_hidden() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    // On Windows, a leading dot doesn't make a file hidden.
+    let dotfile_path = harness.root.child_path(".not-hidden-file");
+    std::fs::write(&dotfile_path, b"not hidden")?;
+
+    let hidden_directory_path = harness.root.child_path("hidden-directory");
+    let file_in_hidden_directory_path = hidden_directory_path.join("visible-file.txt");
+    std::fs::create_dir(&hidden_directory_path)?;
+    std::fs::write(&file_in_hidden_directory_path, b"visible")?;
+
+    let attrib_status = std::process::Command::new("attrib")
+        .arg("+H")
+        .arg(&hidden_directory_path)
+        .status()?;
+    assert!(attrib_status.success());
+
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            exclude_hidden: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(path_vec_contains_path(
+        scan.files(),
+        &dotfile_path
+    ));
+    assert!(!path_vec_contains_path(
+        scan.directories(),
+        &hidden_directory_path
+    ));
+    assert!(!path_vec_contains_path(
+        scan.files(),
+        &file_in_hidden_directory_path
+    ));
+    assert!(path_vec_contains_path(
+        scan.files(),
+        harness.file_a.path()
+    ));
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn scan_directory_with_fast_enumeration_matches_standard_scan() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    // Long file names make the directory large enough to need more than one `getdents64` batch.
+    let many_files_directory_path = harness.root.child_path("many-files");
+    std::fs::create_dir(&many_files_directory_path)?;
+
+    for file_index in 0..2_000 {
+        std::fs::File::create(many_files_directory_path.join(format!(
+            "{}-{}",
+            "f".repeat(200),
+            file_index
+        )))?;
+    }
+
+
+    let scans = [false, true].map(|fast_enumeration| {
+        fs_more::directory::DirectoryScan::scan_with_options(
+            harness.root.path(),
+            DirectoryScanOptions {
+                fast_enumeration,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    });
+
+    assert_eq!(scans[0].files().len(), 2_006);
+    assert_eq!(scans[0], scans[1]);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn scan_directory_stops_following_symlinks_after_limit() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+    let linked_harness = DeepTreeHarness::new()?;
+
+    std::os::unix::fs::symlink(
+        linked_harness.dir_foo_2.path(),
+        harness.root.child_path("dir_foo2-link"),
+    )?;
+    std::os::unix::fs::symlink(
+        linked_harness.dir_foo_3.path(),
+        harness.root.child_path("dir_foo3-link"),
+    )?;
+
+    let scan = fs_more::directory::DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions {
+            follow_symbolic_links: true,
+            max_symlinks_to_follow: Some(1),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let num_followed_links = [
+        linked_harness.dir_foo_2.path(),
+        linked_harness.dir_foo_3.path(),
+    ]
+    .iter()
+    .filter(|linked_path| path_vec_contains_path(scan.directories(), linked_path))
+    .count();
+
+    assert_eq!(num_followed_links, 1);
+    assert!(path_vec_contains_path(
+        scan.directories(),
+        harness.subdirectory_b.path()
+    ));
+
+
+    linked_harness.destroy()?;
+    harness.destroy()?;
+    Ok(())
+}
@@ -1,4 +1,16 @@
-use fs_more::directory::{DirectoryMoveOptions, DirectoryScan, TargetDirectoryRule};
+use assert_matches::assert_matches;
+use fs_more::{
+    directory::{
+        DirectoryMoveOptions,
+        DirectoryMoveProgress,
+        DirectoryMoveWithProgressOptions,
+        DirectoryScan,
+        DirectoryScanOptions,
+        MovePhase,
+        TargetDirectoryRule,
+    },
+    error::DirectoryError,
+};
 use fs_more_test_harness::{
     error::TestResult,
     trees::{DeepTreeHarness, EmptyTreeHarness},
@@ -12,7 +24,11 @@ pub fn move_directory() -> TestResult<()> {
     let harness = DeepTreeHarness::new()?;
     let empty_harness = EmptyTreeHarness::new()?;
 
-    let source_scan = DirectoryScan::scan_with_options(harness.root.path(), None, false).unwrap();
+    let source_scan = DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .unwrap();
     let source_size_bytes = source_scan.total_size_in_bytes().unwrap();
 
     empty_harness.root.assert_is_empty();
@@ -22,6 +38,7 @@ pub fn move_directory() -> TestResult<()> {
         empty_harness.root.path(),
         DirectoryMoveOptions {
             target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            remove_source_if_empty: false,
         },
     );
 
@@ -46,3 +63,354 @@ pub fn move_directory() -> TestResult<()> {
 }
 
 // TODO Add a test for behaviour when moving "symlink to directory A" to "A".
+
+
+#[test]
+pub fn move_directory_through_staging_directory() -> TestResult<()> {
+    let harness_for_comparison = DeepTreeHarness::new()?;
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = EmptyTreeHarness::new()?;
+    let staging_harness = EmptyTreeHarness::new()?;
+
+    // A non-empty target directory can't simply be renamed onto,
+    // so the move has to fall back to copying (through the staging directory).
+    let unrelated_file_path = target_harness.root.child_path("unrelated.txt");
+    std::fs::write(&unrelated_file_path, "unrelated")?;
+
+    fs_more::directory::move_directory_with_staging_directory(
+        harness.root.path(),
+        target_harness.root.path(),
+        staging_harness.root.path(),
+        DirectoryMoveOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: false,
+                overwrite_existing_files: false,
+            },
+            remove_source_if_empty: false,
+        },
+    )
+    .unwrap();
+
+    harness.root.assert_not_exists();
+    assert!(unrelated_file_path.is_file());
+
+    std::fs::remove_file(&unrelated_file_path)?;
+    harness_for_comparison
+        .root
+        .assert_directory_contents_match_directory(target_harness.root.path());
+
+    // The temporary staging directory must be cleaned up.
+    staging_harness.root.assert_is_empty();
+
+
+    harness_for_comparison.destroy()?;
+    target_harness.destroy()?;
+    staging_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn move_directory_through_staging_directory_creates_missing_target_parent() -> TestResult<()> {
+    let harness_for_comparison = DeepTreeHarness::new()?;
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = EmptyTreeHarness::new()?;
+    let staging_harness = EmptyTreeHarness::new()?;
+
+    // Neither the target directory nor its parent exist, so the source can't simply be renamed onto it.
+    let target_directory_path = target_harness
+        .root
+        .child_path("missing-parent")
+        .join("target");
+
+    fs_more::directory::move_directory_with_staging_directory(
+        harness.root.path(),
+        &target_directory_path,
+        staging_harness.root.path(),
+        DirectoryMoveOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            remove_source_if_empty: false,
+        },
+    )
+    .unwrap();
+
+    harness.root.assert_not_exists();
+    harness_for_comparison
+        .root
+        .assert_directory_contents_match_directory(&target_directory_path);
+
+    // The temporary staging directory must be cleaned up.
+    staging_harness.root.assert_is_empty();
+
+
+    harness_for_comparison.destroy()?;
+    target_harness.destroy()?;
+    staging_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn move_directory_stages_next_to_target_without_staging_directory() -> TestResult<()> {
+    let harness_for_comparison = DeepTreeHarness::new()?;
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = EmptyTreeHarness::new()?;
+
+    let target_directory_path = target_harness.root.child_path("target");
+    std::fs::create_dir(&target_directory_path)?;
+
+    let unrelated_file_path = target_directory_path.join("unrelated.txt");
+    std::fs::write(&unrelated_file_path, "unrelated")?;
+
+    fs_more::directory::move_directory(
+        harness.root.path(),
+        &target_directory_path,
+        DirectoryMoveOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: false,
+                overwrite_existing_files: false,
+            },
+            remove_source_if_empty: false,
+        },
+    )
+    .unwrap();
+
+    harness.root.assert_not_exists();
+    assert!(unrelated_file_path.is_file());
+
+    std::fs::remove_file(&unrelated_file_path)?;
+    harness_for_comparison
+        .root
+        .assert_directory_contents_match_directory(&target_directory_path);
+
+    // The temporary staging directory next to the target must be cleaned up.
+    assert_eq!(
+        std::fs::read_dir(target_harness.root.path())?.count(),
+        1
+    );
+
+
+    harness_for_comparison.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn move_directory_errors_on_missing_staging_directory() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let missing_staging_directory_path = empty_harness.root.child_path("missing");
+
+    let move_result = fs_more::directory::move_directory_with_staging_directory(
+        harness.root.path(),
+        empty_harness.root.path(),
+        &missing_staging_directory_path,
+        DirectoryMoveOptions {
+            target_directory_rule: TargetDirectoryRule::AllowEmpty,
+            remove_source_if_empty: false,
+        },
+    );
+
+    assert_matches!(
+        move_result,
+        Err(DirectoryError::UnableToUseStagingDirectory { path, .. })
+            if path == missing_staging_directory_path
+    );
+
+    // Nothing should have been moved.
+    harness.root.assert_is_not_empty();
+    empty_harness.root.assert_is_empty();
+
+
+    harness.destroy()?;
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn move_directory_with_progress_reports_renaming() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let empty_harness = EmptyTreeHarness::new()?;
+
+    let mut reported_phases: Vec<MovePhase> = Vec::new();
+
+    let finished_move = fs_more::directory::move_directory_with_progress(
+        harness.root.path(),
+        empty_harness.root.path(),
+        DirectoryMoveWithProgressOptions::default(),
+        |progress| reported_phases.push(progress.phase),
+    )
+    .unwrap();
+
+    // Both directories are on the same filesystem and the target is empty, so this is a simple rename.
+    assert_eq!(reported_phases, vec![MovePhase::Renaming]);
+    assert_eq!(finished_move.num_files_moved, 6);
+
+    harness.root.assert_not_exists();
+
+
+    empty_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn move_directory_with_progress_reports_deleting_phase() -> TestResult<()> {
+    let harness_for_comparison = DeepTreeHarness::new()?;
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = EmptyTreeHarness::new()?;
+
+    // A non-empty target directory can't be renamed onto, so the move must copy and delete.
+    let unrelated_file_path = target_harness.root.child_path("unrelated.txt");
+    std::fs::write(&unrelated_file_path, "unrelated")?;
+
+    let mut last_progress: Option<DirectoryMoveProgress> = None;
+    let mut reported_copying_phase = false;
+
+    let finished_move = fs_more::directory::move_directory_with_progress(
+        harness.root.path(),
+        target_harness.root.path(),
+        DirectoryMoveWithProgressOptions {
+            target_directory_rule: TargetDirectoryRule::AllowNonEmpty {
+                overwrite_existing_subdirectories: false,
+                overwrite_existing_files: false,
+            },
+            ..Default::default()
+        },
+        |progress| {
+            if progress.phase == MovePhase::Copying {
+                reported_copying_phase = true;
+            }
+
+            if let Some(last_progress) = &last_progress {
+                assert!(progress.entries_deleted >= last_progress.entries_deleted);
+            }
+
+            last_progress = Some(progress.clone());
+        },
+    )
+    .unwrap();
+
+    assert!(reported_copying_phase);
+
+    let last_progress = last_progress.unwrap();
+    assert_eq!(last_progress.phase, MovePhase::Deleting);
+    assert!(last_progress.entries_to_delete > 1);
+    assert_eq!(
+        last_progress.entries_deleted,
+        last_progress.entries_to_delete
+    );
+    assert_eq!(
+        last_progress.bytes_copied,
+        finished_move.total_bytes_moved
+    );
+
+    harness.root.assert_not_exists();
+
+    std::fs::remove_file(&unrelated_file_path)?;
+    harness_for_comparison
+        .root
+        .assert_directory_contents_match_directory(target_harness.root.path());
+
+
+    harness_for_comparison.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn move_directory_merge_keeps_existing_target_files() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = EmptyTreeHarness::new()?;
+
+    let existing_target_file_path = target_harness.root.child_path("file_a.bin");
+    std::fs::write(&existing_target_file_path, "existing")?;
+
+    let finished_move = fs_more::directory::move_directory(
+        harness.root.path(),
+        target_harness.root.path(),
+        DirectoryMoveOptions {
+            target_directory_rule: TargetDirectoryRule::Merge {
+                overwrite_existing_files: false,
+            },
+            remove_source_if_empty: false,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read(&existing_target_file_path)?,
+        b"existing"
+    );
+
+    // The skipped file stays in the source directory, everything else is moved.
+    harness.file_a.assert_exists();
+    harness.dir_foo.assert_not_exists();
+    assert_eq!(finished_move.num_files_moved, 5);
+
+    assert!(target_harness
+        .root
+        .child_path("dir_foo/dir_bar/hello/world/file_f.bin")
+        .is_file());
+
+
+    harness.destroy()?;
+    target_harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn move_directory_with_remove_source_if_empty_keeps_non_empty_source() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+    let target_harness = EmptyTreeHarness::new()?;
+
+    let existing_target_file_path = target_harness.root.child_path("file_a.bin");
+    std::fs::write(&existing_target_file_path, "existing")?;
+
+    fs_more::directory::move_directory(
+        harness.root.path(),
+        target_harness.root.path(),
+        DirectoryMoveOptions {
+            target_directory_rule: TargetDirectoryRule::Merge {
+                overwrite_existing_files: false,
+            },
+            remove_source_if_empty: true,
+        },
+    )
+    .unwrap();
+
+    // The skipped file is left in place, along with the source directory itself.
+    // Subdirectories that are empty after the move are removed.
+    harness.file_a.assert_exists();
+    harness.dir_foo.assert_not_exists();
+    assert_eq!(std::fs::read_dir(harness.root.path())?.count(), 1);
+
+
+    // Once everything is moved, the now-empty source directory is removed as well.
+    fs_more::directory::move_directory(
+        harness.root.path(),
+        target_harness.root.path(),
+        DirectoryMoveOptions {
+            target_directory_rule: TargetDirectoryRule::Merge {
+                overwrite_existing_files: true,
+            },
+            remove_source_if_empty: true,
+        },
+    )
+    .unwrap();
+
+    harness.root.assert_not_exists();
+    assert_ne!(
+        std::fs::read(&existing_target_file_path)?,
+        b"existing"
+    );
+
+
+    target_harness.destroy()?;
+    Ok(())
+}
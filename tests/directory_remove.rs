@@ -0,0 +1,206 @@
+use std::ops::ControlFlow;
+
+use assert_matches::assert_matches;
+use fs_more::{
+    directory::{CountOptions, DirectoryDeleteProgress},
+    error::DirectoryError,
+};
+use fs_more_test_harness::{
+    assertable::AssertableDirectoryPath,
+    error::TestResult,
+    trees::{DeepTreeHarness, SimpleTreeHarness},
+};
+
+#[test]
+pub fn remove_empty_directories() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let empty_directory = AssertableDirectoryPath::from_path(harness.root.path().join("empty"));
+    let nested_empty_directory =
+        AssertableDirectoryPath::from_path(empty_directory.path().join("nested/deeper"));
+    std::fs::create_dir_all(nested_empty_directory.path())?;
+
+    let removed_directory_paths =
+        fs_more::directory::remove_empty_directories(harness.root.path()).unwrap();
+
+    // Subdirectories must be removed before their parents.
+    assert_eq!(
+        removed_directory_paths,
+        vec![
+            nested_empty_directory.path().to_path_buf(),
+            empty_directory.path().join("nested"),
+            empty_directory.path().to_path_buf(),
+        ]
+    );
+
+    empty_directory.assert_not_exists();
+
+    harness.root.assert_exists();
+    harness.subdirectory_b.assert_is_directory();
+    harness.binary_file_a.assert_content_unchanged();
+    harness.binary_file_b.assert_content_unchanged();
+
+
+    // With nothing left to remove, a second call is a no-op.
+    let removed_directory_paths =
+        fs_more::directory::remove_empty_directories(harness.root.path()).unwrap();
+    assert!(removed_directory_paths.is_empty());
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn remove_empty_directories_errors_on_missing_directory() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let removal_result =
+        fs_more::directory::remove_empty_directories(harness.root.path().join("missing"));
+
+    assert_matches!(
+        removal_result,
+        Err(DirectoryError::SourceDirectoryNotFound)
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn delete_directory_removes_directory_with_contents() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    fs_more::directory::delete_directory(harness.subdirectory_b.path()).unwrap();
+
+    harness.subdirectory_b.assert_not_exists();
+    harness.binary_file_a.assert_exists();
+
+    assert_matches!(
+        fs_more::directory::delete_directory(harness.subdirectory_b.path()),
+        Err(DirectoryError::SourceDirectoryNotFound)
+    );
+    assert_matches!(
+        fs_more::directory::delete_directory(harness.binary_file_a.path()),
+        Err(DirectoryError::SourceDirectoryIsNotADirectory)
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn delete_directory_with_progress_reports_each_removed_entry() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let counts =
+        fs_more::directory::count_entries(harness.dir_foo.path(), CountOptions::default()).unwrap();
+
+    let mut reports: Vec<DirectoryDeleteProgress> = Vec::new();
+
+    fs_more::directory::delete_directory_with_progress(harness.dir_foo.path(), |progress| {
+        reports.push(progress.clone());
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+
+    harness.dir_foo.assert_not_exists();
+    harness.file_a.assert_exists();
+
+    // One report per removed entry, including the directory itself.
+    assert_eq!(
+        reports.len(),
+        counts.files + counts.directories + 1
+    );
+
+    let last_report = reports.last().unwrap();
+    assert_eq!(last_report.files_deleted, counts.files);
+    assert_eq!(
+        last_report.directories_deleted,
+        counts.directories + 1
+    );
+    assert_eq!(last_report.current_path, harness.dir_foo.path());
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn delete_directory_with_progress_can_be_cancelled() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let mut num_reports = 0;
+
+    let delete_result =
+        fs_more::directory::delete_directory_with_progress(harness.dir_foo.path(), |_| {
+            num_reports += 1;
+
+            if num_reports == 2 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+
+    let progress = assert_matches!(
+        delete_result,
+        Err(DirectoryError::Cancelled { progress }) => progress
+    );
+
+    // Nothing is removed after the cancelling report.
+    assert_eq!(num_reports, 2);
+    assert_eq!(
+        progress.files_deleted + progress.directories_deleted,
+        2
+    );
+    assert!(!progress.current_path.exists());
+
+    harness.dir_foo.assert_is_not_empty();
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(feature = "trash")]
+#[test]
+pub fn trash_directory_moves_directory_to_trash_or_reports_unavailable_trash() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let trashed_directory = AssertableDirectoryPath::from_path(harness.root.path().join("trashed"));
+    std::fs::create_dir_all(trashed_directory.path().join("nested"))?;
+    std::fs::write(
+        trashed_directory.path().join("nested/file.txt"),
+        b"trash me",
+    )?;
+
+    let trash_result = fs_more::directory::trash_directory(trashed_directory.path());
+
+    // Not every environment has a trash available (e.g. headless CI runners),
+    // in which case the directory must be left in place.
+    match trash_result {
+        Ok(()) => trashed_directory.assert_not_exists(),
+        Err(error) => {
+            assert_matches!(error, DirectoryError::TrashUnavailable { .. });
+            trashed_directory.assert_exists();
+        }
+    }
+
+    let file_trash_result = fs_more::directory::trash_directory(harness.binary_file_a.path());
+    assert_matches!(
+        file_trash_result.unwrap_err(),
+        DirectoryError::SourceDirectoryIsNotADirectory
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use assert_matches::assert_matches;
+use fs_more::{
+    directory::{
+        CountOptions,
+        DirectoryScan,
+        DirectoryScanOptions,
+        EntryCounts,
+        WalkEntry,
+        WalkOptions,
+        WalkSortOrder,
+    },
+    error::DirectoryError,
+};
+use fs_more_test_harness::{
+    error::TestResult,
+    trees::{DeepTreeHarness, SimpleTreeHarness},
+};
+
+#[test]
+pub fn walk_directory() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let source_scan = DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .expect("failed to scan temporary directory");
+
+    let entries = fs_more::directory::walk(harness.root.path(), WalkOptions::default())
+        .collect::<Result<Vec<WalkEntry>, DirectoryError>>()
+        .expect("walk unexpectedly yielded an Err");
+
+    assert_eq!(
+        entries
+            .iter()
+            .filter(|entry| entry.file_type.is_file())
+            .count(),
+        source_scan.files().len(),
+        "walk and DirectoryScan report different number of files"
+    );
+    assert_eq!(
+        entries
+            .iter()
+            .filter(|entry| entry.file_type.is_dir())
+            .count(),
+        source_scan.directories().len(),
+        "walk and DirectoryScan report different number of directories"
+    );
+
+    // Each directory must be visited before its contents.
+    for (index, entry) in entries.iter().enumerate() {
+        let parent_directory = entry.path.parent().unwrap();
+
+        if parent_directory != harness.root.path() {
+            let parent_index = entries
+                .iter()
+                .position(|other_entry| other_entry.path == parent_directory)
+                .expect("walk did not visit the parent directory of an entry");
+
+            assert!(parent_index < index);
+            assert_eq!(entries[parent_index].depth + 1, entry.depth);
+        } else {
+            assert_eq!(entry.depth, 0);
+        }
+    }
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn walk_directory_with_limited_depth_and_sorting() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let entries = fs_more::directory::walk(
+        harness.root.path(),
+        WalkOptions {
+            maximum_depth: Some(0),
+            sort: WalkSortOrder::ByName,
+            ..Default::default()
+        },
+    )
+    .collect::<Result<Vec<WalkEntry>, DirectoryError>>()
+    .expect("walk unexpectedly yielded an Err");
+
+    let mut expected_paths = vec![
+        harness.binary_file_a.path().to_path_buf(),
+        harness.subdirectory_b.path().to_path_buf(),
+    ];
+    expected_paths.sort();
+
+    assert_eq!(
+        entries
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect::<Vec<_>>(),
+        expected_paths,
+        "walk did not respect the depth limit or sort order"
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn walk_directory_sorted_by_size() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let entries = fs_more::directory::walk(
+        harness.root.path(),
+        WalkOptions {
+            maximum_depth: Some(0),
+            sort: WalkSortOrder::BySizeDesc,
+            ..Default::default()
+        },
+    )
+    .collect::<Result<Vec<WalkEntry>, DirectoryError>>()
+    .expect("walk unexpectedly yielded an Err");
+
+    let sizes = entries
+        .iter()
+        .map(|entry| std::fs::symlink_metadata(&entry.path).unwrap().len())
+        .collect::<Vec<_>>();
+
+    assert!(
+        sizes.windows(2).all(|pair| pair[0] >= pair[1]),
+        "walk did not visit entries in descending size order"
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn error_on_walk_non_existent_directory() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let mut walk = fs_more::directory::walk(
+        harness.root.child_path("non-existent"),
+        WalkOptions::default(),
+    );
+
+    assert_matches!(
+        walk.next(),
+        Some(Err(DirectoryError::SourceDirectoryNotFound)),
+        "walk should have yielded an error for a non-existent directory"
+    );
+    assert!(walk.next().is_none());
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn count_directory_entries() -> TestResult<()> {
+    let harness = DeepTreeHarness::new()?;
+
+    let source_scan = DirectoryScan::scan_with_options(
+        harness.root.path(),
+        DirectoryScanOptions::default(),
+    )
+    .expect("failed to scan temporary directory");
+
+    let counts = fs_more::directory::count_entries(harness.root.path(), CountOptions::default())
+        .expect("failed to count entries");
+
+    assert_eq!(
+        counts,
+        EntryCounts {
+            files: source_scan.files().len(),
+            directories: source_scan.directories().len(),
+            symlinks: 0,
+        }
+    );
+
+    let shallow_counts = fs_more::directory::count_entries(
+        harness.root.path(),
+        CountOptions {
+            maximum_depth: Some(0),
+            ..Default::default()
+        },
+    )
+    .expect("failed to count entries");
+
+    assert_eq!(
+        shallow_counts,
+        EntryCounts {
+            files: 1,
+            directories: 3,
+            symlinks: 0,
+        }
+    );
+
+    assert_matches!(
+        fs_more::directory::count_entries(
+            harness.root.child_path("non-existent"),
+            CountOptions::default()
+        ),
+        Err(DirectoryError::SourceDirectoryNotFound)
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn count_directory_entries_with_symlinks() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let link_path = harness.root.child_path("link-to-subdirectory_b");
+    std::os::unix::fs::symlink(harness.subdirectory_b.path(), &link_path)?;
+
+    let counts = fs_more::directory::count_entries(harness.root.path(), CountOptions::default())
+        .expect("failed to count entries");
+
+    assert_eq!(
+        counts,
+        EntryCounts {
+            files: 2,
+            directories: 1,
+            symlinks: 1,
+        }
+    );
+
+    let followed_counts = fs_more::directory::count_entries(
+        harness.root.path(),
+        CountOptions {
+            follow_symbolic_links: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to count entries");
+
+    // The file inside the linked directory is now counted twice, the link itself only once.
+    assert_eq!(
+        followed_counts,
+        EntryCounts {
+            files: 3,
+            directories: 1,
+            symlinks: 1,
+        }
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
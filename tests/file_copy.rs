@@ -1,10 +1,18 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use assert_fs::fixture::FixtureError;
 use assert_matches::assert_matches;
 use fs_more::{
     error::FileError,
-    file::{FileCopyOptions, FileCopyWithProgressOptions, FileProgress},
+    file::{
+        BufferStrategy,
+        CopyMethod,
+        FileCopyOptions,
+        FileCopyWithProgressOptions,
+        FileProgress,
+        SparseMode,
+        DEFAULT_COPY_BUFFER_SIZE,
+    },
 };
 use fs_more_test_harness::{
     assertable::AssertableFilePath,
@@ -31,6 +39,14 @@ pub fn copy_file() -> TestResult<()> {
         FileCopyOptions {
             overwrite_existing: false,
             skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
         },
     );
 
@@ -69,6 +85,14 @@ pub fn copy_binary_file() -> TestResult<()> {
         FileCopyOptions {
             overwrite_existing: false,
             skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
         },
     );
 
@@ -100,6 +124,14 @@ pub fn forbid_copy_into_self() -> TestResult<()> {
         FileCopyOptions {
             overwrite_existing: false,
             skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
         },
     );
 
@@ -147,6 +179,14 @@ pub fn case_insensitive_copy_into_self() -> Result<(), FixtureError> {
         FileCopyOptions {
             overwrite_existing: false,
             skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
         },
     );
 
@@ -243,6 +283,14 @@ pub fn forbid_non_trivial_copy_into_self() -> Result<(), FixtureError> {
         FileCopyOptions {
             overwrite_existing: false,
             skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
         },
     );
 
@@ -300,6 +348,14 @@ pub fn allow_copy_overwriting_file_with_flag() -> TestResult<()> {
         FileCopyOptions {
             overwrite_existing: true,
             skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
         },
     );
 
@@ -323,6 +379,49 @@ pub fn allow_copy_overwriting_file_with_flag() -> TestResult<()> {
     Ok(())
 }
 
+#[test]
+pub fn copy_overwrites_only_if_source_is_newer_with_flag() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let options = FileCopyOptions {
+        overwrite_existing: true,
+        overwrite_only_if_source_is_newer: true,
+        ..Default::default()
+    };
+
+    // The target is modified after the source, so it must not be overwritten.
+    std::fs::write(harness.foo_bar.path(), "newer target")?;
+
+    let num_bytes_copied = fs_more::file::copy_file(
+        harness.test_file.path(),
+        harness.foo_bar.path(),
+        options,
+    )
+    .unwrap();
+
+    assert_eq!(num_bytes_copied, 0);
+    harness.foo_bar.assert_content_matches_str("newer target");
+
+
+    // Now the source is modified after the target, so the target must be overwritten.
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    std::fs::write(harness.test_file.path(), "newer source")?;
+
+    let num_bytes_copied = fs_more::file::copy_file(
+        harness.test_file.path(),
+        harness.foo_bar.path(),
+        options,
+    )
+    .unwrap();
+
+    assert_eq!(num_bytes_copied, "newer source".len() as u64);
+    harness.foo_bar.assert_content_matches_str("newer source");
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
 #[test]
 pub fn forbid_copy_overwriting_file_without_flag() -> TestResult<()> {
     let harness = SimpleFileHarness::new()?;
@@ -333,6 +432,14 @@ pub fn forbid_copy_overwriting_file_without_flag() -> TestResult<()> {
         FileCopyOptions {
             overwrite_existing: false,
             skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
         },
     );
 
@@ -363,6 +470,14 @@ pub fn skip_existing_target_file_move_with_flag() -> TestResult<()> {
         FileCopyOptions {
             overwrite_existing: false,
             skip_existing: true,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
         },
     );
 
@@ -388,69 +503,131 @@ pub fn skip_existing_target_file_move_with_flag() -> TestResult<()> {
     Ok(())
 }
 
+#[test]
+pub fn copy_file_atomically_overwriting_existing() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
 
+    let file_copy_result = fs_more::file::copy_file(
+        harness.test_file.path(),
+        harness.foo_bar.path(),
+        FileCopyOptions {
+            overwrite_existing: true,
+            skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: true,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
+        },
+    );
 
-/*
- * COPYING WITH PROGRESS
- */
+    assert!(
+        file_copy_result.is_ok(),
+        "failed to execute atomic copy_file: expected Ok, got {}",
+        file_copy_result.unwrap_err()
+    );
 
+    harness.test_file.assert_content_unchanged();
+    harness
+        .foo_bar
+        .assert_content_matches_expected_value_of_assertable(&harness.test_file);
 
-#[test]
-pub fn copy_file_with_progress() -> TestResult<()> {
-    let harness = SimpleFileHarness::new()?;
+    // No temporary files should be left behind.
+    let num_root_entries = std::fs::read_dir(harness.root.path())?.count();
+    assert_eq!(
+        num_root_entries, 2,
+        "atomic copy_file left extra files behind in the target directory"
+    );
 
-    let target_file =
-        AssertableFilePath::from_path(harness.test_file.path().with_file_name("test_file2.txt"));
-    target_file.assert_not_exists();
 
-    let expected_final_file_size_bytes = harness.test_file.path().metadata()?.len();
+    harness.destroy()?;
+    Ok(())
+}
 
-    let mut last_bytes_copied = 0;
-    let mut total_bytes = 0;
+#[test]
+pub fn fail_atomic_copy_when_temporary_file_cannot_be_created() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
 
-    let file_copy_result: Result<u64, FileError> = fs_more::file::copy_file_with_progress(
+    let target_file = AssertableFilePath::from_path(
+        harness
+            .root
+            .child_path("nonexistent_directory")
+            .join("test_file.txt"),
+    );
+
+    let file_copy_result = fs_more::file::copy_file(
         harness.test_file.path(),
         target_file.path(),
-        FileCopyWithProgressOptions {
+        FileCopyOptions {
             overwrite_existing: false,
             skip_existing: false,
-            ..Default::default()
-        },
-        |progress| {
-            last_bytes_copied = progress.bytes_finished;
-            total_bytes = progress.bytes_total;
+            overwrite_only_if_source_is_newer: false,
+            atomic: true,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
         },
     );
 
-    assert!(
-        file_copy_result.is_ok(),
-        "failed to execute copy_file_with_progress: expected Ok, got {}.",
-        file_copy_result.unwrap_err()
+    let file_copy_err = file_copy_result.unwrap_err();
+    assert_matches!(
+        file_copy_err,
+        FileError::UnableToCreateTemporaryFile { .. },
+        "copy_file should have errored with UnableToCreateTemporaryFile, got {} instead",
+        file_copy_err
     );
 
-    let bytes_copied = file_copy_result.unwrap();
-    assert_eq!(
-        bytes_copied, last_bytes_copied,
-        "copy_file_with_progress failed to report some last writes \
-        (return value and last progress update do not match)"
+    target_file.assert_not_exists();
+    harness.test_file.assert_content_unchanged();
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+
+#[test]
+pub fn forbid_copy_when_target_parent_is_a_file() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let target_file_path = harness.foo_bar.path().join("test_file2.txt");
+
+    let file_copy_result = fs_more::file::copy_file(
+        harness.test_file.path(),
+        &target_file_path,
+        FileCopyOptions::default(),
     );
 
-    assert_eq!(
-        bytes_copied, expected_final_file_size_bytes,
-        "copied vs real total bytes mismatch: the copy_file_with_progress return value \
-        doesn't match the entire file size reported by the filesystem"
+    assert_matches!(
+        file_copy_result,
+        Err(FileError::TargetParentNotADirectory { path }) if path == harness.foo_bar.path(),
+        "copy_file should have errored because the target's parent is a file"
     );
-    assert_eq!(
-        bytes_copied, total_bytes,
-        "copied vs total bytes mismatch: the copy_file_with_progress return value \
-        doesn't match the entire file size reported by the same function"
+
+    harness.foo_bar.assert_content_unchanged();
+
+
+    let file_copy_result = fs_more::file::copy_file_with_progress(
+        harness.test_file.path(),
+        target_file_path.join("nested.txt"),
+        FileCopyWithProgressOptions::default(),
+        |_| {},
     );
 
-    harness.test_file.assert_exists();
-    harness.test_file.assert_content_unchanged();
+    assert_matches!(
+        file_copy_result,
+        Err(FileError::TargetParentNotADirectory { path }) if path == harness.foo_bar.path(),
+        "copy_file_with_progress should have errored because an ancestor of the target is a file"
+    );
 
-    target_file.assert_exists();
-    target_file.assert_content_matches_expected_value_of_assertable(&harness.test_file);
+    harness.foo_bar.assert_content_unchanged();
 
 
     harness.destroy()?;
@@ -458,171 +635,1387 @@ pub fn copy_file_with_progress() -> TestResult<()> {
 }
 
 
-/// **On Windows**, creating symbolic links requires administrator privileges, unless Developer mode is enabled.
-/// See [https://stackoverflow.com/questions/58038683/allow-mklink-for-a-non-admin-user].
 #[test]
-pub fn copy_file_symlink_behaviour() -> TestResult<()> {
+pub fn copy_file_range() -> TestResult<()> {
     let harness = SimpleTreeHarness::new()?;
 
-    let symlinked_file = AssertableFilePath::from_path(harness.root.child_path("my-symlink.txt"));
-    symlinked_file.assert_not_exists();
-    symlinked_file.symlink_to_file(harness.binary_file_a.path())?;
-    symlinked_file.assert_is_symlink();
-
-    let real_file_size_in_bytes = symlinked_file.file_size_in_bytes()?;
+    let source_contents = std::fs::read(harness.binary_file_b.path())?;
 
-    let target_file =
-        AssertableFilePath::from_path(harness.root.child_path("my-copied-symlink.txt"));
+    let target_file = AssertableFilePath::from_path(
+        harness
+            .binary_file_b
+            .path()
+            .with_file_name("range_of_file_b.bin"),
+    );
     target_file.assert_not_exists();
 
-
-    let num_copied_bytes = fs_more::file::copy_file(
-        symlinked_file.path(),
+    let num_bytes_copied = fs_more::file::copy_file_range(
+        harness.binary_file_b.path(),
         target_file.path(),
+        1000,
+        Some(5000),
         FileCopyOptions::default(),
     )
-    .unwrap();
-
-    assert_eq!(real_file_size_in_bytes, num_copied_bytes);
-
-    symlinked_file.assert_is_symlink();
-    target_file.assert_is_file();
+    .unwrap_or_else(|error| {
+        panic!(
+            "copy_file_range unexpectedly failed with Err: {}",
+            error
+        );
+    });
 
+    assert_eq!(num_bytes_copied, 5000);
     assert_eq!(
-        real_file_size_in_bytes,
-        target_file.file_size_in_bytes()?
+        std::fs::read(target_file.path())?,
+        &source_contents[1000..6000],
+        "copy_file_range did not copy the requested range"
     );
 
+    harness.binary_file_b.assert_content_unchanged();
+
     harness.destroy()?;
     Ok(())
 }
 
-/// **On Windows**, creating symbolic links requires administrator privileges, unless Developer mode is enabled.
-/// See [https://stackoverflow.com/questions/58038683/allow-mklink-for-a-non-admin-user].
+
 #[test]
-pub fn copy_file_with_progress_symlink_behaviour() -> TestResult<()> {
+pub fn copy_file_range_up_to_end_of_file() -> TestResult<()> {
     let harness = SimpleTreeHarness::new()?;
 
-    let symlinked_file = AssertableFilePath::from_path(harness.root.child_path("my-symlink.txt"));
-    symlinked_file.assert_not_exists();
-    symlinked_file.symlink_to_file(harness.binary_file_a.path())?;
-    symlinked_file.assert_is_symlink_to_file();
-
-    let real_file_size_in_bytes = symlinked_file.file_size_in_bytes()?;
-
-    let target_file =
-        AssertableFilePath::from_path(harness.root.child_path("my-copied-symlink.txt"));
-    target_file.assert_not_exists();
+    let source_contents = std::fs::read(harness.binary_file_b.path())?;
+    let start = source_contents.len() as u64 - 100;
 
+    let target_file = AssertableFilePath::from_path(
+        harness
+            .binary_file_b
+            .path()
+            .with_file_name("range_of_file_b.bin"),
+    );
 
-    let num_copied_bytes = fs_more::file::copy_file_with_progress(
-        symlinked_file.path(),
+    // A range extending past the end of the source file is truncated.
+    let num_bytes_copied = fs_more::file::copy_file_range(
+        harness.binary_file_b.path(),
         target_file.path(),
-        FileCopyWithProgressOptions::default(),
-        |_| {},
+        start,
+        Some(1000),
+        FileCopyOptions::default(),
     )
     .unwrap();
 
-    assert_eq!(real_file_size_in_bytes, num_copied_bytes);
+    assert_eq!(num_bytes_copied, 100);
+    assert_eq!(
+        std::fs::read(target_file.path())?,
+        &source_contents[start as usize..],
+    );
 
-    symlinked_file.assert_is_symlink_to_file();
-    target_file.assert_is_file();
+    let num_bytes_copied = fs_more::file::copy_file_range(
+        harness.binary_file_b.path(),
+        target_file.path(),
+        start,
+        None,
+        FileCopyOptions {
+            overwrite_existing: true,
+            atomic: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
 
+    assert_eq!(num_bytes_copied, 100);
     assert_eq!(
-        real_file_size_in_bytes,
-        target_file.file_size_in_bytes()?
+        std::fs::read(target_file.path())?,
+        &source_contents[start as usize..],
     );
 
+    // A range starting past the end of the source file is empty.
+    let num_bytes_copied = fs_more::file::copy_file_range(
+        harness.binary_file_b.path(),
+        target_file.path(),
+        source_contents.len() as u64 + 10,
+        None,
+        FileCopyOptions {
+            overwrite_existing: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(num_bytes_copied, 0);
+    assert!(std::fs::read(target_file.path())?.is_empty());
+
     harness.destroy()?;
     Ok(())
 }
 
 
+#[cfg(unix)]
 #[test]
-pub fn forbid_copy_file_when_source_is_symlink_to_target() -> TestResult<()> {
-    // Tests behaviour when copying "symlink to file A" to "A".
-    // This should fail.
+pub fn copy_file_range_applies_target_permissions() -> TestResult<()> {
+    use std::os::unix::fs::PermissionsExt;
 
-    let harness = SimpleFileHarness::new()?;
+    let harness = SimpleTreeHarness::new()?;
 
+    let target_file = AssertableFilePath::from_path(
+        harness
+            .binary_file_b
+            .path()
+            .with_file_name("binary_file_b-range.bin"),
+    );
+    target_file.assert_not_exists();
 
-    let test_symlink =
-        AssertableFilePath::from_path(harness.root.child_path("symlink-test-file.txt"));
-    test_symlink.assert_not_exists();
-    test_symlink
-        .symlink_to_file(harness.test_file.path())
-        .unwrap();
-    test_symlink.assert_is_symlink_to_file();
+    let source_contents = std::fs::read(harness.binary_file_b.path())?;
 
-    let copy_result: Result<u64, FileError> = fs_more::file::copy_file(
-        test_symlink.path(),
-        harness.test_file.path(),
+    let num_bytes_copied = fs_more::file::copy_file_range(
+        harness.binary_file_b.path(),
+        target_file.path(),
+        0,
+        Some(10),
         FileCopyOptions {
-            overwrite_existing: true,
-            skip_existing: false,
+            target_permissions: Some(0o440),
+            ..Default::default()
         },
+    )
+    .unwrap();
+
+    assert_eq!(num_bytes_copied, 10);
+    assert_eq!(
+        std::fs::read(target_file.path())?,
+        &source_contents[..10]
+    );
+    assert_eq!(
+        std::fs::metadata(target_file.path())?.permissions().mode() & 0o777,
+        0o440
     );
 
-    let copy_err = copy_result.unwrap_err();
 
-    match &copy_err {
-        FileError::SourceAndTargetAreTheSameFile => {
-            // This is the expected behaviour in this case.
-        }
-        _ => panic!("Unexpected Err: {}", copy_err),
-    }
+    harness.destroy()?;
+    Ok(())
+}
 
 
-    harness.destroy()?;
 
-    Ok(())
+/// Creates a 1 MiB sparse file with a single 4 KiB block of data in the middle.
+fn create_sparse_file(file_path: &std::path::Path) -> TestResult<Vec<u8>> {
+    use std::io::{Seek, SeekFrom, Write};
+
+    let mut file = std::fs::File::create(file_path)?;
+    file.set_len(1024 * 1024)?;
+    file.seek(SeekFrom::Start(512 * 1024))?;
+    file.write_all(&[0xAB; 4096])?;
+    drop(file);
+
+    Ok(std::fs::read(file_path)?)
 }
 
 
 #[test]
-pub fn forbid_copy_file_with_progress_when_source_is_symlink_to_target() -> TestResult<()> {
-    // Tests behaviour when copying "symlink to file A" to "A".
-    // This should fail.
+pub fn copy_sparse_file_punching_zero_runs() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let source_file_path = harness.root.path().join("sparse.bin");
+    let source_contents = create_sparse_file(&source_file_path)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(
+            &source_file_path,
+            std::fs::Permissions::from_mode(0o700),
+        )?;
+    }
+
+    let target_file = AssertableFilePath::from_path(harness.root.path().join("sparse-copy.bin"));
+    target_file.assert_not_exists();
+
+    let finished_copy = fs_more::file::copy_file_detailed(
+        &source_file_path,
+        target_file.path(),
+        FileCopyOptions {
+            sparse: SparseMode::Always,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        finished_copy.bytes_copied,
+        source_contents.len() as u64
+    );
+    assert_eq!(finished_copy.bytes_written, 4096);
+    assert_eq!(
+        std::fs::read(target_file.path())?,
+        source_contents
+    );
+
+    // The source file's permissions are copied as well.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        assert_eq!(
+            std::fs::metadata(target_file.path())?.permissions().mode() & 0o777,
+            0o700
+        );
+    }
+
+    harness.destroy()?;
+    Ok(())
+}
+
 
+#[test]
+pub fn copy_sparse_file_preserving_holes() -> TestResult<()> {
     let harness = SimpleFileHarness::new()?;
 
-    let test_symlink =
-        AssertableFilePath::from_path(harness.root.child_path("symlink-test-file.txt"));
-    test_symlink.assert_not_exists();
-    test_symlink
-        .symlink_to_file(harness.test_file.path())
-        .unwrap();
-    test_symlink.assert_is_symlink_to_file();
+    let source_file_path = harness.root.path().join("sparse.bin");
+    let source_contents = create_sparse_file(&source_file_path)?;
 
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
 
-    let mut last_progress: Option<FileProgress> = None;
+        std::fs::set_permissions(
+            &source_file_path,
+            std::fs::Permissions::from_mode(0o700),
+        )?;
+    }
 
-    let copy_result: Result<u64, FileError> = fs_more::file::copy_file_with_progress(
-        test_symlink.path(),
+    let target_file = AssertableFilePath::from_path(harness.root.path().join("sparse-copy.bin"));
+    target_file.assert_not_exists();
+
+    let finished_copy = fs_more::file::copy_file_detailed(
+        &source_file_path,
+        target_file.path(),
+        FileCopyOptions {
+            sparse: SparseMode::Auto,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Whether any holes are recreated depends on the platform and filesystem.
+    assert_eq!(
+        finished_copy.bytes_copied,
+        source_contents.len() as u64
+    );
+    assert!(finished_copy.bytes_written <= finished_copy.bytes_copied);
+    assert!(finished_copy.bytes_written >= 4096);
+    assert_eq!(
+        std::fs::read(target_file.path())?,
+        source_contents
+    );
+
+    // The source file's permissions are copied as well.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        assert_eq!(
+            std::fs::metadata(target_file.path())?.permissions().mode() & 0o777,
+            0o700
+        );
+    }
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+/// `/proc/self/mem` looks like a regular file, but reading it from the start fails with an I/O error,
+/// which lets us simulate a copy that fails midway.
+#[cfg(target_os = "linux")]
+const UNREADABLE_SOURCE_FILE_PATH: &str = "/proc/self/mem";
+
+
+#[cfg(target_os = "linux")]
+#[test]
+pub fn copy_file_removes_partial_target_on_failure() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let target_file = AssertableFilePath::from_path(harness.root.path().join("partial.bin"));
+    target_file.assert_not_exists();
+
+    let copy_result = fs_more::file::copy_file(
+        UNREADABLE_SOURCE_FILE_PATH,
+        target_file.path(),
+        FileCopyOptions::default(),
+    );
+
+    assert_matches!(copy_result, Err(FileError::OtherIoError { .. }));
+    target_file.assert_not_exists();
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+/*
+ * COPYING WITH PROGRESS
+ */
+
+
+#[test]
+pub fn copy_file_with_progress() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let target_file =
+        AssertableFilePath::from_path(harness.test_file.path().with_file_name("test_file2.txt"));
+    target_file.assert_not_exists();
+
+    let expected_final_file_size_bytes = harness.test_file.path().metadata()?.len();
+
+    let mut last_bytes_copied = 0;
+    let mut total_bytes = 0;
+
+    let file_copy_result: Result<u64, FileError> = fs_more::file::copy_file_with_progress(
         harness.test_file.path(),
+        target_file.path(),
         FileCopyWithProgressOptions {
-            overwrite_existing: true,
+            overwrite_existing: false,
             skip_existing: false,
             ..Default::default()
         },
         |progress| {
-            last_progress = Some(progress.clone());
+            last_bytes_copied = progress.bytes_finished;
+            total_bytes = progress.bytes_total;
         },
     );
 
-    assert!(last_progress.is_none());
+    assert!(
+        file_copy_result.is_ok(),
+        "failed to execute copy_file_with_progress: expected Ok, got {}.",
+        file_copy_result.unwrap_err()
+    );
 
-    let copy_err = copy_result.unwrap_err();
+    let bytes_copied = file_copy_result.unwrap();
+    assert_eq!(
+        bytes_copied, last_bytes_copied,
+        "copy_file_with_progress failed to report some last writes \
+        (return value and last progress update do not match)"
+    );
 
-    match &copy_err {
-        FileError::SourceAndTargetAreTheSameFile => {
-            // This is the expected behaviour in this case.
-        }
-        _ => panic!("Unexpected Err: {}", copy_err),
-    }
+    assert_eq!(
+        bytes_copied, expected_final_file_size_bytes,
+        "copied vs real total bytes mismatch: the copy_file_with_progress return value \
+        doesn't match the entire file size reported by the filesystem"
+    );
+    assert_eq!(
+        bytes_copied, total_bytes,
+        "copied vs total bytes mismatch: the copy_file_with_progress return value \
+        doesn't match the entire file size reported by the same function"
+    );
+
+    harness.test_file.assert_exists();
+    harness.test_file.assert_content_unchanged();
+
+    target_file.assert_exists();
+    target_file.assert_content_matches_expected_value_of_assertable(&harness.test_file);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn file_progress_fraction_and_ordering() -> TestResult<()> {
+    let halfway = FileProgress {
+        bytes_finished: 50,
+        bytes_total: 100,
+    };
+    let finished = FileProgress {
+        bytes_finished: 100,
+        bytes_total: 100,
+    };
+
+    assert_eq!(halfway.fraction_complete(), 0.5);
+    assert_eq!(finished.fraction_complete(), 1.0);
+    assert!(halfway < finished);
+
+    // Progress of an empty file is always complete.
+    let empty = FileProgress {
+        bytes_finished: 0,
+        bytes_total: 0,
+    };
+    assert_eq!(empty.fraction_complete(), 1.0);
+
+    // Progress with different totals can't be compared.
+    assert_eq!(halfway.partial_cmp(&empty), None);
+
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_file_with_progress_preallocating_target() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let target_file =
+        AssertableFilePath::from_path(harness.root.child_path("preallocated-copy.bin"));
+    target_file.assert_not_exists();
+
+    let bytes_copied = fs_more::file::copy_file_with_progress(
+        harness.binary_file_a.path(),
+        target_file.path(),
+        FileCopyWithProgressOptions {
+            preallocate_target: true,
+            ..Default::default()
+        },
+        |_| {},
+    )
+    .unwrap();
+
+    // Pre-allocation must not change the size of the target file.
+    assert_eq!(
+        bytes_copied,
+        harness.binary_file_a.file_size_in_bytes()?
+    );
+    assert_eq!(bytes_copied, target_file.file_size_in_bytes()?);
+    target_file.assert_content_matches_file(harness.binary_file_a.path());
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_file_with_progress_unbuffered() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    // A size that is not a multiple of the block size, so the final partial block is written separately.
+    let source_file =
+        AssertableFilePath::from_path(harness.root.child_path("unaligned-source.bin"));
+    let source_contents: Vec<u8> = (0..(4096 * 3 + 123))
+        .map(|index| (index % 251) as u8)
+        .collect();
+    std::fs::write(source_file.path(), &source_contents)?;
+
+    let target_file = AssertableFilePath::from_path(harness.root.child_path("unbuffered-copy.bin"));
+    target_file.assert_not_exists();
+
+    let mut last_progress = None;
+
+    let bytes_copied = fs_more::file::copy_file_with_progress(
+        source_file.path(),
+        target_file.path(),
+        FileCopyWithProgressOptions {
+            unbuffered: true,
+            buffer_strategy: BufferStrategy::Fixed(4096),
+            ..Default::default()
+        },
+        |progress| {
+            last_progress = Some(progress.clone());
+        },
+    )
+    .unwrap();
+
+    assert_eq!(bytes_copied, source_contents.len() as u64);
+    assert_eq!(
+        std::fs::read(target_file.path())?,
+        source_contents
+    );
+
+    let last_progress = last_progress.unwrap();
+    assert_eq!(last_progress.bytes_finished, bytes_copied);
+    assert_eq!(last_progress.bytes_total, bytes_copied);
 
 
     harness.destroy()?;
+    Ok(())
+}
+
+
+/// **On Windows**, creating symbolic links requires administrator privileges, unless Developer mode is enabled.
+/// See [https://stackoverflow.com/questions/58038683/allow-mklink-for-a-non-admin-user].
+#[test]
+pub fn copy_file_symlink_behaviour() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let symlinked_file = AssertableFilePath::from_path(harness.root.child_path("my-symlink.txt"));
+    symlinked_file.assert_not_exists();
+    symlinked_file.symlink_to_file(harness.binary_file_a.path())?;
+    symlinked_file.assert_is_symlink();
+
+    let real_file_size_in_bytes = symlinked_file.file_size_in_bytes()?;
+
+    let target_file =
+        AssertableFilePath::from_path(harness.root.child_path("my-copied-symlink.txt"));
+    target_file.assert_not_exists();
+
+
+    let num_copied_bytes = fs_more::file::copy_file(
+        symlinked_file.path(),
+        target_file.path(),
+        FileCopyOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(real_file_size_in_bytes, num_copied_bytes);
+
+    symlinked_file.assert_is_symlink();
+    target_file.assert_is_file();
+
+    assert_eq!(
+        real_file_size_in_bytes,
+        target_file.file_size_in_bytes()?
+    );
 
+    harness.destroy()?;
+    Ok(())
+}
+
+/// **On Windows**, creating symbolic links requires administrator privileges, unless Developer mode is enabled.
+/// See [https://stackoverflow.com/questions/58038683/allow-mklink-for-a-non-admin-user].
+#[test]
+pub fn copy_file_with_progress_symlink_behaviour() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let symlinked_file = AssertableFilePath::from_path(harness.root.child_path("my-symlink.txt"));
+    symlinked_file.assert_not_exists();
+    symlinked_file.symlink_to_file(harness.binary_file_a.path())?;
+    symlinked_file.assert_is_symlink_to_file();
+
+    let real_file_size_in_bytes = symlinked_file.file_size_in_bytes()?;
+
+    let target_file =
+        AssertableFilePath::from_path(harness.root.child_path("my-copied-symlink.txt"));
+    target_file.assert_not_exists();
+
+
+    let num_copied_bytes = fs_more::file::copy_file_with_progress(
+        symlinked_file.path(),
+        target_file.path(),
+        FileCopyWithProgressOptions::default(),
+        |_| {},
+    )
+    .unwrap();
+
+    assert_eq!(real_file_size_in_bytes, num_copied_bytes);
+
+    symlinked_file.assert_is_symlink_to_file();
+    target_file.assert_is_file();
+
+    assert_eq!(
+        real_file_size_in_bytes,
+        target_file.file_size_in_bytes()?
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn forbid_copy_file_when_source_is_symlink_to_target() -> TestResult<()> {
+    // Tests behaviour when copying "symlink to file A" to "A".
+    // This should fail.
+
+    let harness = SimpleFileHarness::new()?;
+
+
+    let test_symlink =
+        AssertableFilePath::from_path(harness.root.child_path("symlink-test-file.txt"));
+    test_symlink.assert_not_exists();
+    test_symlink
+        .symlink_to_file(harness.test_file.path())
+        .unwrap();
+    test_symlink.assert_is_symlink_to_file();
+
+    let copy_result: Result<u64, FileError> = fs_more::file::copy_file(
+        test_symlink.path(),
+        harness.test_file.path(),
+        FileCopyOptions {
+            overwrite_existing: true,
+            skip_existing: false,
+            overwrite_only_if_source_is_newer: false,
+            atomic: false,
+            sparse: SparseMode::Never,
+            preserve_alternate_data_streams: false,
+            copy_method: CopyMethod::Standard,
+            preserve_ownership: false,
+            preserve_creation_time: false,
+            target_permissions: None,
+        },
+    );
+
+    let copy_err = copy_result.unwrap_err();
+
+    match &copy_err {
+        FileError::SourceAndTargetAreTheSameFile => {
+            // This is the expected behaviour in this case.
+        }
+        _ => panic!("Unexpected Err: {}", copy_err),
+    }
+
+
+    harness.destroy()?;
+
+    Ok(())
+}
+
+
+#[test]
+pub fn forbid_copy_file_with_progress_when_source_is_symlink_to_target() -> TestResult<()> {
+    // Tests behaviour when copying "symlink to file A" to "A".
+    // This should fail.
+
+    let harness = SimpleFileHarness::new()?;
+
+    let test_symlink =
+        AssertableFilePath::from_path(harness.root.child_path("symlink-test-file.txt"));
+    test_symlink.assert_not_exists();
+    test_symlink
+        .symlink_to_file(harness.test_file.path())
+        .unwrap();
+    test_symlink.assert_is_symlink_to_file();
+
+
+    let mut last_progress: Option<FileProgress> = None;
+
+    let copy_result: Result<u64, FileError> = fs_more::file::copy_file_with_progress(
+        test_symlink.path(),
+        harness.test_file.path(),
+        FileCopyWithProgressOptions {
+            overwrite_existing: true,
+            skip_existing: false,
+            ..Default::default()
+        },
+        |progress| {
+            last_progress = Some(progress.clone());
+        },
+    );
+
+    assert!(last_progress.is_none());
+
+    let copy_err = copy_result.unwrap_err();
+
+    match &copy_err {
+        FileError::SourceAndTargetAreTheSameFile => {
+            // This is the expected behaviour in this case.
+        }
+        _ => panic!("Unexpected Err: {}", copy_err),
+    }
+
+
+    harness.destroy()?;
+
+    Ok(())
+}
+
+
+#[cfg(target_os = "linux")]
+#[test]
+pub fn copy_file_with_progress_removes_partial_target_on_failure() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    // The target file exists and is being overwritten, so it must be removed as well.
+    let copy_result = fs_more::file::copy_file_with_progress(
+        UNREADABLE_SOURCE_FILE_PATH,
+        harness.foo_bar.path(),
+        FileCopyWithProgressOptions {
+            overwrite_existing: true,
+            ..Default::default()
+        },
+        |_| {},
+    );
+
+    assert_matches!(copy_result, Err(FileError::OtherIoError { .. }));
+    harness.foo_bar.assert_not_exists();
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_file_as_renames_copy_in_target_directory() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let num_bytes_copied = fs_more::file::copy_file_as(
+        harness.binary_file_a.path(),
+        harness.subdirectory_b.path(),
+        |source_file_path| {
+            format!(
+                "backup-{}",
+                source_file_path.file_name().unwrap().to_string_lossy()
+            )
+        },
+        FileCopyOptions::default(),
+    )
+    .unwrap();
+
+    let target_file = AssertableFilePath::from_path(
+        harness
+            .subdirectory_b
+            .path()
+            .join("backup-binary_file_a.bin"),
+    );
+
+    assert_eq!(
+        num_bytes_copied,
+        harness.binary_file_a.file_size_in_bytes()?
+    );
+    harness.binary_file_a.assert_content_unchanged();
+    target_file.assert_content_matches_file(harness.binary_file_a.path());
+
+
+    // The copy already exists, which is handled by the usual options.
+    let file_copy_result = fs_more::file::copy_file_as(
+        harness.binary_file_a.path(),
+        harness.subdirectory_b.path(),
+        |_| "backup-binary_file_a.bin",
+        FileCopyOptions::default(),
+    );
+
+    assert_matches!(file_copy_result, Err(FileError::AlreadyExists));
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_file_as_rejects_invalid_target_file_names() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    for invalid_file_name in ["", "..", "nested/file.bin"] {
+        let file_copy_result = fs_more::file::copy_file_as(
+            harness.binary_file_a.path(),
+            harness.subdirectory_b.path(),
+            |_| invalid_file_name,
+            FileCopyOptions::default(),
+        );
+
+        assert_matches!(
+            file_copy_result,
+            Err(FileError::InvalidTargetFileName { path }) if path == Path::new(invalid_file_name)
+        );
+    }
+
+    harness.binary_file_a.assert_content_unchanged();
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_file_preserving_alternate_data_streams() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let target_file =
+        AssertableFilePath::from_path(harness.root.child_path("copied-binary_file_a.bin"));
+
+    // On NTFS, the source file is given a named stream (`file:name` addresses it).
+    #[cfg(windows)]
+    std::fs::write(
+        format!(
+            "{}:metadata",
+            harness.binary_file_a.path().display()
+        ),
+        "stream contents",
+    )?;
+
+    fs_more::file::copy_file(
+        harness.binary_file_a.path(),
+        target_file.path(),
+        FileCopyOptions {
+            sparse: SparseMode::Always,
+            preserve_alternate_data_streams: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    target_file.assert_content_matches_expected_value_of_assertable(&harness.binary_file_a);
+
+    #[cfg(windows)]
+    assert_eq!(
+        std::fs::read_to_string(format!(
+            "{}:metadata",
+            target_file.path().display()
+        ))?,
+        "stream contents"
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_file_memory_mapped_above_threshold() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let large_file =
+        AssertableFilePath::from_path(harness.root.child_path("large-binary-file.bin"));
+    let large_file_contents: Vec<u8> = (0..(16 * 1024 * 1024))
+        .map(|index: u32| (index % 251) as u8)
+        .collect();
+    std::fs::write(large_file.path(), &large_file_contents)?;
+
+    let memory_mapped_options = FileCopyOptions {
+        copy_method: CopyMethod::MemoryMapped {
+            minimum_size_in_bytes: 1024 * 1024,
+        },
+        ..Default::default()
+    };
+
+
+    let standard_target_file =
+        AssertableFilePath::from_path(harness.root.child_path("standard-copy.bin"));
+
+    let standard_copy = fs_more::file::copy_file_detailed(
+        large_file.path(),
+        standard_target_file.path(),
+        FileCopyOptions::default(),
+    )
+    .unwrap();
+
+    assert!(!standard_copy.was_memory_mapped);
+
+
+    let mapped_target_file =
+        AssertableFilePath::from_path(harness.root.child_path("memory-mapped-copy.bin"));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(
+            large_file.path(),
+            std::fs::Permissions::from_mode(0o700),
+        )?;
+    }
+
+    let mapped_copy = fs_more::file::copy_file_detailed(
+        large_file.path(),
+        mapped_target_file.path(),
+        memory_mapped_options,
+    )
+    .unwrap();
+
+    // Memory mapping is only supported on Unix, other platforms fall back to the standard copy.
+    assert_eq!(mapped_copy.was_memory_mapped, cfg!(unix));
+    assert_eq!(
+        mapped_copy.bytes_copied,
+        large_file_contents.len() as u64
+    );
+    assert_eq!(
+        std::fs::read(mapped_target_file.path())?,
+        large_file_contents
+    );
+
+    // The source file's permissions are copied as well.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        assert_eq!(
+            std::fs::metadata(mapped_target_file.path())?
+                .permissions()
+                .mode()
+                & 0o777,
+            0o700
+        );
+    }
+
+
+    // Source files without holes are still memory-mapped when copying sparsely.
+    let sparse_target_file =
+        AssertableFilePath::from_path(harness.root.child_path("sparse-memory-mapped-copy.bin"));
+
+    let sparse_copy = fs_more::file::copy_file_detailed(
+        large_file.path(),
+        sparse_target_file.path(),
+        FileCopyOptions {
+            sparse: SparseMode::Auto,
+            ..memory_mapped_options
+        },
+    )
+    .unwrap();
+
+    assert_eq!(sparse_copy.was_memory_mapped, cfg!(unix));
+    assert_eq!(
+        std::fs::read(sparse_target_file.path())?,
+        large_file_contents
+    );
+
+
+    // Files below the threshold are copied the standard way.
+    let small_target_file =
+        AssertableFilePath::from_path(harness.root.child_path("small-copy.bin"));
+
+    let small_copy = fs_more::file::copy_file_detailed(
+        harness.binary_file_a.path(),
+        small_target_file.path(),
+        memory_mapped_options,
+    )
+    .unwrap();
+
+    assert!(!small_copy.was_memory_mapped);
+    small_target_file.assert_content_matches_expected_value_of_assertable(&harness.binary_file_a);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn io_errors_convert_into_file_error() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let missing_file_path = harness.test_file.path().with_file_name("missing.txt");
+
+    let copy_then_read_missing = || -> Result<u64, FileError> {
+        let bytes_copied = fs_more::file::copy_file(
+            harness.test_file.path(),
+            harness.test_file.path().with_file_name("test_file2.txt"),
+            FileCopyOptions::default(),
+        )?;
+
+        std::fs::metadata(&missing_file_path)?;
+
+        Ok(bytes_copied)
+    };
+
+    assert_matches!(
+        copy_then_read_missing(),
+        Err(FileError::OtherIoError { error })
+        if error.kind() == std::io::ErrorKind::NotFound
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn file_errors_expose_io_error_as_source() -> TestResult<()> {
+    use std::error::Error;
+
+    let file_errors = [
+        FileError::UnableToAccessSourceFile {
+            error: std::io::Error::new(std::io::ErrorKind::NotFound, "source"),
+        },
+        FileError::UnableToAccessTargetFile {
+            error: std::io::Error::new(std::io::ErrorKind::NotFound, "target"),
+        },
+        FileError::from(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "other",
+        )),
+    ];
+
+    for file_error in file_errors {
+        let source = file_error
+            .source()
+            .expect("FileError should expose the underlying IO error as its source");
+
+        let io_error = source
+            .downcast_ref::<std::io::Error>()
+            .expect("FileError source should be an IO error");
+        assert_eq!(io_error.kind(), std::io::ErrorKind::NotFound);
+
+        // The source is reported by the error chain, so it isn't repeated in the message.
+        assert!(!file_error.to_string().ends_with(&io_error.to_string()));
+    }
+
+    assert!(FileError::NotFound.source().is_none());
+
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn copy_file_preserves_ownership_with_flag() -> TestResult<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let harness = SimpleFileHarness::new()?;
+
+    let source_metadata = std::fs::metadata(harness.test_file.path())?;
+    let is_privileged = source_metadata.uid() == 0;
+
+    // Only a privileged user can give away files, so we can only use a foreign owner when running as root.
+    if is_privileged {
+        std::os::unix::fs::chown(harness.test_file.path(), Some(1234), Some(1234))?;
+    }
+
+    let target_file = AssertableFilePath::from_path(harness.root.child_path("test_file-owned.txt"));
+
+    fs_more::file::copy_file(
+        harness.test_file.path(),
+        target_file.path(),
+        FileCopyOptions {
+            preserve_ownership: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let source_metadata = std::fs::metadata(harness.test_file.path())?;
+    let target_metadata = std::fs::metadata(target_file.path())?;
+
+    assert_eq!(target_metadata.uid(), source_metadata.uid());
+    assert_eq!(target_metadata.gid(), source_metadata.gid());
+    target_file.assert_content_matches_file(harness.test_file.path());
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_file_with_progress_adaptive_buffer_compared_to_fixed() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let fixed_strategy = BufferStrategy::Fixed(1024 * 1024);
+    let adaptive_strategy = BufferStrategy::Adaptive {
+        small: 1024 * 8,
+        large: 1024 * 1024,
+        threshold: 1024 * 256,
+    };
+
+    assert_eq!(fixed_strategy.buffer_size_for(100), 1024 * 1024);
+    assert_eq!(adaptive_strategy.buffer_size_for(100), 1024 * 8);
+    assert_eq!(
+        adaptive_strategy.buffer_size_for(1024 * 256),
+        1024 * 1024
+    );
+
+
+    // A small file (below the adaptive threshold) and a large one.
+    let small_file_contents: Vec<u8> = (0..(1024 * 64))
+        .map(|index: u32| (index % 251) as u8)
+        .collect();
+    let large_file_contents: Vec<u8> = (0..(1024 * 1024 * 4))
+        .map(|index: u32| (index % 251) as u8)
+        .collect();
+
+    let small_file_path = harness.root.child_path("small-file.bin");
+    let large_file_path = harness.root.child_path("large-file.bin");
+    std::fs::write(&small_file_path, &small_file_contents)?;
+    std::fs::write(&large_file_path, &large_file_contents)?;
+
+
+    // The file is written in chunks of at most the buffer size, so with the smallest
+    // progress interval, the largest progress step is the size of the buffer that was used.
+    let largest_progress_step = |source_file_path: &Path,
+                                 buffer_strategy: BufferStrategy|
+     -> TestResult<u64> {
+        let target_file_path = harness.root.child_path("progress-copy.bin");
+
+        let mut previous_bytes_finished = 0;
+        let mut largest_step = 0;
+
+        fs_more::file::copy_file_with_progress(
+            source_file_path,
+            &target_file_path,
+            FileCopyWithProgressOptions {
+                overwrite_existing: true,
+                buffer_strategy,
+                progress_update_byte_interval: 1,
+                ..Default::default()
+            },
+            |progress| {
+                largest_step = largest_step.max(progress.bytes_finished - previous_bytes_finished);
+                previous_bytes_finished = progress.bytes_finished;
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read(&target_file_path)?,
+            std::fs::read(source_file_path)?
+        );
+
+        Ok(largest_step)
+    };
+
+    // The fixed strategy copies the entire small file at once.
+    assert_eq!(
+        largest_progress_step(&small_file_path, fixed_strategy)?,
+        1024 * 64
+    );
+    assert_eq!(
+        largest_progress_step(&large_file_path, fixed_strategy)?,
+        1024 * 1024
+    );
+
+    // The adaptive strategy only uses the large buffer for the large file.
+    assert_eq!(
+        largest_progress_step(&small_file_path, adaptive_strategy)?,
+        1024 * 8
+    );
+    assert_eq!(
+        largest_progress_step(&large_file_path, adaptive_strategy)?,
+        1024 * 1024
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_file_with_progress_detects_source_modification() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let source_file = AssertableFilePath::from_path(harness.root.child_path("growing-log.txt"));
+    std::fs::write(source_file.path(), vec![b'a'; 1024 * 64])?;
+
+    let options = FileCopyWithProgressOptions {
+        detect_source_modification: true,
+        buffer_strategy: BufferStrategy::Fixed(1024),
+        progress_update_byte_interval: 1024,
+        ..Default::default()
+    };
+
+
+    // An unmodified source is copied as usual.
+    let unmodified_target_file =
+        AssertableFilePath::from_path(harness.root.child_path("unmodified-copy.txt"));
+
+    fs_more::file::copy_file_with_progress(
+        source_file.path(),
+        unmodified_target_file.path(),
+        options,
+        |_| {},
+    )
+    .unwrap();
+
+    unmodified_target_file.assert_content_matches_file(source_file.path());
+
+
+    // Appending to the source mid-copy makes the copy fail and removes the target.
+    let modified_target_file =
+        AssertableFilePath::from_path(harness.root.child_path("modified-copy.txt"));
+    let mut has_appended = false;
+
+    let copy_result = fs_more::file::copy_file_with_progress(
+        source_file.path(),
+        modified_target_file.path(),
+        options,
+        |_| {
+            if !has_appended {
+                use std::io::Write;
+
+                let mut source = std::fs::OpenOptions::new()
+                    .append(true)
+                    .open(source_file.path())
+                    .unwrap();
+                source.write_all(b"appended log line\n").unwrap();
+
+                has_appended = true;
+            }
+        },
+    );
+
+    assert!(has_appended);
+    assert_matches!(
+        copy_result.unwrap_err(),
+        FileError::SourceModifiedDuringCopy
+    );
+    modified_target_file.assert_not_exists();
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_file_preserves_creation_time_with_flag() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let target_file =
+        AssertableFilePath::from_path(harness.root.child_path("test_file-created.txt"));
+
+    fs_more::file::copy_file(
+        harness.test_file.path(),
+        target_file.path(),
+        FileCopyOptions {
+            preserve_creation_time: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    target_file.assert_content_matches_file(harness.test_file.path());
+
+    // Creation times can only be set on Windows and macOS; elsewhere the flag has no effect.
+    #[cfg(any(windows, target_os = "macos"))]
+    {
+        let source_metadata = std::fs::metadata(harness.test_file.path())?;
+        let target_metadata = std::fs::metadata(target_file.path())?;
+
+        assert_eq!(
+            target_metadata.created()?,
+            source_metadata.created()?
+        );
+    }
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn copy_file_applies_target_permissions() -> TestResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let harness = SimpleFileHarness::new()?;
+
+    std::fs::set_permissions(
+        harness.test_file.path(),
+        std::fs::Permissions::from_mode(0o644),
+    )?;
+
+    let target_file =
+        AssertableFilePath::from_path(harness.root.child_path("test_file-restricted.txt"));
+
+    fs_more::file::copy_file(
+        harness.test_file.path(),
+        target_file.path(),
+        FileCopyOptions {
+            target_permissions: Some(0o440),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let target_permissions = std::fs::metadata(target_file.path())?.permissions();
+
+    assert_eq!(target_permissions.mode() & 0o777, 0o440);
+    assert!(target_permissions.readonly());
+    target_file.assert_content_matches_file(harness.test_file.path());
+
+    // The source file is left untouched.
+    assert_eq!(
+        std::fs::metadata(harness.test_file.path())?
+            .permissions()
+            .mode()
+            & 0o777,
+        0o644
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_file_errors_when_target_is_hard_link_to_source() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let hard_link_path = harness.root.child_path("test_file-hard-link.txt");
+    std::fs::hard_link(harness.test_file.path(), &hard_link_path)?;
+
+    let copy_result = fs_more::file::copy_file(
+        harness.test_file.path(),
+        &hard_link_path,
+        FileCopyOptions {
+            overwrite_existing: true,
+            ..Default::default()
+        },
+    );
+
+    assert_matches!(
+        copy_result.unwrap_err(),
+        FileError::SourceAndTargetAreTheSameFile
+    );
+    harness.test_file.assert_content_unchanged();
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn default_buffer_sizes_match_default_copy_buffer_size() -> TestResult<()> {
+    assert_eq!(DEFAULT_COPY_BUFFER_SIZE, 1024 * 64);
+    assert_eq!(
+        BufferStrategy::default(),
+        BufferStrategy::Fixed(DEFAULT_COPY_BUFFER_SIZE)
+    );
+    assert_eq!(
+        FileCopyWithProgressOptions::default()
+            .buffer_strategy
+            .buffer_size_for(u64::MAX),
+        DEFAULT_COPY_BUFFER_SIZE
+    );
+    assert_eq!(
+        fs_more::file::FileMoveWithProgressOptions::default().buffer_size,
+        DEFAULT_COPY_BUFFER_SIZE
+    );
+    assert_eq!(
+        fs_more::directory::DirectoryCopyWithProgressOptions::default().buffer_size,
+        DEFAULT_COPY_BUFFER_SIZE
+    );
+
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn copy_file_keeps_existing_target_if_source_is_unreadable() -> TestResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let harness = SimpleFileHarness::new()?;
+
+    std::fs::set_permissions(
+        harness.test_file.path(),
+        std::fs::Permissions::from_mode(0o000),
+    )?;
+
+    // Privileged users (e.g. root) can read the file regardless of its permissions,
+    // in which case there is nothing to test.
+    if std::fs::File::open(harness.test_file.path()).is_ok() {
+        std::fs::set_permissions(
+            harness.test_file.path(),
+            std::fs::Permissions::from_mode(0o644),
+        )?;
+        harness.destroy()?;
+        return Ok(());
+    }
+
+    let copy_result = fs_more::file::copy_file(
+        harness.test_file.path(),
+        harness.foo_bar.path(),
+        FileCopyOptions {
+            overwrite_existing: true,
+            ..Default::default()
+        },
+    );
+
+    assert_matches!(
+        copy_result.unwrap_err(),
+        FileError::UnableToAccessSourceFile { .. }
+    );
+
+    // The target file was never opened, so it must be left as it was.
+    harness.foo_bar.assert_content_unchanged();
+
+
+    std::fs::set_permissions(
+        harness.test_file.path(),
+        std::fs::Permissions::from_mode(0o644),
+    )?;
+
+    harness.destroy()?;
     Ok(())
 }
@@ -0,0 +1,90 @@
+use assert_matches::assert_matches;
+use fs_more::{error::FileError, file::FileLengthChange};
+use fs_more_test_harness::{
+    assertable::AssertableFilePath,
+    error::TestResult,
+    trees::SimpleFileHarness,
+};
+
+#[test]
+pub fn set_file_length() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let original_contents = std::fs::read(harness.test_file.path())?;
+    let original_length = original_contents.len() as u64;
+
+
+    let change = fs_more::file::set_file_length(harness.test_file.path(), original_length + 10)
+        .unwrap_or_else(|error| {
+            panic!(
+                "set_file_length unexpectedly failed with Err: {}",
+                error
+            )
+        });
+
+    assert_eq!(
+        change,
+        FileLengthChange::Grew {
+            previous_length: original_length
+        }
+    );
+
+    let extended_contents = std::fs::read(harness.test_file.path())?;
+    assert_eq!(
+        &extended_contents[..original_contents.len()],
+        original_contents
+    );
+    assert!(extended_contents[original_contents.len()..]
+        .iter()
+        .all(|byte| *byte == 0));
+
+
+    let change = fs_more::file::set_file_length(harness.test_file.path(), 4).unwrap();
+
+    assert_eq!(
+        change,
+        FileLengthChange::Shrank {
+            previous_length: original_length + 10
+        }
+    );
+    assert_eq!(
+        std::fs::read(harness.test_file.path())?,
+        &original_contents[..4]
+    );
+
+
+    let change = fs_more::file::set_file_length(harness.test_file.path(), 4).unwrap();
+    assert_eq!(change, FileLengthChange::Unchanged);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn fail_to_set_file_length_when_it_doesnt_exist() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let non_existent_file = AssertableFilePath::from_path(
+        harness
+            .foo_bar
+            .path()
+            .with_file_name("random_nonexistent_file.md"),
+    );
+    non_existent_file.assert_not_exists();
+
+    let set_length_result = fs_more::file::set_file_length(non_existent_file.path(), 10);
+
+    assert_matches!(
+        set_length_result,
+        Err(FileError::NotFound),
+        "set_file_length should have returned NotFound"
+    );
+
+    non_existent_file.assert_not_exists();
+
+
+    harness.destroy()?;
+    Ok(())
+}
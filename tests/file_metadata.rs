@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use fs_more::{error::FileError, file::MetadataCopyOptions};
+use fs_more_test_harness::{error::TestResult, trees::SimpleFileHarness};
+
+#[test]
+pub fn copy_metadata() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    // Make sure the target has a different modification time than the source.
+    std::thread::sleep(Duration::from_millis(20));
+    std::fs::write(harness.foo_bar.path(), "Some new contents.")?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(
+            harness.test_file.path(),
+            std::fs::Permissions::from_mode(0o600),
+        )?;
+        std::fs::set_permissions(
+            harness.foo_bar.path(),
+            std::fs::Permissions::from_mode(0o644),
+        )?;
+    }
+
+    assert_ne!(
+        std::fs::metadata(harness.test_file.path())?.modified()?,
+        std::fs::metadata(harness.foo_bar.path())?.modified()?
+    );
+
+
+    fs_more::file::copy_metadata(
+        harness.test_file.path(),
+        harness.foo_bar.path(),
+        MetadataCopyOptions {
+            extended_attributes: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let source_metadata = std::fs::metadata(harness.test_file.path())?;
+    let target_metadata = std::fs::metadata(harness.foo_bar.path())?;
+
+    assert_eq!(
+        target_metadata.modified()?,
+        source_metadata.modified()?
+    );
+    assert_eq!(
+        target_metadata.permissions(),
+        source_metadata.permissions()
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        assert_eq!(
+            target_metadata.permissions().mode() & 0o777,
+            0o600
+        );
+    }
+
+    // The contents are left untouched.
+    harness
+        .foo_bar
+        .assert_content_matches_str("Some new contents.");
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn copy_metadata_copies_only_selected_classes() -> TestResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let harness = SimpleFileHarness::new()?;
+
+    std::thread::sleep(Duration::from_millis(20));
+    std::fs::write(harness.foo_bar.path(), "Some new contents.")?;
+
+    std::fs::set_permissions(
+        harness.test_file.path(),
+        std::fs::Permissions::from_mode(0o600),
+    )?;
+    std::fs::set_permissions(
+        harness.foo_bar.path(),
+        std::fs::Permissions::from_mode(0o644),
+    )?;
+
+    let target_modification_time = std::fs::metadata(harness.foo_bar.path())?.modified()?;
+
+
+    fs_more::file::copy_metadata(
+        harness.test_file.path(),
+        harness.foo_bar.path(),
+        MetadataCopyOptions {
+            permissions: true,
+            timestamps: false,
+            extended_attributes: false,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let target_metadata = std::fs::metadata(harness.foo_bar.path())?;
+
+    assert_eq!(
+        target_metadata.permissions().mode() & 0o777,
+        0o600
+    );
+    assert_eq!(
+        target_metadata.modified()?,
+        target_modification_time
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn copy_metadata_errors_on_missing_source_or_target() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let missing_file_path = harness.root.child_path("missing.txt");
+
+    let copy_result = fs_more::file::copy_metadata(
+        &missing_file_path,
+        harness.foo_bar.path(),
+        MetadataCopyOptions::default(),
+    );
+
+    assert_matches!(copy_result, Err(FileError::NotFound));
+
+
+    let copy_result = fs_more::file::copy_metadata(
+        harness.test_file.path(),
+        &missing_file_path,
+        MetadataCopyOptions::default(),
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(FileError::UnableToAccessTargetFile { error })
+        if error.kind() == std::io::ErrorKind::NotFound
+    );
+
+
+    let copy_result = fs_more::file::copy_metadata(
+        harness.test_file.path(),
+        harness.root.path(),
+        MetadataCopyOptions::default(),
+    );
+
+    assert_matches!(
+        copy_result,
+        Err(FileError::UnableToAccessTargetFile { .. })
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn copy_metadata_reads_timestamps_from_symlink_or_its_target() -> TestResult<()> {
+    use fs_more::file::MetadataSource;
+
+    let harness = SimpleFileHarness::new()?;
+
+    // The link is created after the file it points to, so their modification times differ.
+    std::thread::sleep(Duration::from_millis(20));
+    let link_path = harness.root.child_path("test_file-link.txt");
+    std::os::unix::fs::symlink(harness.test_file.path(), &link_path)?;
+
+    let file_modification_time = std::fs::metadata(harness.test_file.path())?.modified()?;
+    let link_modification_time = std::fs::symlink_metadata(&link_path)?.modified()?;
+    assert_ne!(file_modification_time, link_modification_time);
+
+
+    for (metadata_source, expected_modification_time) in [
+        (MetadataSource::Target, file_modification_time),
+        (MetadataSource::Link, link_modification_time),
+    ] {
+        fs_more::file::copy_metadata(
+            &link_path,
+            harness.foo_bar.path(),
+            MetadataCopyOptions {
+                symlink_metadata_source: metadata_source,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            std::fs::metadata(harness.foo_bar.path())?.modified()?,
+            expected_modification_time
+        );
+    }
+
+
+    harness.destroy()?;
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use assert_matches::assert_matches;
 use fs_more::{
     error::FileError,
-    file::{FileMoveOptions, FileMoveWithProgressOptions, FileProgress},
+    file::{ChecksumAlgorithm, FileMoveOptions, FileMoveWithProgressOptions, FileProgress},
 };
 use fs_more_test_harness::{
     assertable::AssertableFilePath,
@@ -21,6 +21,8 @@ pub fn move_file() -> TestResult<()> {
         target_file.path(),
         FileMoveOptions {
             overwrite_existing: false,
+            verify_before_delete: None,
+            create_missing_parent_directories: false,
         },
     );
 
@@ -59,7 +61,7 @@ pub fn move_file_with_progress() -> TestResult<()> {
         },
         |progress| {
             if let Some(previous_progress) = last_progress.as_ref() {
-                assert!(progress.bytes_finished >= previous_progress.bytes_finished);
+                assert!(progress >= previous_progress);
             }
 
             last_progress = Some(progress.clone());
@@ -73,6 +75,7 @@ pub fn move_file_with_progress() -> TestResult<()> {
         source_file_size_bytes
     );
     assert_eq!(last_progress.bytes_total, source_file_size_bytes);
+    assert_eq!(last_progress.fraction_complete(), 1.0);
 
     assert!(
         file_copy_result.is_ok(),
@@ -100,6 +103,8 @@ pub fn forbid_move_into_itself() -> TestResult<()> {
         harness.foo_bar.path(),
         FileMoveOptions {
             overwrite_existing: false,
+            verify_before_delete: None,
+            create_missing_parent_directories: false,
         },
     );
 
@@ -135,6 +140,8 @@ pub fn forbid_move_into_itself_with_overwrite_flag() -> TestResult<()> {
         harness.foo_bar.path(),
         FileMoveOptions {
             overwrite_existing: true,
+            verify_before_delete: None,
+            create_missing_parent_directories: false,
         },
     );
 
@@ -186,6 +193,8 @@ pub fn forbid_case_insensitive_move_into_itself() -> TestResult<()> {
         target_file.path(),
         FileMoveOptions {
             overwrite_existing: false,
+            verify_before_delete: None,
+            create_missing_parent_directories: false,
         },
     );
 
@@ -239,6 +248,8 @@ pub fn allow_move_overwriting_target_file_with_flag() -> TestResult<()> {
         harness.foo_bar.path(),
         FileMoveOptions {
             overwrite_existing: true,
+            verify_before_delete: None,
+            create_missing_parent_directories: false,
         },
     );
 
@@ -277,6 +288,8 @@ pub fn forbid_move_overwriting_target_file_without_flag() -> TestResult<()> {
         harness.foo_bar.path(),
         FileMoveOptions {
             overwrite_existing: false,
+            verify_before_delete: None,
+            create_missing_parent_directories: false,
         },
     );
 
@@ -345,6 +358,51 @@ pub fn move_file_symlink_behaviour() -> TestResult<()> {
     Ok(())
 }
 
+/// Moving a symbolic link to a file always copies the file (and then removes the link),
+/// which lets us exercise verification without needing a second filesystem.
+///
+/// **On Windows**, creating symbolic links requires administrator privileges, unless Developer mode is enabled.
+/// See [https://stackoverflow.com/questions/58038683/allow-mklink-for-a-non-admin-user].
+#[test]
+pub fn move_file_verifies_copy_before_removing_source() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    for (index, algorithm) in [ChecksumAlgorithm::Crc32, ChecksumAlgorithm::Sha256]
+        .into_iter()
+        .enumerate()
+    {
+        let symlinked_file = AssertableFilePath::from_path(
+            harness.root.child_path(format!("my-symlink-{}.txt", index)),
+        );
+        symlinked_file.symlink_to_file(harness.binary_file_a.path())?;
+
+        let target_file = AssertableFilePath::from_path(
+            harness
+                .root
+                .child_path(format!("my-verified-file-{}.txt", index)),
+        );
+        target_file.assert_not_exists();
+
+        fs_more::file::move_file(
+            symlinked_file.path(),
+            target_file.path(),
+            FileMoveOptions {
+                overwrite_existing: false,
+                verify_before_delete: Some(algorithm),
+                create_missing_parent_directories: false,
+            },
+        )
+        .unwrap();
+
+        symlinked_file.assert_not_exists();
+        harness.binary_file_a.assert_content_unchanged();
+        target_file.assert_content_matches_file(harness.binary_file_a.path());
+    }
+
+    harness.destroy()?;
+    Ok(())
+}
+
 /// **On Windows**, creating symbolic links requires administrator privileges, unless Developer mode is enabled.
 /// See [https://stackoverflow.com/questions/58038683/allow-mklink-for-a-non-admin-user].
 #[test]
@@ -403,6 +461,8 @@ pub fn forbid_move_file_when_source_is_symlink_to_target() -> TestResult<()> {
         harness.test_file.path(),
         FileMoveOptions {
             overwrite_existing: true,
+            verify_before_delete: None,
+            create_missing_parent_directories: false,
         },
     );
 
@@ -468,3 +528,66 @@ pub fn forbid_move_file_with_progress_when_source_is_symlink_to_target() -> Test
     harness.destroy()?;
     Ok(())
 }
+
+#[test]
+pub fn move_file_into_directory() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let target_file =
+        AssertableFilePath::from_path(harness.subdirectory_b.path().join("binary_file_a.bin"));
+    target_file.assert_not_exists();
+
+    let num_bytes_moved = fs_more::file::move_file_into_directory(
+        harness.binary_file_a.path(),
+        harness.subdirectory_b.path(),
+        FileMoveOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(num_bytes_moved, target_file.file_size_in_bytes()?);
+
+    harness.binary_file_a.assert_not_exists();
+    target_file.assert_content_matches_expected_value_of_assertable(&harness.binary_file_a);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+#[test]
+pub fn move_file_into_missing_directory_only_with_flag() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let target_directory_path = harness.root.child_path("archive/2024");
+
+    let move_result = fs_more::file::move_file_into_directory(
+        harness.binary_file_a.path(),
+        &target_directory_path,
+        FileMoveOptions::default(),
+    );
+
+    assert_matches!(
+        move_result.unwrap_err(),
+        FileError::UnableToAccessTargetFile { .. }
+    );
+    harness.binary_file_a.assert_content_unchanged();
+
+
+    fs_more::file::move_file_into_directory(
+        harness.binary_file_a.path(),
+        &target_directory_path,
+        FileMoveOptions {
+            create_missing_parent_directories: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    harness.binary_file_a.assert_not_exists();
+    AssertableFilePath::from_path(target_directory_path.join("binary_file_a.bin"))
+        .assert_content_matches_expected_value_of_assertable(&harness.binary_file_a);
+
+
+    harness.destroy()?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use assert_matches::assert_matches;
+use fs_more::error::FileError;
+use fs_more_test_harness::{error::TestResult, trees::SimpleFileHarness};
+
+#[test]
+pub fn read_to_string_limited_reads_file_within_limit() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let expected_contents = std::fs::read_to_string(harness.test_file.path())?;
+    let file_size_bytes = expected_contents.len() as u64;
+
+    let contents =
+        fs_more::file::read_to_string_limited(harness.test_file.path(), file_size_bytes).unwrap();
+
+    assert_eq!(contents, expected_contents);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn read_to_string_limited_rejects_file_over_limit() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let file_size_bytes = std::fs::metadata(harness.test_file.path())?.len();
+    assert!(file_size_bytes > 0);
+
+    let read_result =
+        fs_more::file::read_to_string_limited(harness.test_file.path(), file_size_bytes - 1);
+
+    assert_matches!(
+        read_result,
+        Err(FileError::FileTooLarge { size, limit })
+            if size == file_size_bytes && limit == file_size_bytes - 1
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn read_to_string_limited_rejects_invalid_utf8() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let invalid_file_path = harness.root.child_path("invalid-utf8.bin");
+    std::fs::write(&invalid_file_path, [0x66, 0x6f, 0xff, 0x6f])?;
+
+    assert_matches!(
+        fs_more::file::read_to_string_limited(&invalid_file_path, 1024),
+        Err(FileError::InvalidUtf8 { .. })
+    );
+
+    assert_matches!(
+        fs_more::file::read_to_string_limited(harness.root.child_path("missing.txt"), 1024),
+        Err(FileError::NotFound)
+    );
+
+
+    harness.destroy()?;
+    Ok(())
+}
@@ -57,6 +57,57 @@ pub fn fail_file_removal_when_it_doesnt_exist() -> TestResult<()> {
     );
 
 
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(feature = "trash")]
+#[test]
+pub fn trash_file_moves_file_to_trash_or_reports_unavailable_trash() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let trash_result = fs_more::file::trash_file(harness.test_file.path());
+
+    // Not every environment has a trash available (e.g. headless CI runners),
+    // in which case the file must be left in place.
+    match trash_result {
+        Ok(()) => harness.test_file.assert_not_exists(),
+        Err(error) => {
+            assert_matches!(error, FileRemoveError::TrashUnavailable { .. });
+            harness.test_file.assert_exists();
+        }
+    }
+
+    harness.foo_bar.assert_exists();
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(feature = "trash")]
+#[test]
+pub fn fail_trashing_file_when_it_is_not_a_file() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let trash_result = fs_more::file::trash_file(harness.root.path());
+
+    assert_matches!(
+        trash_result.unwrap_err(),
+        FileRemoveError::NotAFile
+    );
+
+    let non_existent_file = harness.root.path().join("random_nonexistent_file.md");
+    let trash_result = fs_more::file::trash_file(non_existent_file);
+
+    assert_matches!(
+        trash_result.unwrap_err(),
+        FileRemoveError::NotFound
+    );
+
+
     harness.destroy()?;
     Ok(())
 }
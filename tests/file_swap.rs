@@ -0,0 +1,70 @@
+use assert_matches::assert_matches;
+use fs_more::error::FileError;
+use fs_more_test_harness::{error::TestResult, trees::SimpleFileHarness};
+
+#[test]
+pub fn swap_files() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let test_file_contents = std::fs::read(harness.test_file.path())?;
+    let foo_bar_contents = std::fs::read(harness.foo_bar.path())?;
+    assert_ne!(test_file_contents, foo_bar_contents);
+
+    fs_more::file::swap_files(harness.test_file.path(), harness.foo_bar.path()).unwrap();
+
+    harness
+        .test_file
+        .assert_content_matches_bytes(&foo_bar_contents);
+    harness
+        .foo_bar
+        .assert_content_matches_bytes(&test_file_contents);
+
+    // No temporary files should be left behind.
+    assert_eq!(std::fs::read_dir(harness.root.path())?.count(), 2);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn swap_files_errors_on_missing_file() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let missing_file_path = harness.root.path().join("missing.txt");
+
+    let swap_result = fs_more::file::swap_files(&missing_file_path, harness.foo_bar.path());
+    assert_matches!(swap_result, Err(FileError::NotFound));
+
+    let swap_result = fs_more::file::swap_files(harness.test_file.path(), &missing_file_path);
+    assert_matches!(swap_result, Err(FileError::NotFound));
+
+    harness.test_file.assert_content_unchanged();
+    harness.foo_bar.assert_content_unchanged();
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn swap_files_errors_on_same_file() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let hard_link_path = harness.root.path().join("test_file-link.txt");
+    std::fs::hard_link(harness.test_file.path(), &hard_link_path)?;
+
+    let swap_result = fs_more::file::swap_files(harness.test_file.path(), &hard_link_path);
+    assert_matches!(
+        swap_result,
+        Err(FileError::SourceAndTargetAreTheSameFile)
+    );
+
+    harness.test_file.assert_content_unchanged();
+
+
+    harness.destroy()?;
+    Ok(())
+}
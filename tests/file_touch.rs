@@ -0,0 +1,97 @@
+use std::time::{Duration, SystemTime};
+
+use assert_matches::assert_matches;
+use fs_more::{
+    error::FileError,
+    file::{TouchOptions, TouchOutcome},
+};
+use fs_more_test_harness::{
+    assertable::AssertableFilePath,
+    error::TestResult,
+    trees::SimpleFileHarness,
+};
+
+#[test]
+pub fn touch_creates_missing_file() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let new_file = AssertableFilePath::from_path(harness.root.child_path("new_file.txt"));
+    new_file.assert_not_exists();
+
+    let outcome = fs_more::file::touch(new_file.path(), TouchOptions::default()).unwrap();
+
+    assert_eq!(outcome, TouchOutcome::Created);
+    new_file.assert_is_file();
+    assert_eq!(std::fs::metadata(new_file.path())?.len(), 0);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn touch_updates_timestamps_of_existing_file() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let original_contents = std::fs::read(harness.test_file.path())?;
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+
+    let outcome = fs_more::file::touch(
+        harness.test_file.path(),
+        TouchOptions {
+            time: Some(time),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(outcome, TouchOutcome::Updated);
+
+    let metadata = std::fs::metadata(harness.test_file.path())?;
+    assert_eq!(metadata.modified()?, time);
+    assert_eq!(metadata.accessed()?, time);
+    assert_eq!(
+        std::fs::read(harness.test_file.path())?,
+        original_contents
+    );
+
+
+    let before_touch = SystemTime::now() - Duration::from_secs(1);
+
+    fs_more::file::touch(harness.test_file.path(), TouchOptions::default()).unwrap();
+
+    assert!(std::fs::metadata(harness.test_file.path())?.modified()? >= before_touch);
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn touch_without_create_requires_existing_file() -> TestResult<()> {
+    let harness = SimpleFileHarness::new()?;
+
+    let missing_file = AssertableFilePath::from_path(harness.root.child_path("missing_file.txt"));
+
+    let result = fs_more::file::touch(
+        missing_file.path(),
+        TouchOptions {
+            create: false,
+            ..Default::default()
+        },
+    );
+
+    assert_matches!(result, Err(FileError::NotFound));
+    missing_file.assert_not_exists();
+
+
+    let result = fs_more::file::touch(harness.root.path(), TouchOptions::default());
+
+    assert_matches!(result, Err(FileError::NotAFile));
+
+
+    harness.destroy()?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use fs_more::path::PathKind;
+use fs_more_test_harness::{
+    assertable::{AssertableDirectoryPath, AssertableFilePath},
+    error::TestResult,
+    trees::SimpleTreeHarness,
+};
+
+#[test]
+pub fn probe_files_and_directories() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    assert_eq!(
+        fs_more::path::probe(harness.binary_file_a.path())?,
+        PathKind::File
+    );
+    assert_eq!(
+        fs_more::path::probe(harness.subdirectory_b.path())?,
+        PathKind::Directory
+    );
+    assert_eq!(
+        fs_more::path::probe(harness.root.child_path("non-existent.bin"))?,
+        PathKind::Missing
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn probe_symbolic_links() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let symlink_to_file =
+        AssertableFilePath::from_path(harness.root.child_path("symlink-to-file.bin"));
+    symlink_to_file.symlink_to_file(harness.binary_file_a.path())?;
+
+    let symlink_to_directory =
+        AssertableDirectoryPath::from_path(harness.root.child_path("symlink-to-directory"));
+    symlink_to_directory.symlink_to_directory(harness.subdirectory_b.path())?;
+
+    let broken_symlink =
+        AssertableFilePath::from_path(harness.root.child_path("broken-symlink.bin"));
+    broken_symlink.symlink_to_file(harness.root.child_path("non-existent.bin"))?;
+
+
+    assert_eq!(
+        fs_more::path::probe(symlink_to_file.path())?,
+        PathKind::SymlinkToFile
+    );
+    assert_eq!(
+        fs_more::path::probe(symlink_to_directory.path())?,
+        PathKind::SymlinkToDirectory
+    );
+    assert_eq!(
+        fs_more::path::probe(broken_symlink.path())?,
+        PathKind::BrokenSymlink
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
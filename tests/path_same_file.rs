@@ -0,0 +1,72 @@
+use fs_more::path::is_same_file;
+use fs_more_test_harness::{error::TestResult, trees::SimpleTreeHarness};
+
+#[test]
+pub fn is_same_file_compares_file_identities() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    assert!(is_same_file(
+        harness.binary_file_a.path(),
+        harness.binary_file_a.path()
+    )
+    .unwrap());
+
+    // A roundabout path to the same file.
+    let roundabout_path = harness
+        .subdirectory_b
+        .path()
+        .join("..")
+        .join(harness.binary_file_a.path().file_name().unwrap());
+    assert!(is_same_file(harness.binary_file_a.path(), &roundabout_path).unwrap());
+
+    assert!(!is_same_file(
+        harness.binary_file_a.path(),
+        harness.binary_file_b.path()
+    )
+    .unwrap());
+
+    // Directories have identities as well.
+    assert!(is_same_file(harness.root.path(), harness.root.path()).unwrap());
+    assert!(!is_same_file(harness.root.path(), harness.subdirectory_b.path()).unwrap());
+
+    // Nothing is the same file as a missing path.
+    let missing_path = harness.root.child_path("missing.bin");
+    assert!(!is_same_file(harness.binary_file_a.path(), &missing_path).unwrap());
+    assert!(!is_same_file(&missing_path, &missing_path).unwrap());
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn is_same_file_detects_hard_links() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let hard_link_path = harness.root.child_path("hard-link.bin");
+    std::fs::hard_link(harness.binary_file_a.path(), &hard_link_path)?;
+
+    assert!(is_same_file(harness.binary_file_a.path(), &hard_link_path).unwrap());
+
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[cfg(unix)]
+#[test]
+pub fn is_same_file_follows_symlinks() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let symlink_path = harness.root.child_path("symlink.bin");
+    std::os::unix::fs::symlink(harness.binary_file_a.path(), &symlink_path)?;
+
+    assert!(is_same_file(harness.binary_file_a.path(), &symlink_path).unwrap());
+    assert!(!is_same_file(harness.binary_file_b.path(), &symlink_path).unwrap());
+
+
+    harness.destroy()?;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use assert_matches::assert_matches;
+use fs_more::error::PathValidationError;
+use fs_more_test_harness::{
+    assertable::AssertableFilePath,
+    error::TestResult,
+    trees::SimpleTreeHarness,
+};
+
+#[test]
+pub fn validate_copy_pair_accepts_valid_pairs() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    fs_more::path::validate_copy_pair(
+        harness.binary_file_a.path(),
+        harness.subdirectory_b.child_path("copied-file.bin"),
+    )
+    .unwrap();
+
+    fs_more::path::validate_copy_pair(
+        harness.subdirectory_b.path(),
+        harness.root.child_path("copied-directory/nested"),
+    )
+    .unwrap();
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn validate_copy_pair_reports_relationship_problems() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    assert_matches!(
+        fs_more::path::validate_copy_pair(
+            harness.root.child_path("non-existent.bin"),
+            harness.root.child_path("target.bin"),
+        ),
+        Err(PathValidationError::SourceNotFound)
+    );
+
+    assert_matches!(
+        fs_more::path::validate_copy_pair(
+            harness.binary_file_a.path(),
+            harness.binary_file_a.path()
+        ),
+        Err(PathValidationError::SourceAndTargetAreTheSame)
+    );
+
+    assert_matches!(
+        fs_more::path::validate_copy_pair(
+            harness.root.path(),
+            harness.subdirectory_b.child_path("new-directory"),
+        ),
+        Err(PathValidationError::TargetInsideSource)
+    );
+
+    assert_matches!(
+        fs_more::path::validate_copy_pair(
+            harness.subdirectory_b.path(),
+            harness.binary_file_a.path().join("nested.bin"),
+        ),
+        Err(PathValidationError::TargetParentNotADirectory { path })
+            if path == harness.binary_file_a.path()
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn validate_copy_pair_resolves_symbolic_links() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    let symlink_to_file =
+        AssertableFilePath::from_path(harness.root.child_path("symlink-to-file.bin"));
+    symlink_to_file.symlink_to_file(harness.binary_file_a.path())?;
+
+    assert_matches!(
+        fs_more::path::validate_copy_pair(
+            harness.binary_file_a.path(),
+            symlink_to_file.path()
+        ),
+        Err(PathValidationError::SourceAndTargetAreTheSame)
+    );
+
+    harness.destroy()?;
+    Ok(())
+}
+
+
+#[test]
+pub fn validate_copy_pair_rejects_file_as_directory_target() -> TestResult<()> {
+    let harness = SimpleTreeHarness::new()?;
+
+    assert_matches!(
+        fs_more::path::validate_copy_pair(
+            harness.subdirectory_b.path(),
+            harness.binary_file_a.path()
+        ),
+        Err(PathValidationError::TargetNotADirectory)
+    );
+
+    // Copying the directory onto the file fails the same way.
+    assert!(fs_more::directory::copy_directory(
+        harness.subdirectory_b.path(),
+        harness.binary_file_a.path(),
+        fs_more::directory::DirectoryCopyOptions::default(),
+    )
+    .is_err());
+
+    harness.binary_file_a.assert_content_unchanged();
+
+
+    harness.destroy()?;
+    Ok(())
+}